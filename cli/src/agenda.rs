@@ -0,0 +1,182 @@
+use crate::commands::Task;
+use crate::config::Config;
+use crate::slots::{self, Interval};
+use chrono::{Duration, NaiveDate};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Kind {
+    Event,
+    Todo,
+    /// Do-not-schedule zone (quiet hours)
+    Quiet,
+    Break,
+}
+
+pub struct Entry {
+    pub kind: Kind,
+    pub interval: Interval,
+    pub label: String,
+    /// An `--optional` todo, shown de-emphasized
+    pub optional: bool,
+}
+
+pub struct Day {
+    pub date: NaiveDate,
+    /// All-day events, shown above the timed entries
+    pub all_day: Vec<String>,
+    pub entries: Vec<Entry>,
+    /// One of the configured `[week] weekend` days
+    pub weekend: bool,
+    /// The configured first day of the week
+    pub starts_week: bool,
+}
+
+/// Case-insensitive match on part of the task's location
+pub fn at_location(task: &Task, wanted: &str) -> bool {
+    task.location.as_deref().is_some_and(|l| l.to_lowercase().contains(&wanted.to_lowercase()))
+}
+
+/// One piece per day, labelled "(day 2/3)" when the span crosses midnight
+fn per_day(b: slots::Busy) -> Vec<slots::Busy> {
+    let pieces = b.interval.by_day();
+    let n = pieces.len();
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(i, interval)| {
+            let label = if n > 1 { format!("{} (day {}/{})", b.label, i + 1, n) } else { b.label.clone() };
+            slots::Busy { interval, label }
+        })
+        .collect()
+}
+
+/// Scheduled tasks plus the zones the scheduler keeps clear, one day at a time
+pub fn build(tasks: &[Task], cfg: &Config, from: NaiveDate, days: i64) -> Vec<Day> {
+    // Labels carry the category icon, as configured
+    let busy_of = |t: &Task| {
+        let mut b = slots::busy_from_tasks(std::slice::from_ref(t)).pop()?;
+        b.label = format!("[{}] {}", t.id, crate::display::with_icon(t, cfg.category_icons));
+        Some(b)
+    };
+    let (whole_days, timed): (Vec<&Task>, Vec<&Task>) = tasks.iter().partition(|t| t.is_all_day());
+    let whole_days: Vec<slots::Busy> = whole_days.into_iter().filter_map(busy_of).flat_map(per_day).collect();
+    let busy: Vec<(Kind, bool, slots::Busy)> = timed
+        .into_iter()
+        .filter_map(|t| {
+            let mut b = busy_of(t)?;
+            if let Some(loc) = t.location.as_deref().filter(|l| !l.is_empty()) {
+                b.label = format!("{} @ {}", b.label, loc);
+            }
+            if !t.blocks_time() {
+                b.label = format!("{} ({})", b.label, t.transparency.as_deref().unwrap_or_default());
+            }
+            if t.kind == "event" {
+                return Some((Kind::Event, false, b));
+            }
+            if t.is_optional() {
+                b.label = format!("{} (optional)", b.label);
+            }
+            // Placements from older backends may be off the slot grid
+            let step = cfg.scheduling.slot_minutes;
+            let interval = Interval::new(slots::round_to(b.interval.start, step), slots::round_to(b.interval.end, step));
+            Some((Kind::Todo, t.is_optional(), slots::Busy { interval, ..b }))
+        })
+        .flat_map(|(kind, optional, b)| per_day(b).into_iter().map(move |piece| (kind, optional, piece)))
+        .collect();
+
+    (0..days)
+        .map(|offset| {
+            let date = from + Duration::days(offset);
+            let mut entries: Vec<Entry> = busy
+                .iter()
+                .filter(|(_, _, b)| b.interval.start.date() == date)
+                .map(|(kind, optional, b)| Entry { kind: *kind, interval: b.interval, label: b.label.clone(), optional: *optional })
+                .collect();
+            let zones = [(Kind::Quiet, &cfg.scheduling.do_not_schedule, "quiet hours"), (Kind::Break, &cfg.availability.breaks, "break")];
+            for (kind, blocks, label) in zones {
+                entries.extend(slots::blocks_on(blocks, date).into_iter().map(|interval| Entry { kind, interval, label: label.into(), optional: false }));
+            }
+            entries.sort_by_key(|e| (e.interval.start, e.interval.end));
+            let all_day = whole_days.iter().filter(|b| b.interval.start.date() == date).map(|b| b.label.clone()).collect();
+            let weekend = cfg.week.is_weekend(date);
+            let starts_week = cfg.week.start_of(date) == date;
+            Day { date, all_day, entries, weekend, starts_week }
+        })
+        .collect()
+}
+
+/// Colour is used only on a terminal that shows it, and never when NO_COLOR is set
+pub fn use_color() -> bool {
+    crate::platform::ansi_colors()
+}
+
+/// "[4] Night shift" → "task 4, Night shift"
+fn spoken(label: &str) -> String {
+    match label.strip_prefix('[').and_then(|l| l.split_once("] ")) {
+        Some((id, rest)) => format!("task {}, {}", id, rest),
+        None => label.to_string(),
+    }
+}
+
+/// `--accessible` agenda: every line says what it is rather than relying on grey or "~"
+pub fn render_accessible(days: &[Day]) -> String {
+    let mut out = String::new();
+    for (i, day) in days.iter().enumerate() {
+        if day.starts_week && i > 0 {
+            out.push_str("New week.\n");
+        }
+        let weekend = if day.weekend { ", weekend" } else { "" };
+        out.push_str(&format!("{}{}:\n", day.date.format("%A %Y-%m-%d"), weekend));
+        for label in &day.all_day {
+            out.push_str(&format!("All day, event: {}\n", spoken(label)));
+        }
+        if day.entries.is_empty() && day.all_day.is_empty() {
+            out.push_str("Nothing scheduled.\n");
+        }
+        for e in &day.entries {
+            let end = if e.interval.end.date() == day.date { e.interval.end.format("%H:%M").to_string() } else { "midnight".into() };
+            let when = format!("{} to {}", e.interval.start.format("%H:%M"), end);
+            match e.kind {
+                Kind::Event => out.push_str(&format!("{}, event: {}\n", when, spoken(&e.label))),
+                Kind::Todo => out.push_str(&format!("{}, todo: {}\n", when, spoken(&e.label))),
+                Kind::Quiet | Kind::Break => out.push_str(&format!("{}, {}, nothing gets scheduled\n", when, e.label)),
+            }
+        }
+    }
+    out
+}
+
+pub fn render(days: &[Day], color: bool) -> String {
+    let mut out = String::new();
+    for (i, day) in days.iter().enumerate() {
+        if day.starts_week && i > 0 {
+            out.push('\n');
+        }
+        let weekend = if day.weekend { " (weekend)" } else { "" };
+        out.push_str(&format!("{}{}\n", day.date.format("%a %Y-%m-%d"), weekend));
+        for label in &day.all_day {
+            out.push_str(&format!("  all day      {}\n", label));
+        }
+        if day.entries.is_empty() && day.all_day.is_empty() {
+            out.push_str("  (nothing scheduled)\n");
+        }
+        for e in &day.entries {
+            let end = if e.interval.end.date() == day.date { e.interval.end.format("%H:%M").to_string() } else { "24:00".into() };
+            let line = format!("  {}-{}  {}", e.interval.start.format("%H:%M"), end, e.label);
+            let background = matches!(e.kind, Kind::Quiet | Kind::Break);
+            let theme = crate::theme::current();
+            // Optional todos are greyed too; without colour their "(optional)" label says it
+            if color && (background || e.optional) {
+                out.push_str(&format!("{}\n", theme.muted.paint(&line)));
+            } else if color {
+                let ink = if e.kind == Kind::Event { theme.event } else { theme.todo };
+                out.push_str(&format!("{}\n", ink.paint(&line)));
+            } else if background {
+                out.push_str(&format!("{}  ~\n", line));
+            } else {
+                out.push_str(&format!("{}\n", line));
+            }
+        }
+    }
+    out
+}
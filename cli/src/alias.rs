@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+/// Split an alias template like a shell would: whitespace separates words, quotes group them
+pub fn split(template: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => word.extend(chars.next()),
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                word.extend(chars.next());
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("unclosed quote in `{}`", template));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Index of the first argument that isn't a global option or its value
+fn command_position(cli: &clap::Command, args: &[OsString]) -> Option<usize> {
+    let mut i = 1;
+    while i < args.len() {
+        let a = args[i].to_str()?;
+        if !a.starts_with('-') {
+            return Some(i);
+        }
+        let takes_value = cli
+            .get_arguments()
+            .find(|arg| match a.strip_prefix("--") {
+                Some(long) => arg.get_long() == Some(long),
+                None => a.len() == 2 && arg.get_short() == a.chars().nth(1),
+            })
+            .is_some_and(|arg| arg.get_action().takes_values());
+        i += if takes_value { 2 } else { 1 };
+    }
+    None
+}
+
+/// Replace a user alias with its template: `$1`…`$9` take the following arguments, `$@` the
+/// rest; arguments no placeholder used are appended
+pub fn expand(cli: &clap::Command, args: Vec<OsString>, aliases: &BTreeMap<String, String>) -> Result<Vec<OsString>, String> {
+    let Some(i) = command_position(cli, &args) else {
+        return Ok(args);
+    };
+    let Some(template) = args[i].to_str().and_then(|name| aliases.get(name)) else {
+        return Ok(args);
+    };
+    let rest = &args[i + 1..];
+    let mut used = 0;
+    let mut spliced = false;
+    let mut out = args[..i].to_vec();
+    for word in split(template)? {
+        if word == "$@" {
+            out.extend_from_slice(rest);
+            spliced = true;
+        } else if let Some(n) = word.strip_prefix('$').and_then(|n| n.parse::<usize>().ok()).filter(|n| *n > 0) {
+            let arg = rest.get(n - 1).ok_or_else(|| format!("alias `{}` needs at least {} argument(s)", args[i].to_string_lossy(), n))?;
+            out.push(arg.clone());
+            used = used.max(n);
+        } else {
+            out.push(word.into());
+        }
+    }
+    if !spliced {
+        out.extend_from_slice(&rest[used..]);
+    }
+    Ok(out)
+}
@@ -0,0 +1,338 @@
+use crate::audit;
+use crate::cassette::{self, Cassette, Interaction};
+use crate::ratelimit::{self, RateLimiter, ThrottleStats};
+use crate::commands::{
+    AutoScheduleResult, Category, HistoryEvent, ImportResult, PushAllResult, PushTaskResult, SyncResult, Task,
+};
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// 429 retries before giving up
+const MAX_RETRIES: u32 = 3;
+
+/// An error status from the backend; callers that care which one downcast to this
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: u16,
+    message: String,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ApiError {}
+
+/// Turn an error status into an `ApiError` carrying FastAPI's `detail` message
+async fn check_status(resp: reqwest::Response) -> Result<reqwest::Response, Box<dyn Error>> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let url = resp.url().path().to_string();
+    let body: Value = resp.json().await.unwrap_or(Value::Null);
+    let detail = match &body["detail"] {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    let message = match detail.is_empty() {
+        true => format!("{} from {}", status, url),
+        false => format!("{} from {}: {}", status, url, detail),
+    };
+    Err(Box::new(ApiError { status: status.as_u16(), message }))
+}
+
+/// Thin client for the scheduler backend; every mutation is written to the audit log
+pub struct Api {
+    client: reqwest::Client,
+    base: String,
+    cassette: Option<Cassette>,
+    limiter: Option<RateLimiter>,
+    stats: ThrottleStats,
+}
+
+impl Api {
+    pub fn new(base: &str) -> Self {
+        Api::with_client(reqwest::Client::new(), base)
+    }
+
+    pub fn with_client(client: reqwest::Client, base: &str) -> Self {
+        Api {
+            client,
+            base: base.trim_end_matches('/').to_string(),
+            cassette: None,
+            limiter: None,
+            stats: ThrottleStats::default(),
+        }
+    }
+
+    /// Cap outgoing requests at `rps` per second (bursts up to `burst`)
+    pub fn with_rate_limit(mut self, rps: f64, burst: f64) -> Self {
+        self.limiter = Some(RateLimiter::new(rps, burst));
+        self
+    }
+
+    pub fn throttle_stats(&self) -> &ThrottleStats {
+        &self.stats
+    }
+
+    /// Record interactions to, or replay them from, a cassette file
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base
+    }
+
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path)
+    }
+
+    /// Every request goes through here so cassettes and the log see all traffic
+    pub async fn execute(&self, method: Method, path: &str, payload: &Value) -> Result<reqwest::Response, Box<dyn Error>> {
+        let started = std::time::Instant::now();
+        let result = self.execute_unlogged(&method, path, payload).await;
+        let mut fields = json!({ "method": method.as_str(), "path": path, "ms": started.elapsed().as_millis() as u64 });
+        match &result {
+            Ok(resp) => fields["status"] = json!(resp.status().as_u16()),
+            Err(e) => fields["error"] = json!(e.to_string()),
+        }
+        crate::logfile::event("api", fields);
+        result
+    }
+
+    async fn execute_unlogged(&self, method: &Method, path: &str, payload: &Value) -> Result<reqwest::Response, Box<dyn Error>> {
+        let method = method.clone();
+        if let Some(c) = self.cassette.as_ref().filter(|c| c.is_replay()) {
+            return cassette::to_response(&c.next(method.as_str(), path)?);
+        }
+        let resp = self.send_throttled(&method, path, payload).await?;
+        let Some(c) = &self.cassette else {
+            return Ok(resp);
+        };
+
+        // Recording consumes the body, so rebuild an equivalent response afterwards
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let bytes = resp.bytes().await?;
+        let text = String::from_utf8_lossy(&bytes).to_string();
+        let json = serde_json::from_slice::<Value>(&bytes).ok();
+        c.save(Interaction {
+            method: method.to_string(),
+            path: path.to_string(),
+            request: payload.clone(),
+            status: status.as_u16(),
+            text: if json.is_none() && !text.is_empty() { Some(text) } else { None },
+            json,
+        })?;
+        let mut rebuilt = http::Response::builder().status(status);
+        for (k, v) in headers.iter() {
+            rebuilt = rebuilt.header(k, v);
+        }
+        Ok(reqwest::Response::from(rebuilt.body(bytes)?))
+    }
+
+    /// Respect the local limiter and retry 429s, honoring Retry-After
+    async fn send_throttled(&self, method: &Method, path: &str, payload: &Value) -> Result<reqwest::Response, Box<dyn Error>> {
+        let mut attempt = 0;
+        loop {
+            if let Some(l) = &self.limiter {
+                if !l.acquire().await.is_zero() {
+                    self.stats.delayed.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            let mut req = self.client.request(method.clone(), self.url(path));
+            if !payload.is_null() {
+                req = req.json(payload);
+            }
+            let resp = req.send().await?;
+            if resp.status() != reqwest::StatusCode::TOO_MANY_REQUESTS || attempt >= MAX_RETRIES {
+                return Ok(resp);
+            }
+            let wait = resp
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(ratelimit::retry_after)
+                .unwrap_or_else(|| Duration::from_secs(1 << attempt));
+            eprintln!("Rate limited by server; retrying {} {} in {:.1}s", method, path, wait.as_secs_f64());
+            self.stats.retried.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, Box<dyn Error>> {
+        let resp = check_status(self.execute(Method::GET, path, &Value::Null).await?).await?;
+        Ok(resp.json().await?)
+    }
+
+    async fn send(&self, method: Method, path: &str, payload: &Value) -> Result<Value, Box<dyn Error>> {
+        let resp = check_status(self.execute(method, path, payload).await?).await?;
+        if resp.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok(Value::Null);
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Send a mutating request, record it in the audit log, and return the JSON body
+    async fn mutate(
+        &self,
+        action: &str,
+        task_id: Option<i32>,
+        method: Method,
+        path: &str,
+        payload: Value,
+    ) -> Result<Value, Box<dyn Error>> {
+        let outcome = self.send(method, path, &payload).await;
+        // Replayed traffic never reached a server, so it isn't history
+        if self.cassette.as_ref().is_some_and(Cassette::is_replay) {
+            return outcome;
+        }
+        match &outcome {
+            Ok(body) => {
+                // Newly created tasks only learn their ID from the response (categories have no "type")
+                let id = task_id.or_else(|| {
+                    body.get("type")?;
+                    body.get("id").and_then(Value::as_i64).map(|i| i as i32)
+                });
+                audit::record(action, id, &payload, true, body);
+            }
+            Err(e) => audit::record(action, task_id, &payload, false, &json!({ "error": e.to_string() })),
+        }
+        outcome
+    }
+
+    pub async fn categories(&self) -> Result<Vec<Category>, Box<dyn Error>> {
+        self.get("/categories/").await
+    }
+
+    pub async fn create_category(&self, name: &str, color: &str, icon: Option<&str>) -> Result<Category, Box<dyn Error>> {
+        let mut payload = json!({ "name": name, "color": color });
+        if let Some(icon) = icon {
+            payload["icon"] = json!(icon);
+        }
+        let body = self.mutate("create-category", None, Method::POST, "/categories/", payload).await?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    pub async fn update_category(&self, category_id: i32, payload: Map<String, Value>) -> Result<Category, Box<dyn Error>> {
+        let path = format!("/categories/{}", category_id);
+        let body = self.mutate("update-category", None, Method::PATCH, &path, Value::Object(payload)).await?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    /// All tasks in backend order
+    pub async fn tasks(&self) -> Result<Vec<Task>, Box<dyn Error>> {
+        self.get("/tasks/").await
+    }
+
+    /// All tasks exactly as the backend returns them, for backups
+    pub async fn tasks_json(&self) -> Result<Vec<Value>, Box<dyn Error>> {
+        self.get("/tasks/").await
+    }
+
+    pub async fn task(&self, task_id: i32) -> Result<Task, Box<dyn Error>> {
+        self.get(&format!("/tasks/{}", task_id)).await
+    }
+
+    /// Tasks ordered by start/deadline, then priority
+    pub async fn tasks_ordered(&self) -> Result<Vec<Task>, Box<dyn Error>> {
+        self.get("/taskslist/").await
+    }
+
+    /// Backends that don't send `created_at` get it stamped in local metadata instead
+    pub async fn create_task(&self, action: &str, payload: Map<String, Value>) -> Result<Task, Box<dyn Error>> {
+        let body = self.mutate(action, None, Method::POST, "/tasks/", Value::Object(payload)).await?;
+        let mut task: Task = serde_json::from_value(body)?;
+        if task.created_at.is_none() {
+            let now = crate::stamp::format(chrono::Utc::now().naive_utc());
+            crate::meta::update(task.id, |m| m.created_at = Some(now.clone()))?;
+            task.created_at = Some(now);
+        }
+        crate::search::update(&task)?;
+        Ok(task)
+    }
+
+    pub async fn update_task(&self, task_id: i32, payload: Map<String, Value>) -> Result<Task, Box<dyn Error>> {
+        let path = format!("/tasks/{}", task_id);
+        let body = self.mutate("update-task", Some(task_id), Method::PATCH, &path, Value::Object(payload)).await?;
+        let task = serde_json::from_value(body)?;
+        crate::search::update(&task)?;
+        Ok(task)
+    }
+
+    pub async fn delete_task(&self, task_id: i32) -> Result<(), Box<dyn Error>> {
+        let path = format!("/tasks/{}", task_id);
+        self.mutate("delete-task", Some(task_id), Method::DELETE, &path, Value::Null).await?;
+        crate::search::forget(task_id)
+    }
+
+    pub async fn sync_calendar(&self) -> Result<SyncResult, Box<dyn Error>> {
+        let body = self.mutate("sync-calendar", None, Method::POST, "/calendar/sync", Value::Null).await?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    /// Google Tasks via the backend's Google sign-in; re-imports update earlier copies
+    pub async fn import_google_tasks(&self, list: Option<&str>, completed: bool) -> Result<ImportResult, Box<dyn Error>> {
+        let payload = json!({ "task_list": list, "include_completed": completed });
+        let body = self.mutate("import-google-tasks", None, Method::POST, "/import/google-tasks", payload).await?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    /// User-initiated scheduling run (audited)
+    pub async fn auto_schedule(&self, payload: Value) -> Result<AutoScheduleResult, Box<dyn Error>> {
+        let body = self.mutate("auto-schedule", None, Method::POST, "/auto-schedule/", payload).await?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    /// Background refresh before listing; not worth an audit entry on every `lt`
+    pub async fn request_schedule(&self, payload: &Value) -> Result<AutoScheduleResult, Box<dyn Error>> {
+        Ok(serde_json::from_value(self.send(Method::POST, "/auto-schedule/", payload).await?)?)
+    }
+
+    /// `icons`: put the category's icon before the event title
+    pub async fn push_task(&self, task_id: i32, icons: bool) -> Result<PushTaskResult, Box<dyn Error>> {
+        let path = format!("/calendar/push/{}{}", task_id, if icons { "" } else { "?icons=false" });
+        let body = self.mutate("push-task", Some(task_id), Method::POST, &path, Value::Null).await?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    pub async fn push_all(&self, icons: bool) -> Result<PushAllResult, Box<dyn Error>> {
+        let path = if icons { "/calendar/push-all" } else { "/calendar/push-all?icons=false" };
+        let body = self.mutate("push-all", None, Method::POST, path, Value::Null).await?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    /// Server-side placement explanation; `None` when the backend has no such endpoint
+    pub async fn explain(&self, task_id: i32) -> Result<Option<Value>, Box<dyn Error>> {
+        let resp = self.execute(Method::GET, &format!("/tasks/{}/explain", task_id), &Value::Null).await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND || resp.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(None);
+        }
+        Ok(Some(check_status(resp).await?.json().await?))
+    }
+
+    /// Server-side task history; `None` when the backend has no such endpoint, an [`ApiError`]
+    /// for any other failure
+    pub async fn task_history(&self, task_id: i32) -> Result<Option<Vec<HistoryEvent>>, Box<dyn Error>> {
+        let resp = self.execute(Method::GET, &format!("/tasks/{}/history", task_id), &Value::Null).await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND || resp.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(None);
+        }
+        Ok(Some(check_status(resp).await?.json().await?))
+    }
+}
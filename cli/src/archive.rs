@@ -0,0 +1,29 @@
+use crate::commands::Task;
+use crate::db;
+use crate::meta::TaskMeta;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// A task removed by `todo cleanup --archive`, with its local notes and fields
+#[derive(Serialize, Deserialize)]
+pub struct Record {
+    /// Local time, YYYY-MM-DD HH:MM
+    pub archived_at: String,
+    pub task: Task,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<TaskMeta>,
+}
+
+pub const STORE: &str = "archive";
+
+/// Add one task to the end of the archive
+pub fn append(task: &Task, meta: Option<&TaskMeta>, at: NaiveDateTime) -> Result<(), Box<dyn Error>> {
+    let record = Record { archived_at: at.format("%Y-%m-%d %H:%M").to_string(), task: task.clone(), meta: meta.cloned() };
+    db::append_line(STORE, &serde_json::to_string(&record)?)
+}
+
+/// Everything archived so far, oldest first
+pub fn read_all() -> Result<Vec<Record>, Box<dyn Error>> {
+    db::read_lines(STORE)?.iter().map(|l| Ok(serde_json::from_str(l)?)).collect()
+}
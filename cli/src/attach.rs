@@ -0,0 +1,39 @@
+use crate::meta;
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+fn is_url(s: &str) -> bool {
+    s.split_once("://").is_some_and(|(scheme, _)| !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+'))
+        || s.starts_with("mailto:")
+}
+
+/// URLs are kept as given; files must exist and are stored as absolute paths
+pub fn normalize(target: &str) -> Result<String, Box<dyn Error>> {
+    if is_url(target) {
+        return Ok(target.to_string());
+    }
+    let path = Path::new(target).canonicalize().map_err(|e| format!("{}: {}", target, e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Attach a link or file to a task (skipping duplicates); returns all its attachments
+pub fn add(task_id: i32, target: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let target = normalize(target)?;
+    let updated = meta::update(task_id, |m| {
+        if !m.attachments.contains(&target) {
+            m.attachments.push(target);
+        }
+    })?;
+    Ok(updated.attachments)
+}
+
+/// Hand a URL or file to the desktop's default handler
+pub fn open(target: &str) -> Result<(), Box<dyn Error>> {
+    let (program, args) = crate::platform::Host::current().opener();
+    let status = Command::new(program).args(args).arg(target).status().map_err(|e| format!("Couldn't launch an opener for {}: {}", target, e))?;
+    if !status.success() {
+        return Err(format!("Opener exited with {} for {}", status, target).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+
+/// One line of the append-only audit log
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Entry {
+    pub timestamp: String,
+    pub action: String,
+    pub task_id: Option<i32>,
+    pub payload: Value,
+    pub ok: bool,
+    pub result: Value,
+}
+
+/// The audit log's key in the local database
+pub const STORE: &str = "audit";
+
+/// Append an entry; never rewrites existing lines
+pub fn append(entry: &Entry) -> Result<(), Box<dyn Error>> {
+    crate::db::append_line(STORE, &serde_json::to_string(entry)?)
+}
+
+/// Record a mutation, warning (not failing) if the log can't be written
+pub fn record(action: &str, task_id: Option<i32>, payload: &Value, ok: bool, result: &Value) {
+    let entry = Entry {
+        timestamp: Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+        action: action.to_string(),
+        task_id,
+        payload: payload.clone(),
+        ok,
+        result: result.clone(),
+    };
+    if let Err(e) = append(&entry) {
+        eprintln!("Warning: could not write audit log: {}", e);
+    }
+}
+
+/// All entries, oldest first; unreadable lines are skipped
+pub fn read_all() -> Result<Vec<Entry>, Box<dyn Error>> {
+    Ok(crate::db::read_lines(STORE)?
+        .iter()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+/// Entries touching a single task
+pub fn for_task(task_id: i32) -> Result<Vec<Entry>, Box<dyn Error>> {
+    Ok(read_all()?
+        .into_iter()
+        .filter(|e| e.task_id == Some(task_id))
+        .collect())
+}
+
+/// Short human summary of an entry's payload/result
+pub fn summarize(entry: &Entry) -> String {
+    if !entry.ok {
+        return entry
+            .result
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("failed")
+            .to_string();
+    }
+    match &entry.payload {
+        Value::Object(map) if !map.is_empty() => map
+            .iter()
+            .map(|(k, v)| match v {
+                Value::String(s) => format!("{}={}", k, s),
+                other => format!("{}={}", k, other),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => "-".to_string(),
+    }
+}
+
+/// Timeline wording for a single task's entry ("status → done", "pushed to calendar")
+pub fn describe(entry: &Entry) -> String {
+    if !entry.ok {
+        return format!("{} failed: {}", entry.action, summarize(entry));
+    }
+    match entry.action.as_str() {
+        "create-todo" | "create-event" => "created".to_string(),
+        "delete-task" => "deleted".to_string(),
+        "push-task" => format!(
+            "pushed to calendar as {}",
+            entry.result.get("google_event_id").and_then(Value::as_str).unwrap_or("-")
+        ),
+        "update-task" => match &entry.payload {
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| match v {
+                    Value::String(s) => format!("{} \u{2192} {}", k, s),
+                    other => format!("{} \u{2192} {}", k, other),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => "updated".to_string(),
+        },
+        other => other.to_string(),
+    }
+}
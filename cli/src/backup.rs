@@ -0,0 +1,318 @@
+use crate::api::Api;
+use crate::commands::Category;
+use crate::meta::{self, MetaMap};
+use crate::push::{self, Journal};
+use crate::views::{self, Views};
+use crate::{config, db, store};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use chrono::{Datelike, NaiveDateTime};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Bumped when the layout changes in a way older restores can't read
+pub const FORMAT: u32 = 1;
+
+/// Backend fields that are assigned on creation, or replaced by `category_id`; `external_id`
+/// stays so pushed tasks keep their calendar events instead of getting second ones
+const SERVER_FIELDS: [&str; 5] = ["id", "created_at", "updated_at", "category", "scheduled_for"];
+
+/// The one file inside a `.tar.zst` backup
+const ENTRY: &str = "backup.json";
+
+/// How every zstd frame starts; anything else is read as a plain JSON backup
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Everything needed to rebuild this setup: backend data as the API returned it, plus local stores
+#[derive(Serialize, Deserialize)]
+pub struct Backup {
+    pub format: u32,
+    pub created_at: String,
+    pub categories: Vec<Category>,
+    /// Raw task objects, so fields this CLI doesn't model survive the round trip
+    pub tasks: Vec<Value>,
+    pub meta: MetaMap,
+    pub views: Views,
+    /// The config file as written, comments included
+    pub config: Option<String>,
+    /// Pushes a resumed `push-all` would still retry, by backed-up task ID
+    #[serde(default)]
+    pub push_journal: Journal,
+}
+
+pub async fn create(api: &Api, created_at: &str) -> Result<Backup, Box<dyn Error>> {
+    let config = match config::path().exists() {
+        true => Some(fs::read_to_string(config::path())?),
+        false => None,
+    };
+    Ok(Backup {
+        format: FORMAT,
+        created_at: created_at.to_string(),
+        categories: api.categories().await?,
+        tasks: api.tasks_json().await?,
+        meta: meta::load()?,
+        views: views::load()?,
+        config,
+        push_journal: push::load_journal()?,
+    })
+}
+
+/// Write `b` as a zstd-compressed tar holding `backup.json`
+pub fn write(path: &Path, b: &Backup) -> Result<(), Box<dyn Error>> {
+    let json = serde_json::to_vec_pretty(b)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    let mut archive = tar::Builder::new(zstd::Encoder::new(fs::File::create(path)?, 0)?);
+    archive.append_data(&mut header, ENTRY, json.as_slice())?;
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// `backup.json` out of a `.tar.zst` backup
+fn unpack(bytes: &[u8]) -> Result<Option<String>, Box<dyn Error>> {
+    let mut archive = tar::Archive::new(zstd::Decoder::new(bytes)?);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()? == Path::new(ENTRY) {
+            let mut text = String::new();
+            entry.read_to_string(&mut text)?;
+            return Ok(Some(text));
+        }
+    }
+    Ok(None)
+}
+
+/// A backup written by `write`, or a plain JSON one from before backups were compressed
+pub fn read(path: &Path) -> Result<Backup, Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    let text = match bytes.starts_with(&ZSTD_MAGIC) {
+        true => unpack(&bytes)?.ok_or_else(|| format!("{} has no {} in it", path.display(), ENTRY))?,
+        false => String::from_utf8(bytes).map_err(|_| format!("{} is not a todo backup", path.display()))?,
+    };
+    let b: Backup = serde_json::from_str(&text).map_err(|e| format!("{} is not a todo backup: {}", path.display(), e))?;
+    if b.format > FORMAT {
+        return Err(format!("{} was written by a newer todo (format {}); upgrade to restore it", path.display(), b.format).into());
+    }
+    Ok(b)
+}
+
+/// POST body recreating a backed-up task, with its category pointed at the new ID
+pub fn task_payload(raw: &Value, categories: &BTreeMap<i32, i32>) -> Map<String, Value> {
+    let mut payload: Map<String, Value> = raw
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(k, v)| !v.is_null() && !SERVER_FIELDS.contains(&k.as_str()))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    let old = raw.get("category").and_then(|c| c.get("id")).or_else(|| raw.get("category_id")).and_then(Value::as_i64);
+    match old.and_then(|id| categories.get(&(id as i32))) {
+        Some(new) => payload.insert("category_id".into(), Value::from(*new)),
+        None => payload.remove("category_id"),
+    };
+    payload
+}
+
+/// What a restore did
+#[derive(Default, Debug)]
+pub struct Restored {
+    pub categories: usize,
+    /// The recreated tasks' new IDs and titles
+    pub tasks: Vec<(i32, String)>,
+    pub failed: Vec<String>,
+    /// Where the backed-up config went when one already existed
+    pub config_copy: Option<std::path::PathBuf>,
+}
+
+/// Replay a backup into the backend and local stores; tasks get new IDs, and local metadata
+/// follows them. Refuses a backend that already has tasks unless `force`
+pub async fn restore(api: &Api, b: &Backup, force: bool) -> Result<Restored, Box<dyn Error>> {
+    let existing = api.tasks().await?;
+    if !existing.is_empty() && !force {
+        return Err(format!("The backend already has {} tasks; restore into an empty one, or pass --force to add duplicates", existing.len()).into());
+    }
+    let mut done = Restored::default();
+
+    let current = api.categories().await?;
+    let mut category_ids = BTreeMap::new();
+    for c in &b.categories {
+        let new = match current.iter().find(|x| x.name.eq_ignore_ascii_case(&c.name)) {
+            Some(x) => x.id,
+            None => {
+                done.categories += 1;
+                api.create_category(&c.name, &c.color, c.icon.as_deref()).await?.id
+            }
+        };
+        category_ids.insert(c.id, new);
+    }
+
+    let mut task_ids = BTreeMap::new();
+    for raw in &b.tasks {
+        let old = raw.get("id").and_then(Value::as_i64).unwrap_or_default() as i32;
+        let mut payload = task_payload(raw, &category_ids);
+        // A duplicate can't share the original's calendar event
+        if !existing.is_empty() {
+            payload.remove("external_id");
+        }
+        match api.create_task("restore", payload).await {
+            Ok(t) => {
+                task_ids.insert(old, t.id);
+                done.tasks.push((t.id, t.title));
+            }
+            Err(e) => done.failed.push(format!("[{}] {}: {}", old, raw.get("title").and_then(Value::as_str).unwrap_or("?"), e)),
+        }
+    }
+
+    let mut local = meta::load()?;
+    for (old, m) in &b.meta {
+        if let Some(new) = task_ids.get(old) {
+            local.insert(*new, m.clone());
+        }
+    }
+    meta::save(&local)?;
+    let new_ids = |ids: &[i32]| ids.iter().filter_map(|old| task_ids.get(old).copied()).collect::<Vec<i32>>();
+    let journal = Journal { failed: new_ids(&b.push_journal.failed), not_reached: new_ids(&b.push_journal.not_reached) };
+    if !journal.is_empty() {
+        db::write_doc(push::JOURNAL, &journal)?;
+    }
+    let mut saved = views::load()?;
+    saved.extend(b.views.clone());
+    db::write_doc(views::STORE, &saved)?;
+
+    if let Some(text) = &b.config {
+        let path = config::path();
+        let target = match path.exists() {
+            true if fs::read_to_string(&path)? == *text => None,
+            true => Some(path.with_file_name("config.restored.toml")),
+            false => Some(path.clone()),
+        };
+        if let Some(target) = target {
+            if let Some(dir) = target.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&target, text)?;
+            done.config_copy = (target != path).then_some(target);
+        }
+    }
+    Ok(done)
+}
+
+/// Automatic snapshots kept by `todo backup snapshot`: newest per day for this many days…
+pub const KEEP_DAILY: usize = 7;
+/// …and newest per ISO week for this many weeks
+pub const KEEP_WEEKLY: usize = 4;
+
+const SNAPSHOT_NAME: &str = "%Y-%m-%dT%H%M%S";
+
+pub fn snapshot_dir() -> PathBuf {
+    crate::paths::data_dir().join("backups")
+}
+
+/// Snapshot names (their timestamps), oldest first
+pub fn snapshots() -> Result<Vec<NaiveDateTime>, Box<dyn Error>> {
+    let dir = snapshot_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut found: Vec<NaiveDateTime> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok()?.path().file_stem()?.to_str().map(String::from))
+        .filter_map(|stem| NaiveDateTime::parse_from_str(&stem, SNAPSHOT_NAME).ok())
+        .collect();
+    found.sort();
+    Ok(found)
+}
+
+pub fn snapshot_path(at: NaiveDateTime) -> PathBuf {
+    snapshot_dir().join(format!("{}.json", at.format(SNAPSHOT_NAME)))
+}
+
+/// Which snapshots retention keeps: the newest of each of the last `daily` days that have one,
+/// plus the newest of each of the last `weekly` ISO weeks
+pub fn retained(all: &[NaiveDateTime], daily: usize, weekly: usize) -> BTreeSet<NaiveDateTime> {
+    let mut newest_first = all.to_vec();
+    newest_first.sort_by(|a, b| b.cmp(a));
+    let mut keep = BTreeSet::new();
+    let (mut days, mut weeks) = (BTreeSet::new(), BTreeSet::new());
+    for at in newest_first {
+        let week = (at.iso_week().year(), at.iso_week().week());
+        if days.len() < daily && days.insert(at.date()) {
+            keep.insert(at);
+        }
+        if weeks.len() < weekly && weeks.insert(week) {
+            keep.insert(at);
+        }
+    }
+    keep
+}
+
+/// Save a snapshot (encrypted when the vault is locked) and prune old ones; returns how many were removed
+pub fn snapshot(b: &Backup, at: NaiveDateTime) -> Result<usize, Box<dyn Error>> {
+    store::write_doc(&snapshot_path(at), b)?;
+    let all = snapshots()?;
+    let keep = retained(&all, KEEP_DAILY, KEEP_WEEKLY);
+    let mut removed = 0;
+    for old in all.iter().filter(|s| !keep.contains(s)) {
+        store::remove(&snapshot_path(*old))?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// A backup file, or a snapshot by its name as shown in `todo backup list`
+pub fn open(name: &str) -> Result<Backup, Box<dyn Error>> {
+    let file = Path::new(name);
+    if file.is_file() {
+        return read(file);
+    }
+    let at = NaiveDateTime::parse_from_str(name.trim_end_matches(".json"), SNAPSHOT_NAME)
+        .map_err(|_| format!("'{}' is neither a backup file nor a snapshot name (see `todo backup list`)", name))?;
+    store::read_doc(&snapshot_path(at))?.ok_or_else(|| format!("No snapshot {}", name).into())
+}
+
+/// Fields whose changes are noise when comparing snapshots
+const VOLATILE: [&str; 2] = ["updated_at", "category_id"];
+
+fn title(t: &Value) -> &str {
+    t.get("title").and_then(Value::as_str).unwrap_or("?")
+}
+
+fn shown(v: Option<&Value>) -> String {
+    match v {
+        None | Some(Value::Null) => "-".into(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Object(o)) if o.contains_key("name") => shown(o.get("name")),
+        Some(v) => v.to_string(),
+    }
+}
+
+/// One line per added (+), removed (-) or changed (~) task between two backups, by task ID
+pub fn diff(a: &Backup, b: &Backup) -> Vec<String> {
+    let by_id = |bk: &Backup| -> BTreeMap<i64, Value> { bk.tasks.iter().filter_map(|t| Some((t.get("id")?.as_i64()?, t.clone()))).collect() };
+    let (before, after) = (by_id(a), by_id(b));
+    let mut lines = Vec::new();
+    for (id, old) in &before {
+        let Some(new) = after.get(id) else {
+            lines.push(format!("- [{}] {}", id, title(old)));
+            continue;
+        };
+        let keys: BTreeSet<&String> = old.as_object().into_iter().chain(new.as_object()).flat_map(|o| o.keys()).collect();
+        let changes: Vec<String> = keys
+            .into_iter()
+            .filter(|k| !VOLATILE.contains(&k.as_str()) && old.get(k.as_str()) != new.get(k.as_str()))
+            .map(|k| format!("{} {} → {}", k, shown(old.get(k.as_str())), shown(new.get(k.as_str()))))
+            .collect();
+        if !changes.is_empty() {
+            lines.push(format!("~ [{}] {}: {}", id, title(new), changes.join("; ")));
+        }
+    }
+    for (id, new) in after.iter().filter(|(id, _)| !before.contains_key(id)) {
+        lines.push(format!("+ [{}] {}", id, title(new)));
+    }
+    lines
+}
@@ -0,0 +1,241 @@
+use crate::api::Api;
+use crate::attach;
+use crate::commands::{Commands, ShowAs, Task};
+use crate::db;
+use crate::handlers::{self, EventInput, TaskChanges, TodoInput};
+use crate::meta::{self, MetaMap};
+use crate::rows::{self, TaskRef};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+/// One operation, from a JSON line like {"op": "done", "id": 12}
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum Op {
+    CreateTodo {
+        title: String,
+        estimate: i32,
+        deadline: String,
+        #[serde(default)]
+        priority: i32,
+        description: Option<String>,
+        assignee: Option<String>,
+        #[serde(default)]
+        optional: bool,
+    },
+    CreateEvent {
+        title: String,
+        start: String,
+        end: Option<String>,
+        /// A length instead of an end, as in `--for`
+        #[serde(rename = "for")]
+        length: Option<String>,
+        #[serde(default)]
+        all_day: bool,
+        description: Option<String>,
+        location: Option<String>,
+        #[serde(default)]
+        attendees: Vec<String>,
+        organizer: Option<String>,
+        meeting_url: Option<String>,
+        show_as: Option<ShowAs>,
+        assignee: Option<String>,
+    },
+    Update {
+        id: i32,
+        status: Option<String>,
+        title: Option<String>,
+        priority: Option<i32>,
+        show_as: Option<ShowAs>,
+        waiting_on: Option<String>,
+        assignee: Option<String>,
+        optional: Option<bool>,
+    },
+    Done {
+        id: i32,
+    },
+    Delete {
+        id: i32,
+    },
+    Attach {
+        id: i32,
+        target: String,
+    },
+}
+
+/// A text line is parsed exactly like the command line, minus the program name
+#[derive(Parser)]
+#[command(name = "todo", no_binary_name = true)]
+struct Line {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+fn id(r: Option<TaskRef>) -> Result<i32, String> {
+    let r = r.ok_or("--where isn't supported in a batch; name one task per line")?;
+    rows::resolve(r).map_err(|e| e.to_string())
+}
+
+/// JSON operation or command line → operation; only commands that change tasks are allowed
+pub fn parse(line: &str) -> Result<Op, String> {
+    if line.starts_with('{') {
+        return serde_json::from_str(line).map_err(|e| format!("bad operation: {}", e));
+    }
+    let words = crate::alias::split(line)?;
+    let parsed = Line::try_parse_from(words).map_err(|e| e.render().to_string().lines().next().unwrap_or_default().to_string())?;
+    Ok(match parsed.command {
+        Commands::CreateTodo { title, estimate, deadline, deadline_strict, allow_past, priority, description, assignee, optional } => {
+            let due = match deadline_strict {
+                true => handlers::check_deadline(&deadline, true),
+                false => crate::date_parser::parse_deadline(&deadline).map(|d| d.chosen),
+            };
+            due.and_then(|due| handlers::check_past("deadline", &due, allow_past)).map_err(|e| e.to_string())?;
+            Op::CreateTodo { title, estimate, deadline, priority, description, assignee, optional }
+        }
+        Commands::CreateEvent { title, start, end, length, all_day, description, location, attendees, organizer, meeting_url, show_as, assignee, .. } => {
+            Op::CreateEvent { title, start, end, length, all_day, description, location, attendees, organizer, meeting_url, show_as, assignee }
+        }
+        Commands::UpdateTask { task_id, status, title, priority, show_as, waiting_on, assignee, optional, .. } => {
+            Op::Update { id: id(task_id)?, status, title, priority, show_as, waiting_on, assignee, optional }
+        }
+        Commands::Done { task_id, .. } => Op::Done { id: id(task_id)? },
+        Commands::DeleteTask { task_id, .. } => Op::Delete { id: id(task_id)? },
+        Commands::Attach { task_id, target: Some(target) } => Op::Attach { id: id(Some(task_id))?, target },
+        _ => return Err("only create-todo, create-event, update-task, done, delete-task and attach work in a batch".into()),
+    })
+}
+
+fn task_result(t: &Task) -> Value {
+    json!({ "ok": true, "id": t.id, "title": t.title })
+}
+
+/// Run one operation; local metadata changes go into `meta` and are saved once at the end
+async fn apply(api: &Api, op: Op, meta: &mut MetaMap) -> Result<Value, Box<dyn Error>> {
+    let clear_waiting = |meta: &mut MetaMap, id: i32| {
+        if let Some(m) = meta.get_mut(&id) {
+            (m.waiting_on, m.waiting_since) = (None, None);
+        }
+    };
+    Ok(match op {
+        Op::CreateTodo { title, estimate, deadline, priority, description, assignee, optional } => {
+            task_result(&handlers::create_todo(api, TodoInput { title, estimate, deadline, priority, description, assignee, optional }).await?)
+        }
+        Op::CreateEvent { title, start, end, length, all_day, description, location, attendees, organizer, meeting_url, show_as, assignee } => {
+            let (start, end) = match all_day {
+                true => handlers::all_day_span(&start, end.as_deref())?,
+                false => handlers::timed_span(&start, end.as_deref(), length.as_deref())?,
+            };
+            let input = EventInput { title, start, end, all_day, description, location, attendees, organizer, meeting_url, show_as, assignee };
+            task_result(&handlers::create_event(api, input).await?)
+        }
+        Op::Update { id, status, title, priority, show_as, waiting_on, assignee, optional } => {
+            let status = if waiting_on.is_some() { Some("waiting".to_string()) } else { status };
+            let changed_status = status.is_some();
+            let t = handlers::update_task(api, id, TaskChanges { status, title, priority, show_as, assignee, optional, ..Default::default() }).await?;
+            if let Some(who) = waiting_on {
+                let m = meta.entry(id).or_default();
+                (m.waiting_on, m.waiting_since) = (Some(who), Some(chrono::Local::now().date_naive().format("%Y-%m-%d").to_string()));
+            } else if changed_status {
+                clear_waiting(meta, id);
+            }
+            task_result(&t)
+        }
+        Op::Done { id } => {
+            let t = handlers::update_task(api, id, TaskChanges { status: Some("done".into()), ..Default::default() }).await?;
+            clear_waiting(meta, id);
+            crate::habits::completed(api, &t, chrono::Local::now().date_naive()).await?;
+            task_result(&t)
+        }
+        Op::Delete { id } => {
+            api.delete_task(id).await?;
+            json!({ "ok": true, "id": id })
+        }
+        Op::Attach { id, target } => {
+            let target = attach::normalize(&target)?;
+            let m = meta.entry(id).or_default();
+            if !m.attachments.contains(&target) {
+                m.attachments.push(target);
+            }
+            json!({ "ok": true, "id": id, "attachments": m.attachments })
+        }
+    })
+}
+
+/// How far an interrupted run got: the number of input lines it consumed and a hash of
+/// them, so `--resume` can check it is fed the same input
+#[derive(Serialize, Deserialize, Default, PartialEq, Debug)]
+pub struct Journal {
+    pub lines: usize,
+    pub hash: u64,
+}
+
+pub const JOURNAL: &str = "batch_journal";
+
+pub fn load_journal() -> Result<Option<Journal>, Box<dyn Error>> {
+    db::read_doc(JOURNAL)
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+/// FNV-1a, stable across builds unlike `DefaultHasher`
+fn fnv(hash: u64, line: &str) -> u64 {
+    line.bytes().chain([b'\n']).fold(hash, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Execute every non-blank, non-`#` line of `input`, writing one JSON result per line to
+/// `out`; returns how many failed. After an interrupt (see `interrupt::watch`) the line in hand
+/// finishes and progress is journalled; `resume` skips the lines an interrupted run got through
+pub async fn run(api: &Api, input: impl BufRead, mut out: impl Write, resume: bool) -> Result<usize, Box<dyn Error>> {
+    let skip = match resume {
+        true => load_journal()?.ok_or("Nothing to resume; the last batch wasn't interrupted")?,
+        false => Journal::default(),
+    };
+    let mut all_meta = meta::load()?;
+    let before = all_meta.clone();
+    let mut failed = 0;
+    let mut done = Journal { lines: 0, hash: FNV_OFFSET };
+    let mut lines = input.lines();
+    while done.lines < skip.lines {
+        let line = lines.next().ok_or("The input is shorter than the interrupted batch's; resume with the same input")??;
+        done = Journal { lines: done.lines + 1, hash: fnv(done.hash, &line) };
+    }
+    if done.hash != skip.hash && resume {
+        return Err("The input differs from the interrupted batch's; resume with the same input".into());
+    }
+    for (n, line) in lines.enumerate() {
+        let n = n + skip.lines;
+        if crate::interrupt::requested() {
+            break;
+        }
+        let line = line?;
+        done = Journal { lines: done.lines + 1, hash: fnv(done.hash, &line) };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let result = match parse(line) {
+            Ok(op) => apply(api, op, &mut all_meta).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        let mut value = result.unwrap_or_else(|e| {
+            failed += 1;
+            json!({ "ok": false, "error": e })
+        });
+        value["line"] = json!(n + 1);
+        writeln!(out, "{}", value)?;
+    }
+    if crate::interrupt::requested() {
+        db::write_doc(JOURNAL, &done)?;
+        eprintln!("Interrupted after line {}; rerun with --resume and the same input to continue.", done.lines);
+    } else {
+        db::remove(JOURNAL)?;
+    }
+    if all_meta != before {
+        all_meta.retain(|_, m| *m != meta::TaskMeta::default());
+        meta::save(&all_meta)?;
+    }
+    Ok(failed)
+}
@@ -0,0 +1,219 @@
+use crate::api::Api;
+use crate::commands::Task;
+use crate::display::{humanize_datetime, titled};
+use crate::filter::Filter;
+use crate::handlers::{self, TaskChanges};
+use console::{Key, Term};
+use std::error::Error;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Column {
+    Todo,
+    InProgress,
+    Done,
+}
+
+pub const COLUMNS: [Column; 3] = [Column::Todo, Column::InProgress, Column::Done];
+
+impl Column {
+    /// pending, not-started, later and waiting are all still to do
+    pub fn of(status: Option<&str>) -> Column {
+        match status {
+            Some("in-progress") => Column::InProgress,
+            Some("done") => Column::Done,
+            _ => Column::Todo,
+        }
+    }
+
+    /// The status a card moved into this column gets
+    pub fn status(self) -> &'static str {
+        match self {
+            Column::Todo => "pending",
+            Column::InProgress => "in-progress",
+            Column::Done => "done",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Todo => "TODO",
+            Column::InProgress => "IN PROGRESS",
+            Column::Done => "DONE",
+        }
+    }
+
+    /// The neighbouring column, left (-1) or right (1)
+    pub fn step(self, dir: i32) -> Option<Column> {
+        let i = COLUMNS.iter().position(|c| *c == self)? as i32 + dir;
+        COLUMNS.get(usize::try_from(i).ok()?).copied()
+    }
+}
+
+/// Todos by column; done ones newest first and capped at `done_limit`
+pub fn columns(tasks: &[Task], done_limit: usize) -> [Vec<&Task>; 3] {
+    let mut cols: [Vec<&Task>; 3] = Default::default();
+    for t in tasks.iter().filter(|t| t.kind == "todo") {
+        let i = COLUMNS.iter().position(|c| *c == Column::of(t.status.as_deref())).unwrap_or(0);
+        cols[i].push(t);
+    }
+    cols[0].sort_by(|a, b| a.deadline.cmp(&b.deadline));
+    cols[1].sort_by(|a, b| a.deadline.cmp(&b.deadline));
+    cols[2].sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    cols[2].truncate(done_limit);
+    cols
+}
+
+/// "#3fa34d" → a coloured bar in that colour
+fn swatch(hex: &str) -> Option<String> {
+    let (r, g, b) = crate::color::rgb(hex)?;
+    Some(format!("\x1b[38;2;{};{};{}m▌\x1b[0m", r, g, b))
+}
+
+/// `s` cut or padded to exactly `width` characters
+fn fit(s: &str, width: usize) -> String {
+    let count = s.chars().count();
+    if count > width {
+        let cut: String = s.chars().take(width.saturating_sub(1)).collect();
+        return format!("{}…", cut);
+    }
+    format!("{}{}", s, " ".repeat(width - count))
+}
+
+/// Two lines per card: "▌[12] Pay rent" and "  due Friday at 21:00 · Home"; the selected card
+/// is marked with ">"
+fn card(t: &Task, width: usize, selected: bool, color: bool) -> [String; 2] {
+    let bar = match (selected, color) {
+        (true, _) => ">".to_string(),
+        (false, true) => t.category.as_ref().and_then(|c| swatch(&crate::theme::current().category(&c.color))).unwrap_or_else(|| " ".into()),
+        (false, false) => " ".into(),
+    };
+    let mut about: Vec<String> = t.deadline.as_deref().map(|d| format!("due {}", humanize_datetime(d))).into_iter().collect();
+    about.extend(t.category.as_ref().map(|c| c.name.clone()));
+    if let Some(s) = t.status.as_deref().filter(|s| !matches!(*s, "pending" | "in-progress" | "done")) {
+        about.push(s.to_string());
+    }
+    let inner = width.saturating_sub(1);
+    [format!("{}{}", bar, fit(&format!("[{}] {}", t.id, titled(t)), inner)), format!(" {}", fit(&format!(" {}", about.join(" · ")), inner))]
+}
+
+/// The board as text, columns side by side; `selected` is (column, card)
+pub fn render(cols: &[Vec<&Task>; 3], width: usize, selected: Option<(usize, usize)>, color: bool) -> String {
+    let col_width = (width.saturating_sub(4) / 3).max(16);
+    let mut cells: [Vec<String>; 3] = Default::default();
+    for (i, col) in cols.iter().enumerate() {
+        cells[i].push(fit(&format!("{} ({})", COLUMNS[i].title(), col.len()), col_width));
+        cells[i].push("─".repeat(col_width));
+        for (j, t) in col.iter().enumerate() {
+            cells[i].extend(card(t, col_width, selected == Some((i, j)), color));
+        }
+    }
+    let rows = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let blank = " ".repeat(col_width);
+    (0..rows)
+        .map(|r| {
+            let line: Vec<&str> = cells.iter().map(|c| c.get(r).map_or(blank.as_str(), String::as_str)).collect();
+            format!("{}\n", line.join("  ").trim_end())
+        })
+        .collect()
+}
+
+/// `--accessible`: one line per card under each column's name
+pub fn render_accessible(cols: &[Vec<&Task>; 3]) -> String {
+    let mut out = String::new();
+    for (i, col) in cols.iter().enumerate() {
+        out.push_str(&format!("{}, {} tasks.\n", COLUMNS[i].title().to_lowercase(), col.len()));
+        for t in col {
+            let mut parts = vec![format!("Task {}", t.id), format!("title {}", titled(t))];
+            parts.extend(t.deadline.as_deref().map(|d| format!("due {}", humanize_datetime(d))));
+            parts.extend(t.category.as_ref().map(|c| format!("category {}", c.name)));
+            parts.extend(t.status.clone().map(|s| format!("status {}", s)));
+            out.push_str(&format!("{}\n", parts.join(", ")));
+        }
+    }
+    out
+}
+
+/// "[all]  urgent  work": the board's tabs, one per saved view, the current one bracketed
+pub fn tab_bar(views: &[&str], current: usize) -> String {
+    let names = std::iter::once("all").chain(views.iter().copied());
+    let tabs: Vec<String> = names.enumerate().map(|(i, n)| if i == current { format!("[{}]", n) } else { format!(" {} ", n) }).collect();
+    tabs.join(" ").trim_end().to_string()
+}
+
+const HELP: &str = "←/→ ↑/↓ (or h l k j) select · < > (or H L) move the card · Tab switch view · q quit";
+
+/// Keyboard triage: select a card and move it between columns, which PATCHes its status;
+/// Tab steps through saved views (`todo view save`), showing only the cards each matches
+pub async fn interactive(api: &Api, mut tasks: Vec<Task>, done_limit: usize) -> Result<(), Box<dyn Error>> {
+    let term = Term::stdout();
+    let color = crate::agenda::use_color();
+    // A view saved before a filter change may no longer parse; it just gets no tab
+    let views: Vec<(String, Filter)> = crate::views::load()?.into_iter().filter_map(|(name, expr)| Some((name, expr.parse().ok()?))).collect();
+    let names: Vec<&str> = views.iter().map(|(name, _)| name.as_str()).collect();
+    let meta = crate::meta::load()?;
+    let now = crate::status::now();
+    let shown = |tasks: &[Task], tab: usize| match tab.checked_sub(1).and_then(|i| views.get(i)) {
+        Some((_, f)) => f.select(tasks.to_vec(), &meta, now),
+        None => tasks.to_vec(),
+    };
+    let (mut col, mut row, mut tab) = (0usize, 0usize, 0usize);
+    let mut note = String::new();
+    term.hide_cursor()?;
+    let result: Result<(), Box<dyn Error>> = async {
+        loop {
+            let visible = shown(&tasks, tab);
+            let cols = columns(&visible, done_limit);
+            row = row.min(cols[col].len().saturating_sub(1));
+            term.clear_screen()?;
+            let width = term.size().1 as usize;
+            let selected = (!cols[col].is_empty()).then_some((col, row));
+            if !names.is_empty() {
+                term.write_line(&format!("{}\n", tab_bar(&names, tab)))?;
+            }
+            term.write_str(&render(&cols, width, selected, color))?;
+            term.write_line(&format!("\n{}\n{}", HELP, note))?;
+            let dir = match term.read_key()? {
+                Key::Tab => {
+                    tab = (tab + 1) % (names.len() + 1);
+                    continue;
+                }
+                Key::BackTab => {
+                    tab = (tab + names.len()) % (names.len() + 1);
+                    continue;
+                }
+                Key::ArrowLeft | Key::Char('h') => {
+                    col = col.saturating_sub(1);
+                    continue;
+                }
+                Key::ArrowRight | Key::Char('l') => {
+                    col = (col + 1).min(COLUMNS.len() - 1);
+                    continue;
+                }
+                Key::ArrowUp | Key::Char('k') => {
+                    row = row.saturating_sub(1);
+                    continue;
+                }
+                Key::ArrowDown | Key::Char('j') => {
+                    row += 1;
+                    continue;
+                }
+                Key::Char('<') | Key::Char('H') => -1,
+                Key::Char('>') | Key::Char('L') => 1,
+                Key::Char('q') | Key::Escape => return Ok(()),
+                _ => continue,
+            };
+            let (Some(t), Some(to)) = (cols[col].get(row), COLUMNS[col].step(dir)) else { continue };
+            let id = t.id;
+            let updated = handlers::update_task(api, id, TaskChanges { status: Some(to.status().into()), ..Default::default() }).await?;
+            note = format!("Moved [{}] {} to {}", updated.id, updated.title, to.title().to_lowercase());
+            if let Some(t) = tasks.iter_mut().find(|t| t.id == id) {
+                *t = updated;
+            }
+            col = COLUMNS.iter().position(|c| *c == to).unwrap_or(col);
+            row = columns(&shown(&tasks, tab), done_limit)[col].iter().position(|t| t.id == id).unwrap_or(0);
+        }
+    }
+    .await;
+    term.show_cursor()?;
+    result
+}
@@ -0,0 +1,96 @@
+use crate::commands::Task;
+use crate::display::{format_minutes, titled};
+use crate::slots::{self, Interval};
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+
+/// How many open todos the briefing singles out
+pub const TOP: usize = 3;
+
+/// Everything worth knowing at the start of a day
+#[derive(Default)]
+pub struct Briefing {
+    /// Today's events, all-day ones first
+    pub events: Vec<Task>,
+    /// Today's todo blocks that aren't done yet
+    pub blocks: Vec<Task>,
+    /// Highest-priority open todos, soonest deadline breaking ties
+    pub top: Vec<Task>,
+    /// Open todos past their deadline, oldest first
+    pub overdue: Vec<Task>,
+    /// Due today with work not yet placed before the deadline: (task, unplaced minutes)
+    pub at_risk: Vec<(Task, i32)>,
+}
+
+fn open(t: &Task) -> bool {
+    !matches!(t.status.as_deref(), Some("done" | "later"))
+}
+
+fn parse(s: Option<&String>) -> Option<NaiveDateTime> {
+    s.and_then(|s| slots::parse_datetime(s))
+}
+
+/// The briefing for `now`'s day; tasks assigned to someone other than `me` are left out
+pub fn build(tasks: &[Task], me: Option<&str>, now: NaiveDateTime) -> Briefing {
+    let midnight = now.date().and_time(NaiveTime::MIN);
+    let today = Interval::new(midnight, midnight + Duration::days(1));
+    let mine: Vec<&Task> = tasks.iter().filter(|t| !t.belongs_to_other(me)).collect();
+    let span = |t: &Task| Some(Interval::new(parse(t.start_time.as_ref())?, parse(t.end_time.as_ref())?));
+    let on_today = |t: &Task| span(t).is_some_and(|s| s.start < today.end && s.end > today.start);
+    let todos = || mine.iter().copied().filter(|t| t.kind == "todo" && open(t));
+
+    let mut events: Vec<Task> = mine.iter().copied().filter(|t| t.kind == "event" && on_today(t)).cloned().collect();
+    events.sort_by_key(|t| (!t.is_all_day(), span(t).map(|s| s.start)));
+    let mut blocks: Vec<Task> = todos().filter(|t| on_today(t)).cloned().collect();
+    blocks.sort_by_key(|t| span(t).map(|s| s.start));
+
+    let mut top: Vec<Task> = todos().filter(|t| t.status.as_deref() != Some("waiting")).cloned().collect();
+    top.sort_by_key(|t| (-t.priority.unwrap_or(0), parse(t.deadline.as_ref()).is_none(), parse(t.deadline.as_ref())));
+    top.truncate(TOP);
+
+    let mut overdue: Vec<Task> = todos().filter(|t| parse(t.deadline.as_ref()).is_some_and(|d| d < now)).cloned().collect();
+    overdue.sort_by_key(|t| parse(t.deadline.as_ref()));
+
+    let at_risk = todos()
+        .filter_map(|t| {
+            let deadline = parse(t.deadline.as_ref()).filter(|d| *d >= now && d.date() == now.date())?;
+            let placed = span(t).is_some_and(|s| s.end <= deadline);
+            let minutes = t.estimate.unwrap_or(0);
+            (!placed && minutes > 0).then(|| (t.clone(), minutes))
+        })
+        .collect();
+    Briefing { events, blocks, top, overdue, at_risk }
+}
+
+fn when(t: &Task) -> String {
+    match (parse(t.start_time.as_ref()), parse(t.end_time.as_ref())) {
+        _ if t.is_all_day() => "all day".into(),
+        (Some(s), Some(e)) => format!("{}-{}", s.format("%H:%M"), e.format("%H:%M")),
+        _ => "-".into(),
+    }
+}
+
+/// Plain text, one section per heading; empty sections are skipped. Each task is followed by
+/// its `link` in the web UI, when there is one
+pub fn render(b: &Briefing, now: NaiveDateTime, link: impl Fn(i32) -> Option<String>) -> String {
+    let mut out = format!("Good morning — {}\n", now.format("%A %Y-%m-%d"));
+    let mut section = |heading: &str, lines: Vec<(i32, String)>| {
+        if !lines.is_empty() {
+            out.push_str(&format!("\n{}\n", heading));
+            for (id, l) in lines {
+                out.push_str(&format!("  {}\n", l));
+                if let Some(url) = link(id) {
+                    out.push_str(&format!("    {}\n", url));
+                }
+            }
+        }
+    };
+    section("Warnings", b.at_risk.iter().map(|(t, m)| (t.id, format!("! [{}] {} is due today with {} not scheduled before its deadline", t.id, titled(t), format_minutes(*m)))).collect());
+    section("Overdue", b.overdue.iter().map(|t| (t.id, format!("[{}] {} (due {})", t.id, titled(t), t.deadline.as_deref().unwrap_or_default()))).collect());
+    section("Events", b.events.iter().map(|t| (t.id, format!("{:<11} {}", when(t), titled(t)))).collect());
+    section("Scheduled", b.blocks.iter().map(|t| (t.id, format!("{:<11} [{}] {}", when(t), t.id, titled(t)))).collect());
+    section("Top priorities", b.top.iter().map(|t| (t.id, format!("[{}] {} (priority {})", t.id, titled(t), t.priority.unwrap_or(0)))).collect());
+    if b.events.is_empty() && b.blocks.is_empty() && b.top.is_empty() && b.overdue.is_empty() {
+        out.push_str("\nNothing on today.\n");
+    }
+    out
+}
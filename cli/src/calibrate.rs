@@ -0,0 +1,72 @@
+use crate::commands::Task;
+use crate::meta::MetaMap;
+use std::collections::BTreeMap;
+
+/// Categories with fewer finished, timed tasks than this keep their estimates as they are
+pub const MIN_SAMPLES: usize = 3;
+
+/// Bucket for tasks without a category
+pub const UNCATEGORIZED: &str = "(none)";
+
+/// How one category's actuals compare with its estimates
+#[derive(Debug, PartialEq)]
+pub struct Bias {
+    pub samples: usize,
+    pub estimated: i32,
+    pub actual: i32,
+}
+
+impl Bias {
+    /// Actual time per estimated minute: 1.6 means tasks take 1.6× their estimate
+    pub fn ratio(&self) -> f64 {
+        self.actual as f64 / self.estimated.max(1) as f64
+    }
+}
+
+fn category(t: &Task) -> String {
+    t.category.as_ref().map(|c| c.name.clone()).unwrap_or_else(|| UNCATEGORIZED.into())
+}
+
+/// Per-category totals over done todos with both an estimate and a recorded actual
+pub fn bias(tasks: &[Task], meta: &MetaMap) -> BTreeMap<String, Bias> {
+    let mut out: BTreeMap<String, Bias> = BTreeMap::new();
+    for t in tasks.iter().filter(|t| t.kind == "todo" && t.status.as_deref() == Some("done")) {
+        let (Some(estimate), Some(actual)) = (t.estimate.filter(|e| *e > 0), meta.get(&t.id).and_then(|m| m.actual_minutes)) else {
+            continue;
+        };
+        let b = out.entry(category(t)).or_insert(Bias { samples: 0, estimated: 0, actual: 0 });
+        b.samples += 1;
+        b.estimated = b.estimated.saturating_add(estimate);
+        b.actual = b.actual.saturating_add(actual);
+    }
+    out
+}
+
+/// "coding tasks take 1.6× your estimate"
+pub fn describe(name: &str, b: &Bias) -> String {
+    let who = match name {
+        UNCATEGORIZED => "Uncategorized tasks".to_string(),
+        _ => format!("{} tasks", name),
+    };
+    match b.ratio() {
+        r if (r - 1.0).abs() < 0.05 => format!("{} take about as long as you estimate", who),
+        r => format!("{} take {:.1}× your estimate", who, r),
+    }
+}
+
+/// Copies of `tasks` with open todos' estimates scaled by their category's ratio, for planning
+pub fn apply(tasks: &[Task], meta: &MetaMap) -> Vec<Task> {
+    let ratios: BTreeMap<String, f64> = bias(tasks, meta).into_iter().filter(|(_, b)| b.samples >= MIN_SAMPLES).map(|(k, b)| (k, b.ratio())).collect();
+    tasks
+        .iter()
+        .cloned()
+        .map(|mut t| {
+            if t.kind == "todo" && t.status.as_deref() != Some("done") {
+                if let (Some(est), Some(r)) = (t.estimate, ratios.get(&category(&t))) {
+                    t.estimate = Some((est as f64 * r).round() as i32);
+                }
+            }
+            t
+        })
+        .collect()
+}
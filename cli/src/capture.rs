@@ -0,0 +1,98 @@
+use regex::Regex;
+use std::error::Error;
+use std::process::Command;
+use std::time::Duration;
+
+/// Longest title we'll make from free text before cutting it short
+const MAX_TITLE: usize = 80;
+
+/// Title and description for a new todo
+#[derive(Debug, PartialEq)]
+pub struct Captured {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// Clipboard text via whichever platform tool is installed
+pub fn read_clipboard() -> Result<String, Box<dyn Error>> {
+    let tools: [(&str, &[&str]); 5] = [
+        ("pbpaste", &[]),
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-selection", "clipboard", "-o"]),
+        ("xsel", &["--clipboard", "--output"]),
+        ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+    ];
+    for (tool, args) in tools {
+        if let Ok(out) = Command::new(tool).args(args).output() {
+            if out.status.success() {
+                let text = String::from_utf8_lossy(&out.stdout).trim().to_string();
+                if text.is_empty() {
+                    return Err("Clipboard is empty".into());
+                }
+                return Ok(text);
+            }
+        }
+    }
+    Err("No clipboard tool found (tried pbpaste, wl-paste, xclip, xsel, powershell)".into())
+}
+
+pub fn find_url(text: &str) -> Option<&str> {
+    let re = Regex::new(r"https?://[^\s<>]+").ok()?;
+    re.find(text).map(|m| m.as_str().trim_end_matches(['.', ',', ')', ']']))
+}
+
+/// The page's `<title>`, if it can be fetched quickly
+pub async fn page_title(client: &reqwest::Client, url: &str) -> Option<String> {
+    let resp = client.get(url).timeout(Duration::from_secs(5)).send().await.ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    let body = resp.text().await.ok()?;
+    let re = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").ok()?;
+    let raw = re.captures(&body)?.get(1)?.as_str();
+    let title = decode_entities(&raw.split_whitespace().collect::<Vec<_>>().join(" "));
+    (!title.is_empty()).then_some(title)
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn shorten(s: &str) -> String {
+    if s.chars().count() <= MAX_TITLE {
+        return s.to_string();
+    }
+    let cut: String = s.chars().take(MAX_TITLE - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Links become "<page title>" with the full text as description; other text uses its first line
+pub async fn prepare(client: &reqwest::Client, text: &str, fetch: bool) -> Captured {
+    let text = text.trim();
+    if let Some(url) = find_url(text) {
+        let title = match fetch {
+            true => page_title(client, url).await,
+            false => None,
+        };
+        let rest = text.replace(url, "").split_whitespace().collect::<Vec<_>>().join(" ");
+        let rest = rest.trim_matches(|c: char| c.is_ascii_punctuation() || c == ' ');
+        let title = title
+            .or_else(|| Some(rest.to_string()).filter(|r| !r.is_empty()))
+            .unwrap_or_else(|| url.to_string());
+        return Captured { title: shorten(&title), description: Some(text.to_string()) };
+    }
+    let (first, rest) = text.split_once('\n').unwrap_or((text, ""));
+    let rest = rest.trim();
+    let description = if first.chars().count() > MAX_TITLE {
+        Some(text.to_string())
+    } else {
+        Some(rest.to_string()).filter(|r| !r.is_empty())
+    };
+    Captured { title: shorten(first.trim()), description }
+}
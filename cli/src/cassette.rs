@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// One recorded request/response pair
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Interaction {
+    pub method: String,
+    /// Path plus query, relative to the API base URL
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub request: Value,
+    pub status: u16,
+    /// JSON bodies are stored as-is so cassettes stay readable in bug reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl Interaction {
+    pub fn body_bytes(&self) -> Vec<u8> {
+        match (&self.json, &self.text) {
+            (Some(v), _) => serde_json::to_vec(v).unwrap_or_default(),
+            (None, Some(t)) => t.clone().into_bytes(),
+            (None, None) => Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct CassetteFile {
+    pub interactions: Vec<Interaction>,
+}
+
+pub enum Cassette {
+    /// Append every live interaction and rewrite the file after each one
+    Record { path: PathBuf, tape: Mutex<Vec<Interaction>> },
+    /// Serve responses from the file; nothing touches the network
+    Replay { tape: Mutex<Vec<(bool, Interaction)>> },
+}
+
+impl Cassette {
+    pub fn record(path: &Path) -> Self {
+        Cassette::Record { path: path.to_path_buf(), tape: Mutex::new(Vec::new()) }
+    }
+
+    pub fn replay(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let file: CassetteFile = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| format!("Invalid cassette {}: {}", path.display(), e))?;
+        let tape = file.interactions.into_iter().map(|i| (false, i)).collect();
+        Ok(Cassette::Replay { tape: Mutex::new(tape) })
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self, Cassette::Replay { .. })
+    }
+
+    /// Store a live interaction (no-op when replaying)
+    pub fn save(&self, interaction: Interaction) -> Result<(), Box<dyn Error>> {
+        if let Cassette::Record { path, tape } = self {
+            let mut tape = tape.lock().map_err(|_| "cassette lock poisoned")?;
+            tape.push(interaction);
+            let file = CassetteFile { interactions: tape.clone() };
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        }
+        Ok(())
+    }
+
+    /// Next unused interaction for this method and path, in recorded order
+    pub fn next(&self, method: &str, path: &str) -> Result<Interaction, Box<dyn Error>> {
+        let Cassette::Replay { tape } = self else {
+            return Err("cassette is not in replay mode".into());
+        };
+        let mut tape = tape.lock().map_err(|_| "cassette lock poisoned")?;
+        let slot = tape
+            .iter_mut()
+            .find(|(used, i)| !*used && i.method == method && i.path == path)
+            .ok_or_else(|| format!("No recorded response left for {} {}", method, path))?;
+        slot.0 = true;
+        Ok(slot.1.clone())
+    }
+}
+
+/// Build a reqwest response from a recorded interaction
+pub fn to_response(i: &Interaction) -> Result<reqwest::Response, Box<dyn Error>> {
+    let mut builder = http::Response::builder().status(i.status);
+    if i.json.is_some() {
+        builder = builder.header("content-type", "application/json");
+    }
+    Ok(reqwest::Response::from(builder.body(i.body_bytes())?))
+}
@@ -0,0 +1,83 @@
+/// Colors `--color` accepts by name
+const NAMED: [(&str, &str); 18] = [
+    ("black", "#000000"),
+    ("blue", "#4E79A7"),
+    ("brown", "#9C755F"),
+    ("cyan", "#00BCD4"),
+    ("gray", "#BAB0AC"),
+    ("green", "#59A14F"),
+    ("grey", "#BAB0AC"),
+    ("lime", "#8BC34A"),
+    ("magenta", "#D81B60"),
+    ("maroon", "#800000"),
+    ("navy", "#1F3A68"),
+    ("olive", "#808000"),
+    ("orange", "#F28E2B"),
+    ("pink", "#FF9DA7"),
+    ("purple", "#B07AA1"),
+    ("red", "#E15759"),
+    ("teal", "#76B7B2"),
+    ("yellow", "#EDC948"),
+];
+
+/// What `--color auto` picks from, in order of preference
+pub const PALETTE: [&str; 12] = [
+    "#4E79A7", "#F28E2B", "#E15759", "#76B7B2", "#59A14F", "#EDC948", "#B07AA1", "#FF9DA7", "#9C755F", "#BAB0AC", "#1F3A68", "#8BC34A",
+];
+
+/// "#RRGGBB" from "#RRGGBB", "#RGB" (with or without the "#") or a name like "teal"
+pub fn parse(input: &str) -> Result<String, String> {
+    let s = input.trim();
+    if let Some((_, hex)) = NAMED.iter().find(|(n, _)| n.eq_ignore_ascii_case(s)) {
+        return Ok(hex.to_string());
+    }
+    let digits = s.strip_prefix('#').unwrap_or(s);
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{}' isn't a color: use #RRGGBB, #RGB, a name like teal, or auto", input));
+    }
+    match digits.len() {
+        6 => Ok(format!("#{}", digits.to_uppercase())),
+        3 => Ok(format!("#{}", digits.chars().flat_map(|c| [c, c]).collect::<String>().to_uppercase())),
+        _ => Err(format!("'{}' isn't a color: use #RRGGBB, #RGB, a name like teal, or auto", input)),
+    }
+}
+
+/// Red, green and blue of a color `parse` accepts
+pub fn rgb(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = parse(color).ok()?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(1)?, channel(3)?, channel(5)?))
+}
+
+/// Roughly how different two colors look ("redmean" weighted RGB distance)
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let mean = (a.0 as f64 + b.0 as f64) / 2.0;
+    let (dr, dg, db) = (a.0 as f64 - b.0 as f64, a.1 as f64 - b.1 as f64, a.2 as f64 - b.2 as f64);
+    ((2.0 + mean / 256.0) * dr * dr + 4.0 * dg * dg + (2.0 + (255.0 - mean) / 256.0) * db * db).sqrt()
+}
+
+/// The entry of `palette` that looks most like `color`; unreadable colors keep the first entry
+pub fn nearest(color: &str, palette: &[&str]) -> String {
+    let Some(c) = rgb(color) else {
+        return palette.first().map_or_else(|| color.to_string(), |p| p.to_string());
+    };
+    let apart = |p: &&&str| distance(rgb(p).unwrap_or_default(), c);
+    palette.iter().min_by(|a, b| apart(a).total_cmp(&apart(b))).map_or_else(|| color.to_string(), |p| p.to_string())
+}
+
+/// The palette color farthest from all of `existing`; unreadable existing colors are ignored
+pub fn distinct(existing: &[String]) -> String {
+    let taken: Vec<(u8, u8, u8)> = existing.iter().filter_map(|c| rgb(c)).collect();
+    let spread = |c: &&str| taken.iter().map(|t| distance(rgb(c).unwrap_or_default(), *t)).fold(f64::MAX, f64::min);
+    // Earlier palette entries win ties, so an empty list gets the first one
+    let best = PALETTE.iter().rev().max_by(|a, b| spread(a).total_cmp(&spread(b)));
+    best.unwrap_or(&PALETTE[0]).to_string()
+}
+
+/// Two cells painted in `color` with a truecolor background; empty for an unreadable color
+pub fn swatch(color: &str) -> String {
+    match rgb(color) {
+        Some((r, g, b)) => format!("\x1b[48;2;{};{};{}m  \x1b[0m", r, g, b),
+        None => String::new(),
+    }
+}
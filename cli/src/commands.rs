@@ -1,30 +1,113 @@
 use clap::{Subcommand, ValueEnum};
-use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use crate::config::Block;
+use crate::filter::Filter;
+use crate::rows::TaskRef;
+use crate::sort::SortSpec;
+use serde::{Deserialize, Serialize};
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Interactive first-run setup: API URL, availability, completions
+    Init {
+        #[arg(long)]
+        api_url: Option<String>,
+    },
+
     #[command(alias = "lc")]
     ListCategories,
 
     #[command(alias = "cc")]
     CreateCategory {
         name: String,
+        /// #RRGGBB, #RGB, a name like teal, or auto for one that stands out from the existing categories
         #[arg(short, long, default_value = "#CCCCCC")]
         color: String,
+        /// Emoji shown before the titles of its tasks, e.g. 📚
+        #[arg(short, long)]
+        icon: Option<String>,
+    },
+
+    /// Rename a category or change its color or icon
+    UpdateCategory {
+        /// ID or name
+        category: String,
+        #[arg(short, long)]
+        name: Option<String>,
+        /// #RRGGBB, #RGB, a name like teal, or auto
+        #[arg(short, long)]
+        color: Option<String>,
+        /// Emoji shown before the titles of its tasks; "" removes it
+        #[arg(short, long)]
+        icon: Option<String>,
     },
 
     #[command(alias = "lt")]
-    ListTasks,
+    ListTasks {
+        /// Only tasks in these contexts, e.g. @errands
+        contexts: Vec<String>,
+        /// Only tasks matching a filter, e.g. 'status!=done AND due<eow'
+        #[arg(long = "where", value_name = "FILTER")]
+        filter: Option<Filter>,
+        /// Extra columns for custom fields, e.g. +effort_points,+client
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        columns: Vec<String>,
+        /// Only tasks assigned to this person, "me" or "unassigned"
+        #[arg(long, value_name = "WHO")]
+        assignee: Option<String>,
+        /// `alfred` prints script-filter JSON for Alfred and Raycast extensions
+        #[arg(long, value_enum, default_value = "table")]
+        format: ListFormat,
+        /// Keys to sort by in turn, e.g. `deadline,priority:desc,title`; also created, due, estimate, id
+        #[arg(long, default_value = "due")]
+        sort: SortSpec,
+        /// Only tasks created on or after this day, e.g. "last monday"
+        #[arg(long, value_name = "WHEN")]
+        created_since: Option<String>,
+    },
 
     #[command(alias = "ce")]
     CreateEvent {
         title: String,
+        /// Start time, e.g. 2030-05-06T15:00 or "tomorrow 3pm"; or the first day with --all-day
         #[arg(short = 's', long)]
         start: String,
-        #[arg(short = 'e', long)]
-        end: String,
+        /// End time, or the last day with --all-day (default: same day); a bare HH:MM before the start means the next day
+        #[arg(short = 'e', long, required_unless_present_any = ["all_day", "length"])]
+        end: Option<String>,
+        /// How long instead of an end, e.g. 2h, 45m or 1h30m
+        #[arg(long = "for", value_name = "DURATION", conflicts_with_all = ["end", "all_day"])]
+        length: Option<String>,
+        /// Whole days: birthdays, holidays, conference days
+        #[arg(long)]
+        all_day: bool,
         #[arg(short = 'd', long)]
         description: Option<String>,
+        #[arg(short = 'l', long)]
+        location: Option<String>,
+        /// Attendee email address (repeatable)
+        #[arg(short = 'a', long = "attendee", value_name = "EMAIL")]
+        attendees: Vec<String>,
+        #[arg(long, value_name = "EMAIL")]
+        organizer: Option<String>,
+        #[arg(long, value_name = "URL")]
+        meeting_url: Option<String>,
+        /// Only busy events block scheduling and conflict checks
+        #[arg(long, value_enum, value_name = "AVAILABILITY")]
+        show_as: Option<ShowAs>,
+        /// Whose event it is: a name or "me"
+        #[arg(long, value_name = "WHO")]
+        assignee: Option<String>,
+        /// Create the event even if it overlaps existing events or scheduled todos
+        #[arg(long)]
+        force: bool,
+        /// On conflict, print the nearest free slots of the same length
+        #[arg(long)]
+        suggest: bool,
+        /// Create the event even if it's already in the past (only warn)
+        #[arg(long)]
+        allow_past: bool,
     },
 
     #[command(alias = "ct")]
@@ -34,73 +117,845 @@ pub enum Commands {
         estimate: i32,
         #[arg(short = 'd', long)]
         deadline: String,
+        /// Fail instead of guessing when the deadline could mean more than one day
+        #[arg(long)]
+        deadline_strict: bool,
+        /// Create it even though it's already in the past (only warn)
+        #[arg(long)]
+        allow_past: bool,
         #[arg(short = 'p', long, default_value_t = 0)]
         priority: i32,
         #[arg(short = 'D', long)]
         description: Option<String>,
+        /// Who does it: a name or "me"
+        #[arg(long, value_name = "WHO")]
+        assignee: Option<String>,
+        /// Nice to have: scheduled last, only into slack, and the first to give way
+        #[arg(long)]
+        optional: bool,
+    },
+
+    /// Turn a link or a snippet into a todo; links are titled from the page's <title>
+    #[command(alias = "add")]
+    Capture {
+        /// Text or URL (omit with --from-clipboard)
+        #[arg(required_unless_present = "from_clipboard")]
+        text: Option<String>,
+        #[arg(long, conflicts_with = "text")]
+        from_clipboard: bool,
+        /// Use this title instead of a derived one
+        #[arg(short = 't', long)]
+        title: Option<String>,
+        #[arg(short = 'e', long, default_value_t = 30)]
+        estimate: i32,
+        #[arg(short = 'd', long, default_value = "next week")]
+        deadline: String,
+        /// Fail instead of guessing when the deadline could mean more than one day
+        #[arg(long)]
+        deadline_strict: bool,
+        /// Create it even though it's already in the past (only warn)
+        #[arg(long)]
+        allow_past: bool,
+        #[arg(short = 'p', long, default_value_t = 0)]
+        priority: i32,
+        /// Don't fetch the linked page
+        #[arg(long)]
+        no_fetch: bool,
+    },
+
+    /// Every field of one task, plus its local metadata
+    Show {
+        task_id: TaskRef,
+        /// Print the event as a QR code instead, for a phone to scan into its calendar
+        #[arg(long)]
+        qr: bool,
     },
 
     #[command(alias = "ut")]
     UpdateTask {
-        task_id: i32,
+        #[arg(required_unless_present = "filter")]
+        task_id: Option<TaskRef>,
+        /// Update every task matching a filter instead of one
+        #[arg(long = "where", value_name = "FILTER", conflicts_with = "task_id")]
+        filter: Option<Filter>,
+        /// Required when more tasks than `bulk.confirm_above` match: the exact number
+        #[arg(long, value_name = "N", requires = "filter")]
+        confirm_count: Option<usize>,
         #[arg(short = 's', long)]
         status: Option<String>,
         #[arg(short = 't', long)]
         title: Option<String>,
         #[arg(short = 'p', long)]
         priority: Option<i32>,
+        /// Hand the task off: sets the status to "waiting" and notes who it's waiting on
+        #[arg(long, value_name = "WHO", conflicts_with = "status")]
+        waiting_on: Option<String>,
+        /// For events: busy, free or tentative
+        #[arg(long, value_enum, value_name = "AVAILABILITY")]
+        show_as: Option<ShowAs>,
+        /// Hand the task to someone: a name, "me" or "unassigned"
+        #[arg(long, value_name = "WHO")]
+        assignee: Option<String>,
+        /// Mark the todo nice-to-have (true) or required again (false)
+        #[arg(long, value_name = "BOOL")]
+        optional: Option<bool>,
+    },
+
+    /// Set a custom field on a task, e.g. `todo set-field 12 effort_points 5`; leave out the value to clear it
+    SetField {
+        task_id: TaskRef,
+        name: String,
+        value: Option<String>,
+    },
+
+    /// Add a timestamped note to a task, e.g. `todo comment 12 "waiting on the quote"`; without text, print its notes
+    Comment {
+        task_id: TaskRef,
+        text: Option<String>,
+    },
+
+    /// Mark a task done
+    Done {
+        #[arg(required_unless_present = "filter")]
+        task_id: Option<TaskRef>,
+        /// Finish every task matching a filter
+        #[arg(long = "where", value_name = "FILTER", conflicts_with = "task_id")]
+        filter: Option<Filter>,
+        #[arg(long, value_name = "N", requires = "filter")]
+        confirm_count: Option<usize>,
+        /// How long it really took (2h, 45m, 1h30m), for `todo calibrate`
+        #[arg(long, value_name = "DURATION", conflicts_with = "filter")]
+        took: Option<String>,
+    },
+
+    /// Log time spent on a todo, e.g. `todo progress 42 --done 1h`; scheduling uses what's left
+    Progress {
+        task_id: TaskRef,
+        /// Time spent (2h, 45m, 1h30m)
+        #[arg(long, value_name = "DURATION")]
+        done: String,
     },
 
     #[command(alias = "dt")]
     DeleteTask {
-        task_id: i32,
+        #[arg(required_unless_present = "filter")]
+        task_id: Option<TaskRef>,
+        /// Delete every task matching a filter
+        #[arg(long = "where", value_name = "FILTER", conflicts_with = "task_id")]
+        filter: Option<Filter>,
+        #[arg(long, value_name = "N", requires = "filter")]
+        confirm_count: Option<usize>,
+        /// Don't ask before deleting
+        #[arg(short = 'y', long)]
+        yes: bool,
     },
 
     #[command(alias = "sc")]
     SyncCalendar,
 
+    /// Bring in todos kept in another app
+    #[command(subcommand)]
+    Import(ImportCommand),
+
     #[command(alias = "as")]
     AutoSchedule {
-        #[arg(short = 'c', long, value_name = "FILE")]
+        #[arg(short = 'c', long, value_name = "FILE", conflicts_with = "local")]
         config: Option<String>,
+        /// Plan with the local engine and write placements back task by task
+        #[arg(long)]
+        local: bool,
+        /// Show the local plan without updating any task
+        #[arg(long, requires = "local")]
+        dry_run: bool,
+        /// Keep existing placements and only place new or changed tasks
+        #[arg(long)]
+        incremental: bool,
+        /// How firmly to keep placements, 0-1 (implies --incremental; default from config)
+        #[arg(long, value_name = "0-1")]
+        stability: Option<f64>,
     },
 
     #[command(alias = "pt")]
     PushTask {
-        task_id: i32,
+        task_id: TaskRef,
     },
 
     #[command(alias = "pa")]
-    PushAll,
+    PushAll {
+        /// Push task by task from the CLI instead of the backend's bulk endpoint
+        #[arg(long)]
+        client_side: bool,
+        /// Parallel requests in client-side mode
+        #[arg(short = 'j', long, default_value_t = 4)]
+        concurrency: usize,
+        /// Retry only the tasks that failed or weren't reached last time (implies --client-side)
+        #[arg(long)]
+        resume: bool,
+        /// Push only tasks matching a filter (implies --client-side)
+        #[arg(long = "where", value_name = "FILTER", conflicts_with = "resume")]
+        filter: Option<Filter>,
+    },
+
+    /// Show why a todo landed in its slot, or why it couldn't be scheduled
+    Explain {
+        task_id: TaskRef,
+        /// Skip the backend and replay the local engine
+        #[arg(long)]
+        local: bool,
+    },
+
+    /// For a todo that can't make its deadline: the earliest it could be done, and keys to move
+    /// the deadline, raise its priority or split it
+    Negotiate { task_id: TaskRef },
+
+    /// List free slots long enough for a meeting of the given length
+    Free {
+        /// Slot length, e.g. 2h, 45m, 1h30m
+        #[arg(short = 'd', long)]
+        duration: String,
+        /// Period to search: today, tomorrow, this week, next week, next N days, YYYY-MM-DD..YYYY-MM-DD
+        #[arg(short = 'w', long, default_value = "this week")]
+        within: String,
+        /// Other people's calendars (.ics exports or free/busy files) to avoid
+        #[arg(short = 'p', long, value_delimiter = ',')]
+        participants: Vec<std::path::PathBuf>,
+    },
+
+    /// Write scheduled events and todo blocks as an iCalendar file
+    ExportIcs {
+        /// File to write (default: stdout)
+        #[arg(short = 'o', long)]
+        output: Option<std::path::PathBuf>,
+        /// Only this period, e.g. "next 30 days"
+        #[arg(short = 'w', long)]
+        within: Option<String>,
+        /// Only tasks matching a filter, e.g. 'category=work'
+        #[arg(long = "where", value_name = "FILTER")]
+        filter: Option<Filter>,
+        /// Order of the events in the file, e.g. `due,title`
+        #[arg(long, default_value = "due")]
+        sort: SortSpec,
+    },
+
+    /// Pictures of your schedule, for wikis and chat
+    #[command(subcommand)]
+    Export(ExportCommand),
+
+    /// Write a read-only page and JSON file of your busy and free times, without any task details
+    Share {
+        /// This calendar week (same as --within "this week")
+        #[arg(long, conflicts_with = "within")]
+        week: bool,
+        #[arg(short = 'w', long, default_value = "next 7 days")]
+        within: String,
+        /// Shares are always read-only; accepted so scripts can say so
+        #[arg(long)]
+        readonly: bool,
+        /// Directory for index.html and schedule.json
+        #[arg(short = 'o', long, default_value = "share")]
+        output: std::path::PathBuf,
+    },
+
+    /// Save everything to one JSON file, or rebuild a fresh backend from one
+    #[command(subcommand)]
+    Backup(BackupCommand),
+
+    /// Look after the local database that holds notes, journals, caches and the audit log
+    #[command(subcommand)]
+    Db(DbCommand),
+
+    /// Calendar sync, daily snapshots, follow-up nags and end-of-day rollover (`daemon.rollover_at`)
+    /// in the background, set up as a login service
+    #[command(subcommand)]
+    Daemon(DaemonCommand),
+
+    /// Make a todo repeat: finishing it creates the next one, and streaks are tracked
+    Repeat {
+        task_id: TaskRef,
+        #[arg(long, value_enum, required_unless_present = "stop")]
+        every: Option<crate::habits::Every>,
+        /// Stop repeating and forget its history
+        #[arg(long, conflicts_with = "every")]
+        stop: bool,
+    },
+
+    /// How far your estimates are off, per category, from times recorded with `done --took`
+    Calibrate,
+
+    /// Local statistics from the audit log: most-used actions, when you add tasks, how long they stay open
+    Insights {
+        /// Rows in the action ranking
+        #[arg(short = 'n', long, default_value_t = 10)]
+        top: usize,
+    },
+
+    /// Summaries of booked time
+    #[command(subcommand)]
+    Report(ReportCommand),
+
+    /// Office, WFH or travel days: give a date the hours of a `[day_templates]` entry
+    #[command(subcommand)]
+    Day(DayCommand),
+
+    /// Contribution-style grid of tasks finished per day, from the audit log
+    Heatmap {
+        /// A calendar year instead of the last 52 weeks
+        #[arg(long)]
+        year: Option<i32>,
+        /// Shade by hours (recorded with `done --took`, else estimates) instead of task count
+        #[arg(long)]
+        hours: bool,
+        /// Write the grid as an SVG file instead
+        #[arg(long, value_name = "FILE")]
+        svg: Option<std::path::PathBuf>,
+    },
+
+    /// Repeating tasks with their streaks and misses
+    Habits,
+
+    /// Today at a glance: events, blocks, top priorities, overdue and at-risk deadlines; for a login hook
+    Briefing,
+
+    /// Open todos in an urgent/important grid, for weekly planning
+    Matrix {
+        /// Deadlines within this many days (or past) are urgent
+        #[arg(long, default_value_t = 2)]
+        urgent_days: i64,
+        /// Priorities from this one up are important (4 is medium)
+        #[arg(long, default_value_t = 4)]
+        important: i32,
+    },
+
+    /// Upcoming events and todo blocks, with quiet hours and breaks greyed out
+    #[command(alias = "ag")]
+    Agenda {
+        #[arg(short = 'n', long, default_value_t = 7)]
+        days: i64,
+        /// Only events and todos whose location contains this text
+        #[arg(short = 'l', long)]
+        location: Option<String>,
+    },
+
+    /// Only what's actionable today: tasks scheduled or due today (or overdue) and anything in progress
+    Today,
+
+    /// Todos in todo / in-progress / done columns; --interactive moves cards with the keyboard
+    Board {
+        /// Select cards with the arrow keys and move them between columns, updating their status;
+        /// Tab switches between saved views
+        #[arg(short = 'i', long)]
+        interactive: bool,
+        /// Most recently finished todos to show
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        done: usize,
+        /// Only cards `todo today` would show
+        #[arg(long)]
+        today: bool,
+    },
+
+    /// Show or change a task's quiet-hours override
+    QuietHours {
+        task_id: TaskRef,
+        /// Let the scheduler place this task inside quiet hours
+        #[arg(long, conflicts_with = "reset")]
+        ignore: bool,
+        /// Extra do-not-schedule block for this task, e.g. "after 18:00" or "fri 12:00-17:00"
+        #[arg(long, value_name = "BLOCK", conflicts_with = "reset")]
+        add: Vec<Block>,
+        /// Drop the override
+        #[arg(long)]
+        reset: bool,
+    },
+
+    /// Attach a URL or file to a task, or list its attachments
+    Attach {
+        task_id: TaskRef,
+        /// URL or path; omit to list
+        target: Option<String>,
+    },
+
+    /// Open a task's attachment in the browser or default app
+    Open {
+        task_id: TaskRef,
+        /// Which attachment, counting from 1
+        #[arg(short = 'n', long, default_value_t = 1)]
+        index: usize,
+    },
+
+    /// Link to a task in the backend's web UI, from `task_url` in the config
+    Url {
+        task_id: TaskRef,
+        /// Open it in the browser instead of printing it
+        #[arg(long)]
+        open: bool,
+    },
+
+    /// Delegated tasks and when to follow up on them
+    Waiting {
+        /// Record that you chased this task up, restarting its follow-up interval
+        #[arg(long, value_name = "TASK")]
+        followed_up: Option<TaskRef>,
+    },
+
+    /// Tasks nobody has touched for a while, optionally archived, deleted or bumped
+    Stale {
+        /// Days without changes (default from config, 30)
+        #[arg(short = 'd', long)]
+        days: Option<i64>,
+        /// Set them to "later"
+        #[arg(long, conflicts_with_all = ["delete", "bump"])]
+        later: bool,
+        #[arg(long, conflicts_with = "bump")]
+        delete: bool,
+        /// Raise their priority by one so the scheduler gets to them
+        #[arg(long)]
+        bump: bool,
+        /// Don't ask before deferring or deleting
+        #[arg(short = 'y', long)]
+        yes: bool,
+        /// Required when more tasks than `bulk.confirm_above` would change: the exact number
+        #[arg(long, value_name = "N")]
+        confirm_count: Option<usize>,
+    },
+
+    /// Old done tasks that are already on the calendar: preview them, then archive or delete them
+    Cleanup {
+        /// Done and unchanged for at least this many days; 30 or more once they're on the
+        /// calendar, since their events stay there
+        #[arg(short = 'd', long, default_value_t = 90)]
+        days: i64,
+        /// Keep a copy in the local archive, then remove them from the backend
+        #[arg(long, conflicts_with = "delete")]
+        archive: bool,
+        /// Remove them from the backend without keeping a copy
+        #[arg(long)]
+        delete: bool,
+        /// Also take done tasks that were never pushed to the calendar
+        #[arg(long)]
+        include_unpushed: bool,
+        /// Don't ask first
+        #[arg(short = 'y', long)]
+        yes: bool,
+        /// Required when more tasks than `bulk.confirm_above` would go: the exact number
+        #[arg(long, value_name = "N")]
+        confirm_count: Option<usize>,
+    },
+
+    /// Move today's unfinished todo blocks to tomorrow onwards and list what slipped
+    Rollover {
+        /// Raise their priority by one as well
+        #[arg(long)]
+        bump: bool,
+        /// Show where they would go without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Find near-identical tasks and merge each group into its oldest task
+    Dedupe {
+        /// Merge every group without asking
+        #[arg(long)]
+        auto: bool,
+        /// How alike titles must be, 0-1
+        #[arg(long, default_value_t = 0.85)]
+        threshold: f64,
+        /// Required with --auto when more tasks than `bulk.confirm_above` would go: the exact number
+        #[arg(long, value_name = "N", requires = "auto")]
+        confirm_count: Option<usize>,
+    },
+
+    #[command(alias = "hist")]
+    History {
+        #[arg(short = 't', long)]
+        task: Option<i32>,
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+
+    #[command(alias = "tl")]
+    Log {
+        task_id: TaskRef,
+    },
+
+    /// Find tasks by title, or with --full-text inside descriptions too
+    Search {
+        query: String,
+        /// Use the local index over titles and descriptions (built on first use)
+        #[arg(long)]
+        full_text: bool,
+        /// Only tasks matching a filter as well
+        #[arg(long = "where", value_name = "FILTER")]
+        filter: Option<Filter>,
+        /// Order hits by these keys instead of best match first, e.g. `priority,title`
+        #[arg(long)]
+        sort: Option<SortSpec>,
+    },
+
+    /// Saved searches: `todo view save urgent 'status!=done AND (priority>=7 OR due<2d)'`, then `todo view urgent`
+    #[command(args_conflicts_with_subcommands = true)]
+    View {
+        #[command(subcommand)]
+        action: Option<ViewCommand>,
+        /// View to show
+        name: Option<String>,
+    },
+
+    /// Local full-text search index
+    #[command(subcommand)]
+    Index(IndexCommand),
+
+    /// GTD-style contexts (@home, @errands, …) on tasks
+    #[command(subcommand, alias = "ctx")]
+    Context(ContextCommand),
+
+    /// Run command lines or JSON operations from stdin in one process, one JSON result per line
+    Batch {
+        /// Skip the lines an interrupted run already got through (feed it the same input)
+        #[arg(long)]
+        resume: bool,
+    },
+
+    /// One-line summary for tmux or a status bar; cached, so it's cheap to poll
+    Status {
+        #[arg(short = 'f', long, value_enum, default_value = "plain")]
+        format: crate::status::Format,
+        /// Ignore the cache and ask the API now
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Print snippets that hook todo into your shell
+    #[command(subcommand)]
+    Integration(IntegrationCommand),
+
+    #[command(subcommand)]
+    Vault(VaultCommand),
+
+    /// Carry your settings to another machine, or see which differ from the defaults
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Move files from the legacy ~/.todo directory into the XDG layout
+    MigrateData {
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Check API, auth, calendar link, config and clock health
+    Doctor {
+        /// Also compare sample API payloads with the fields the CLI expects
+        #[arg(long)]
+        schema: bool,
+    },
 
     #[command(alias = "comp")]
     Completions {
         #[arg(value_enum)]
         shell: Shell,
+        /// Write them where the shell finds them (and load them from its profile if it needs that)
+        #[arg(long)]
+        install: bool,
     },
 }
 
-#[derive(Deserialize)]
+/// All subcommands, for looking names and aliases up
+pub fn command_tree() -> clap::Command {
+    Commands::augment_subcommands(clap::Command::new("todo"))
+}
+
+/// Put the configured default flags for the subcommand in `args` right after its name, so
+/// anything typed on the command line comes later and wins
+pub fn with_defaults(args: Vec<OsString>, defaults: &BTreeMap<String, Vec<String>>) -> Vec<OsString> {
+    let tree = command_tree();
+    let Some(i) = args.iter().skip(1).position(|a| a.to_str().is_some_and(|a| tree.find_subcommand(a).is_some())) else {
+        return args;
+    };
+    let i = i + 1;
+    let name = tree.find_subcommand(args[i].to_str().unwrap_or_default()).map(|c| c.get_name().to_string());
+    let Some(extra) = name.and_then(|n| defaults.get(&n)) else {
+        return args;
+    };
+    let mut out = args[..=i].to_vec();
+    out.extend(extra.iter().map(OsString::from));
+    out.extend_from_slice(&args[i + 1..]);
+    out
+}
+
+#[derive(Subcommand)]
+pub enum ViewCommand {
+    /// Save a filter under a name, replacing any view of that name
+    Save { name: String, filter: String },
+    /// Saved views and their filters
+    List,
+    Delete { name: String },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Your config file as one TOML file, for `todo config import` elsewhere
+    Export {
+        /// Write here instead of printing
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// Passwords in URLs are left out unless you ask to keep or encrypt them
+        #[arg(long, value_enum, default_value = "omit")]
+        secrets: Secrets,
+    },
+    /// Take settings from an export, laid over your own unless --replace
+    Import {
+        file: std::path::PathBuf,
+        #[arg(long)]
+        replace: bool,
+    },
+    /// Settings that differ from the defaults, wherever they were set
+    Diff,
+}
+
+#[derive(Subcommand)]
+pub enum DbCommand {
+    /// Run SQLite's integrity check and look for lines that aren't valid
+    Check,
+    /// Rebuild a damaged database from what still reads, and quarantine invalid lines
+    Repair,
+}
+
+#[derive(Subcommand)]
+pub enum DaemonCommand {
+    /// Work in the foreground every [daemon] interval_minutes until stopped; what the service runs
+    Run,
+    /// Start it now and at every login: a systemd user unit, a launchd agent, or a scheduled task on Windows
+    Install,
+    /// Whether it's installed, and what the service manager says about it
+    Status,
+    /// Stop it and remove the service
+    Uninstall,
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommand {
+    /// Tasks, categories, local metadata, views, config and the push journal in one compressed file
+    Create {
+        /// Default: todo-backup-YYYY-MM-DD.tar.zst
+        #[arg(short = 'o', long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Recreate a backup's categories and tasks (with new IDs) and its local data
+    Restore {
+        /// Backup file or snapshot name
+        file: String,
+        /// Restore even though the backend already has tasks
+        #[arg(long)]
+        force: bool,
+    },
+    /// Save a snapshot to the data directory and prune old ones (keeps 7 daily, 4 weekly); run it from cron
+    Snapshot,
+    /// Snapshots on disk, oldest first
+    List,
+    /// Tasks added, removed or changed between two backups or snapshots
+    Diff { a: String, b: String },
+}
+
+#[derive(Subcommand)]
+pub enum ReportCommand {
+    /// Hours per category this week, done and still scheduled, against `[quotas]`
+    Week,
+}
+
+#[derive(Subcommand)]
+pub enum DayCommand {
+    /// Use a template's hours on a date, e.g. `todo day set 2025-07-03 office`
+    Set { date: String, template: String },
+    /// Back to the weekday's usual hours
+    Clear { date: String },
+    /// Templates, and the dates from today on that use them
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum ExportCommand {
+    /// Scheduled events and todo blocks drawn as a calendar grid, one column per day (SVG)
+    Image {
+        /// This whole calendar week, including days already past (the default)
+        #[arg(long, conflicts_with = "within")]
+        week: bool,
+        /// Another period instead, e.g. "next week" or 2030-05-06..2030-05-10
+        #[arg(short = 'w', long)]
+        within: Option<String>,
+        /// File to write; only .svg is supported
+        #[arg(short = 'o', long)]
+        output: std::path::PathBuf,
+    },
+    /// One day's plan as a narrow plain-text card, for a thermal receipt printer
+    Receipt {
+        /// Which day (default: tomorrow)
+        #[arg(short = 'd', long, default_value = "tomorrow")]
+        date: String,
+        /// Characters per line: 32 for 58mm paper, 48 for 80mm
+        #[arg(long, default_value_t = 32)]
+        width: usize,
+        /// File to write instead of printing
+        #[arg(short = 'o', long)]
+        output: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportCommand {
+    /// Google Tasks lists as categories, due dates as deadlines, notes as descriptions; safe to repeat
+    GoogleTasks {
+        /// Only this task list
+        #[arg(short = 'l', long)]
+        list: Option<String>,
+        /// Also import completed tasks, marked done
+        #[arg(long)]
+        completed: bool,
+    },
+    /// Calendar events from an .ics export (Outlook, Google, iCloud), one event per occurrence of recurring meetings;
+    /// events already there are skipped. Outlook .pst files need exporting to .ics first
+    Ics {
+        file: std::path::PathBuf,
+        /// Import occurrences up to this day (default: 12 weeks from today)
+        #[arg(long)]
+        until: Option<String>,
+    },
+    /// Apple Reminders: lists as categories, due dates as deadlines; reminders imported before are skipped
+    Reminders {
+        /// An .ics export, or JSON from the bridge; without one, builds with the reminders-bridge feature read Reminders.app
+        file: Option<std::path::PathBuf>,
+        /// Only this list
+        #[arg(short = 'l', long)]
+        list: Option<String>,
+        /// Also import completed reminders, marked done
+        #[arg(long)]
+        completed: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IndexCommand {
+    /// Fetch all tasks and rebuild the index from scratch
+    Rebuild,
+}
+
+#[derive(Subcommand)]
+pub enum ContextCommand {
+    /// Contexts in use, with task counts and scheduling windows
+    List,
+    /// Tag a task, e.g. `todo context add 12 @errands`
+    Add {
+        task_id: TaskRef,
+        #[arg(required = true)]
+        contexts: Vec<String>,
+    },
+    Remove {
+        task_id: TaskRef,
+        #[arg(required = true)]
+        contexts: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IntegrationCommand {
+    /// Zsh widget for quick-add; use with eval "$(todo integration zsh-widget)"
+    ZshWidget {
+        /// Key sequence in bindkey notation (default: Alt-A, "^[a")
+        #[arg(long)]
+        key: Option<String>,
+    },
+    /// Fish binding for quick-add; use with `todo integration fish-binding | source`
+    FishBinding {
+        /// Key sequence in bind notation (default: Alt-A, "\ea")
+        #[arg(long)]
+        key: Option<String>,
+    },
+    /// Bash (readline) binding for quick-add; use with eval "$(todo integration bash-binding)"
+    BashBinding {
+        /// Key sequence in readline notation (default: Alt-A, "\ea")
+        #[arg(long)]
+        key: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VaultCommand {
+    /// Encrypt local stores with a key kept in the OS keyring
+    Lock,
+    /// Decrypt local stores back to plaintext
+    Unlock,
+    Status,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Category {
     pub id: i32,
     pub name: String,
     pub color: String,
+    /// Emoji shown before the titles of its tasks, e.g. "📚"
+    pub icon: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Task {
     pub id: i32,
     pub title: String,
     #[serde(rename = "type")]
     pub kind: String,
+    pub description: Option<String>,
     pub status: Option<String>,
     pub priority: Option<i32>,
     pub estimate: Option<i32>,
+    /// Minutes still to do, on backends that track it
+    pub remaining: Option<i32>,
     pub duration: Option<i32>,
+    pub all_day: Option<bool>,
+    pub location: Option<String>,
+    pub attendees: Option<Vec<String>>,
+    pub organizer: Option<String>,
+    pub meeting_url: Option<String>,
+    /// "busy" (the default when missing), "free" or "tentative"
+    pub transparency: Option<String>,
+    /// GTD contexts without the "@", e.g. ["errands"]
+    pub contexts: Option<Vec<String>>,
+    /// Household member the task belongs to; unassigned tasks are everyone's
+    pub assignee: Option<String>,
+    /// Nice to have: only scheduled into slack
+    pub optional: Option<bool>,
     pub deadline: Option<String>,
     pub start_time: Option<String>,
+    pub end_time: Option<String>,
     pub scheduled_for: Option<String>,
+    /// Google Calendar event it was pushed to or synced from
+    pub external_id: Option<String>,
     pub category: Option<Category>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl Task {
+    pub fn is_all_day(&self) -> bool {
+        self.all_day == Some(true)
+    }
+
+    pub fn is_optional(&self) -> bool {
+        self.optional == Some(true)
+    }
+
+    pub fn contexts(&self) -> &[String] {
+        self.contexts.as_deref().unwrap_or_default()
+    }
+
+    /// Assigned to someone other than `me`; with no identity configured nothing is
+    pub fn belongs_to_other(&self, me: Option<&str>) -> bool {
+        match (self.assignee.as_deref().filter(|a| !a.is_empty()), me) {
+            (Some(a), Some(me)) => !a.eq_ignore_ascii_case(me),
+            _ => false,
+        }
+    }
+
+    /// Todo blocks always do; events only when shown as busy
+    pub fn blocks_time(&self) -> bool {
+        self.kind != "event" || matches!(self.transparency.as_deref(), None | Some("busy"))
+    }
 }
 
 #[derive(Deserialize)]
@@ -108,6 +963,17 @@ pub struct SyncResult {
     pub imported: Option<i32>,
 }
 
+#[derive(Deserialize)]
+pub struct ImportResult {
+    pub imported: u32,
+    pub updated: u32,
+    /// Left out for having no due date
+    #[serde(default)]
+    pub skipped: u32,
+    #[serde(default)]
+    pub lists: Vec<String>,
+}
+
 #[derive(Deserialize)]
 pub struct AutoScheduleResult {
     pub status: Option<String>,
@@ -124,7 +990,51 @@ pub struct PushAllResult {
     pub updated: Option<u32>,
 }
 
+#[derive(Deserialize)]
+pub struct HistoryEvent {
+    pub timestamp: String,
+    pub event: String,
+    pub detail: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum ListFormat {
+    Table,
+    Alfred,
+}
+
+/// What `config export` does with passwords in URLs
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum Secrets {
+    /// As written, in plaintext
+    Keep,
+    Omit,
+    /// Sealed with the vault key; import with the same key in TODO_VAULT_KEY
+    Encrypt,
+}
+
+/// How an event shows on the calendar
+#[derive(ValueEnum, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ShowAs {
+    Busy,
+    Free,
+    /// Optional meetings and unanswered invites
+    Tentative,
+}
+
+impl ShowAs {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ShowAs::Busy => "busy",
+            ShowAs::Free => "free",
+            ShowAs::Tentative => "tentative",
+        }
+    }
+}
+
 #[derive(ValueEnum, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Shell {
     Bash,
     Zsh,
@@ -0,0 +1,1215 @@
+use crate::commands::Secrets;
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Overrides `api_url` without touching the config file
+const API_URL_ENV: &str = "TODO_API_URL";
+
+pub const DEFAULT_API_URL: &str = "http://127.0.0.1:8000";
+
+/// A daily time window, written as "HH:MM-HH:MM" in the config file
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Window {
+    pub start: String,
+    pub end: String,
+}
+
+impl Window {
+    pub fn new(start: &str, end: &str) -> Self {
+        Window { start: start.into(), end: end.into() }
+    }
+}
+
+impl FromStr for Window {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("Expected START-END, got '{}'", s.trim()))?;
+        let (start, end) = (start.trim(), end.trim());
+        for t in [start, end] {
+            NaiveTime::parse_from_str(t, "%H:%M").map_err(|_| format!("Invalid time '{}' (use HH:MM)", t))?;
+        }
+        if start >= end {
+            return Err(format!("Window {}-{} ends before it starts", start, end));
+        }
+        Ok(Window::new(start, end))
+    }
+}
+
+impl TryFrom<String> for Window {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Window> for String {
+    fn from(w: Window) -> String {
+        format!("{}-{}", w.start, w.end)
+    }
+}
+
+impl Window {
+    pub fn times(&self) -> (NaiveTime, NaiveTime) {
+        let t = |s: &str| NaiveTime::parse_from_str(s, "%H:%M").unwrap_or(NaiveTime::MIN);
+        (t(&self.start), t(&self.end))
+    }
+
+    fn from_times(start: NaiveTime, end: NaiveTime) -> Self {
+        Window::new(&start.format("%H:%M").to_string(), &end.format("%H:%M").to_string())
+    }
+}
+
+pub const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// "mon", "Tuesday", … → 0=Mon … 6=Sun
+pub fn day_index(name: &str) -> Result<usize, String> {
+    let d = name.trim().to_lowercase();
+    DAY_NAMES.iter().position(|n| d.starts_with(n)).ok_or_else(|| format!("Unknown day '{}' (use mon, tue, …)", d))
+}
+
+/// A window repeating on some weekdays: "12:00-13:00" (daily), "mon,wed 09:00-11:00" or "fri after 15:00"
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Block {
+    /// 0=Mon … 6=Sun; empty means every day
+    pub days: Vec<usize>,
+    pub window: Window,
+}
+
+impl Block {
+    pub fn applies_to(&self, day: usize) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+}
+
+impl FromStr for Block {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (days, window) = match s.split_once(' ') {
+            Some((d, w)) if !matches!(d, "after" | "before") => (d, w.trim()),
+            _ => ("", s),
+        };
+        let days = days
+            .split(',')
+            .filter(|d| !d.is_empty())
+            .map(day_index)
+            .collect::<Result<Vec<_>, _>>()?;
+        // "after 20:00" / "before 08:00" run to the end / from the start of the day
+        let window = if let Some(t) = window.strip_prefix("after ") {
+            format!("{}-23:59", t.trim()).parse()?
+        } else if let Some(t) = window.strip_prefix("before ") {
+            format!("00:00-{}", t.trim()).parse()?
+        } else {
+            window.parse()?
+        };
+        Ok(Block { days, window })
+    }
+}
+
+impl TryFrom<String> for Block {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Block> for String {
+    fn from(b: Block) -> String {
+        let window = String::from(b.window);
+        if b.days.is_empty() {
+            return window;
+        }
+        let days: Vec<&str> = b.days.iter().map(|&d| DAY_NAMES[d]).collect();
+        format!("{} {}", days.join(","), window)
+    }
+}
+
+/// Cut `block` out of `windows`
+fn subtract(windows: Vec<Window>, block: &Window) -> Vec<Window> {
+    let (bs, be) = block.times();
+    let mut out = Vec::new();
+    for w in windows {
+        let (ws, we) = w.times();
+        if be <= ws || bs >= we {
+            out.push(w);
+            continue;
+        }
+        if bs > ws {
+            out.push(Window::from_times(ws, bs));
+        }
+        if be < we {
+            out.push(Window::from_times(be, we));
+        }
+    }
+    out
+}
+
+/// Availability windows per weekday
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Availability {
+    pub mon: Vec<Window>,
+    pub tue: Vec<Window>,
+    pub wed: Vec<Window>,
+    pub thu: Vec<Window>,
+    pub fri: Vec<Window>,
+    pub sat: Vec<Window>,
+    pub sun: Vec<Window>,
+    /// Recurring breaks nothing gets scheduled into, e.g. lunch
+    pub breaks: Vec<Block>,
+    /// Protected focus time kept clear of scheduled tasks and meeting suggestions
+    pub focus: Vec<Block>,
+    /// Cap on scheduled todo time per day
+    pub max_hours_per_day: Option<f64>,
+    /// Windows for particular dates, from the day templates assigned to them; they replace the
+    /// weekday's windows
+    #[serde(skip)]
+    pub dated: BTreeMap<NaiveDate, Vec<Window>>,
+}
+
+impl Default for Availability {
+    fn default() -> Self {
+        Availability::split_week(&[Window::new("09:00", "17:00")], &[Window::new("10:00", "14:00")], &[5, 6])
+    }
+}
+
+impl Availability {
+    /// `workday` windows on every day except `weekend` (0=Mon … 6=Sun), which get `weekend_windows`
+    pub fn split_week(workday: &[Window], weekend_windows: &[Window], weekend: &[usize]) -> Availability {
+        let mut a = Availability {
+            mon: Vec::new(),
+            tue: Vec::new(),
+            wed: Vec::new(),
+            thu: Vec::new(),
+            fri: Vec::new(),
+            sat: Vec::new(),
+            sun: Vec::new(),
+            breaks: Vec::new(),
+            focus: Vec::new(),
+            max_hours_per_day: None,
+            dated: BTreeMap::new(),
+        };
+        for (i, day) in a.days_mut().into_iter().enumerate() {
+            *day = if weekend.contains(&i) { weekend_windows.to_vec() } else { workday.to_vec() };
+        }
+        a
+    }
+
+    fn days_mut(&mut self) -> [&mut Vec<Window>; 7] {
+        [&mut self.mon, &mut self.tue, &mut self.wed, &mut self.thu, &mut self.fri, &mut self.sat, &mut self.sun]
+    }
+
+    /// Days the file left out get the default windows for a week with this weekend
+    fn fill_default_days(&mut self, given: Option<&toml::Table>, weekend: &[usize]) {
+        let defaults = Availability::split_week(&[Window::new("09:00", "17:00")], &[Window::new("10:00", "14:00")], weekend);
+        for (i, day) in self.days_mut().into_iter().enumerate() {
+            if given.is_none_or(|g| !g.contains_key(DAY_NAMES[i])) {
+                *day = defaults.days()[i].clone();
+            }
+        }
+    }
+
+    /// Days in backend order (0=Mon … 6=Sun)
+    pub fn days(&self) -> [&Vec<Window>; 7] {
+        [&self.mon, &self.tue, &self.wed, &self.thu, &self.fri, &self.sat, &self.sun]
+    }
+
+    /// A day's windows with breaks and focus blocks cut out
+    pub fn open_windows(&self, day: usize) -> Vec<Window> {
+        self.cut_blocks(self.days()[day].clone(), day)
+    }
+
+    /// Like `open_windows`, but a day template assigned to `date` replaces the weekday's windows
+    pub fn open_windows_on(&self, date: NaiveDate) -> Vec<Window> {
+        let day = date.weekday().num_days_from_monday() as usize;
+        match self.dated.get(&date) {
+            Some(windows) => self.cut_blocks(windows.clone(), day),
+            None => self.open_windows(day),
+        }
+    }
+
+    fn cut_blocks(&self, mut windows: Vec<Window>, day: usize) -> Vec<Window> {
+        for b in self.breaks.iter().chain(&self.focus).filter(|b| b.applies_to(day)) {
+            windows = subtract(windows, &b.window);
+        }
+        windows
+    }
+}
+
+/// A kind of day with its own hours, e.g. an office or travel day; `todo day set` assigns one
+/// to a date
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DayTemplate {
+    /// Replaces the weekday's windows; none means nothing gets scheduled that day
+    pub windows: Vec<Window>,
+    /// Minutes kept clear at the start of the day, e.g. for the commute in
+    pub buffer_before: u32,
+    /// Minutes kept clear at the end of the day
+    pub buffer_after: u32,
+}
+
+impl DayTemplate {
+    /// The windows with the buffers taken off the first start and the last end; a buffer that
+    /// runs past midnight stops there instead of wrapping into the same day
+    pub fn effective_windows(&self) -> Vec<Window> {
+        let mut times: Vec<(NaiveTime, NaiveTime)> = self.windows.iter().map(Window::times).collect();
+        times.sort();
+        if let Some(first) = times.first_mut() {
+            let (start, wrapped) = first.0.overflowing_add_signed(Duration::minutes(self.buffer_before as i64));
+            first.0 = if wrapped == 0 { start } else { NaiveTime::from_hms_opt(23, 59, 0).unwrap_or_default() };
+        }
+        if let Some(last) = times.last_mut() {
+            let (end, wrapped) = last.1.overflowing_sub_signed(Duration::minutes(self.buffer_after as i64));
+            last.1 = if wrapped == 0 { end } else { NaiveTime::default() };
+        }
+        times.into_iter().filter(|(s, e)| s < e).map(|(s, e)| Window::from_times(s, e)).collect()
+    }
+}
+
+/// Knobs for how tasks are spread out
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Scheduling {
+    /// Most of a day's open hours one category may take, e.g. 0.6
+    pub max_category_share: Option<f64>,
+    /// Keep existing placements and only fill gaps with new or changed tasks
+    pub incremental: bool,
+    /// How firmly incremental runs keep placements: 1 never moves them, 0 lets any higher-scored task bump them
+    pub stability: f64,
+    /// Quiet hours nothing is scheduled into even when available, e.g. "fri after 15:00"
+    pub do_not_schedule: Vec<Block>,
+    /// Placements start on multiples of this many minutes past the hour (5, 15, 30, …)
+    pub slot_minutes: u32,
+    /// Rules packing short tasks into shared blocks instead of scattering them
+    pub batching: Vec<BatchRule>,
+    /// Whether all-day events (birthdays, conference days) block their days
+    pub all_day_busy: bool,
+    /// Context name → the only times its tasks may go, e.g. errands = ["sat 10:00-14:00"]
+    pub context_windows: BTreeMap<String, Vec<Block>>,
+    /// Warn after auto-schedule when a day has more than this many hours scheduled
+    pub max_daily_hours: Option<f64>,
+    /// Warn after auto-schedule when the coming week's committed hours exceed availability by more than this percentage
+    pub overcommit_percent: Option<f64>,
+    /// Scale estimates by each category's track record (see `todo calibrate`) when planning locally
+    pub calibrate: bool,
+    /// What gives when a todo doesn't fit; unset, it's just reported as not scheduled
+    pub eviction: Option<Eviction>,
+}
+
+impl Default for Scheduling {
+    fn default() -> Self {
+        Scheduling {
+            max_category_share: None,
+            incremental: false,
+            stability: 1.0,
+            do_not_schedule: Vec::new(),
+            slot_minutes: 15,
+            batching: Vec::new(),
+            all_day_busy: false,
+            context_windows: BTreeMap::new(),
+            max_daily_hours: None,
+            overcommit_percent: None,
+            calibrate: false,
+            eviction: None,
+        }
+    }
+}
+
+impl Scheduling {
+    /// `Some(stability)` when runs should be incremental
+    pub fn incremental_stability(&self) -> Option<f64> {
+        self.incremental.then_some(self.stability)
+    }
+}
+
+/// How the local scheduler makes room when there isn't enough for everything
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Eviction {
+    /// Unschedule lower-priority todos, lowest first, until it fits; they get another try afterwards
+    DropLowestPriority,
+    /// Cut `--optional` todos, and those with no deadline, down to as little as half their estimate
+    ShrinkOptional,
+    /// Place it after its deadline and warn
+    OverflowPastDeadlineWithWarning,
+}
+
+impl Eviction {
+    /// As written in the config
+    pub fn name(self) -> &'static str {
+        match self {
+            Eviction::DropLowestPriority => "drop-lowest-priority",
+            Eviction::ShrinkOptional => "shrink-optional",
+            Eviction::OverflowPastDeadlineWithWarning => "overflow-past-deadline-with-warning",
+        }
+    }
+}
+
+/// Short tasks in these categories go into the batch windows, up to `max_minutes` per window
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BatchRule {
+    /// Category names, matched case-insensitively
+    pub tags: Vec<String>,
+    /// Only tasks estimated below this many minutes are batched
+    pub under_minutes: i32,
+    pub windows: Vec<Block>,
+    pub max_minutes: i64,
+}
+
+impl Default for BatchRule {
+    fn default() -> Self {
+        BatchRule {
+            tags: vec!["admin".into(), "email".into()],
+            under_minutes: 20,
+            windows: vec!["09:00-09:45".parse().expect("valid block"), "16:00-16:45".parse().expect("valid block")],
+            max_minutes: 45,
+        }
+    }
+}
+
+impl BatchRule {
+    pub fn matches(&self, category: Option<&str>, estimate: i32) -> bool {
+        estimate < self.under_minutes
+            && category.is_some_and(|c| self.tags.iter().any(|t| t.eq_ignore_ascii_case(c)))
+    }
+}
+
+/// When a task counts as stale
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Stale {
+    pub days: i64,
+    /// Warn in list-tasks when more tasks than this are stale
+    pub warn_above: Option<usize>,
+}
+
+impl Default for Stale {
+    fn default() -> Self {
+        Stale { days: 30, warn_above: None }
+    }
+}
+
+/// What `todo daemon run` does in the background, and how often
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Daemon {
+    pub interval_minutes: u64,
+    /// Pull Google Calendar changes into the backend each round
+    pub sync_calendar: bool,
+    /// Take the day's `backup snapshot` if there isn't one yet
+    pub snapshot: bool,
+    /// Retry pushes a client-side `push-all` left in its journal
+    pub retry_pushes: bool,
+    /// Once a day, list waiting tasks due a follow-up (`waiting.follow_up_days`)
+    pub follow_ups: bool,
+    /// Run `todo rollover` once a day from this time (HH:MM); off when unset
+    pub rollover_at: Option<String>,
+    /// Raise rolled-over tasks' priority by one, like `rollover --bump`
+    pub rollover_bump: bool,
+}
+
+impl Daemon {
+    pub fn rollover_time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(self.rollover_at.as_deref()?, "%H:%M").ok()
+    }
+}
+
+impl Default for Daemon {
+    fn default() -> Self {
+        Daemon { interval_minutes: 15, sync_calendar: true, snapshot: true, retry_pushes: true, follow_ups: true, rollover_at: None, rollover_bump: false }
+    }
+}
+
+/// Delegated tasks: how long to wait before chasing them up
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Waiting {
+    pub follow_up_days: i64,
+}
+
+impl Default for Waiting {
+    fn default() -> Self {
+        Waiting { follow_up_days: 3 }
+    }
+}
+
+/// Filter-based changes touching more tasks than this need an explicit count
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Bulk {
+    pub confirm_above: usize,
+}
+
+impl Default for Bulk {
+    fn default() -> Self {
+        Bulk { confirm_above: 20 }
+    }
+}
+
+/// `todo status`: how long a fetched summary is reused
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct StatusBar {
+    pub refresh_seconds: i64,
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        StatusBar { refresh_seconds: 60 }
+    }
+}
+
+/// Client-side request budget
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Network {
+    pub requests_per_second: f64,
+    pub burst: f64,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Network { requests_per_second: 10.0, burst: 10.0 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Json,
+    Pretty,
+}
+
+/// Which day weeks start on and which days are the weekend, e.g. a Sunday–Thursday workweek:
+/// `starts_on = "sun"`, `weekend = ["fri", "sat"]`
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Week {
+    pub starts_on: String,
+    pub weekend: Vec<String>,
+    /// Days off besides the weekend, e.g. `["2030-12-25", "2030-12-26"]`
+    pub holidays: Vec<String>,
+    /// "tomorrow" in dates means the next working day, skipping the weekend and holidays
+    pub tomorrow_is_workday: bool,
+}
+
+impl Default for Week {
+    fn default() -> Self {
+        Week { starts_on: "mon".into(), weekend: vec!["sat".into(), "sun".into()], holidays: Vec::new(), tomorrow_is_workday: false }
+    }
+}
+
+impl Week {
+    /// 0=Mon … 6=Sun
+    pub fn first_day(&self) -> usize {
+        day_index(&self.starts_on).unwrap_or(0)
+    }
+
+    pub fn weekend_days(&self) -> Vec<usize> {
+        self.weekend.iter().filter_map(|d| day_index(d).ok()).collect()
+    }
+
+    pub fn is_weekend(&self, date: NaiveDate) -> bool {
+        self.weekend_days().contains(&(date.weekday().num_days_from_monday() as usize))
+    }
+
+    /// First day of the week `date` falls in
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        let back = (date.weekday().num_days_from_monday() as i64 - self.first_day() as i64).rem_euclid(7);
+        date - Duration::days(back)
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.iter().any(|h| NaiveDate::parse_from_str(h, "%Y-%m-%d").is_ok_and(|d| d == date))
+    }
+
+    pub fn is_workday(&self, date: NaiveDate) -> bool {
+        !self.is_weekend(date) && !self.is_holiday(date)
+    }
+
+    /// First working day after `date` ("next workday"); a year on at most
+    pub fn next_workday(&self, date: NaiveDate) -> NaiveDate {
+        (1..=366).map(|i| date + Duration::days(i)).find(|d| self.is_workday(*d)).unwrap_or(date + Duration::days(1))
+    }
+
+    /// What "tomorrow" means: the next day, or the next working day with `tomorrow_is_workday`
+    pub fn tomorrow(&self, today: NaiveDate) -> NaiveDate {
+        match self.tomorrow_is_workday {
+            true => self.next_workday(today),
+            false => today + Duration::days(1),
+        }
+    }
+
+    /// Last working day of the week `date` falls in ("end of this week")
+    pub fn last_workday(&self, date: NaiveDate) -> NaiveDate {
+        let start = self.start_of(date);
+        (0..7).rev().map(|i| start + Duration::days(i)).find(|d| self.is_workday(*d)).unwrap_or(start + Duration::days(6))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        day_index(&self.starts_on).map_err(|e| format!("week.starts_on: {}", e))?;
+        for d in &self.weekend {
+            day_index(d).map_err(|e| format!("week.weekend: {}", e))?;
+        }
+        if self.weekend_days().iter().collect::<std::collections::BTreeSet<_>>().len() == 7 {
+            return Err("week.weekend can't be every day".into());
+        }
+        for h in &self.holidays {
+            NaiveDate::parse_from_str(h, "%Y-%m-%d").map_err(|_| format!("week.holidays: invalid date '{}' (use YYYY-MM-DD)", h))?;
+        }
+        Ok(())
+    }
+}
+
+/// What "friday evening" or "tomorrow morning" means; each part lasts until the next one
+/// starts, and night until midnight
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DayParts {
+    pub morning: String,
+    pub afternoon: String,
+    pub evening: String,
+    pub night: String,
+}
+
+impl Default for DayParts {
+    fn default() -> Self {
+        DayParts { morning: "09:00".into(), afternoon: "14:00".into(), evening: "19:00".into(), night: "22:00".into() }
+    }
+}
+
+impl DayParts {
+    fn all(&self) -> [(&'static str, &str); 4] {
+        [("morning", &self.morning), ("afternoon", &self.afternoon), ("evening", &self.evening), ("night", &self.night)]
+    }
+
+    /// Start of the named part, and when it ends (None: at midnight)
+    pub fn span(&self, name: &str) -> Option<(NaiveTime, Option<NaiveTime>)> {
+        let time = |s: &str| NaiveTime::parse_from_str(s, "%H:%M").ok();
+        let start = self.all().into_iter().find(|(n, _)| *n == name).and_then(|(_, t)| time(t))?;
+        let end = self.all().into_iter().filter_map(|(_, t)| time(t)).filter(|t| *t > start).min();
+        Some((start, end))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (name, t) in self.all() {
+            NaiveTime::parse_from_str(t, "%H:%M").map_err(|_| format!("day_parts.{}: invalid time '{}' (use HH:MM)", name, t))?;
+        }
+        Ok(())
+    }
+}
+
+/// A named stretch of days for phrases like "end of sprint" or "before exam week": dated
+/// ranges ("2030-06-09..2030-06-13"), yearly ones ("07-01..09-30" for Q3), or a repeating
+/// cycle (`{ start = "2030-01-06", length = "2w" }`)
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Period {
+    One(String),
+    Ranges(Vec<String>),
+    Cycle { start: String, length: String },
+}
+
+/// "2w", "10d" or a bare number of days
+fn cycle_days(length: &str) -> Result<i64, String> {
+    let l = length.trim().to_lowercase();
+    let days = match l.strip_suffix('w') {
+        Some(w) => w.trim().parse::<i64>().map(|w| w * 7),
+        None => l.trim_end_matches('d').trim().parse::<i64>(),
+    };
+    days.ok().filter(|d| *d > 0).ok_or_else(|| format!("'{}': length must be like 2w or 10d", length))
+}
+
+impl Period {
+    /// First and last day of the occurrence under way on `day`, else of the next one;
+    /// `skip` moves on past that many more
+    pub fn occurrence(&self, day: NaiveDate, skip: usize) -> Result<Option<(NaiveDate, NaiveDate)>, String> {
+        let ranges = match self {
+            Period::One(r) => std::slice::from_ref(r),
+            Period::Ranges(r) => r.as_slice(),
+            Period::Cycle { start, length } => {
+                let first = NaiveDate::parse_from_str(start.trim(), "%Y-%m-%d").map_err(|_| format!("'{}': start must be YYYY-MM-DD", start))?;
+                let len = cycle_days(length)?;
+                let k = if day < first { 0 } else { (day - first).num_days() / len } + skip as i64;
+                let s = first + Duration::days(k * len);
+                return Ok(Some((s, s + Duration::days(len - 1))));
+            }
+        };
+        let mut spans = Vec::new();
+        for r in ranges {
+            let (a, b) = r.split_once("..").ok_or_else(|| format!("'{}': use FIRST..LAST", r))?;
+            let dated = |d: &str| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d");
+            if let (Ok(a), Ok(b)) = (dated(a), dated(b)) {
+                spans.push((a, b));
+                continue;
+            }
+            // Yearly, possibly wrapping into the next year ("12-15..01-05")
+            let on = |md: &str, year: i32| dated(&format!("{}-{}", year, md.trim())).map_err(|_| format!("'{}': days are YYYY-MM-DD or MM-DD", r));
+            for year in day.year() - 1..=day.year() + skip as i32 + 1 {
+                let s = on(a, year)?;
+                let e = on(b, year)?;
+                spans.push((s, if e < s { on(b, year + 1)? } else { e }));
+            }
+        }
+        spans.sort();
+        Ok(spans.into_iter().filter(|(s, e)| e >= s && *e >= day).nth(skip))
+    }
+}
+
+/// Weekly hours for a category: "max 40h", "min 5h" or "min 5h, max 10h"
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub struct Quota {
+    pub min_minutes: Option<i64>,
+    pub max_minutes: Option<i64>,
+}
+
+/// "40h", "7.5h", "90m" or bare hours
+fn quota_minutes(amount: &str) -> Option<i64> {
+    let a = amount.trim().to_lowercase();
+    let minutes = match a.strip_suffix('m') {
+        Some(m) => m.trim().parse::<f64>().ok()?,
+        None => a.trim_end_matches('h').trim().parse::<f64>().ok()? * 60.0,
+    };
+    (minutes.is_finite() && minutes >= 0.0).then(|| minutes.round() as i64)
+}
+
+impl FromStr for Quota {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut quota = Quota { min_minutes: None, max_minutes: None };
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (bound, amount) = part.split_once(' ').unwrap_or((part, ""));
+            let minutes = quota_minutes(amount).ok_or_else(|| format!("'{}': use \"max 40h\", \"min 5h\" or both", s))?;
+            match bound.to_lowercase().as_str() {
+                "min" => quota.min_minutes = Some(minutes),
+                "max" => quota.max_minutes = Some(minutes),
+                _ => return Err(format!("'{}': use \"max 40h\", \"min 5h\" or both", s)),
+            }
+        }
+        match (quota.min_minutes, quota.max_minutes) {
+            (None, None) => Err(format!("'{}': use \"max 40h\", \"min 5h\" or both", s)),
+            (Some(min), Some(max)) if min > max => Err(format!("'{}': min is above max", s)),
+            _ => Ok(quota),
+        }
+    }
+}
+
+impl TryFrom<String> for Quota {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Quota> for String {
+    fn from(q: Quota) -> String {
+        let hours = |m: i64| if m % 60 == 0 { format!("{}h", m / 60) } else { format!("{}m", m) };
+        let parts: Vec<String> = [("min", q.min_minutes), ("max", q.max_minutes)]
+            .into_iter()
+            .filter_map(|(bound, m)| Some(format!("{} {}", bound, hours(m?))))
+            .collect();
+        parts.join(", ")
+    }
+}
+
+/// Opt-in debug log of commands, API calls and errors, in the state directory
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Log {
+    pub enabled: bool,
+    pub format: LogFormat,
+    /// Rotate once the log reaches this size
+    pub max_kb: u64,
+    /// Rotated files to keep (todo.log.1 … todo.log.N)
+    pub keep: usize,
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        Log { enabled: false, format: LogFormat::Json, max_kb: 1024, keep: 3 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub api_url: String,
+    /// Your name on a shared backend; "me" in --assignee means this, and scheduling
+    /// leaves other people's tasks alone
+    pub user: Option<String>,
+    pub availability: Availability,
+    /// Named alternative availability tables, e.g. "work" and "personal"
+    pub profiles: BTreeMap<String, Availability>,
+    /// Category name → profile name; other categories use `availability`
+    pub category_profiles: BTreeMap<String, String>,
+    pub weights: BTreeMap<String, f64>,
+    pub scheduling: Scheduling,
+    pub stale: Stale,
+    pub waiting: Waiting,
+    pub status: StatusBar,
+    pub bulk: Bulk,
+    /// Subcommand → flags it always gets, one argument per item, e.g. create-todo = ["-p", "4"]
+    pub defaults: BTreeMap<String, Vec<String>>,
+    /// Your own commands, e.g. today = "lt @home" or "wait" = "ut $1 --waiting-on $2"
+    pub alias: BTreeMap<String, String>,
+    pub network: Network,
+    pub log: Log,
+    pub daemon: Daemon,
+    pub week: Week,
+    pub day_parts: DayParts,
+    /// Named periods for the date parser, keyed by name ("sprint", "q3", "exam week")
+    pub periods: BTreeMap<String, Period>,
+    /// Category name → weekly hours, e.g. work = "max 40h"; the local scheduler treats them as
+    /// soft limits and `todo report week` compares them with what's booked
+    pub quotas: BTreeMap<String, Quota>,
+    /// Named kinds of day ("office", "wfh", "travel") with their own hours and buffers
+    pub day_templates: BTreeMap<String, DayTemplate>,
+    /// Category icons before task titles, in every view and in events pushed to the calendar
+    pub category_icons: bool,
+    /// A task's page in the backend's web UI, with `{id}` for its ID, e.g.
+    /// "https://tasks.example.com/tasks/{id}"
+    pub task_url: Option<String>,
+    /// Language for deadlines besides English: "zh" (明天, 下周五, 3天后) or "ja" (明日, 来週の金曜日, 3日後)
+    pub locale: Option<String>,
+    /// Read dictated dates leniently, as `--fuzzy-dates` does
+    pub fuzzy_dates: bool,
+    /// Terminal colors: "default", "solarized", "high-contrast" or "deuteranopia-safe"
+    pub theme: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            api_url: DEFAULT_API_URL.to_string(),
+            user: None,
+            availability: Availability::default(),
+            profiles: BTreeMap::new(),
+            category_profiles: BTreeMap::new(),
+            weights: BTreeMap::from([("priority".to_string(), 1.0), ("deadline".to_string(), 100.0)]),
+            scheduling: Scheduling::default(),
+            stale: Stale::default(),
+            waiting: Waiting::default(),
+            status: StatusBar::default(),
+            bulk: Bulk::default(),
+            defaults: BTreeMap::new(),
+            alias: BTreeMap::new(),
+            network: Network::default(),
+            log: Log::default(),
+            daemon: Daemon::default(),
+            week: Week::default(),
+            day_parts: DayParts::default(),
+            periods: BTreeMap::new(),
+            quotas: BTreeMap::new(),
+            day_templates: BTreeMap::new(),
+            category_icons: true,
+            task_url: None,
+            locale: None,
+            fuzzy_dates: false,
+            theme: None,
+        }
+    }
+}
+
+impl Config {
+    /// Availability that applies to tasks in `category`
+    pub fn availability_for(&self, category: Option<&str>) -> &Availability {
+        category
+            .and_then(|c| self.category_profiles.get(c))
+            .and_then(|p| self.profiles.get(p))
+            .unwrap_or(&self.availability)
+    }
+
+    /// What the scheduler may use for a task: its availability minus quiet hours (unless
+    /// `ignore_quiet`) and any extra blocks of its own
+    pub fn scheduling_availability(&self, category: Option<&str>, ignore_quiet: bool, extra: &[Block]) -> Availability {
+        let mut a = self.availability_for(category).clone();
+        if !ignore_quiet {
+            a.breaks.extend(self.scheduling.do_not_schedule.iter().cloned());
+        }
+        a.breaks.extend(extra.iter().cloned());
+        a
+    }
+
+    /// Link to the task in the web UI; None without `task_url`
+    pub fn task_link(&self, id: i32) -> Option<String> {
+        self.task_url.as_ref().map(|u| u.replace("{id}", &id.to_string()))
+    }
+
+    /// The `[quotas]` entry for a category, ignoring case
+    pub fn quota(&self, category: &str) -> Option<&Quota> {
+        self.quotas.iter().find(|(n, _)| n.eq_ignore_ascii_case(category.trim())).map(|(_, q)| q)
+    }
+
+    /// A `[day_templates]` entry and its name as configured, ignoring case
+    pub fn day_template(&self, name: &str) -> Option<(&String, &DayTemplate)> {
+        self.day_templates.iter().find(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Apply day templates assigned to dates (date → template name) to the main availability;
+    /// category profiles keep their own hours. Unknown templates are skipped
+    pub fn assign_days(&mut self, days: &BTreeMap<NaiveDate, String>) {
+        for (date, name) in days {
+            if let Some(windows) = self.day_template(name).map(|(_, t)| t.effective_windows()) {
+                self.availability.dated.insert(*date, windows);
+            }
+        }
+    }
+
+    /// A `[periods]` entry, ignoring case
+    pub fn period(&self, name: &str) -> Option<&Period> {
+        self.periods.iter().find(|(n, _)| n.eq_ignore_ascii_case(name.trim())).map(|(_, p)| p)
+    }
+
+    /// An --assignee value as stored: "me" becomes `user`, "unassigned" or "none" the empty string
+    pub fn assignee(&self, who: &str) -> Result<String, String> {
+        match who.trim().to_lowercase().as_str() {
+            "me" => self.user.clone().ok_or_else(|| "Set `user` in the config file to use \"me\"".to_string()),
+            "unassigned" | "none" | "" => Ok(String::new()),
+            _ => Ok(who.trim().to_string()),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.week.validate()?;
+        self.day_parts.validate()?;
+        for (name, period) in &self.periods {
+            period.occurrence(chrono::Local::now().date_naive(), 0).map_err(|e| format!("periods.{}: {}", name, e))?;
+        }
+        if self.user.as_deref().is_some_and(|u| u.trim().is_empty() || ["me", "unassigned", "none"].contains(&u.trim().to_lowercase().as_str())) {
+            return Err("user must be a name (not empty, \"me\" or \"unassigned\")".into());
+        }
+        if let Some(share) = self.scheduling.max_category_share {
+            if !(share > 0.0 && share <= 1.0) {
+                return Err(format!("scheduling.max_category_share must be in (0, 1], got {}", share));
+            }
+        }
+        if !(1..=1440).contains(&self.daemon.interval_minutes) {
+            return Err("daemon.interval_minutes must be between 1 and 1440 (a day)".into());
+        }
+        if let Some(t) = &self.daemon.rollover_at {
+            NaiveTime::parse_from_str(t, "%H:%M").map_err(|_| format!("daemon.rollover_at: invalid time '{}' (use HH:MM)", t))?;
+        }
+        if self.scheduling.max_daily_hours.is_some_and(|h| !(h > 0.0 && h <= 24.0)) {
+            return Err("scheduling.max_daily_hours must be in (0, 24]".into());
+        }
+        if self.scheduling.overcommit_percent.is_some_and(|p| p.is_nan() || p < 0.0) {
+            return Err("scheduling.overcommit_percent must not be negative".into());
+        }
+        if !(0.0..=1.0).contains(&self.scheduling.stability) {
+            return Err(format!("scheduling.stability must be between 0 and 1, got {}", self.scheduling.stability));
+        }
+        let g = self.scheduling.slot_minutes;
+        if g == 0 || 60 % g != 0 {
+            return Err(format!("scheduling.slot_minutes must divide an hour (e.g. 5, 15, 30), got {}", g));
+        }
+        if let Some(i) = self.scheduling.batching.iter().position(|r| r.windows.is_empty() || r.max_minutes <= 0) {
+            return Err(format!("scheduling.batching[{}] needs at least one window and a positive max_minutes", i));
+        }
+        let tree = crate::commands::command_tree();
+        for name in self.defaults.keys() {
+            match tree.find_subcommand(name) {
+                Some(c) if c.get_name() != name => return Err(format!("defaults.{} must use the full command name '{}'", name, c.get_name())),
+                Some(c) if c.has_subcommands() => return Err(format!("defaults.{} has subcommands; defaults only apply to plain commands", name)),
+                Some(_) => {}
+                None => return Err(format!("defaults.{}: no such command", name)),
+            }
+        }
+        for (name, template) in &self.alias {
+            if tree.find_subcommand(name).is_some() {
+                return Err(format!("alias.{} would hide the built-in command", name));
+            }
+            let words = crate::alias::split(template).map_err(|e| format!("alias.{}: {}", name, e))?;
+            if words.first().is_none_or(|w| tree.find_subcommand(w).is_none()) {
+                return Err(format!("alias.{} must start with a todo command", name));
+            }
+        }
+        for (category, profile) in &self.category_profiles {
+            if !self.profiles.contains_key(profile) {
+                return Err(format!("category '{}' uses unknown profile '{}'", category, profile));
+            }
+        }
+        if let Some(l) = self.locale.as_deref().filter(|l| crate::lang::pack(l).is_none()) {
+            let known: Vec<&str> = crate::lang::PACKS.iter().map(|p| p.code()).collect();
+            return Err(format!("locale '{}' has no language pack (known: {})", l, known.join(", ")));
+        }
+        if let Some(t) = &self.theme {
+            crate::theme::find(t)?;
+        }
+        if self.task_url.as_ref().is_some_and(|u| !u.contains("{id}")) {
+            return Err("task_url needs an {id} placeholder, e.g. \"https://tasks.example.com/tasks/{id}\"".into());
+        }
+        Ok(())
+    }
+
+    /// Body for POST /auto-schedule/
+    pub fn schedule_payload(&self) -> Value {
+        // Quiet hours are global here; per-task overrides only apply to the local engine
+        let quiet = |a: &Availability| {
+            let mut a = a.clone();
+            a.breaks.extend(self.scheduling.do_not_schedule.iter().cloned());
+            availability_table(&a)
+        };
+        let mut payload = json!({
+            "availability": quiet(&self.availability),
+            "weights": self.weights,
+            "slot_minutes": self.scheduling.slot_minutes,
+        });
+        // Ignored by backends that don't support a daily cap
+        if let Some(h) = self.availability.max_hours_per_day {
+            payload["max_hours_per_day"] = json!(h);
+        }
+        if let Some(share) = self.scheduling.max_category_share {
+            payload["max_category_share"] = json!(share);
+        }
+        if let Some(policy) = self.scheduling.eviction {
+            payload["eviction"] = json!(policy);
+        }
+        if let Some(stability) = self.scheduling.incremental_stability() {
+            set_incremental(&mut payload, stability);
+        }
+        if self.scheduling.all_day_busy {
+            payload["all_day_busy"] = json!(true);
+        }
+        // Only schedule our own and unassigned todos into our availability
+        if let Some(user) = &self.user {
+            payload["assignee"] = json!(user);
+        }
+        if !self.scheduling.batching.is_empty() {
+            payload["batching"] = json!(self.scheduling.batching);
+        }
+        // Backends without weekly quotas ignore them
+        if !self.quotas.is_empty() {
+            payload["quotas"] = json!(self.quotas);
+        }
+        // Backends without day templates use the weekday's windows on those dates too
+        if !self.availability.dated.is_empty() {
+            let mut a = self.availability.clone();
+            a.breaks.extend(self.scheduling.do_not_schedule.iter().cloned());
+            let dates: serde_json::Map<String, Value> = a
+                .dated
+                .keys()
+                .map(|d| (d.to_string(), a.open_windows_on(*d).iter().map(|w| json!({ "start": w.start, "end": w.end })).collect()))
+                .collect();
+            payload["dates"] = Value::Object(dates);
+        }
+        // Backends without per-category profiles only use the global table
+        if !self.category_profiles.is_empty() {
+            let profiles: serde_json::Map<String, Value> =
+                self.profiles.iter().map(|(name, a)| (name.clone(), quiet(a))).collect();
+            payload["profiles"] = Value::Object(profiles);
+            payload["category_profiles"] = json!(self.category_profiles);
+        }
+        payload
+    }
+}
+
+/// Ask the backend to keep existing placements (ignored by backends that always reschedule)
+pub fn set_incremental(payload: &mut Value, stability: f64) {
+    payload["incremental"] = json!(true);
+    payload["stability"] = json!(stability);
+}
+
+/// Weekday-indexed windows as the backend expects them; breaks and focus blocks are cut out
+/// here because the backend only knows plain windows
+fn availability_table(a: &Availability) -> Value {
+    let table: serde_json::Map<String, Value> = (0..7)
+        .map(|i| {
+            let list: Vec<Value> = a.open_windows(i).iter().map(|w| json!({ "start": w.start, "end": w.end })).collect();
+            (i.to_string(), Value::Array(list))
+        })
+        .collect();
+    Value::Object(table)
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+pub fn path() -> PathBuf {
+    crate::paths::config_dir().join("config.toml")
+}
+
+/// Settings for every user of the machine, e.g. %ProgramData%\todo\config.toml
+pub fn system_path() -> Option<PathBuf> {
+    crate::paths::system_config_dir().map(|d| d.join("config.toml"))
+}
+
+fn overlay(base: &mut toml::Table, top: toml::Table) {
+    for (key, value) in top {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(t)) => overlay(b, t),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The user's config laid over the machine's, table by table, so setting `[availability]`
+/// keeps the machine's `[network]`
+pub fn layer(system: &str, user: &str) -> Result<String, Box<dyn Error>> {
+    let mut base: toml::Table = toml::from_str(system)?;
+    overlay(&mut base, toml::from_str(user)?);
+    Ok(toml::to_string(&base)?)
+}
+
+/// Read the config file over the machine-wide one, falling back to defaults when neither exists
+pub fn load() -> Result<Config, Box<dyn Error>> {
+    let p = path();
+    let user = if p.exists() { Some(fs::read_to_string(&p)?) } else { None };
+    let mut cfg: Config = match (system_path().filter(|s| s.exists()), user) {
+        (None, None) => Config::default(),
+        (None, Some(text)) => parse(&text).map_err(|e| format!("Invalid config {}: {}", p.display(), e))?,
+        (Some(s), user) => {
+            let text = layer(&fs::read_to_string(&s)?, user.as_deref().unwrap_or_default());
+            text.and_then(|t| parse(&t)).map_err(|e| format!("Invalid config {} (over {}): {}", p.display(), s.display(), e))?
+        }
+    };
+    if let Ok(url) = std::env::var(API_URL_ENV) {
+        cfg.api_url = url;
+    }
+    cfg.api_url = cfg.api_url.trim_end_matches('/').to_string();
+    cfg.validate().map_err(|e| format!("Invalid config {}: {}", p.display(), e))?;
+    Ok(cfg)
+}
+
+/// Config from TOML text; availability days it leaves out follow `[week] weekend`
+pub fn parse(text: &str) -> Result<Config, Box<dyn Error>> {
+    let mut cfg: Config = toml::from_str(text)?;
+    let raw: toml::Table = toml::from_str(text)?;
+    let weekend = cfg.week.weekend_days();
+    cfg.availability.fill_default_days(raw.get("availability").and_then(|a| a.as_table()), &weekend);
+    let given_profiles = raw.get("profiles").and_then(|p| p.as_table());
+    for (name, profile) in cfg.profiles.iter_mut() {
+        profile.fill_default_days(given_profiles.and_then(|p| p.get(name)).and_then(|a| a.as_table()), &weekend);
+    }
+    Ok(cfg)
+}
+
+/// Loaded once at startup by `main`
+pub fn init() -> Result<(), Box<dyn Error>> {
+    let mut cfg = load()?;
+    // Unreadable assignments (say, a locked vault without its key) only lose the day templates
+    cfg.assign_days(&crate::days::load().unwrap_or_default());
+    let _ = CONFIG.set(cfg);
+    Ok(())
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// `scheme://user:password@` at the start of a URL
+fn credentials() -> &'static regex::Regex {
+    static RE: OnceLock<regex::Regex> = OnceLock::new();
+    RE.get_or_init(|| regex::Regex::new(r"^([a-zA-Z][a-zA-Z0-9+.-]*://[^/:@]*):[^/@]*@").unwrap())
+}
+
+/// Apply `f` to every string in a TOML tree, however deep
+fn each_string<F: FnMut(&mut String) -> Result<(), Box<dyn Error>>>(value: &mut toml::Value, f: &mut F) -> Result<(), Box<dyn Error>> {
+    match value {
+        toml::Value::String(s) => f(s),
+        toml::Value::Array(items) => items.iter_mut().try_for_each(|v| each_string(v, f)),
+        toml::Value::Table(table) => table.iter_mut().try_for_each(|(_, v)| each_string(v, f)),
+        _ => Ok(()),
+    }
+}
+
+/// `todo config export`: a config file as portable TOML, with passwords in URLs (the only secrets
+/// a config holds) kept, dropped, or sealed with the vault key
+pub fn export(text: &str, secrets: Secrets) -> Result<String, Box<dyn Error>> {
+    let mut doc = toml::Value::Table(toml::from_str(text)?);
+    each_string(&mut doc, &mut |s| {
+        let Some(found) = credentials().captures(s) else { return Ok(()) };
+        let replaced = match secrets {
+            Secrets::Keep => return Ok(()),
+            Secrets::Omit => format!("{}@{}", &found[1], &s[found[0].len()..]),
+            Secrets::Encrypt => crate::vault::seal(s).map_err(|e| format!("Can't encrypt secrets: {}", e))?,
+        };
+        *s = replaced;
+        Ok(())
+    })?;
+    Ok(toml::to_string_pretty(&doc)?)
+}
+
+/// `todo config import`: an export with its sealed values opened, laid over `current` when
+/// given, and checked as `load` would
+pub fn import(exported: &str, current: Option<&str>) -> Result<String, Box<dyn Error>> {
+    let mut doc = toml::Value::Table(toml::from_str(exported)?);
+    each_string(&mut doc, &mut |s| {
+        if crate::vault::is_sealed(s) {
+            *s = crate::vault::open(s).map_err(|e| format!("Can't decrypt secrets ({}); set {} to the exporting machine's vault key", e, crate::vault::KEY_ENV))?;
+        }
+        Ok(())
+    })?;
+    let text = toml::to_string_pretty(&doc)?;
+    let text = match current {
+        Some(current) => layer(current, &text)?,
+        None => text,
+    };
+    parse(&text)?.validate()?;
+    Ok(text)
+}
+
+fn flatten(prefix: &str, value: &toml::Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, v) in table {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&path, v, out);
+            }
+        }
+        v => {
+            out.insert(prefix.to_string(), v.to_string());
+        }
+    }
+}
+
+/// `todo config diff`: every setting that isn't at its default, one line each
+pub fn diff(cfg: &Config) -> Result<Vec<String>, Box<dyn Error>> {
+    let (mut ours, mut base) = (BTreeMap::new(), BTreeMap::new());
+    flatten("", &toml::Value::try_from(cfg)?, &mut ours);
+    flatten("", &toml::Value::try_from(Config::default())?, &mut base);
+    let mut lines = Vec::new();
+    for (key, value) in &ours {
+        match base.get(key) {
+            Some(default) if default == value => {}
+            Some(default) => lines.push(format!("{} = {} (default {})", key, value, default)),
+            None => lines.push(format!("{} = {} (not set by default)", key, value)),
+        }
+    }
+    for (key, default) in &base {
+        if !ours.contains_key(key) {
+            lines.push(format!("{} removed (default {})", key, default));
+        }
+    }
+    Ok(lines)
+}
+
+pub fn save(cfg: &Config) -> Result<PathBuf, Box<dyn Error>> {
+    let p = path();
+    if let Some(dir) = p.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&p, toml::to_string_pretty(cfg)?)?;
+    Ok(p)
+}
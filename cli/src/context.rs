@@ -0,0 +1,44 @@
+use crate::commands::Task;
+use std::collections::BTreeMap;
+
+/// "@Errands" or "errands" → "errands"
+pub fn normalize(name: &str) -> String {
+    name.trim().trim_start_matches('@').to_lowercase()
+}
+
+fn is_context(word: &str) -> bool {
+    word.strip_prefix('@').is_some_and(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_'))
+}
+
+/// Pull `@word` tags out of a title: "Buy milk @errands" → ("Buy milk", ["errands"])
+pub fn split_title(title: &str) -> (String, Vec<String>) {
+    let mut contexts: Vec<String> = Vec::new();
+    let mut words = Vec::new();
+    for word in title.split_whitespace() {
+        if is_context(word) {
+            let c = normalize(word);
+            if !contexts.contains(&c) {
+                contexts.push(c);
+            }
+        } else {
+            words.push(word);
+        }
+    }
+    (words.join(" "), contexts)
+}
+
+/// True if the task carries any of `wanted` (already normalized)
+pub fn has_any(task: &Task, wanted: &[String]) -> bool {
+    task.contexts().iter().any(|c| wanted.contains(c))
+}
+
+/// Contexts on open tasks, with how many carry each
+pub fn in_use(tasks: &[Task]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for t in tasks.iter().filter(|t| t.status.as_deref() != Some("done")) {
+        for c in t.contexts() {
+            *counts.entry(c.clone()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
@@ -0,0 +1,266 @@
+use crate::api::Api;
+use crate::config::Daemon;
+use crate::db;
+use crate::platform::{Host, Os};
+use crate::push::Outcome;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the unit, agent or scheduled task
+pub const NAME: &str = "todo-daemon";
+const LAUNCHD_LABEL: &str = "com.importly.todo.daemon";
+
+/// Today's snapshot, unless one was already taken
+async fn snapshot(api: &Api, now: NaiveDateTime) -> Result<Option<String>, Box<dyn Error>> {
+    if crate::backup::snapshots()?.iter().any(|at| at.date() == now.date()) {
+        return Ok(None);
+    }
+    let b = crate::backup::create(api, &crate::stamp::format(now)).await?;
+    let pruned = crate::backup::snapshot(&b, now)?;
+    Ok(Some(format!("snapshot saved ({} tasks); pruned {}", b.tasks.len(), pruned)))
+}
+
+/// The last day each once-a-day job ran
+pub const STORE: &str = "daemon";
+
+/// Days as YYYY-MM-DD
+#[derive(Serialize, Deserialize, Default)]
+struct LastRun {
+    follow_ups: Option<String>,
+    rollover: Option<String>,
+}
+
+/// Whether a once-a-day job hasn't run on `today` yet; marks it as run
+fn first_today(job: fn(&mut LastRun) -> &mut Option<String>, today: NaiveDate) -> Result<bool, Box<dyn Error>> {
+    let today = today.format("%Y-%m-%d").to_string();
+    let mut last: LastRun = db::read_doc(STORE)?.unwrap_or_default();
+    if job(&mut last).as_deref() == Some(today.as_str()) {
+        return Ok(false);
+    }
+    *job(&mut last) = Some(today);
+    db::write_doc(STORE, &last)?;
+    Ok(true)
+}
+
+/// A line per waiting task due a follow-up, on the first round of each day
+async fn follow_ups(api: &Api, now: NaiveDateTime) -> Result<Vec<String>, Box<dyn Error>> {
+    if !first_today(|l| &mut l.follow_ups, now.date())? {
+        return Ok(Vec::new());
+    }
+    let meta = crate::meta::load()?;
+    let due = crate::handlers::follow_ups(api.tasks().await?, &meta, crate::config::get().waiting.follow_up_days, now.date());
+    Ok(due
+        .iter()
+        .map(|t| {
+            let m = meta.get(&t.id).cloned().unwrap_or_default();
+            let who = m.waiting_on.unwrap_or_else(|| "someone".into());
+            format!("follow up: [{}] {} (waiting on {} since {})", t.id, t.title, who, m.waiting_since.unwrap_or_default())
+        })
+        .collect())
+}
+
+/// Today's rollover, on the first round at or after `at`
+async fn rollover(api: &Api, at: NaiveTime, bump: bool, now: NaiveDateTime) -> Result<Option<String>, Box<dyn Error>> {
+    if now.time() < at || !first_today(|l| &mut l.rollover, now.date())? {
+        return Ok(None);
+    }
+    let r = crate::handlers::rollover(api, now.date(), bump, false).await?;
+    let ids: Vec<String> = r.slipped.iter().map(|t| format!("[{}]", t.id)).collect();
+    Ok(Some(match ids.is_empty() {
+        true => "rollover: nothing slipped today".into(),
+        false => format!("rolled over {} task(s): {}", ids.len(), ids.join(" ")),
+    }))
+}
+
+/// Pushes left in the journal, tried again; None when there were none
+async fn retry_pushes(api: &Api) -> Result<Option<String>, Box<dyn Error>> {
+    let journal = crate::push::load_journal()?;
+    if journal.is_empty() {
+        return Ok(None);
+    }
+    let reports = crate::push::push_all(api, Some(&journal.remaining()), 4).await?;
+    let count = |wanted: fn(&Outcome) -> bool| reports.iter().filter(|r| wanted(&r.outcome)).count();
+    Ok(Some(format!(
+        "retried {} pushes; {} pushed, {} still failing",
+        reports.len(),
+        count(|o| matches!(o, Outcome::Pushed(_))),
+        count(|o| matches!(o, Outcome::Failed(_) | Outcome::NotReached))
+    )))
+}
+
+/// One round of background work; failures are reported and the next round tries again
+pub async fn tick(api: &Api, cfg: &Daemon, now: NaiveDateTime) -> Vec<String> {
+    let mut out = Vec::new();
+    if cfg.sync_calendar {
+        out.push(match api.sync_calendar().await {
+            Ok(r) => format!("calendar synced; {} events imported", r.imported.unwrap_or(0)),
+            Err(e) => format!("calendar sync failed: {}", e),
+        });
+    }
+    if cfg.snapshot {
+        match snapshot(api, now).await {
+            Ok(Some(line)) => out.push(line),
+            Ok(None) => {}
+            Err(e) => out.push(format!("snapshot failed: {}", e)),
+        }
+    }
+    if cfg.follow_ups {
+        match follow_ups(api, now).await {
+            Ok(lines) => out.extend(lines),
+            Err(e) => out.push(format!("follow-up check failed: {}", e)),
+        }
+    }
+    if let Some(at) = cfg.rollover_time() {
+        match rollover(api, at, cfg.rollover_bump, now).await {
+            Ok(Some(line)) => out.push(line),
+            Ok(None) => {}
+            Err(e) => out.push(format!("rollover failed: {}", e)),
+        }
+    }
+    if cfg.retry_pushes {
+        match retry_pushes(api).await {
+            Ok(Some(line)) => out.push(line),
+            Ok(None) => {}
+            Err(e) => out.push(format!("push retry failed: {}", e)),
+        }
+    }
+    out
+}
+
+/// `todo daemon run`: a round every `interval_minutes` until stopped
+pub async fn run(api: &Api, cfg: &Daemon) -> Result<(), Box<dyn Error>> {
+    loop {
+        for line in tick(api, cfg, crate::status::now()).await {
+            crate::logfile::event("daemon", serde_json::json!({ "result": line }));
+            println!("{}", line);
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(cfg.interval_minutes * 60)) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// How the OS's service manager is told to keep `todo daemon run` going: a file to write, if
+/// it takes one, and the commands that start, inspect and remove it
+#[derive(PartialEq, Debug)]
+pub struct Service {
+    pub file: Option<(PathBuf, String)>,
+    pub enable: Vec<Vec<String>>,
+    pub status: Vec<String>,
+    pub disable: Vec<Vec<String>>,
+}
+
+fn argv(words: &[&str]) -> Vec<String> {
+    words.iter().map(|w| w.to_string()).collect()
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// A systemd user unit, a launchd agent, or a Task Scheduler task started at logon
+pub fn service(host: &Host, exe: &Path, log: &Path) -> Option<Service> {
+    let exe = exe.display().to_string();
+    match host.os {
+        Os::Linux => {
+            let unit = format!("{}.service", NAME);
+            let path = host.config_home()?.join("systemd").join("user").join(&unit);
+            let text = format!(
+                "[Unit]\nDescription=todo background sync\nAfter=network-online.target\n\n\
+                 [Service]\nExecStart=\"{}\" --no-input daemon run\nRestart=on-failure\nRestartSec=60\n\n\
+                 [Install]\nWantedBy=default.target\n",
+                exe
+            );
+            Some(Service {
+                file: Some((path, text)),
+                enable: vec![argv(&["systemctl", "--user", "daemon-reload"]), argv(&["systemctl", "--user", "enable", "--now", &unit])],
+                status: argv(&["systemctl", "--user", "status", "--no-pager", &unit]),
+                disable: vec![argv(&["systemctl", "--user", "disable", "--now", &unit])],
+            })
+        }
+        Os::MacOs => {
+            let path = host.home()?.join("Library").join("LaunchAgents").join(format!("{}.plist", LAUNCHD_LABEL));
+            let args: String = [exe.as_str(), "--no-input", "daemon", "run"].iter().map(|a| format!("    <string>{}</string>\n", xml_escape(a))).collect();
+            let log = xml_escape(&log.display().to_string());
+            let text = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+                 <plist version=\"1.0\">\n<dict>\n  <key>Label</key>\n  <string>{}</string>\n  <key>ProgramArguments</key>\n  <array>\n{}  </array>\n\
+                 \x20 <key>RunAtLoad</key>\n  <true/>\n  <key>KeepAlive</key>\n  <true/>\n\
+                 \x20 <key>StandardOutPath</key>\n  <string>{}</string>\n  <key>StandardErrorPath</key>\n  <string>{}</string>\n</dict>\n</plist>\n",
+                LAUNCHD_LABEL, args, log, log
+            );
+            let plist = path.display().to_string();
+            Some(Service {
+                file: Some((path, text)),
+                enable: vec![argv(&["launchctl", "load", "-w", &plist])],
+                status: argv(&["launchctl", "list", LAUNCHD_LABEL]),
+                disable: vec![argv(&["launchctl", "unload", "-w", &plist])],
+            })
+        }
+        Os::Windows => {
+            let action = format!("\"{}\" --no-input daemon run", exe);
+            Some(Service {
+                file: None,
+                enable: vec![
+                    argv(&["schtasks", "/Create", "/TN", NAME, "/TR", &action, "/SC", "ONLOGON", "/RL", "LIMITED", "/F"]),
+                    argv(&["schtasks", "/Run", "/TN", NAME]),
+                ],
+                status: argv(&["schtasks", "/Query", "/TN", NAME, "/V", "/FO", "LIST"]),
+                disable: vec![argv(&["schtasks", "/End", "/TN", NAME]), argv(&["schtasks", "/Delete", "/TN", NAME, "/F"])],
+            })
+        }
+    }
+}
+
+/// Run one of a service's commands; the exit status and everything it printed
+pub fn exec(command: &[String]) -> Result<(bool, String), Box<dyn Error>> {
+    let (program, args) = command.split_first().ok_or("Empty command")?;
+    let out = Command::new(program).args(args).output().map_err(|e| format!("Couldn't run {}: {}", program, e))?;
+    let text = format!("{}{}", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr));
+    Ok((out.status.success(), text.trim_end().to_string()))
+}
+
+impl Service {
+    /// Whether it's set up: its file is there, or the service manager knows it
+    pub fn installed(&self) -> bool {
+        match &self.file {
+            Some((path, _)) => path.exists(),
+            None => exec(&self.status).is_ok_and(|(ok, _)| ok),
+        }
+    }
+
+    /// Write the file and have the service manager start it now and at every login
+    pub fn install(&self) -> Result<(), Box<dyn Error>> {
+        if let Some((path, text)) = &self.file {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, text)?;
+        }
+        for command in &self.enable {
+            if let (false, out) = exec(command)? {
+                return Err(format!("`{}` failed: {}", command.join(" "), out).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop it and remove it; a step that fails (say, it was already stopped) doesn't stop the rest
+    pub fn uninstall(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut problems = Vec::new();
+        for command in &self.disable {
+            if let (false, out) = exec(command)? {
+                problems.push(format!("`{}`: {}", command.join(" "), out));
+            }
+        }
+        if let Some((path, _)) = &self.file {
+            std::fs::remove_file(path)?;
+        }
+        Ok(problems)
+    }
+}
@@ -1,6 +1,19 @@
-use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, Weekday};
+use crate::config::Config;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 use regex::Regex;
 use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static FUZZY: AtomicBool = AtomicBool::new(false);
+
+/// `--fuzzy-dates`: tidy dictated phrases with `spoken` before parsing them
+pub fn set_fuzzy(on: bool) {
+    FUZZY.store(on, Ordering::SeqCst);
+}
+
+fn fuzzy() -> bool {
+    FUZZY.load(Ordering::SeqCst) || crate::config::get().fuzzy_dates
+}
 
 /// Leap year check
 fn is_leap_year(year: i32) -> bool {
@@ -42,19 +55,217 @@ fn last_day_of_month(year: i32, month: u32) -> NaiveDate {
     NaiveDate::from_ymd_opt(year, month, day).unwrap()
 }
 
-/// Parse natural date/time to "YYYY-MM-DDTHH:MM:SS"
-pub fn parse_deadline(input: &str) -> Result<String, Box<dyn Error>> {
+/// The configured period a phrase like "sprint", "this Q3" or "next exam week" names, as its
+/// first and last day; `None` when no `[periods]` entry matches
+pub fn period_range(phrase: &str, today: NaiveDate, cfg: &Config) -> Result<Option<(NaiveDate, NaiveDate)>, String> {
+    let phrase = phrase.trim();
+    let phrase = phrase.strip_prefix("the ").unwrap_or(phrase);
+    let (name, skip) = match phrase.strip_prefix("next ") {
+        Some(name) => (name, 1),
+        None => (phrase.strip_prefix("this ").unwrap_or(phrase), 0),
+    };
+    let Some(period) = cfg.period(name) else { return Ok(None) };
+    match period.occurrence(today, skip)? {
+        Some(span) => Ok(Some(span)),
+        None => Err(format!("No upcoming '{}' in [periods]", name)),
+    }
+}
+
+/// "end of sprint", "start of next Q3", "before exam week" against `[periods]`;
+/// `None` when the phrase doesn't name a configured period
+pub fn period_date(phrase: &str, today: NaiveDate, cfg: &Config) -> Result<Option<NaiveDate>, String> {
+    if let Some(rest) = phrase.strip_prefix("end of ") {
+        return Ok(period_range(rest, today, cfg)?.map(|(_, last)| last));
+    }
+    if let Some(rest) = phrase.strip_prefix("start of ").or_else(|| phrase.strip_prefix("beginning of ")) {
+        return Ok(period_range(rest, today, cfg)?.map(|(first, _)| first));
+    }
+    let Some(rest) = phrase.strip_prefix("before ") else { return Ok(None) };
+    // The day before it starts, so one already under way counts from the next
+    Ok(match period_range(rest, today, cfg)? {
+        Some((first, last)) if first <= today => period_range(rest, last + Duration::days(1), cfg)?.map(|(f, _)| f - Duration::days(1)),
+        span => span.map(|(first, _)| first - Duration::days(1)),
+    })
+}
+
+/// "friday evening" as "friday" and the `[day_parts]` span of evening; "this evening" and
+/// "tonight" are today
+fn day_part<'a>(phrase: &'a str, cfg: &Config) -> Option<(&'a str, NaiveTime, Option<NaiveTime>)> {
+    let (day, part) = match phrase.rsplit_once(' ') {
+        _ if phrase == "tonight" => ("today", "night"),
+        Some(("this", part)) => ("today", part),
+        Some((day, part)) => (day.trim(), part),
+        None => ("today", phrase),
+    };
+    let (start, end) = cfg.day_parts.span(part)?;
+    Some((day, start, end))
+}
+
+const ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve", "thirteen",
+    "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const TENS: [(&str, u32); 4] = [("twenty", 20), ("thirty", 30), ("forty", 40), ("fifty", 50)];
+
+/// Spelled-out numbers as digits: "twenty five" → "25", "five oh five" → "5 05"
+fn digits(phrase: &str) -> String {
+    let ones = |w: &str| ONES.iter().position(|o| *o == w).map(|n| n as u32);
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let next = words.get(i + 1).and_then(|w| ones(w)).filter(|n| (1..10).contains(n));
+        if let Some((_, tens)) = TENS.iter().find(|(w, _)| *w == words[i]) {
+            out.push((tens + next.unwrap_or(0)).to_string());
+            i += if next.is_some() { 2 } else { 1 };
+        } else if let (Some(n), true) = (next, words[i] == "oh" && !out.is_empty()) {
+            out.push(format!("0{}", n));
+            i += 2;
+        } else {
+            out.push(ones(words[i]).map_or(words[i].to_string(), |n| n.to_string()));
+            i += 1;
+        }
+    }
+    out.join(" ")
+}
+
+/// A dictated phrase tidied for the parser: "due next thursday at five thirty in the afternoon"
+/// → "next thursday at 5:30 pm". Times without am/pm are read as working hours (7 to 6)
+pub fn spoken(input: &str) -> String {
+    let s = input.to_lowercase().replace("a.m.", "am").replace("p.m.", "pm");
+    // "5.30" is a time; other dots and commas only count where they end a word, so ISO dates
+    // and "2030-05-06..2030-05-10" ranges come through whole
+    let s = Regex::new(r"\b(\d{1,2})\.(\d{2})\b").unwrap().replace_all(&s, "$1:$2");
+    let s = digits(&Regex::new(r"[.,](\s|$)").unwrap().replace_all(&s, " "));
+    let time = Regex::new(
+        r"(?P<at>\bat )?\b(?:(?P<frac>half|quarter) (?P<dir>past|to) (?P<fh>\d{1,2})|(?P<h>\d{1,2})(?:[: ](?P<m>\d{2}))?(?P<oclock> o'?clock)?|(?P<noon>noon|midday))\b(?: (?P<mer>am|pm|in the morning|in the afternoon|in the evening|at night|tonight))?",
+    )
+    .unwrap();
+    // The last thing that is clearly a time; bare numbers may be part of the date
+    let found = time.captures_iter(&s).filter(|c| ["at", "frac", "m", "oclock", "noon", "mer"].iter().any(|n| c.name(n).is_some())).last();
+    let Some(c) = found else {
+        let s = Regex::new(r"\b(?:in the |this )?(morning|afternoon|evening)\b").unwrap().replace_all(&s, "$1");
+        return tidy(&s);
+    };
+    let (mut h, m): (u32, u32) = match (c.name("frac"), c.name("fh"), c.name("h")) {
+        _ if c.name("noon").is_some() => (12, 0),
+        (Some(f), Some(fh), _) => {
+            let fh: u32 = fh.as_str().parse().unwrap_or(0);
+            match (f.as_str(), c.name("dir").map(|d| d.as_str())) {
+                ("half", Some("past")) => (fh, 30),
+                ("quarter", Some("past")) => (fh, 15),
+                ("half", _) => ((fh + 11) % 12, 30),
+                _ => ((fh + 11) % 12, 45),
+            }
+        }
+        (_, _, Some(h)) => (h.as_str().parse().unwrap_or(0), c.name("m").map_or(0, |m| m.as_str().parse().unwrap_or(0))),
+        _ => (0, 0),
+    };
+    let pm = match c.name("mer").map(|m| m.as_str()) {
+        Some("am" | "in the morning") => false,
+        Some(_) => true,
+        None => h >= 12 || (1..7).contains(&h),
+    };
+    if h > 12 {
+        h -= 12;
+    }
+    let whole = c.get(0).unwrap();
+    let mut day = tidy(&format!("{} {}", &s[..whole.start()], &s[whole.end()..]));
+    if day.is_empty() {
+        day = "today".into();
+    }
+    format!("{} at {}:{:02} {}", day, if h == 0 { 12 } else { h }, m, if pm { "pm" } else { "am" })
+}
+
+/// Single spaces, without leading "due"/"by"/"on" or a dangling "at"
+fn tidy(s: &str) -> String {
+    let mut s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    for lead in ["due ", "by ", "on "] {
+        s = s.strip_prefix(lead).map_or(s.clone(), str::to_string);
+    }
+    s.strip_suffix(" at").or(s.strip_suffix(" on")).unwrap_or(&s).to_string()
+}
+
+/// What a deadline phrase was read as, and the other days it could have meant
+#[derive(Debug, PartialEq)]
+pub struct Interpretation {
+    /// "YYYY-MM-DDTHH:MM:SS"
+    pub chosen: String,
+    pub alternatives: Vec<String>,
+}
+
+/// "friday", "this fri", "next friday", "friday after next" or "last friday", plus the same day a week later when that's a
+/// plausible reading ("friday" on a Friday, "next friday" said in the same week or on a Friday)
+fn weekday_date(phrase: &str, today: NaiveDate) -> Option<(NaiveDate, Option<NaiveDate>)> {
+    if let Some(name) = phrase.strip_suffix(" after next") {
+        // "monday after next": a week past the coming one
+        let (d, _) = weekday_date(&format!("next {}", name), today)?;
+        return Some((d + Duration::weeks(1), None));
+    }
+    if let Some(name) = phrase.strip_prefix("last ") {
+        // The most recent one before today
+        let wd: Weekday = name.parse().ok()?;
+        let back = (today.weekday().num_days_from_monday() as i64 - wd.num_days_from_monday() as i64).rem_euclid(7);
+        return Some((today - Duration::days(if back == 0 { 7 } else { back }), None));
+    }
+    let (next, name) = match phrase.split_once(' ') {
+        Some(("next", name)) => (true, name),
+        Some(("this", name)) => (false, name),
+        Some(_) => return None,
+        None => (false, phrase),
+    };
+    let wd: Weekday = name.parse().ok()?;
+    let ahead = (wd.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64).rem_euclid(7);
+    if !next {
+        let d = today + Duration::days(ahead);
+        return Some((d, (ahead == 0).then(|| d + Duration::weeks(1))));
+    }
+    let ahead = if ahead == 0 { 7 } else { ahead };
+    let d = today + Duration::days(ahead);
+    let week = &crate::config::get().week;
+    let same_week = week.start_of(d) == week.start_of(today);
+    Some((d, (ahead == 7 || same_week).then(|| d + Duration::weeks(1))))
+}
+
+/// Parse natural date/time to "YYYY-MM-DDTHH:MM:SS", with any other readings of an ambiguous
+/// phrase ("next friday", "3/4/25")
+pub fn parse_deadline(input: &str) -> Result<Interpretation, Box<dyn Error>> {
+    parse_deadline_on(input, Local::now().date_naive())
+}
+
+/// `parse_deadline` as if it were `today`
+pub fn parse_deadline_on(input: &str, today: NaiveDate) -> Result<Interpretation, Box<dyn Error>> {
     let raw = input.trim();
+    let cfg = crate::config::get();
+    let default_time = NaiveTime::from_hms_opt(21, 0, 0).unwrap();
+    if let Some((date, time)) = cfg.locale.as_deref().and_then(crate::lang::pack).and_then(|p| p.read(raw, today, cfg)) {
+        let time = time.unwrap_or(default_time);
+        return Ok(Interpretation { chosen: crate::stamp::format(date.and_time(time)), alternatives: Vec::new() });
+    }
+    let tidied;
+    let raw = match fuzzy() {
+        true => {
+            tidied = spoken(raw);
+            tidied.as_str()
+        }
+        false => raw,
+    };
     let s = raw.strip_prefix("due ").unwrap_or(raw).trim().to_lowercase();
 
     let (date_part, time_part_str) = if let Some(idx) = s.rfind(" at ") {
         let (d, t) = s.split_at(idx);
         (d.trim(), Some(t[4..].trim()))
+    } else if let Some((d, t)) = s.rsplit_once(' ').filter(|(_, t)| parse_time(t).is_some()) {
+        // "tomorrow 3pm" reads as "tomorrow at 3pm"
+        (d.trim(), Some(t))
     } else {
         (s.as_str(), None)
     };
 
-    let default_time = NaiveTime::from_hms_opt(21, 0, 0).unwrap();
+    let (date_part, part_time) = match day_part(date_part, cfg) {
+        Some((day, start, _)) => (day, Some(start)),
+        None => (date_part, None),
+    };
 
     /// Parse "5 pm", "5:00 pm", etc.
     fn parse_time(t: &str) -> Option<NaiveTime> {
@@ -70,7 +281,7 @@ pub fn parse_deadline(input: &str) -> Result<String, Box<dyn Error>> {
         let re = Regex::new(r"^(?P<h>\d{1,2})\s*(?P<ap>(?:AM|PM))$").unwrap();
         if let Some(cap) = re.captures(&t_up) {
             if let Ok(hour12) = cap["h"].parse::<u32>() {
-                if hour12 >= 1 && hour12 <= 12 {
+                if (1..=12).contains(&hour12) {
                     let mut hour24 = hour12;
                     if &cap["ap"] == "PM" && hour12 != 12 {
                         hour24 += 12;
@@ -89,25 +300,21 @@ pub fn parse_deadline(input: &str) -> Result<String, Box<dyn Error>> {
         parse_time(tstr)
             .ok_or_else(|| format!("Invalid time format: '{}'", tstr))?
     } else {
-        default_time
+        part_time.unwrap_or(default_time)
     };
 
-    let today = Local::now().date_naive();
 
+    let mut alternatives = Vec::new();
     let date = match date_part {
         "today" => today,
-        "tmr" | "tomorrow" => today + Duration::days(1),
+        "tmr" | "tomorrow" => cfg.week.tomorrow(today),
+        "next workday" | "next working day" | "next business day" => cfg.week.next_workday(today),
         "day after tomorrow" | "day after tmr" => today + Duration::days(2),
         "yesterday" => today - Duration::days(1),
         "day before yesterday" => today - Duration::days(2),
         "next week" => today + Duration::weeks(1),
         "week after next week" => today + Duration::weeks(2),
-        "end of this week" | "end of week" => {
-            let today_wd = today.weekday().number_from_monday();
-            let friday_wd = Weekday::Fri.number_from_monday();
-            let delta = friday_wd as i8 - today_wd as i8;
-            today + Duration::days(delta as i64)
-        }
+        "end of this week" | "end of week" => cfg.week.last_workday(today),
         "next month" => add_months(today, 1),
         "end of this month" | "end of month" => last_day_of_month(today.year(), today.month()),
         "end of next month" => {
@@ -116,10 +323,19 @@ pub fn parse_deadline(input: &str) -> Result<String, Box<dyn Error>> {
         }
         other => {
             let re = Regex::new(r"^(\d{1,2})/(\d{1,2})/(\d{2})$").map_err(|e| e.to_string())?;
-            if let Some(cap) = re.captures(other) {
+            if let Some(d) = period_date(other, today, cfg)? {
+                d
+            } else if let Some((d, alternative)) = weekday_date(other, today) {
+                alternatives.extend(alternative);
+                d
+            } else if let Some(cap) = re.captures(other) {
                 let m: u32 = cap[1].parse()?;
                 let d: u32 = cap[2].parse()?;
                 let y: u32 = 2000 + cap[3].parse::<u32>()?;
+                // Read as M/D/YY; D/M/YY is also a real date when both are 12 or less
+                if m != d && d <= 12 {
+                    alternatives.extend(NaiveDate::from_ymd_opt(y as i32, d, m));
+                }
                 NaiveDate::from_ymd_opt(y as i32, m, d)
                     .ok_or_else(|| "Invalid calendar date".to_string())?
             } else {
@@ -128,5 +344,83 @@ pub fn parse_deadline(input: &str) -> Result<String, Box<dyn Error>> {
         }
     };
 
-    Ok(format!("{}T{}", date.format("%Y-%m-%d"), time.format("%H:%M:%S")))
+    let iso = |d: NaiveDate| crate::stamp::format(d.and_time(time));
+    Ok(Interpretation { chosen: iso(date), alternatives: alternatives.into_iter().map(iso).collect() })
+}
+
+/// Parse "2h", "90m", "1h30m", "1.5h" or bare minutes
+pub fn parse_duration(input: &str) -> Result<Duration, Box<dyn Error>> {
+    let s = input.trim().to_lowercase().replace(' ', "");
+    let too_long = || format!("Duration '{}' is too long", input);
+    if let Ok(mins) = s.parse::<i64>() {
+        return Ok(Duration::try_minutes(mins).ok_or_else(too_long)?);
+    }
+    let re = Regex::new(r"^(?:(?P<h>\d+(?:\.\d+)?)h)?(?:(?P<m>\d+)m(?:in)?)?$").map_err(|e| e.to_string())?;
+    let cap = re.captures(&s).ok_or_else(|| format!("Invalid duration: '{}' (try 2h, 45m, 1h30m)", input))?;
+    let hours: f64 = cap.name("h").map_or(Ok(0.0), |h| h.as_str().parse())?;
+    let mins: i64 = cap.name("m").map_or(Ok(0), |m| m.as_str().parse())?;
+    // `as` saturates, so an absurd hour count fails below rather than wrapping
+    let total = ((hours * 60.0).round() as i64).checked_add(mins).ok_or_else(too_long)?;
+    if total <= 0 {
+        return Err(format!("Invalid duration: '{}' (try 2h, 45m, 1h30m)", input).into());
+    }
+    Ok(Duration::try_minutes(total).ok_or_else(too_long)?)
+}
+
+/// Parse a period like "today", "this week", "next 3 days" or "2030-05-06..2030-05-10"
+/// into a start/end pair; periods already under way start now
+pub fn parse_range(input: &str) -> Result<(NaiveDateTime, NaiveDateTime), Box<dyn Error>> {
+    parse_range_at(input, Local::now().naive_local())
+}
+
+/// `parse_range` as if it were `now`
+pub fn parse_range_at(input: &str, now: NaiveDateTime) -> Result<(NaiveDateTime, NaiveDateTime), Box<dyn Error>> {
+    let today = now.date();
+    let midnight = |d: NaiveDate| d.and_hms_opt(0, 0, 0).unwrap();
+    let week = &crate::config::get().week;
+    let week_start = week.start_of(today);
+    let s = match fuzzy() {
+        true => spoken(input),
+        false => input.trim().to_lowercase(),
+    };
+    let too_far = || format!("Period '{}' runs past the end of the calendar", input);
+    let plus = |d: NaiveDate, days: i64| Duration::try_days(days).and_then(|n| d.checked_add_signed(n)).ok_or_else(too_far);
+
+    let (start, end) = match s.as_str() {
+        "today" => (now, midnight(plus(today, 1)?)),
+        "tmr" | "tomorrow" => {
+            let day = week.tomorrow(today);
+            (midnight(day), midnight(plus(day, 1)?))
+        }
+        "next workday" | "next working day" | "next business day" => {
+            let day = week.next_workday(today);
+            (midnight(day), midnight(plus(day, 1)?))
+        }
+        "this week" | "week" => (now, midnight(plus(week_start, 7)?)),
+        "next week" => (midnight(plus(week_start, 7)?), midnight(plus(week_start, 14)?)),
+        "this month" | "month" => (now, midnight(plus(last_day_of_month(today.year(), today.month()), 1)?)),
+        other => {
+            let days = Regex::new(r"^next (\d+) days?$").map_err(|e| e.to_string())?;
+            if let Some(cap) = days.captures(other) {
+                let count = cap[1].parse::<i64>().ok().and_then(|n| n.checked_add(1)).ok_or_else(too_far)?;
+                (now, midnight(plus(today, count)?))
+            } else if let Some((a, b)) = other.split_once("..") {
+                let parse = |d: &str| {
+                    NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").map_err(|_| format!("Invalid date '{}' (use YYYY-MM-DD)", d.trim()))
+                };
+                (midnight(parse(a)?), midnight(plus(parse(b)?, 1)?))
+            } else if let Some((first, last)) = period_range(other, today, crate::config::get())? {
+                (now.max(midnight(first)), midnight(plus(last, 1)?))
+            } else if let Some((day, from, to)) = day_part(other, crate::config::get()) {
+                let date = crate::slots::parse_datetime(&parse_deadline_on(day, today)?.chosen).ok_or("Invalid date")?.date();
+                (now.max(date.and_time(from)), to.map_or_else(|| plus(date, 1).map(midnight), |t| Ok(date.and_time(t)))?)
+            } else {
+                return Err(format!("Unrecognized period: '{}'", input).into());
+            }
+        }
+    };
+    if end <= start {
+        return Err(format!("Period '{}' is empty", input).into());
+    }
+    Ok((start, end))
 }
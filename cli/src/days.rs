@@ -0,0 +1,42 @@
+use crate::config::Config;
+use crate::db;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Day templates assigned to dates: date → template name
+pub type DayPlan = BTreeMap<NaiveDate, String>;
+
+pub const STORE: &str = "days";
+
+pub fn load() -> Result<DayPlan, Box<dyn Error>> {
+    let raw: BTreeMap<String, String> = db::read_doc(STORE)?.unwrap_or_default();
+    raw.into_iter()
+        .map(|(d, name)| Ok((NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|e| format!("days.json: bad date '{}': {}", d, e))?, name)))
+        .collect()
+}
+
+fn save(plan: &DayPlan) -> Result<(), Box<dyn Error>> {
+    let raw: BTreeMap<String, &String> = plan.iter().map(|(d, name)| (d.to_string(), name)).collect();
+    db::write_doc(STORE, &raw)
+}
+
+/// Assign the template `name` to `date`; returns the name as configured
+pub fn set(date: NaiveDate, name: &str, cfg: &Config) -> Result<String, Box<dyn Error>> {
+    let (name, _) = cfg.day_template(name).ok_or_else(|| match cfg.day_templates.is_empty() {
+        true => format!("No day template '{}'; add one under [day_templates.{}] in the config file", name, name),
+        false => format!("No day template '{}' (configured: {})", name, cfg.day_templates.keys().cloned().collect::<Vec<_>>().join(", ")),
+    })?;
+    let mut plan = load()?;
+    plan.insert(date, name.clone());
+    save(&plan)?;
+    Ok(name.clone())
+}
+
+/// Back to the weekday's usual hours; returns whether a template was assigned
+pub fn clear(date: NaiveDate) -> Result<bool, Box<dyn Error>> {
+    let mut plan = load()?;
+    let existed = plan.remove(&date).is_some();
+    save(&plan)?;
+    Ok(existed)
+}
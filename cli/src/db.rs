@@ -0,0 +1,255 @@
+use crate::store::{decode, encode};
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Schema changes in order; a database at `user_version` n has had the first n applied
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE lines (store TEXT NOT NULL, seq INTEGER NOT NULL, line TEXT NOT NULL, PRIMARY KEY (store, seq));
+     CREATE TABLE quarantine (store TEXT NOT NULL, seq INTEGER NOT NULL, line TEXT NOT NULL, reason TEXT NOT NULL, moved_at TEXT NOT NULL);",
+    // Full-text search by task ID; the JSON index it replaces is dropped, to be rebuilt on first use
+    "CREATE VIRTUAL TABLE search USING fts5(title, text, tokenize = 'unicode61 remove_diacritics 0');
+     DELETE FROM lines WHERE store = 'search_index';",
+];
+
+/// The one SQLite file every local store lives in (the data dir's `todo.db`)
+pub fn path() -> PathBuf {
+    crate::paths::data_dir().join("todo.db")
+}
+
+/// JSON files the stores were kept in before the database; read in once, then deleted
+fn legacy_files() -> Vec<(&'static str, PathBuf)> {
+    let (data, state, cache) = (crate::paths::data_dir(), crate::paths::state_dir(), crate::paths::cache_dir());
+    vec![
+        (crate::audit::STORE, data.join("audit.jsonl")),
+        (crate::archive::STORE, data.join("archive.jsonl")),
+        (crate::meta::STORE, data.join("task_meta.json")),
+        (crate::views::STORE, data.join("views.json")),
+        (crate::habits::STORE, data.join("habits.json")),
+        (crate::reminders::STORE, data.join("reminders.json")),
+        (crate::days::STORE, data.join("days.json")),
+        (crate::rows::STORE, state.join("last_rows.json")),
+        (crate::push::JOURNAL, state.join("push_journal.json")),
+        (crate::batch::JOURNAL, state.join("batch_journal.json")),
+        (crate::status::STORE, cache.join("status.json")),
+        (crate::search::STORE, cache.join("search-index.json")),
+    ]
+}
+
+/// WAL keeps a crash from tearing what's already committed; FULL syncs each commit to disk
+fn connect(path: &Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.busy_timeout(Duration::from_secs(5))?;
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |r| r.get::<_, String>(0))?;
+    conn.pragma_update(None, "synchronous", "FULL")?;
+    Ok(conn)
+}
+
+/// Bring the schema up to date and read in any JSON files left from before the database
+fn migrate(conn: &mut Connection) -> Result<(), Box<dyn Error>> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+    let legacy: Vec<(&str, PathBuf)> = legacy_files().into_iter().filter(|(_, file)| file.exists()).collect();
+    if version == MIGRATIONS.len() && legacy.is_empty() {
+        return Ok(());
+    }
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    // Another process may have got here first
+    let version: usize = tx.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+    if version > MIGRATIONS.len() {
+        return Err(format!("{} is from a newer todo (schema {}); upgrade to use it", path().display(), version).into());
+    }
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+    }
+    for (store, file) in &legacy {
+        let (count, first): (i64, i64) = tx.query_row("SELECT COUNT(*), COALESCE(MIN(seq), 1) FROM lines WHERE store = ?1", [store], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        let log = file.extension().is_some_and(|e| e == "jsonl");
+        // The database's copy of a document is the newer one
+        if count > 0 && !log {
+            continue;
+        }
+        // Lines go in as they are on disk, sealed or not; a log's go before the database's,
+        // being older (`migrate-data` can bring one back from ~/.todo)
+        let text = fs::read_to_string(file)?;
+        let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+        let start = first - lines.len() as i64;
+        for (i, line) in lines.iter().enumerate() {
+            tx.execute("INSERT INTO lines (store, seq, line) VALUES (?1, ?2, ?3)", params![store, start + i as i64, line])?;
+        }
+    }
+    tx.commit()?;
+    for (_, file) in legacy {
+        fs::remove_file(file)?;
+    }
+    Ok(())
+}
+
+/// A connection with the schema up to date, for tables of their own (`search`)
+pub fn open() -> Result<Connection, Box<dyn Error>> {
+    let db = path();
+    if let Some(dir) = db.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut conn = connect(&db).map_err(|e| format!("Can't open {}: {}; `todo db repair` may fix it", db.display(), e))?;
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+/// Keys of every store with something in it
+pub fn stores() -> Result<Vec<String>, Box<dyn Error>> {
+    let conn = open()?;
+    let mut stmt = conn.prepare("SELECT DISTINCT store FROM lines ORDER BY store")?;
+    let keys = stmt.query_map([], |r| r.get(0))?.collect::<Result<_, _>>()?;
+    Ok(keys)
+}
+
+/// A store's lines in order, decrypting sealed ones
+pub fn read_lines(store: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let conn = open()?;
+    let mut stmt = conn.prepare("SELECT line FROM lines WHERE store = ?1 ORDER BY seq")?;
+    let raw: Vec<String> = stmt.query_map([store], |r| r.get(0))?.collect::<Result<_, _>>()?;
+    raw.iter().map(|l| decode(l)).collect()
+}
+
+/// Add one line after the rest
+pub fn append_line(store: &str, line: &str) -> Result<(), Box<dyn Error>> {
+    let encoded = encode(line)?;
+    open()?.execute(
+        "INSERT INTO lines (store, seq, line) VALUES (?1, (SELECT COALESCE(MAX(seq), 0) + 1 FROM lines WHERE store = ?1), ?2)",
+        params![store, encoded],
+    )?;
+    Ok(())
+}
+
+/// Replace a store's lines in one transaction, so a crash leaves the old ones or the new
+pub fn write_lines(store: &str, lines: &[String]) -> Result<(), Box<dyn Error>> {
+    let encoded = lines.iter().map(|l| encode(l)).collect::<Result<Vec<_>, _>>()?;
+    let mut conn = open()?;
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM lines WHERE store = ?1", [store])?;
+    for (seq, line) in encoded.iter().enumerate() {
+        tx.execute("INSERT INTO lines (store, seq, line) VALUES (?1, ?2, ?3)", params![store, seq + 1, line])?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Load a single-document store, if it has been saved
+pub fn read_doc<T: DeserializeOwned>(store: &str) -> Result<Option<T>, Box<dyn Error>> {
+    match read_lines(store)?.first() {
+        Some(line) => Ok(Some(serde_json::from_str(line)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn write_doc<T: Serialize>(store: &str, doc: &T) -> Result<(), Box<dyn Error>> {
+    write_lines(store, &[serde_json::to_string(doc)?])
+}
+
+pub fn remove(store: &str) -> Result<(), Box<dyn Error>> {
+    open()?.execute("DELETE FROM lines WHERE store = ?1", [store])?;
+    Ok(())
+}
+
+/// What SQLite's own integrity check finds wrong with the file
+fn damage(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let found: Vec<String> = stmt.query_map([], |r| r.get(0))?.collect::<Result<_, _>>()?;
+    Ok(found.into_iter().filter(|f| f != "ok").collect())
+}
+
+/// A stored line: its store, position and text as written
+type Row = (String, i64, String);
+
+/// Every line that decrypts but isn't JSON, and why: what a torn or hand-edited write leaves
+fn unreadable(conn: &Connection) -> Result<Vec<(Row, String)>, Box<dyn Error>> {
+    let mut stmt = conn.prepare("SELECT store, seq, line FROM lines ORDER BY store, seq")?;
+    let rows: Vec<Row> = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?.collect::<Result<_, _>>()?;
+    let mut bad = Vec::new();
+    for (store, seq, line) in rows {
+        // Lines that won't decrypt are left for the vault to report: the key may just be missing
+        let Ok(plain) = decode(&line) else { continue };
+        if let Err(e) = serde_json::from_str::<serde_json::Value>(&plain) {
+            bad.push(((store, seq, line), e.to_string()));
+        }
+    }
+    Ok(bad)
+}
+
+/// `todo db check`: everything wrong with the database; empty when it's healthy
+pub fn check() -> Result<Vec<String>, Box<dyn Error>> {
+    let conn = match open() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(vec![e.to_string()]),
+    };
+    let damaged = damage(&conn)?;
+    if !damaged.is_empty() {
+        return Ok(damaged);
+    }
+    Ok(unreadable(&conn)?.into_iter().map(|((store, seq, _), e)| format!("{} line {} isn't valid: {}", store, seq, e)).collect())
+}
+
+/// Lines still readable in a damaged database, store by store, stopping each at its first bad page
+fn salvage(file: &Path) -> Vec<Row> {
+    let Ok(old) = Connection::open(file) else { return Vec::new() };
+    let stores: Vec<String> = match old.prepare("SELECT DISTINCT store FROM lines") {
+        Ok(mut stmt) => stmt.query_map([], |r| r.get(0)).map(|rows| rows.map_while(Result::ok).collect()).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    let mut found = Vec::new();
+    for store in stores {
+        let Ok(mut stmt) = old.prepare("SELECT seq, line FROM lines WHERE store = ?1 ORDER BY seq") else { continue };
+        let Ok(rows) = stmt.query_map([&store], |r| Ok((r.get(0)?, r.get(1)?))) else { continue };
+        found.extend(rows.map_while(Result::ok).map(|(seq, line)| (store.clone(), seq, line)));
+    }
+    found
+}
+
+/// `todo db repair`: move a damaged database aside and rebuild it from what still reads, then
+/// quarantine lines that aren't valid; a line per thing done
+pub fn repair() -> Result<Vec<String>, Box<dyn Error>> {
+    let db = path();
+    let mut done = Vec::new();
+    let damaged = match connect(&db) {
+        Ok(conn) => !damage(&conn).map(|d| d.is_empty()).unwrap_or(false),
+        Err(_) => true,
+    };
+    if damaged && db.exists() {
+        let aside = PathBuf::from(format!("{}.corrupt-{}", db.display(), chrono::Local::now().format("%Y%m%d-%H%M%S")));
+        // The WAL and shared-memory files belong to the damaged database too
+        for suffix in ["", "-wal", "-shm"] {
+            let from = PathBuf::from(format!("{}{}", db.display(), suffix));
+            if from.exists() {
+                fs::rename(&from, format!("{}{}", aside.display(), suffix))?;
+            }
+        }
+        let rows = salvage(&aside);
+        let mut conn = open()?;
+        let tx = conn.transaction()?;
+        for (store, seq, line) in &rows {
+            tx.execute("INSERT OR IGNORE INTO lines (store, seq, line) VALUES (?1, ?2, ?3)", params![store, seq, line])?;
+        }
+        tx.commit()?;
+        done.push(format!("moved the damaged database to {}", aside.display()));
+        done.push(format!("rebuilt {} from {} lines that still read", db.display(), rows.len()));
+    }
+    let mut conn = open()?;
+    let bad = unreadable(&conn)?;
+    let tx = conn.transaction()?;
+    let now = crate::stamp::format(chrono::Local::now().naive_local());
+    for ((store, seq, line), e) in &bad {
+        tx.execute(
+            "INSERT INTO quarantine (store, seq, line, reason, moved_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![store, seq, line, e, now],
+        )?;
+        tx.execute("DELETE FROM lines WHERE store = ?1 AND seq = ?2", params![store, seq])?;
+        done.push(format!("quarantined {} line {} ({})", store, seq, e));
+    }
+    tx.commit()?;
+    Ok(done)
+}
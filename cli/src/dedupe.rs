@@ -0,0 +1,123 @@
+use crate::commands::Task;
+use crate::{slots, stamp};
+
+/// Lowercased words, punctuation dropped: "Call Bob!" → "call bob"
+fn normalize(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bigrams(s: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = s.chars().collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// Dice coefficient over character pairs of the normalized titles, 0–1
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize(a), normalize(b));
+    if a == b {
+        return 1.0;
+    }
+    let (x, mut y) = (bigrams(&a), bigrams(&b));
+    if x.is_empty() || y.is_empty() {
+        return 0.0;
+    }
+    let total = (x.len() + y.len()) as f64;
+    let mut shared = 0;
+    for pair in x {
+        if let Some(i) = y.iter().position(|p| *p == pair) {
+            y.swap_remove(i);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total
+}
+
+fn deadline_day(t: &Task) -> Option<chrono::NaiveDate> {
+    t.deadline.as_deref().and_then(slots::parse_datetime).map(|d| d.date())
+}
+
+fn same_bucket(a: &Task, b: &Task) -> bool {
+    a.kind == b.kind
+        && deadline_day(a) == deadline_day(b)
+        && a.category.as_ref().map(|c| c.id) == b.category.as_ref().map(|c| c.id)
+}
+
+/// Groups of open tasks with similar titles, the same deadline day and the same category;
+/// each group is ordered by ID
+pub fn clusters(tasks: &[Task], threshold: f64) -> Vec<Vec<Task>> {
+    let open: Vec<&Task> = tasks.iter().filter(|t| t.status.as_deref() != Some("done")).collect();
+    let mut group: Vec<usize> = (0..open.len()).collect();
+    fn root(group: &mut [usize], i: usize) -> usize {
+        let mut r = i;
+        while group[r] != r {
+            r = group[r];
+        }
+        group[i] = r;
+        r
+    }
+    for i in 0..open.len() {
+        for j in i + 1..open.len() {
+            if same_bucket(open[i], open[j]) && similarity(&open[i].title, &open[j].title) >= threshold {
+                let (a, b) = (root(&mut group, i), root(&mut group, j));
+                group[b] = a;
+            }
+        }
+    }
+    let mut out: Vec<Vec<Task>> = Vec::new();
+    let mut roots: Vec<usize> = Vec::new();
+    for (i, task) in open.iter().enumerate() {
+        let r = root(&mut group, i);
+        match roots.iter().position(|x| *x == r) {
+            Some(k) => out[k].push((*task).clone()),
+            None => {
+                roots.push(r);
+                out.push(vec![(*task).clone()]);
+            }
+        }
+    }
+    out.retain(|c| c.len() > 1);
+    for c in &mut out {
+        c.sort_by_key(|t| t.id);
+    }
+    out
+}
+
+/// What a cluster becomes: the oldest task, with the others folded into it
+#[derive(PartialEq, Debug)]
+pub struct Merge {
+    pub keep: i32,
+    pub remove: Vec<i32>,
+    /// Earliest deadline in the group
+    pub deadline: Option<String>,
+    /// Highest priority in the group
+    pub priority: Option<i32>,
+    /// Distinct descriptions, joined by blank lines
+    pub description: Option<String>,
+}
+
+pub fn plan_merge(cluster: &[Task]) -> Merge {
+    let deadline = cluster
+        .iter()
+        .filter_map(|t| Some((slots::parse_datetime(t.deadline.as_deref()?)?, t.deadline.clone()?)))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, raw)| raw)
+        .filter(|d| stamp::changed(cluster[0].deadline.as_deref(), d));
+    let mut descriptions: Vec<&str> = Vec::new();
+    for d in cluster.iter().filter_map(|t| t.description.as_deref()).map(str::trim).filter(|d| !d.is_empty()) {
+        if !descriptions.contains(&d) {
+            descriptions.push(d);
+        }
+    }
+    Merge {
+        keep: cluster[0].id,
+        remove: cluster[1..].iter().map(|t| t.id).collect(),
+        deadline,
+        priority: cluster.iter().filter_map(|t| t.priority).max(),
+        description: (!descriptions.is_empty()).then(|| descriptions.join("\n\n")),
+    }
+}
@@ -0,0 +1,258 @@
+use crate::commands::Task;
+use crate::slots::parse_datetime;
+use chrono::{Duration as ChronoDuration, Local};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ACCESSIBLE: AtomicBool = AtomicBool::new(false);
+
+/// `--accessible` / `TODO_ACCESSIBLE`: one labelled line per item instead of tables, and no
+/// cue carried by colour or symbols alone
+pub fn set_accessible(on: bool) {
+    ACCESSIBLE.store(on, Ordering::SeqCst);
+}
+
+pub fn accessible() -> bool {
+    ACCESSIBLE.load(Ordering::SeqCst)
+}
+
+pub fn humanize_datetime(s: &str) -> String {
+    if let Some(dt) = parse_datetime(s) {
+        let now = Local::now().naive_local();
+        let date = dt.date();
+        let time_str = dt.format("%H:%M").to_string();
+        if date == now.date() {
+            return format!("today at {}", time_str);
+        }
+        if date == now.date() + ChronoDuration::days(1) {
+            return format!("tomorrow at {}", time_str);
+        }
+        if date == now.date() + ChronoDuration::days(2) {
+            return format!("day after tomorrow at {}", time_str);
+        }
+        if date <= now.date() + ChronoDuration::days(7) {
+            return format!("{} at {}", dt.format("%A"), time_str);
+        }
+        dt.format("%Y-%m-%d %H:%M").to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn format_minutes(mins: i32) -> String {
+    if mins <= 0 {
+        return "-".into();
+    }
+    let hours = mins / 60;
+    let minutes = mins % 60;
+    if hours > 0 {
+        if minutes > 0 {
+            format!("{}h {}m", hours, minutes)
+        } else {
+            format!("{}h", hours)
+        }
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+pub fn priority_label(priority: Option<i32>) -> &'static str {
+    match priority.unwrap_or(0) {
+        p if p >= 7 => "High",
+        p if p >= 4 => "Medium",
+        p if p > 0  => "Low",
+        _ => "Low",
+    }
+}
+
+/// Deadline for todos, start time for events
+pub fn due_raw(t: &Task) -> Option<&String> {
+    t.deadline.as_ref().or(t.start_time.as_ref())
+}
+
+/// The line every command that creates or changes a task ends with, e.g. "Created todo task
+/// [ID 42] Write report", so scripts can pick the ID out of `[ID n]`
+pub fn reference(verb: &str, id: i32, title: Option<&str>) -> String {
+    match title {
+        Some(title) => format!("{} task [ID {}] {}", verb, id, title),
+        None => format!("{} task [ID {}]", verb, id),
+    }
+}
+
+/// Category name followed by the task's @contexts
+fn tags(t: &Task) -> String {
+    let category = t.category.as_ref().map(|c| c.name.clone());
+    let contexts = t.contexts().iter().map(|c| format!("@{}", c));
+    category.into_iter().chain(contexts).collect::<Vec<_>>().join(" ")
+}
+
+/// Duration for events; for todos the estimate, as "2h / 5h done" once progress is logged
+fn estimate_cell(t: &Task, remaining: Option<i32>) -> String {
+    if t.kind == "event" {
+        return format_minutes(t.duration.unwrap_or(0));
+    }
+    let est = t.estimate.unwrap_or(0);
+    match remaining.filter(|r| *r < est) {
+        Some(r) => format!("{} / {} done", format_minutes(est - r), format_minutes(est)),
+        None => format_minutes(est),
+    }
+}
+
+/// The title with its category's icon in front when `on`: "📚 Thesis: literature review"
+pub fn with_icon(t: &Task, on: bool) -> String {
+    match t.category.as_ref().and_then(|c| c.icon.as_deref()).filter(|i| on && !i.is_empty()) {
+        Some(icon) => format!("{} {}", icon, t.title),
+        None => t.title.clone(),
+    }
+}
+
+/// The title as views show it, with the icon when `category_icons` is on
+pub fn titled(t: &Task) -> String {
+    with_icon(t, crate::config::get().category_icons)
+}
+
+/// Cells for the list-tasks table: ID, Task Name, Due, Est, Priority, Status, Tags
+pub fn task_row(t: &Task) -> Vec<String> {
+    let due_str = due_raw(t).map(|d| humanize_datetime(d)).unwrap_or_else(|| "-".to_string());
+    let title = if t.is_optional() { format!("{} (optional)", titled(t)) } else { titled(t) };
+    vec![
+        t.id.to_string(),
+        title,
+        due_str,
+        estimate_cell(t, t.remaining),
+        priority_label(t.priority).to_string(),
+        t.status.clone().unwrap_or_default(),
+        tags(t),
+    ]
+}
+
+/// Custom field names from `--columns +effort_points,+client`, and the built-in `created`
+pub fn extra_columns(specs: &[String]) -> Result<Vec<String>, String> {
+    specs
+        .iter()
+        .map(|s| {
+            if s.eq_ignore_ascii_case("created") {
+                return Ok("created".into());
+            }
+            let name = s.strip_prefix('+').ok_or_else(|| format!("'{}': add custom fields with a leading +, e.g. +effort_points", s))?;
+            match crate::filter::is_field_name(name) {
+                true => Ok(name.to_lowercase()),
+                false => Err(format!("'{}' is not a field name", name)),
+            }
+        })
+        .collect()
+}
+
+/// An extra column's cell: when it was created, or a custom field
+fn extra_cell(t: &Task, column: &str, meta: &crate::meta::MetaMap) -> Option<String> {
+    match column {
+        "created" => crate::meta::created(t, meta).map(|c| c.format("%Y-%m-%d %H:%M").to_string()),
+        field => meta.get(&t.id)?.fields.get(field).cloned(),
+    }
+}
+
+/// The list-tasks table, rows numbered for `%N` references, plus one column per custom field;
+/// `badges` (habit streaks) follow the task name
+pub fn task_table(tasks: &[&Task], extra: &[String], meta: &crate::meta::MetaMap, badges: &BTreeMap<i32, String>) -> prettytable::Table {
+    let mut table = prettytable::Table::new();
+    let mut header: Vec<String> = ["#", "ID", "Task Name", "Due", "Est", "Priority", "Status", "Tags"].map(String::from).to_vec();
+    header.extend(extra.iter().map(|c| if c == "created" { "Created".to_string() } else { c.clone() }));
+    table.add_row(prettytable::Row::from(header));
+    for (i, t) in tasks.iter().enumerate() {
+        let [id, mut title, due, _, mut priority, status, tags] = task_row(t).try_into().unwrap_or_default();
+        let est = estimate_cell(t, crate::meta::remaining(t, meta));
+        if crate::agenda::use_color() {
+            priority = crate::theme::current().priority(t.priority, &priority);
+        }
+        if let Some(b) = badges.get(&t.id) {
+            title = format!("{} {}", title, b);
+        }
+        let mut cells = vec![format!("%{}", i + 1), id, title, due, est, priority, status, tags];
+        cells.extend(extra.iter().map(|f| extra_cell(t, f, meta).unwrap_or_else(|| "-".into())));
+        let mut row = prettytable::Row::from(cells);
+        if t.is_optional() && crate::agenda::use_color() {
+            row.iter_mut().for_each(|c| c.style(prettytable::Attr::Dim));
+        }
+        table.add_row(row);
+    }
+    table
+}
+
+/// "Task 42, title Write report, due tomorrow at 17:00, priority high, …" for screen readers;
+/// empty fields are left out, `row` is the `%N` reference
+pub fn task_sentence(t: &Task, row: usize, extra: &[String], meta: &crate::meta::MetaMap, badge: Option<&String>) -> String {
+    let [id, title, due, _, priority, status, tags] = task_row(t).try_into().unwrap_or_default();
+    let est = estimate_cell(t, crate::meta::remaining(t, meta));
+    let mut parts = vec![format!("Task {}", id), format!("title {}", title)];
+    let labelled = [("due", due), ("estimate", est), ("priority", priority.to_lowercase()), ("status", status), ("tags", tags)];
+    parts.extend(labelled.into_iter().filter(|(_, v)| !v.is_empty() && v != "-").map(|(label, v)| format!("{} {}", label, v)));
+    parts.extend(extra.iter().filter_map(|f| Some(format!("{} {}", f, extra_cell(t, f, meta)?))));
+    if let Some(b) = badge {
+        parts.push(b.trim_start_matches("🔥").trim().to_string());
+    }
+    parts.push(format!("reference %{}", row));
+    parts.join(", ")
+}
+
+/// Alfred script-filter JSON: one item per task, with the task ID as its argument
+pub fn alfred_items(tasks: &[&Task]) -> serde_json::Value {
+    let items: Vec<serde_json::Value> = tasks
+        .iter()
+        .map(|t| {
+            let [_, _, due, est, priority, status, tags] = task_row(t).try_into().unwrap_or_default();
+            let subtitle = [format!("due {}", due), est, priority, status, tags]
+                .into_iter()
+                .filter(|s| !s.is_empty() && s != "-" && s != "due -")
+                .collect::<Vec<_>>()
+                .join(" · ");
+            serde_json::json!({
+                "uid": format!("task-{}", t.id),
+                "title": t.title,
+                "subtitle": subtitle,
+                "arg": t.id.to_string(),
+                "autocomplete": t.title,
+                "text": { "copy": t.title },
+            })
+        })
+        .collect();
+    serde_json::json!({ "items": items })
+}
+
+/// "Mon 2030-05-06 10:00-11:30" (end date shown only when it differs)
+pub fn format_interval(i: &crate::slots::Interval) -> String {
+    let end = if i.end.date() == i.start.date() { i.end.format("%H:%M") } else { i.end.format("%a %Y-%m-%d %H:%M") };
+    format!("{}-{}", i.start.format("%a %Y-%m-%d %H:%M"), end)
+}
+
+/// Label/value lines for `todo show`, skipping empty fields
+pub fn task_details(t: &Task, meta: &crate::meta::TaskMeta) -> Vec<(&'static str, String)> {
+    let slot = match (t.start_time.as_deref().and_then(parse_datetime), t.end_time.as_deref().and_then(parse_datetime)) {
+        (Some(s), Some(e)) if t.is_all_day() => {
+            let last = (e - ChronoDuration::days(1)).date();
+            Some(match last > s.date() {
+                true => format!("{} to {} (all day)", s.format("%a %Y-%m-%d"), last.format("%a %Y-%m-%d")),
+                false => format!("{} (all day)", s.format("%a %Y-%m-%d")),
+            })
+        }
+        (Some(s), Some(e)) => Some(format_interval(&crate::slots::Interval::new(s, e))),
+        _ => None,
+    };
+    let fields = [
+        ("Status", t.status.clone()),
+        ("Priority", t.priority.map(|p| format!("{} ({})", p, priority_label(Some(p))))),
+        ("Category", t.category.as_ref().map(|c| c.name.clone())),
+        ("Assignee", t.assignee.clone().filter(|a| !a.is_empty())),
+        ("When", slot),
+        ("Deadline", t.deadline.as_deref().map(humanize_datetime)),
+        ("Estimate", t.estimate.map(format_minutes)),
+        ("Remaining", t.remaining.or(meta.remaining_minutes).map(|r| if r == 0 { "none".into() } else { format_minutes(r) })),
+        ("Location", t.location.clone()),
+        ("Organizer", t.organizer.clone()),
+        ("Attendees", t.attendees.as_ref().filter(|a| !a.is_empty()).map(|a| a.join(", "))),
+        ("Meeting", t.meeting_url.clone()),
+        ("Show as", t.transparency.clone().filter(|_| t.kind == "event")),
+        ("Waiting on", meta.waiting_on.as_ref().map(|w| format!("{} since {}", w, meta.waiting_since.as_deref().unwrap_or("?")))),
+        ("Attachments", Some(meta.attachments.join("\n  ")).filter(|a| !a.is_empty())),
+    ];
+    fields.into_iter().filter_map(|(label, value)| Some((label, value?))).collect()
+}
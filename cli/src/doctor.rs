@@ -0,0 +1,296 @@
+use crate::commands::{Category, Task};
+use crate::config;
+use chrono::{DateTime, Utc};
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::time::Duration;
+
+/// Skew beyond this is a warning, beyond 5× a failure
+const SKEW_WARN_SECS: i64 = 60;
+
+#[derive(PartialEq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Check { name, status: Status::Pass, detail: detail.into(), hint: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Check { name, status: Status::Warn, detail: detail.into(), hint: Some(hint.into()) }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Check { name, status: Status::Fail, detail: detail.into(), hint: Some(hint.into()) }
+    }
+}
+
+fn check_config() -> Check {
+    let path = config::path();
+    if !path.exists() {
+        return Check::warn("Config", "no config file, using defaults", "Run `todo init` to create one");
+    }
+    match config::load() {
+        Ok(_) => Check::pass("Config", format!("{} is valid", path.display())),
+        Err(e) => Check::fail("Config", e.to_string(), format!("Fix or delete {}", path.display())),
+    }
+}
+
+fn check_local_store() -> Check {
+    let dir = crate::paths::data_dir();
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        return Check::fail("Local data", format!("{} not writable: {}", dir.display(), e), "Check permissions or pass --data-dir");
+    }
+    if let Some(problem) = crate::db::check().ok().and_then(|found| found.into_iter().next()) {
+        return Check::fail("Local data", problem, "Run `todo db repair`");
+    }
+    if crate::vault::is_locked() {
+        if let Err(e) = crate::audit::read_all() {
+            return Check::fail("Local data", e.to_string(), "Unlock your OS keyring or set TODO_VAULT_KEY");
+        }
+        return Check::pass("Local data", format!("{} (encrypted)", dir.display()));
+    }
+    Check::pass("Local data", dir.display().to_string())
+}
+
+/// Pushes a client-side push-all failed or never reached
+fn check_pushes() -> Check {
+    match crate::push::load_journal() {
+        Ok(j) if j.is_empty() => Check::pass("Pushes", "none left over"),
+        Ok(j) => Check::warn("Pushes", format!("{} failed, {} not reached by the last push-all", j.failed.len(), j.not_reached.len()), "Run `todo push-all --resume`"),
+        Err(e) => Check::warn("Pushes", format!("can't read the push journal: {}", e), "Fix local data first"),
+    }
+}
+
+/// Reachability + version from FastAPI's OpenAPI document; also returns the server's Date header
+async fn check_api(client: &reqwest::Client, api_url: &str) -> (Check, Option<String>) {
+    let resp = client
+        .get(format!("{}/openapi.json", api_url))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await;
+    match resp {
+        Ok(r) if r.status().is_success() => {
+            let date = r
+                .headers()
+                .get(reqwest::header::DATE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let doc: Value = r.json().await.unwrap_or(Value::Null);
+            let version = doc["info"]["version"].as_str().unwrap_or("unknown");
+            (Check::pass("API", format!("{} reachable (version {})", api_url, version)), date)
+        }
+        Ok(r) => (
+            Check::fail("API", format!("{} answered {}", api_url, r.status()), "Is api_url pointing at the scheduler backend?"),
+            None,
+        ),
+        Err(e) => (
+            Check::fail("API", format!("{} unreachable: {}", api_url, e), "Start the backend or fix api_url via `todo init`"),
+            None,
+        ),
+    }
+}
+
+async fn check_auth(client: &reqwest::Client, api_url: &str) -> Check {
+    match client.get(format!("{}/tasks/?limit=1", api_url)).send().await {
+        Ok(r) if r.status().is_success() => Check::pass("Auth", "requests accepted"),
+        Ok(r) if r.status() == reqwest::StatusCode::UNAUTHORIZED || r.status() == reqwest::StatusCode::FORBIDDEN => {
+            Check::fail("Auth", format!("rejected ({})", r.status()), "Check the backend's credentials")
+        }
+        Ok(r) => Check::warn("Auth", format!("unexpected status {}", r.status()), "Check the backend logs"),
+        Err(e) => Check::fail("Auth", e.to_string(), "Fix API reachability first"),
+    }
+}
+
+/// Prefer a status endpoint; older backends only expose the OAuth URL
+async fn check_calendar(client: &reqwest::Client, api_url: &str) -> Check {
+    const NAME: &str = "Google Calendar";
+    if let Ok(r) = client.get(format!("{}/calendar/status", api_url)).send().await {
+        if r.status().is_success() {
+            let body: Value = r.json().await.unwrap_or(Value::Null);
+            return if body["linked"].as_bool().unwrap_or(false) {
+                Check::pass(NAME, "linked")
+            } else {
+                Check::fail(NAME, "not linked", format!("Open {}/calendar/auth-url to authorize", api_url))
+            };
+        }
+    }
+    match client.get(format!("{}/calendar/auth-url", api_url)).send().await {
+        Ok(r) if r.status().is_success() => Check::warn(
+            NAME,
+            "OAuth client configured; link status not reported by this backend",
+            "Run `todo sync-calendar` to confirm the link works",
+        ),
+        Ok(r) => Check::fail(
+            NAME,
+            format!("OAuth not configured ({})", r.status()),
+            "Set GOOGLE_CREDENTIALS_PATH on the backend",
+        ),
+        Err(e) => Check::fail(NAME, e.to_string(), "Fix API reachability first"),
+    }
+}
+
+fn check_clock(server_date: Option<String>) -> Check {
+    let Some(date) = server_date else {
+        return Check::warn("Clock", "server sent no Date header", "Skew can't be measured");
+    };
+    let Ok(server) = DateTime::parse_from_rfc2822(&date) else {
+        return Check::warn("Clock", format!("unparseable Date header '{}'", date), "Skew can't be measured");
+    };
+    let skew = (Utc::now() - server.with_timezone(&Utc)).num_seconds();
+    let detail = format!("{}s {} server", skew.abs(), if skew >= 0 { "ahead of" } else { "behind" });
+    match skew.abs() {
+        s if s <= SKEW_WARN_SECS => Check::pass("Clock", detail),
+        s if s <= SKEW_WARN_SECS * 5 => Check::warn("Clock", detail, "Enable NTP time sync"),
+        _ => Check::fail("Clock", detail, "Enable NTP; deadlines and schedules will be off"),
+    }
+}
+
+/// Deserializer that only records the field names a derived struct asks for
+struct FieldNames(&'static [&'static str]);
+
+impl<'de> de::Deserializer<'de> for &mut FieldNames {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _: &'static str, fields: &'static [&'static str], _: V) -> Result<V::Value, Self::Error> {
+        self.0 = fields;
+        Err(de::Error::custom("fields recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// JSON keys the CLI reads into `T` (after renames)
+pub fn fields_of<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut names = FieldNames(&[]);
+    let _ = T::deserialize(&mut names);
+    names.0
+}
+
+/// Expected keys no sample had, and keys the CLI doesn't know
+#[derive(Debug, Default, PartialEq)]
+pub struct Drift {
+    pub missing: Vec<String>,
+    pub unknown: Vec<String>,
+}
+
+pub fn drift(fields: &[&str], samples: &[&Value]) -> Drift {
+    let seen: BTreeSet<&str> = samples.iter().filter_map(|s| s.as_object()).flat_map(|o| o.keys().map(String::as_str)).collect();
+    Drift {
+        missing: fields.iter().filter(|f| !seen.contains(*f)).map(|f| f.to_string()).collect(),
+        unknown: seen.iter().filter(|k| !fields.contains(k)).map(|k| k.to_string()).collect(),
+    }
+}
+
+/// Compare one endpoint's payloads with the model the CLI parses them into (tasks' nested category too)
+async fn check_endpoint<T: DeserializeOwned>(client: &reqwest::Client, api_url: &str, name: &'static str, path: &str) -> Check {
+    let body: Value = match client.get(format!("{}{}", api_url, path)).send().await {
+        Ok(r) if r.status().is_success() => r.json().await.unwrap_or(Value::Null),
+        Ok(r) => return Check::warn(name, format!("answered {}", r.status()), "This backend may not have the endpoint"),
+        Err(e) => return Check::fail(name, e.to_string(), "Fix API reachability first"),
+    };
+    let samples: Vec<&Value> = match &body {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+    if samples.is_empty() {
+        return Check::warn(name, "no data to compare", "Create a task and run `todo doctor --schema` again");
+    }
+    if let Some(e) = samples.iter().find_map(|s| T::deserialize(*s).err()) {
+        return Check::fail(name, format!("doesn't parse: {}", e), "The backend and CLI disagree on a type; upgrade whichever is older");
+    }
+    let mut d = drift(fields_of::<T>(), &samples);
+    let categories: Vec<&Value> = samples.iter().filter_map(|s| s.get("category")).filter(|c| c.is_object()).collect();
+    if !categories.is_empty() {
+        let nested = drift(fields_of::<Category>(), &categories);
+        d.missing.extend(nested.missing.iter().map(|f| format!("category.{}", f)));
+        d.unknown.extend(nested.unknown.iter().map(|f| format!("category.{}", f)));
+    }
+    let unknown = match d.unknown.is_empty() {
+        true => String::new(),
+        false => format!("; not read by the CLI: {}", d.unknown.join(", ")),
+    };
+    match d.missing.is_empty() {
+        true => Check::pass(name, format!("{} sample(s) match{}", samples.len(), unknown)),
+        false => Check::warn(
+            name,
+            format!("missing {}{}", d.missing.join(", "), unknown),
+            "Those columns will show as empty; upgrade the backend or the CLI",
+        ),
+    }
+}
+
+/// Sample each endpoint the CLI reads and compare against its models
+pub async fn schema_checks(client: &reqwest::Client, api_url: &str) -> Vec<Check> {
+    let mut checks = vec![
+        check_endpoint::<Task>(client, api_url, "GET /tasks/", "/tasks/").await,
+        check_endpoint::<Task>(client, api_url, "GET /taskslist/", "/taskslist/").await,
+        check_endpoint::<Category>(client, api_url, "GET /categories/", "/categories/").await,
+    ];
+    let first = match client.get(format!("{}/tasks/", api_url)).send().await {
+        Ok(r) => r.json::<Value>().await.ok().and_then(|v| v.get(0)?.get("id")?.as_i64()),
+        Err(_) => None,
+    };
+    if let Some(id) = first {
+        checks.push(check_endpoint::<Task>(client, api_url, "GET /tasks/{id}", &format!("/tasks/{}", id)).await);
+    }
+    checks
+}
+
+/// Run every check; callers decide how to print
+pub async fn run_checks(client: &reqwest::Client, api_url: &str) -> Vec<Check> {
+    let mut checks = vec![check_config(), check_local_store(), check_pushes()];
+    let (api, date) = check_api(client, api_url).await;
+    let api_ok = api.status == Status::Pass;
+    checks.push(api);
+    if api_ok {
+        checks.push(check_auth(client, api_url).await);
+        checks.push(check_calendar(client, api_url).await);
+        checks.push(check_clock(date));
+    }
+    checks
+}
+
+/// Print results; returns an error if anything failed so the exit code is non-zero
+pub async fn run(client: &reqwest::Client, api_url: &str, schema: bool) -> Result<(), Box<dyn Error>> {
+    let mut checks = run_checks(client, api_url).await;
+    if schema && checks.iter().any(|c| c.name == "API" && c.status == Status::Pass) {
+        checks.extend(schema_checks(client, api_url).await);
+    }
+    for c in &checks {
+        let mark = match c.status {
+            Status::Pass => "\u{2714}",
+            Status::Warn => "!",
+            Status::Fail => "\u{2718}",
+        };
+        println!("{} {:<16} {}", mark, c.name, c.detail);
+        if let Some(h) = &c.hint {
+            println!("  \u{2192} {}", h);
+        }
+    }
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if failed > 0 {
+        return Err(format!("{} check(s) failed", failed).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,640 @@
+use crate::commands::Task;
+use crate::config::{Availability, BatchRule, Block, Config, Eviction};
+use crate::meta::MetaMap;
+use crate::slots::{self, Busy, Interval};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Todos placed past their deadline search this many days ahead at most
+pub const HORIZON_DAYS: i64 = 60;
+
+static NO_META: MetaMap = MetaMap::new();
+
+pub struct Options<'a> {
+    pub config: &'a Config,
+    pub now: NaiveDateTime,
+    /// Incremental mode: keep valid placements, bumping one only for a task scoring
+    /// more than `1 / (1 - stability)` times higher (1.0 never bumps)
+    pub stability: Option<f64>,
+    /// Local per-task overrides (quiet hours)
+    pub meta: &'a MetaMap,
+}
+
+impl<'a> Options<'a> {
+    pub fn new(config: &'a Config, now: NaiveDateTime) -> Self {
+        Options { config, now, stability: None, meta: &NO_META }
+    }
+}
+
+pub struct Placement {
+    pub task_id: i32,
+    pub title: String,
+    pub slot: Interval,
+    /// Left where it already was (incremental mode)
+    pub kept: bool,
+    /// Cut short from this many minutes (`shrink-optional`)
+    pub shrunk_from: Option<i64>,
+    /// Ends after the deadline (`overflow-past-deadline-with-warning`)
+    pub late: bool,
+}
+
+pub struct Unplaced {
+    pub task_id: i32,
+    pub title: String,
+    pub reason: String,
+}
+
+#[derive(Default)]
+pub struct Plan {
+    pub placed: Vec<Placement>,
+    pub unplaced: Vec<Unplaced>,
+}
+
+/// One term of a task's score, e.g. ("priority", "7 × 1", 7.0)
+pub struct ScorePart {
+    pub name: String,
+    pub detail: String,
+    pub value: f64,
+}
+
+/// Same formula as the backend: weighted priority, deadline urgency, then any other numeric field
+pub fn score_parts(task: &Task, now: NaiveDateTime, weights: &BTreeMap<String, f64>) -> Vec<ScorePart> {
+    let priority = task.priority.unwrap_or(0) as f64;
+    let w = weights.get("priority").copied().unwrap_or(1.0);
+    let mut parts = vec![ScorePart { name: "priority".into(), detail: format!("{} × {}", priority, w), value: priority * w }];
+    if let Some(deadline) = task.deadline.as_deref().and_then(slots::parse_datetime) {
+        let minutes = ((deadline - now).num_seconds() as f64 / 60.0).max(1.0);
+        let w = weights.get("deadline").copied().unwrap_or(0.0);
+        parts.push(ScorePart { name: "deadline".into(), detail: format!("{} / {:.0} min left", w, minutes), value: w / minutes });
+    }
+    for (field, weight) in weights {
+        let value = match field.as_str() {
+            "estimate" => task.estimate,
+            "duration" => task.duration,
+            _ => None,
+        };
+        if let Some(v) = value {
+            parts.push(ScorePart { name: field.clone(), detail: format!("{} × {}", v, weight), value: v as f64 * weight });
+        }
+    }
+    parts
+}
+
+pub fn score(task: &Task, now: NaiveDateTime, weights: &BTreeMap<String, f64>) -> f64 {
+    score_parts(task, now, weights).iter().map(|p| p.value).sum()
+}
+
+/// Minutes of open availability on `day`
+fn open_minutes(availability: &Availability, day: NaiveDate) -> i64 {
+    slots::windows_on(availability, day).iter().map(|w| w.duration().num_minutes()).sum()
+}
+
+fn schedulable(t: &Task, cfg: &Config) -> bool {
+    // Delegated work, and other household members' todos, are someone else's time
+    t.kind == "todo" && !matches!(t.status.as_deref(), Some("done" | "waiting")) && !t.belongs_to_other(cfg.user.as_deref())
+}
+
+fn category(t: &Task) -> Option<&str> {
+    t.category.as_ref().map(|c| c.name.as_str())
+}
+
+fn minutes(hours: Option<f64>) -> Option<i64> {
+    hours.map(|h| (h * 60.0).round() as i64)
+}
+
+/// What's been placed so far; limits are checked against it
+struct Ledger<'a> {
+    cfg: &'a Config,
+    meta: &'a MetaMap,
+    events: Vec<Busy>,
+    /// Events with a category, which count towards its weekly quota
+    booked: Vec<(&'a str, Interval)>,
+    /// Task, slot, and whether it was kept from before
+    placed: Vec<(&'a Task, Interval, bool)>,
+}
+
+impl<'a> Ledger<'a> {
+    /// The task's availability with quiet hours and its own overrides applied
+    fn availability(&self, task: &Task) -> Availability {
+        match self.meta.get(&task.id) {
+            Some(m) => self.cfg.scheduling_availability(category(task), m.ignore_quiet_hours, &m.quiet_hours),
+            None => self.cfg.scheduling_availability(category(task), false, &[]),
+        }
+    }
+
+    fn busy(&self) -> Vec<Busy> {
+        let placed = self.placed.iter().map(|(t, slot, _)| Busy { interval: *slot, label: format!("[{}] {}", t.id, t.title) });
+        self.events.iter().cloned().chain(placed).collect()
+    }
+
+    fn minutes_on(&self, day: NaiveDate, same: impl Fn(&Task) -> bool) -> i64 {
+        self.placed
+            .iter()
+            .filter(|(t, slot, _)| slot.start.date() == day && same(t))
+            .map(|(_, slot, _)| slot.duration().num_minutes())
+            .sum()
+    }
+
+    /// Minutes of category `name` booked in the week holding `day`, events and placed blocks alike
+    fn week_minutes(&self, name: &str, day: NaiveDate) -> i64 {
+        let start = self.cfg.week.start_of(day);
+        let in_week = |slot: &Interval| (start..start + Duration::weeks(1)).contains(&slot.start.date());
+        let placed = self.placed.iter().filter(|(t, _, _)| category(t).is_some_and(|c| c.eq_ignore_ascii_case(name))).map(|(_, slot, _)| slot);
+        let booked = self.booked.iter().filter(|(c, _)| c.eq_ignore_ascii_case(name)).map(|(_, slot)| slot);
+        placed.chain(booked).filter(|s| in_week(s)).map(|s| s.duration().num_minutes()).sum()
+    }
+
+    /// `task`'s category would go over its weekly max on `day`
+    fn over_quota(&self, task: &Task, day: NaiveDate, est: i64) -> bool {
+        let Some(c) = category(task) else { return false };
+        self.cfg.quota(c).and_then(|q| q.max_minutes).is_some_and(|max| self.week_minutes(c, day) + est > max)
+    }
+
+    /// Which per-day limit `task` would break on `day`, if any
+    fn limit_hit(&self, task: &Task, day: NaiveDate, est: i64) -> Option<&'static str> {
+        let cfg = self.cfg;
+        if minutes(cfg.availability.max_hours_per_day).is_some_and(|c| self.minutes_on(day, |_| true) + est > c) {
+            return Some("daily hour cap reached");
+        }
+        let profile_of = |t: &Task| category(t).and_then(|c| cfg.category_profiles.get(c));
+        if let Some(p) = profile_of(task) {
+            let own = self.minutes_on(day, |t| profile_of(t) == Some(p));
+            if minutes(cfg.availability_for(category(task)).max_hours_per_day).is_some_and(|c| own + est > c) {
+                return Some("daily hour cap reached");
+            }
+        }
+        // A category's first task of the day always fits, so long tasks can't starve
+        if let (Some(share), Some(c)) = (cfg.scheduling.max_category_share, category(task)) {
+            let so_far = self.minutes_on(day, |t| category(t) == Some(c));
+            let open = open_minutes(&cfg.scheduling_availability(Some(c), false, &[]), day);
+            if so_far > 0 && (so_far + est) as f64 > share * open as f64 {
+                return Some("category share limit reached");
+            }
+        }
+        None
+    }
+
+    /// Earliest slot before the deadline, or why there is none; short batchable tasks try
+    /// their batch windows first
+    fn find_slot(&self, task: &Task, now: NaiveDateTime) -> Result<Interval, String> {
+        let est = task.estimate.unwrap_or(0) as i64;
+        if est <= 0 {
+            return Err("no estimate".into());
+        }
+        let deadline = task.deadline.as_deref().and_then(slots::parse_datetime);
+        if deadline.is_some_and(|d| d <= now) {
+            return Err("deadline has passed".into());
+        }
+        // Weekly maximums are soft: go to a later week if possible, over the max if not
+        let has_max = category(task).and_then(|c| self.cfg.quota(c)).is_some_and(|q| q.max_minutes.is_some());
+        let mut found = Err(String::new());
+        for quotas in [true, false].into_iter().skip(usize::from(!has_max)) {
+            if let Some(rule) = self.batch_rule(task) {
+                if let Ok(slot) = self.search(task, now, deadline, Some(rule), quotas) {
+                    return Ok(slot);
+                }
+            }
+            found = self.search(task, now, deadline, None, quotas);
+            if found.is_ok() {
+                break;
+            }
+        }
+        found
+    }
+
+    /// Windows from the task's contexts (e.g. errands only on Saturday morning), if any restrict it
+    fn context_windows(&self, task: &Task) -> Option<Vec<Block>> {
+        let windows: Vec<Block> = task.contexts().iter().filter_map(|c| self.cfg.scheduling.context_windows.get(c)).flatten().cloned().collect();
+        (!windows.is_empty()).then_some(windows)
+    }
+
+    fn batch_rule(&self, task: &Task) -> Option<&BatchRule> {
+        let est = task.estimate.unwrap_or(0);
+        self.cfg.scheduling.batching.iter().find(|r| r.matches(category(task), est))
+    }
+
+    /// Day-by-day first fit, optionally confined to a batch rule's windows
+    fn search(&self, task: &Task, now: NaiveDateTime, deadline: Option<NaiveDateTime>, batch: Option<&BatchRule>, quotas: bool) -> Result<Interval, String> {
+        let est = task.estimate.unwrap_or(0) as i64;
+        let last_day = deadline.map_or(now.date() + Duration::days(HORIZON_DAYS), |d| d.date());
+        let availability = self.availability(task);
+        let busy = self.busy();
+        // Batched tasks pack back to back from the (already aligned) window start
+        let step = if batch.is_some() { 1 } else { self.cfg.scheduling.slot_minutes };
+        let context = self.context_windows(task);
+
+        let mut limited = None;
+        let mut day = now.date();
+        while day <= last_day {
+            if let Some(reason) = self.limit_hit(task, day, est) {
+                limited = Some(reason);
+                day += Duration::days(1);
+                continue;
+            }
+            if quotas && self.over_quota(task, day, est) {
+                day += Duration::days(1);
+                continue;
+            }
+            let start = now.max(day.and_hms_opt(0, 0, 0).unwrap_or(now));
+            let range = Interval::new(start, day.and_hms_opt(23, 59, 59).unwrap_or(start) + Duration::seconds(1));
+            let mut gaps = slots::free_gaps(&availability, &busy, range, Duration::minutes(est));
+            if let Some(rule) = batch {
+                gaps = self.batch_gaps(rule, gaps, day, est);
+            }
+            if let Some(windows) = &context {
+                gaps = clip_to(&gaps, &slots::blocks_on(windows, day), est);
+            }
+            let slot = gaps
+                .into_iter()
+                .map(|g| {
+                    let start = slots::align_up(g.start, step);
+                    (g, Interval::new(start, start + Duration::minutes(est)))
+                })
+                .find(|(g, s)| s.end <= g.end && deadline.is_none_or(|d| s.end <= d))
+                .map(|(_, s)| s);
+            if let Some(s) = slot {
+                return Ok(s);
+            }
+            day += Duration::days(1);
+        }
+        Err(match limited {
+            Some(reason) => format!("{} on every open day before the deadline", reason),
+            None => "no free slot before the deadline".into(),
+        })
+    }
+
+    /// The parts of `gaps` inside batch windows that still have room for `est` minutes
+    fn batch_gaps(&self, rule: &BatchRule, gaps: Vec<Interval>, day: NaiveDate, est: i64) -> Vec<Interval> {
+        let mut out = Vec::new();
+        for w in slots::blocks_on(&rule.windows, day) {
+            let used: i64 = self
+                .placed
+                .iter()
+                .filter(|(t, slot, _)| w.start <= slot.start && slot.end <= w.end && rule.matches(category(t), t.estimate.unwrap_or(0)))
+                .map(|(_, slot, _)| slot.duration().num_minutes())
+                .sum();
+            if used + est > rule.max_minutes {
+                continue;
+            }
+            out.extend(clip_to(&gaps, &[w], est));
+        }
+        out.sort_by_key(|g| g.start);
+        out
+    }
+
+    /// Why `task` couldn't go on `day`
+    fn diagnose_day(&self, task: &Task, day: NaiveDate, now: NaiveDateTime) -> String {
+        let est = task.estimate.unwrap_or(0) as i64;
+        if let Some(reason) = self.limit_hit(task, day, est) {
+            return reason.to_string();
+        }
+        if self.over_quota(task, day, est) {
+            return "weekly quota reached".into();
+        }
+        if self.context_windows(task).is_some_and(|w| slots::blocks_on(&w, day).is_empty()) {
+            return "no window for its context".into();
+        }
+        let windows: Vec<Interval> = slots::windows_on(&self.availability(task), day)
+            .into_iter()
+            .filter(|w| w.end > now)
+            .collect();
+        if windows.is_empty() {
+            return "no open hours left".into();
+        }
+        let blockers: Vec<String> = self
+            .busy()
+            .into_iter()
+            .filter(|b| windows.iter().any(|w| w.overlaps(&b.interval)))
+            .map(|b| b.label)
+            .collect();
+        if blockers.is_empty() {
+            return format!("no gap of {} min before the deadline", est);
+        }
+        format!("no {} min gap left around {}", est, blockers.join(", "))
+    }
+
+    /// The task's current slot, if it's still in the future, the right length, before the
+    /// deadline, inside open hours and clear of everything placed so far
+    fn still_valid(&self, task: &Task, now: NaiveDateTime) -> Option<Interval> {
+        let slot = Interval::new(
+            slots::parse_datetime(task.start_time.as_deref()?)?,
+            slots::parse_datetime(task.end_time.as_deref()?)?,
+        );
+        let est = task.estimate.unwrap_or(0) as i64;
+        let deadline = task.deadline.as_deref().and_then(slots::parse_datetime);
+        let in_window = slots::windows_on(&self.availability(task), slot.start.date())
+            .iter()
+            .any(|w| w.start <= slot.start && slot.end <= w.end);
+        let ok = slot.start >= now
+            && (slots::align_up(slot.start, self.cfg.scheduling.slot_minutes) == slot.start || self.batch_rule(task).is_some())
+            && slot.duration().num_minutes() == est
+            && deadline.is_none_or(|d| slot.end <= d)
+            && in_window
+            && self.context_windows(task).is_none_or(|w| {
+                slots::blocks_on(&w, slot.start.date()).iter().any(|w| w.start <= slot.start && slot.end <= w.end)
+            })
+            && slots::conflicts(&self.busy(), &slot).is_empty()
+            && self.limit_hit(task, slot.start.date(), est).is_none();
+        ok.then_some(slot)
+    }
+
+    /// Place `task` by giving something up under `policy`; the todos unscheduled for it, to be
+    /// tried again, or `None` (with nothing changed) if even that doesn't make it fit
+    fn make_room(&mut self, task: &'a Task, policy: Eviction, now: NaiveDateTime) -> Option<Vec<&'a Task>> {
+        let priority = |t: &Task| t.priority.unwrap_or(0);
+        let before = self.placed.clone();
+        match policy {
+            Eviction::DropLowestPriority => {
+                let mut victims: Vec<&'a Task> = self.placed.iter().map(|p| p.0).filter(|t| priority(t) < priority(task)).collect();
+                victims.sort_by_key(|t| priority(t));
+                let mut dropped = Vec::new();
+                for v in victims {
+                    self.placed.retain(|p| p.0.id != v.id);
+                    dropped.push(v);
+                    if let Ok(slot) = self.find_slot(task, now) {
+                        self.placed.push((task, slot, false));
+                        return Some(dropped);
+                    }
+                }
+            }
+            Eviction::ShrinkOptional => {
+                let step = self.cfg.scheduling.slot_minutes.max(1) as i64;
+                // Todos with no deadline can give up time too, flagged optional or not
+                let mut optional: Vec<usize> = (0..self.placed.len()).filter(|&i| self.placed[i].0.is_optional() || self.placed[i].0.deadline.is_none()).collect();
+                optional.sort_by_key(|&i| priority(self.placed[i].0));
+                for i in optional {
+                    let (t, slot, _) = self.placed[i];
+                    let half = (t.estimate.unwrap_or(0) as i64 / 2).max(step);
+                    if half >= slot.duration().num_minutes() {
+                        continue;
+                    }
+                    self.placed[i].1 = Interval::new(slot.start, slot.start + Duration::minutes(half));
+                    if let Ok(slot) = self.find_slot(task, now) {
+                        self.placed.push((task, slot, false));
+                        return Some(Vec::new());
+                    }
+                }
+            }
+            Eviction::OverflowPastDeadlineWithWarning => {
+                if task.deadline.is_some() && task.estimate.unwrap_or(0) > 0 {
+                    if let Ok(slot) = self.search(task, now, None, None, false) {
+                        self.placed.push((task, slot, false));
+                        return Some(Vec::new());
+                    }
+                }
+            }
+        }
+        self.placed = before;
+        None
+    }
+
+    /// Place `task` by unscheduling optional todos, lowest score first; those unscheduled, or
+    /// `None` (with nothing changed) if even dropping all of them doesn't make it fit
+    fn evict_optional(&mut self, task: &'a Task, now: NaiveDateTime, score_of: impl Fn(&Task) -> f64) -> Option<Vec<&'a Task>> {
+        let before = self.placed.clone();
+        let mut victims: Vec<&'a Task> = self.placed.iter().map(|p| p.0).filter(|t| t.is_optional()).collect();
+        victims.sort_by(|a, b| score_of(a).total_cmp(&score_of(b)));
+        let mut dropped = Vec::new();
+        for v in victims {
+            self.placed.retain(|p| p.0.id != v.id);
+            dropped.push(v);
+            if let Ok(slot) = self.find_slot(task, now) {
+                self.placed.push((task, slot, false));
+                return Some(dropped);
+            }
+        }
+        self.placed = before;
+        None
+    }
+}
+
+/// The parts of `gaps` inside `windows` that are still at least `est` minutes long
+fn clip_to(gaps: &[Interval], windows: &[Interval], est: i64) -> Vec<Interval> {
+    let mut out: Vec<Interval> = windows
+        .iter()
+        .flat_map(|w| gaps.iter().map(|g| Interval::new(g.start.max(w.start), g.end.min(w.end))))
+        .filter(|g| g.duration().num_minutes() >= est)
+        .collect();
+    out.sort_by_key(|g| g.start);
+    out
+}
+
+/// Weekly minimums are soft too: a category short of its min this week has its best-scoring
+/// todos, enough to make up the difference, moved to the front of the queue
+fn promote_minimums<'a>(queue: &mut VecDeque<&'a Task>, ledger: &Ledger<'a>, today: NaiveDate) {
+    let mut short: BTreeMap<String, i64> = ledger
+        .cfg
+        .quotas
+        .iter()
+        .filter_map(|(c, q)| Some((c.to_lowercase(), q.min_minutes? - ledger.week_minutes(c, today))))
+        .filter(|(_, need)| *need > 0)
+        .collect();
+    if short.is_empty() {
+        return;
+    }
+    let (first, rest): (VecDeque<&Task>, VecDeque<&Task>) = queue.drain(..).partition(|t| {
+        if t.is_optional() {
+            return false;
+        }
+        let Some(need) = category(t).and_then(|c| short.get_mut(&c.to_lowercase())).filter(|n| **n > 0) else { return false };
+        *need -= t.estimate.unwrap_or(0) as i64;
+        true
+    });
+    queue.extend(first);
+    queue.extend(rest);
+}
+
+/// Scheduling order: required todos before optional ones, each best score first
+fn in_order(tasks: &mut [&Task], score_of: impl Fn(&Task) -> f64) {
+    tasks.sort_by(|a, b| a.is_optional().cmp(&b.is_optional()).then(score_of(b).total_cmp(&score_of(a))));
+}
+
+/// Greedy placement mirroring the backend's first phase, but honoring breaks, focus blocks,
+/// daily caps, category shares, per-category profiles and batch windows; tasks that don't fit are reported, not overbooked
+pub fn plan(tasks: &[Task], opts: &Options) -> Plan {
+    run(tasks, opts, |_, _| {})
+}
+
+/// The planning loop; `before_place` sees the ledger just before each task is placed
+fn run<'a>(tasks: &'a [Task], opts: &Options<'a>, mut before_place: impl FnMut(&Ledger<'a>, &'a Task)) -> Plan {
+    let cfg = opts.config;
+    let events: Vec<Task> = tasks.iter().filter(|t| t.kind == "event" && !t.belongs_to_other(cfg.user.as_deref())).cloned().collect();
+    let booked = tasks
+        .iter()
+        .filter(|t| t.kind == "event" && t.blocks_time() && !t.is_all_day() && !t.belongs_to_other(cfg.user.as_deref()))
+        .filter_map(|t| Some((category(t)?, slots::busy_from_tasks(std::slice::from_ref(t)).pop()?.interval)))
+        .collect();
+    let events = slots::blocking(&events, cfg.scheduling.all_day_busy);
+    let mut ledger = Ledger { cfg, meta: opts.meta, events, booked, placed: Vec::new() };
+    let score_of = |t: &Task| score(t, opts.now, &cfg.weights);
+
+    let mut pending: Vec<&Task> = tasks.iter().filter(|t| schedulable(t, cfg)).collect();
+    in_order(&mut pending, score_of);
+
+    let mut queue = VecDeque::new();
+    for task in pending {
+        match opts.stability.and_then(|_| ledger.still_valid(task, opts.now)) {
+            Some(slot) => ledger.placed.push((task, slot, true)),
+            None => queue.push_back(task),
+        }
+    }
+
+    promote_minimums(&mut queue, &ledger, opts.now.date());
+
+    let mut plan = Plan::default();
+    // Task → who it was dropped for, under `drop-lowest-priority`
+    let mut dropped_for: BTreeMap<i32, String> = BTreeMap::new();
+    while let Some(task) = queue.pop_front() {
+        before_place(&ledger, task);
+        let reason = match ledger.find_slot(task, opts.now) {
+            Ok(slot) => {
+                ledger.placed.push((task, slot, false));
+                continue;
+            }
+            Err(reason) => reason,
+        };
+        // Optional todos only take what's left over; they never push anything else out
+        if task.is_optional() {
+            let reason = match dropped_for.get(&task.id) {
+                Some(by) => format!("dropped to make room for {}", by),
+                None => format!("optional, no slack left: {}", reason),
+            };
+            plan.unplaced.push(Unplaced { task_id: task.id, title: task.title.clone(), reason });
+            continue;
+        }
+
+        // Optional todos give way first: trimmed under `shrink-optional`, otherwise unscheduled
+        let policy = cfg.scheduling.eviction;
+        let shrunk = match policy {
+            Some(Eviction::ShrinkOptional) => ledger.make_room(task, Eviction::ShrinkOptional, opts.now),
+            _ => None,
+        };
+        if let Some(evicted) = shrunk.or_else(|| ledger.evict_optional(task, opts.now, score_of)) {
+            for e in evicted {
+                dropped_for.insert(e.id, format!("[{}] {}", task.id, task.title));
+                queue.push_back(e);
+            }
+            continue;
+        }
+
+        // Incremental mode: try bumping one kept task that scores low enough
+        let bumped = opts.stability.and_then(|stability| {
+            let threshold = score_of(task) * (1.0 - stability.clamp(0.0, 1.0));
+            let mut victims: Vec<usize> = (0..ledger.placed.len())
+                .filter(|&i| ledger.placed[i].2 && score_of(ledger.placed[i].0) < threshold)
+                .collect();
+            victims.sort_by(|&a, &b| score_of(ledger.placed[a].0).total_cmp(&score_of(ledger.placed[b].0)));
+            for i in victims {
+                let victim = ledger.placed.remove(i);
+                if let Ok(slot) = ledger.find_slot(task, opts.now) {
+                    ledger.placed.push((task, slot, false));
+                    return Some(victim.0);
+                }
+                ledger.placed.insert(i, victim);
+            }
+            None
+        });
+        if let Some(victim) = bumped {
+            queue.push_back(victim);
+            continue;
+        }
+        match policy.filter(|p| *p != Eviction::ShrinkOptional).and_then(|p| ledger.make_room(task, p, opts.now)) {
+            Some(dropped) => {
+                for d in dropped {
+                    dropped_for.insert(d.id, format!("[{}] {}", task.id, task.title));
+                    queue.push_back(d);
+                }
+            }
+            None => {
+                let reason = match dropped_for.get(&task.id) {
+                    Some(by) => format!("dropped to make room for {}", by),
+                    None => reason,
+                };
+                plan.unplaced.push(Unplaced { task_id: task.id, title: task.title.clone(), reason });
+            }
+        }
+    }
+
+    plan.placed = ledger
+        .placed
+        .into_iter()
+        .map(|(t, slot, kept)| {
+            let est = t.estimate.unwrap_or(0) as i64;
+            let shrunk_from = (slot.duration().num_minutes() < est).then_some(est);
+            let late = t.deadline.as_deref().and_then(slots::parse_datetime).is_some_and(|d| slot.end > d);
+            Placement { task_id: t.id, title: t.title.clone(), slot, kept, shrunk_from, late }
+        })
+        .collect();
+    plan.placed.sort_by_key(|p| p.slot.start);
+    plan
+}
+
+/// A task placed ahead of the one being explained, in a slot it could otherwise have used
+pub struct Competitor {
+    pub task_id: i32,
+    pub title: String,
+    pub slot: Interval,
+    pub score: f64,
+}
+
+pub struct Explanation {
+    pub task_id: i32,
+    pub title: String,
+    pub score: Vec<ScorePart>,
+    /// 1-based position in scheduling order among `of` todos
+    pub rank: usize,
+    pub of: usize,
+    pub slot: Option<Interval>,
+    pub kept: bool,
+    /// Why it couldn't be placed at all
+    pub unplaced: Option<String>,
+    pub competitors: Vec<Competitor>,
+    /// Earlier days that were passed over, and why
+    pub skipped_days: Vec<(NaiveDate, String)>,
+}
+
+/// Re-run the plan and report how `task_id` was placed; `None` if it isn't a schedulable todo
+pub fn explain(tasks: &[Task], opts: &Options, task_id: i32) -> Option<Explanation> {
+    let cfg = opts.config;
+    let task = tasks.iter().find(|t| t.id == task_id && schedulable(t, cfg))?;
+    let score_of = |t: &Task| score(t, opts.now, &cfg.weights);
+    let mut order: Vec<&Task> = tasks.iter().filter(|t| schedulable(t, cfg)).collect();
+    in_order(&mut order, score_of);
+
+    let mut competitors = Vec::new();
+    let mut skipped_days = Vec::new();
+    let plan = run(tasks, opts, |ledger, t| {
+        if t.id != task_id {
+            return;
+        }
+        let found = ledger.find_slot(t, opts.now).ok();
+        let deadline = t.deadline.as_deref().and_then(slots::parse_datetime);
+        let until = found.map(|s| s.start).or(deadline).unwrap_or(opts.now + Duration::days(HORIZON_DAYS));
+        competitors = ledger
+            .placed
+            .iter()
+            .filter(|(_, slot, _)| slot.start < until && slot.end > opts.now)
+            .map(|(o, slot, _)| Competitor { task_id: o.id, title: o.title.clone(), slot: *slot, score: score_of(o) })
+            .collect();
+        skipped_days.clear();
+        let mut day = opts.now.date();
+        while day < until.date() {
+            skipped_days.push((day, ledger.diagnose_day(t, day, opts.now)));
+            day += Duration::days(1);
+        }
+    });
+
+    let placement = plan.placed.iter().find(|p| p.task_id == task_id);
+    Some(Explanation {
+        task_id,
+        title: task.title.clone(),
+        score: score_parts(task, opts.now, &cfg.weights),
+        rank: order.iter().position(|t| t.id == task_id).unwrap_or(0) + 1,
+        of: order.len(),
+        slot: placement.map(|p| p.slot),
+        kept: placement.is_some_and(|p| p.kept),
+        unplaced: plan.unplaced.iter().find(|u| u.task_id == task_id).map(|u| u.reason.clone()),
+        competitors,
+        skipped_days,
+    })
+}
@@ -0,0 +1,432 @@
+use crate::commands::Task;
+use crate::context;
+use crate::meta::MetaMap;
+use crate::slots;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Letters, digits and underscores, starting with a letter
+pub fn is_field_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Names filters already give a meaning to, so custom fields can't use them
+pub fn is_reserved(name: &str) -> bool {
+    ["and", "or", "not"].contains(&name.to_lowercase().as_str()) || name.parse::<Field>().is_ok()
+}
+
+/// A task property a filter can test
+#[derive(Clone, PartialEq, Debug)]
+enum Field {
+    Status,
+    Priority,
+    Estimate,
+    Due,
+    Title,
+    Category,
+    Context,
+    Type,
+    Assignee,
+    /// A user-defined field set with `todo set-field`, written `+name`
+    Custom(String),
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix('+') {
+            return match is_field_name(name) {
+                true => Ok(Field::Custom(name.to_lowercase())),
+                false => Err(format!("'{}' is not a field name (use letters, digits and _)", name)),
+            };
+        }
+        Ok(match s.to_lowercase().as_str() {
+            "status" => Field::Status,
+            "priority" | "prio" => Field::Priority,
+            "estimate" | "est" => Field::Estimate,
+            "due" | "deadline" => Field::Due,
+            "title" => Field::Title,
+            "category" | "cat" => Field::Category,
+            "context" | "ctx" => Field::Context,
+            "type" => Field::Type,
+            "assignee" | "owner" => Field::Assignee,
+            // A typo here would otherwise match every task as a missing custom field
+            _ => return Err(format!("Unknown field '{}' (custom fields take a +, e.g. +effort_points>=8)", s)),
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// Contains, case-insensitively
+    Has,
+}
+
+impl Op {
+    fn parse(s: &str) -> Option<Op> {
+        Some(match s {
+            "=" | "==" => Op::Eq,
+            "!=" => Op::Ne,
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            "~" => Op::Has,
+            _ => return None,
+        })
+    }
+
+    fn orders(self) -> bool {
+        matches!(self, Op::Lt | Op::Le | Op::Gt | Op::Ge)
+    }
+}
+
+/// A point in time relative to when the filter runs
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum When {
+    Now,
+    /// Today plus this many days
+    Day(i64),
+    In(Duration),
+    Date(NaiveDate),
+    /// The instant the current day (eod), week (eow) or month (eom) ends
+    EndOf(char),
+}
+
+impl FromStr for When {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        match lower.as_str() {
+            "now" => return Ok(When::Now),
+            "today" => return Ok(When::Day(0)),
+            "tomorrow" => return Ok(When::Day(1)),
+            "yesterday" => return Ok(When::Day(-1)),
+            "eod" => return Ok(When::EndOf('d')),
+            "eow" => return Ok(When::EndOf('w')),
+            "eom" => return Ok(When::EndOf('m')),
+            _ => {}
+        }
+        if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(When::Date(d));
+        }
+        let invalid = || format!("Invalid date '{}' (use 2d, 4h, 1w, today, tomorrow, eod, eow, eom or YYYY-MM-DD)", s);
+        let (n, unit) = match lower.char_indices().last() {
+            Some((i, unit)) => (lower[..i].parse::<i64>().map_err(|_| invalid())?, unit),
+            None => return Err(invalid()),
+        };
+        let d = match unit {
+            'h' => Duration::try_hours(n),
+            'd' => Duration::try_days(n),
+            'w' => Duration::try_weeks(n),
+            _ => return Err(invalid()),
+        };
+        // Far enough out that it would run off the calendar from today
+        let too_far = || format!("'{}' is too far away", s);
+        let d = d.ok_or_else(too_far)?;
+        chrono::Local::now().naive_local().checked_add_signed(d).ok_or_else(too_far)?;
+        Ok(When::In(d))
+    }
+}
+
+impl When {
+    /// The span it names: a whole day for dates, one second for instants; None past the
+    /// calendar's end
+    fn span(self, now: NaiveDateTime) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        let day = |d: NaiveDate| {
+            let start = d.and_time(chrono::NaiveTime::MIN);
+            Some((start, start.checked_add_signed(Duration::days(1))?))
+        };
+        let instant = |at: NaiveDateTime| Some((at, at.checked_add_signed(Duration::seconds(1))?));
+        match self {
+            When::Now => instant(now),
+            When::In(d) => instant(now.checked_add_signed(d)?),
+            When::Day(n) => day(now.date().checked_add_signed(Duration::try_days(n)?)?),
+            When::Date(d) => day(d),
+            When::EndOf(period) => {
+                let today = now.date();
+                let next = match period {
+                    'd' => today.succ_opt()?,
+                    'w' => crate::config::get().week.start_of(today).checked_add_signed(Duration::weeks(1))?,
+                    _ => {
+                        let (y, m) = if today.month() == 12 { (today.year() + 1, 1) } else { (today.year(), today.month() + 1) };
+                        NaiveDate::from_ymd_opt(y, m, 1).unwrap_or(today)
+                    }
+                };
+                instant(next.and_time(chrono::NaiveTime::MIN))
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Value {
+    Number(i32),
+    Time(When),
+    Text(String),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp(Field, Op, Value),
+}
+
+/// A parsed filter expression, e.g. `status!=done AND (priority>=7 OR due<2d)` or
+/// `NOT context=@work AND due<eow`, with custom fields as `+client=acme`; `--where` on list, search, bulk and push commands takes one
+#[derive(Clone, PartialEq, Debug)]
+pub struct Filter {
+    expr: Expr,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Open,
+    Close,
+    Op(Op),
+    Word(String),
+}
+
+const OP_CHARS: &[char] = &['=', '!', '<', '>', '~'];
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let word: String = chars.by_ref().take_while(|&x| x != c).collect();
+            tokens.push(Token::Word(word));
+        } else if OP_CHARS.contains(&c) {
+            let mut op = String::new();
+            while let Some(&x) = chars.peek().filter(|x| OP_CHARS.contains(x)) {
+                op.push(x);
+                chars.next();
+            }
+            tokens.push(Token::Op(Op::parse(&op).ok_or_else(|| format!("Unknown operator '{}'", op))?));
+        } else {
+            let mut word = String::new();
+            while let Some(&x) = chars.peek().filter(|x| !x.is_whitespace() && !"()".contains(**x) && !OP_CHARS.contains(x)) {
+                word.push(x);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.atom()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.atom()?));
+        }
+        Ok(left)
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.atom()?)));
+        }
+        match self.next() {
+            Some(Token::Open) => {
+                let inner = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("Missing ')'".into()),
+                }
+            }
+            Some(Token::Word(name)) => {
+                let field: Field = name.parse()?;
+                let Some(Token::Op(op)) = self.next() else {
+                    return Err(format!("Expected an operator after '{}', e.g. priority>=7", name));
+                };
+                let Some(Token::Word(raw)) = self.next() else {
+                    return Err(format!("Expected a value after '{}'", name));
+                };
+                comparison(field, op, &raw)
+            }
+            Some(t) => Err(format!("Unexpected {:?}; expected a comparison like priority>=7", t)),
+            None => Err("Expected a comparison like priority>=7".into()),
+        }
+    }
+}
+
+fn comparison(field: Field, op: Op, raw: &str) -> Result<Expr, String> {
+    let value = match field {
+        Field::Priority | Field::Estimate => {
+            if op == Op::Has {
+                return Err(format!("'~' only works on text, not {:?}", field).to_lowercase());
+            }
+            Value::Number(raw.parse().map_err(|_| format!("'{}' is not a number", raw))?)
+        }
+        Field::Due => {
+            if op == Op::Has {
+                return Err("'~' only works on text, not due".into());
+            }
+            Value::Time(raw.parse()?)
+        }
+        Field::Custom(_) => Value::Text(raw.to_string()),
+        _ => {
+            if op.orders() {
+                return Err(format!("{:?} can only be compared with =, != or ~", field).to_lowercase());
+            }
+            Value::Text(raw.to_lowercase())
+        }
+    };
+    Ok(Expr::Cmp(field, op, value))
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut p = Parser { tokens: tokenize(s)?, pos: 0 };
+        let expr = p.or()?;
+        match p.next() {
+            None => Ok(Filter { expr }),
+            Some(t) => Err(format!("Unexpected {:?} (join conditions with AND or OR)", t)),
+        }
+    }
+}
+
+fn compare<T: PartialOrd>(a: T, op: Op, b: T) -> bool {
+    match op {
+        Op::Eq => a == b,
+        Op::Ne => a != b,
+        Op::Lt => a < b,
+        Op::Le => a <= b,
+        Op::Gt => a > b,
+        Op::Ge => a >= b,
+        Op::Has => false,
+    }
+}
+
+fn text_matches(value: &str, op: Op, wanted: &str) -> bool {
+    let value = value.to_lowercase();
+    match op {
+        Op::Eq => value == wanted,
+        Op::Ne => value != wanted,
+        Op::Has => value.contains(wanted),
+        _ => false,
+    }
+}
+
+impl Expr {
+    fn matches(&self, t: &Task, fields: &BTreeMap<String, String>, now: NaiveDateTime) -> bool {
+        let (field, op, value) = match self {
+            Expr::And(a, b) => return a.matches(t, fields, now) && b.matches(t, fields, now),
+            Expr::Or(a, b) => return a.matches(t, fields, now) || b.matches(t, fields, now),
+            Expr::Not(a) => return !a.matches(t, fields, now),
+            Expr::Cmp(field, op, value) => (field, *op, value),
+        };
+        match (field, value) {
+            (Field::Priority, Value::Number(n)) => compare(t.priority.unwrap_or(0), op, *n),
+            (Field::Estimate, Value::Number(n)) => t.estimate.is_some_and(|e| compare(e, op, *n)),
+            (Field::Due, Value::Time(when)) => match t.deadline.as_deref().and_then(slots::parse_datetime) {
+                Some(due) => {
+                    let Some((start, end)) = when.span(now) else { return op == Op::Ne };
+                    match op {
+                        Op::Eq => start <= due && due < end,
+                        Op::Ne => !(start <= due && due < end),
+                        Op::Lt => due < start,
+                        Op::Le => due < end,
+                        Op::Gt => due >= end,
+                        Op::Ge => due >= start,
+                        Op::Has => false,
+                    }
+                }
+                None => op == Op::Ne,
+            },
+            (Field::Custom(name), Value::Text(wanted)) => match fields.get(name) {
+                // Numbers compare as numbers, anything else as case-insensitive text
+                Some(v) => match (v.parse::<f64>(), wanted.parse::<f64>()) {
+                    (Ok(a), Ok(b)) if op != Op::Has => compare(a, op, b),
+                    _ if op == Op::Has => v.to_lowercase().contains(&wanted.to_lowercase()),
+                    _ => compare(v.to_lowercase(), op, wanted.to_lowercase()),
+                },
+                None => op == Op::Ne,
+            },
+            (Field::Context, Value::Text(wanted)) => {
+                let wanted = context::normalize(wanted);
+                let any = |f: &dyn Fn(&String) -> bool| t.contexts().iter().any(f);
+                match op {
+                    Op::Eq => any(&|c| *c == wanted),
+                    Op::Ne => !any(&|c| *c == wanted),
+                    _ => any(&|c| c.contains(&wanted)),
+                }
+            }
+            (_, Value::Text(wanted)) => {
+                let value = match field {
+                    Field::Status => t.status.clone(),
+                    Field::Title => Some(t.title.clone()),
+                    Field::Category => t.category.as_ref().map(|c| c.name.clone()),
+                    Field::Assignee => t.assignee.clone(),
+                    _ => Some(t.kind.clone()),
+                };
+                // assignee=me and assignee=unassigned mean what they do in --assignee
+                let wanted = match field {
+                    Field::Assignee => crate::config::get().assignee(wanted).map(|w| w.to_lowercase()).unwrap_or_else(|_| wanted.clone()),
+                    _ => wanted.clone(),
+                };
+                text_matches(value.as_deref().unwrap_or(""), op, &wanted)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Filter {
+    /// `meta` supplies custom field values
+    pub fn matches(&self, t: &Task, meta: &MetaMap, now: NaiveDateTime) -> bool {
+        let none = BTreeMap::new();
+        self.expr.matches(t, meta.get(&t.id).map_or(&none, |m| &m.fields), now)
+    }
+
+    pub fn select(&self, tasks: Vec<Task>, meta: &MetaMap, now: NaiveDateTime) -> Vec<Task> {
+        tasks.into_iter().filter(|t| self.matches(t, meta, now)).collect()
+    }
+}
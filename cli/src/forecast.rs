@@ -0,0 +1,73 @@
+use crate::commands::Task;
+use crate::config::Config;
+use crate::display::format_minutes;
+use crate::slots::{self, Interval};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// Days ahead the forecast looks at
+pub const DAYS: i64 = 7;
+
+/// One day's scheduled time against its open hours, in minutes
+#[derive(Debug, PartialEq)]
+pub struct DayLoad {
+    pub date: NaiveDate,
+    pub scheduled: i64,
+    pub available: i64,
+}
+
+fn mine<'a>(tasks: &'a [Task], cfg: &Config) -> impl Iterator<Item = &'a Task> {
+    let me = cfg.user.clone();
+    tasks.iter().filter(move |t| t.status.as_deref() != Some("done") && !t.belongs_to_other(me.as_deref()))
+}
+
+/// Whether any load warning is configured
+pub fn enabled(cfg: &Config) -> bool {
+    cfg.scheduling.max_daily_hours.is_some() || cfg.scheduling.overcommit_percent.is_some()
+}
+
+/// Busy events and todo blocks per day from `from`, with that day's availability
+pub fn days(tasks: &[Task], cfg: &Config, from: NaiveDate) -> Vec<DayLoad> {
+    let open: Vec<Task> = mine(tasks, cfg).cloned().collect();
+    let pieces: Vec<Interval> = slots::blocking(&open, cfg.scheduling.all_day_busy).into_iter().flat_map(|b| b.interval.by_day()).collect();
+    (0..DAYS)
+        .map(|i| {
+            let date = from + Duration::days(i);
+            DayLoad {
+                date,
+                scheduled: pieces.iter().filter(|p| p.start.date() == date).map(|p| p.duration().num_minutes()).sum(),
+                available: slots::windows_on(&cfg.availability, date).iter().map(|w| w.duration().num_minutes()).sum(),
+            }
+        })
+        .collect()
+}
+
+/// Overcommitment worth hearing about before the week starts: days over
+/// `scheduling.max_daily_hours`, and a week whose scheduled time plus unplaced work due within
+/// it exceeds availability by more than `scheduling.overcommit_percent`
+pub fn warnings(tasks: &[Task], cfg: &Config, now: NaiveDateTime) -> Vec<String> {
+    let loads = days(tasks, cfg, now.date());
+    let mut out = Vec::new();
+    if let Some(max) = cfg.scheduling.max_daily_hours {
+        for d in loads.iter().filter(|d| d.scheduled as f64 > max * 60.0) {
+            out.push(format!("{}: {} scheduled, over your {} a day", d.date.format("%a %Y-%m-%d"), format_minutes(d.scheduled as i32), format_minutes((max * 60.0) as i32)));
+        }
+    }
+    if let Some(pct) = cfg.scheduling.overcommit_percent {
+        let week_end = (now.date() + Duration::days(DAYS)).and_time(NaiveTime::MIN);
+        let unplaced: i64 = mine(tasks, cfg)
+            .filter(|t| t.kind == "todo" && t.start_time.is_none() && t.status.as_deref() != Some("waiting"))
+            .filter(|t| t.deadline.as_deref().and_then(slots::parse_datetime).is_some_and(|d| d < week_end))
+            .map(|t| t.remaining.or(t.estimate).unwrap_or(0) as i64)
+            .sum();
+        let committed = loads.iter().map(|d| d.scheduled).sum::<i64>() + unplaced;
+        let available = loads.iter().map(|d| d.available).sum::<i64>();
+        if committed as f64 > available as f64 * (1.0 + pct / 100.0) {
+            let over = match available {
+                0 => String::new(),
+                a => format!(" ({:.0}% over)", (committed - a) as f64 * 100.0 / a as f64),
+            };
+            out.push(format!("The next {} days have {} committed against {} available{}", DAYS, format_minutes(committed as i32), format_minutes(available as i32), over));
+        }
+    }
+    out
+}
@@ -0,0 +1,223 @@
+use crate::api::Api;
+use crate::commands::Task;
+use crate::{db, slots, stamp};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+#[derive(clap::ValueEnum, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Every {
+    Day,
+    /// Days outside the configured weekend
+    Weekday,
+    Week,
+}
+
+impl Every {
+    /// The occurrence after `due`
+    pub fn next(self, due: NaiveDate) -> NaiveDate {
+        match self {
+            Every::Day => due + Duration::days(1),
+            Every::Week => due + Duration::weeks(1),
+            Every::Weekday => {
+                let mut d = due + Duration::days(1);
+                while crate::config::get().week.is_weekend(d) {
+                    d += Duration::days(1);
+                }
+                d
+            }
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Every::Day => "day",
+            Every::Weekday => "weekday",
+            Every::Week => "week",
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Every::Week => "week",
+            _ => "day",
+        }
+    }
+}
+
+/// Dates as "YYYY-MM-DD", like the other stores
+mod ymd {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(d: &NaiveDate, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&d.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDate, D::Error> {
+        let raw = String::deserialize(d)?;
+        NaiveDate::parse_from_str(&raw, "%Y-%m-%d").map_err(serde::de::Error::custom)
+    }
+}
+
+/// A past occurrence: finished, or missed when its day went by
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Occurrence {
+    #[serde(with = "ymd")]
+    pub due: NaiveDate,
+    pub task_id: i32,
+    pub done: bool,
+}
+
+/// A recurrence rule and the instances it has spawned; only the current one is open
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Habit {
+    /// The first instance's title, shown as the habit's name
+    pub title: String,
+    pub every: Every,
+    /// The open instance and the day it's due
+    pub task_id: i32,
+    #[serde(with = "ymd")]
+    pub due: NaiveDate,
+    #[serde(default)]
+    pub history: Vec<Occurrence>,
+}
+
+impl Habit {
+    pub fn new(title: &str, every: Every, task_id: i32, due: NaiveDate) -> Self {
+        Habit { title: title.to_string(), every, task_id, due, history: Vec::new() }
+    }
+
+    /// Occurrences finished in a row up to now; an open instance already past its day breaks it
+    pub fn streak(&self, today: NaiveDate) -> usize {
+        if self.due < today {
+            return 0;
+        }
+        self.history.iter().rev().take_while(|o| o.done).count()
+    }
+
+    pub fn best(&self) -> usize {
+        self.history.split(|o| !o.done).map(|run| run.len()).max().unwrap_or(0)
+    }
+
+    pub fn misses(&self) -> usize {
+        self.history.iter().filter(|o| !o.done).count()
+    }
+
+    /// "🔥 12-day streak", or nothing before the first one
+    pub fn badge(&self, today: NaiveDate) -> Option<String> {
+        let n = self.streak(today);
+        (n > 0).then(|| format!("🔥 {}-{} streak", n, self.every.unit()))
+    }
+
+    /// Record every occurrence that went by unfinished; returns the new due date if it moved
+    pub fn catch_up(&mut self, today: NaiveDate) -> Option<NaiveDate> {
+        if self.due >= today {
+            return None;
+        }
+        while self.due < today {
+            self.history.push(Occurrence { due: self.due, task_id: self.task_id, done: false });
+            self.due = self.every.next(self.due);
+        }
+        Some(self.due)
+    }
+
+    /// Close the open instance as done; returns the next due date
+    pub fn complete(&mut self, today: NaiveDate) -> NaiveDate {
+        self.history.push(Occurrence { due: self.due, task_id: self.task_id, done: true });
+        let mut next = self.every.next(self.due);
+        while next < today {
+            next = self.every.next(next);
+        }
+        self.due = next;
+        next
+    }
+}
+
+/// The first instance's task ID → rule; titles can repeat and be renamed, IDs can't
+pub type Habits = BTreeMap<i32, Habit>;
+
+pub const STORE: &str = "habits";
+
+pub fn load() -> Result<Habits, Box<dyn Error>> {
+    Ok(db::read_doc(STORE)?.unwrap_or_default())
+}
+
+pub fn save(habits: &Habits) -> Result<(), Box<dyn Error>> {
+    db::write_doc(STORE, habits)
+}
+
+/// The habit whose open instance is `task_id`
+pub fn for_task(habits: &mut Habits, task_id: i32) -> Option<(&i32, &mut Habit)> {
+    habits.iter_mut().find(|(_, h)| h.task_id == task_id)
+}
+
+/// Streak labels for list output, keyed by the open instance's task ID
+pub fn badges(habits: &Habits, today: NaiveDate) -> BTreeMap<i32, String> {
+    habits.values().filter_map(|h| Some((h.task_id, h.badge(today)?))).collect()
+}
+
+/// `t`'s deadline moved to `due`, same time of day (end of day without one)
+fn deadline_on(t: &Task, due: NaiveDate) -> String {
+    let time = t.deadline.as_deref().and_then(slots::parse_datetime).map(|d| d.time()).unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(23, 59, 0).unwrap_or_default());
+    stamp::keep(t.deadline.as_deref(), due.and_time(time))
+}
+
+/// A fresh copy of a finished instance, due on `due`
+pub async fn spawn(api: &Api, t: &Task, due: NaiveDate) -> Result<Task, Box<dyn Error>> {
+    let mut payload = Map::new();
+    payload.insert("title".into(), Value::String(t.title.clone()));
+    payload.insert("type".into(), Value::String("todo".into()));
+    payload.insert("deadline".into(), Value::String(deadline_on(t, due)));
+    payload.insert("estimate".into(), Value::from(t.estimate.unwrap_or(0)));
+    payload.insert("priority".into(), Value::from(t.priority.unwrap_or(0)));
+    for (key, value) in [("description", &t.description), ("assignee", &t.assignee)] {
+        if let Some(v) = value {
+            payload.insert(key.into(), Value::String(v.clone()));
+        }
+    }
+    if !t.contexts().is_empty() {
+        payload.insert("contexts".into(), Value::from(t.contexts().to_vec()));
+    }
+    if let Some(c) = &t.category {
+        payload.insert("category_id".into(), Value::from(c.id));
+    }
+    api.create_task("repeat", payload).await
+}
+
+/// After `t` was marked done: if it's a habit's open instance, record it and create the next one
+pub async fn completed(api: &Api, t: &Task, today: NaiveDate) -> Result<Option<Task>, Box<dyn Error>> {
+    let mut habits = load()?;
+    let Some((_, habit)) = for_task(&mut habits, t.id) else {
+        return Ok(None);
+    };
+    let due = habit.complete(today);
+    let next = spawn(api, t, due).await?;
+    habit.task_id = next.id;
+    save(&habits)?;
+    Ok(Some(next))
+}
+
+/// Record misses for every habit whose day went by and move its open instance to the next
+/// occurrence; returns how many habits were moved
+pub async fn catch_up(api: &Api, today: NaiveDate) -> Result<usize, Box<dyn Error>> {
+    let mut habits = load()?;
+    let mut moved = 0;
+    for habit in habits.values_mut() {
+        let Some(due) = habit.catch_up(today) else {
+            continue;
+        };
+        let t = api.task(habit.task_id).await?;
+        let mut payload = Map::new();
+        payload.insert("deadline".into(), Value::String(deadline_on(&t, due)));
+        api.update_task(t.id, payload).await?;
+        moved += 1;
+    }
+    if moved > 0 {
+        save(&habits)?;
+    }
+    Ok(moved)
+}
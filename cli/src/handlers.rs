@@ -0,0 +1,872 @@
+use crate::api::Api;
+use crate::audit;
+use crate::commands::{AutoScheduleResult, Category, ShowAs, Task};
+use crate::config::Eviction;
+use crate::context;
+use crate::date_parser::{self, parse_deadline};
+use crate::engine::{self, Plan};
+use crate::filter::Filter;
+use crate::ics;
+use crate::meta::{MetaMap, TaskMeta};
+use crate::progress::Progress;
+use crate::prompt;
+use crate::rows::{self, TaskRef};
+use crate::slots::{self, Busy, Interval};
+use crate::stamp;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use tokio::time::{sleep, Duration};
+
+/// How long list-tasks waits for the background scheduler
+pub struct PollOptions {
+    pub attempts: u32,
+    pub interval: Duration,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        PollOptions { attempts: 10, interval: Duration::from_millis(200) }
+    }
+}
+
+/// Result of waiting for the scheduler to place every todo
+pub struct PollOutcome {
+    pub polls: u32,
+    pub unscheduled: usize,
+}
+
+/// Poll tasks until no TODOs remain unscheduled or attempts run out
+pub async fn wait_for_schedule(api: &Api, poll: &PollOptions) -> Result<PollOutcome, Box<dyn Error>> {
+    let mut outcome = PollOutcome { polls: 0, unscheduled: 0 };
+    let progress = Progress::spinner("Waiting for scheduler");
+    for attempt in 0..poll.attempts {
+        if attempt > 0 {
+            sleep(poll.interval).await;
+        }
+        let tasks = api.tasks().await?;
+        outcome.polls += 1;
+        outcome.unscheduled = tasks
+            .iter()
+            .filter(|t| t.kind == "todo" && t.scheduled_for.is_none())
+            .count();
+        progress.set_message(&format!("Waiting for scheduler ({} unscheduled)", outcome.unscheduled));
+        progress.note_throttle(api.throttle_stats());
+        if outcome.unscheduled == 0 {
+            break;
+        }
+    }
+    Ok(outcome)
+}
+
+pub fn parse_iso(s: &str) -> Option<NaiveDateTime> {
+    stamp::parse(s)
+}
+
+/// Sort by due date (deadline or start_time), ties in id order
+pub fn sort_by_due(tasks: &mut [Task]) {
+    crate::sort::SortSpec::default().apply(tasks, &MetaMap::new());
+}
+
+pub struct Listing {
+    pub tasks: Vec<Task>,
+    pub poll: PollOutcome,
+}
+
+/// Trigger scheduling, wait for it to settle, then fetch tasks sorted by due date
+pub async fn list_tasks(api: &Api, payload: &Value, poll: &PollOptions) -> Result<Listing, Box<dyn Error>> {
+    api.request_schedule(payload).await?;
+    let outcome = wait_for_schedule(api, poll).await?;
+    let mut tasks = api.tasks_ordered().await?;
+    sort_by_due(&mut tasks);
+    Ok(Listing { tasks, poll: outcome })
+}
+
+pub struct EventInput {
+    pub title: String,
+    pub start: String,
+    pub end: String,
+    /// `start`/`end` are the midnights around whole days (see `all_day_span`)
+    pub all_day: bool,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub attendees: Vec<String>,
+    pub organizer: Option<String>,
+    pub meeting_url: Option<String>,
+    pub show_as: Option<ShowAs>,
+    /// A name, "me" or "unassigned"
+    pub assignee: Option<String>,
+}
+
+/// Start and (exclusive) end midnights covering `first` through `last`, e.g. "2030-05-07" or "friday"
+pub fn all_day_span(first: &str, last: Option<&str>) -> Result<(String, String), Box<dyn Error>> {
+    let day = |s: &str| -> Result<NaiveDate, Box<dyn Error>> {
+        if let Ok(d) = NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d") {
+            return Ok(d);
+        }
+        let parsed = parse_deadline(s).map_err(|e| format!("Invalid day `{}`: {}", s, e))?;
+        Ok(slots::parse_datetime(&parsed.chosen).ok_or("Invalid day")?.date())
+    };
+    let start = day(first)?;
+    let end = last.map(day).transpose()?.unwrap_or(start);
+    if end < start {
+        return Err("The last day must not be before the first.".into());
+    }
+    let midnight = |d: NaiveDate| format!("{}T00:00:00", d.format("%Y-%m-%d"));
+    Ok((midnight(start), midnight(end + chrono::Duration::days(1))))
+}
+
+/// A start as given when it's already a timestamp, else read like a deadline ("tomorrow 3pm")
+fn event_start(start: &str) -> Result<(String, NaiveDateTime), Box<dyn Error>> {
+    if let Some(t) = slots::parse_datetime(start) {
+        return Ok((start.to_string(), t));
+    }
+    let parsed = parse_deadline(start).map_err(|e| format!("Invalid time `{}` (use YYYY-MM-DDTHH:MM or e.g. \"tomorrow 3pm\"): {}", start, e))?;
+    let t = slots::parse_datetime(&parsed.chosen).ok_or("Invalid time")?;
+    Ok((parsed.chosen, t))
+}
+
+/// Event start and end; a bare "HH:MM" end is on the start's day, or the next one for overnight events
+pub fn event_span(start: &str, end: &str) -> Result<(String, String), Box<dyn Error>> {
+    let (start, begin) = event_start(start)?;
+    let Ok(time) = NaiveTime::parse_from_str(end.trim(), "%H:%M") else {
+        return Ok((start, end.to_string()));
+    };
+    let mut finish = begin.date().and_time(time);
+    if finish <= begin {
+        finish += chrono::Duration::days(1);
+    }
+    Ok((start, stamp::format(finish)))
+}
+
+/// Event start and end from exactly one of an end and a length ("2h", "90m")
+pub fn timed_span(start: &str, end: Option<&str>, length: Option<&str>) -> Result<(String, String), Box<dyn Error>> {
+    match (end, length) {
+        (Some(end), None) => event_span(start, end),
+        (None, Some(length)) => {
+            let (start, begin) = event_start(start)?;
+            let finish = begin.checked_add_signed(date_parser::parse_duration(length)?).ok_or("Duration too long")?;
+            Ok((start, stamp::format(finish)))
+        }
+        (Some(_), Some(_)) => Err("Give an end or a length (--for), not both.".into()),
+        (None, None) => Err("An end or a length (--for) is required.".into()),
+    }
+}
+
+pub async fn create_event(api: &Api, input: EventInput) -> Result<Task, Box<dyn Error>> {
+    if let Some(bad) = input.attendees.iter().chain(&input.organizer).find(|a| !a.contains('@')) {
+        return Err(format!("'{}' is not an email address", bad).into());
+    }
+    let mut payload = Map::new();
+    payload.insert("title".into(), Value::String(input.title));
+    payload.insert("type".into(), Value::String("event".into()));
+    payload.insert("start_time".into(), Value::String(input.start));
+    payload.insert("end_time".into(), Value::String(input.end));
+    if input.all_day {
+        payload.insert("all_day".into(), Value::Bool(true));
+    }
+    if let Some(desc) = input.description {
+        payload.insert("description".into(), Value::String(desc));
+    }
+    if let Some(a) = input.show_as {
+        payload.insert("transparency".into(), Value::String(a.as_str().into()));
+    }
+    if let Some(loc) = input.location {
+        payload.insert("location".into(), Value::String(loc));
+    }
+    if !input.attendees.is_empty() {
+        payload.insert("attendees".into(), input.attendees.into_iter().map(Value::String).collect());
+    }
+    if let Some(o) = input.organizer {
+        payload.insert("organizer".into(), Value::String(o));
+    }
+    if let Some(url) = input.meeting_url {
+        payload.insert("meeting_url".into(), Value::String(url));
+    }
+    insert_assignee(&mut payload, input.assignee.as_deref())?;
+    api.create_task("create-event", payload).await
+}
+
+/// What an .ics import did
+#[derive(Default, Debug)]
+pub struct EventsImported {
+    pub created: usize,
+    /// Already in the backend with the same title and start
+    pub skipped: usize,
+    pub failed: Vec<String>,
+}
+
+/// Create an event per occurrence; re-importing the same file skips what's already there
+pub async fn import_events(api: &Api, events: &[ics::Event]) -> Result<EventsImported, Box<dyn Error>> {
+    let existing: Vec<(String, NaiveDateTime)> = api
+        .tasks()
+        .await?
+        .into_iter()
+        .filter_map(|t| Some((t.title.clone(), slots::parse_datetime(t.start_time.as_deref()?)?)))
+        .collect();
+    let mut done = EventsImported::default();
+    for e in events {
+        if existing.iter().any(|(title, start)| *title == e.summary && *start == e.interval.start) {
+            done.skipped += 1;
+            continue;
+        }
+        let mut payload = Map::new();
+        payload.insert("title".into(), Value::String(if e.summary.is_empty() { "(no title)".into() } else { e.summary.clone() }));
+        payload.insert("type".into(), Value::String("event".into()));
+        payload.insert("start_time".into(), Value::String(stamp::format(e.interval.start)));
+        payload.insert("end_time".into(), Value::String(stamp::format(e.interval.end)));
+        if e.all_day {
+            payload.insert("all_day".into(), Value::Bool(true));
+        }
+        if let Some(loc) = &e.location {
+            payload.insert("location".into(), Value::String(loc.clone()));
+        }
+        if let Some(desc) = &e.description {
+            payload.insert("description".into(), Value::String(desc.clone()));
+        }
+        payload.insert("transparency".into(), Value::String(e.show_as.as_str().into()));
+        match api.create_task("import-ics", payload).await {
+            Ok(_) => done.created += 1,
+            Err(err) => done.failed.push(format!("{} ({}): {}", e.summary, e.interval.start.format("%Y-%m-%d %H:%M"), err)),
+        }
+    }
+    Ok(done)
+}
+
+pub struct Conflict {
+    pub clashes: Vec<Busy>,
+    /// Nearest free slots of the same length (empty unless requested)
+    pub suggestions: Vec<Interval>,
+}
+
+/// Compare a proposed event against existing events and scheduled todo blocks
+pub async fn event_conflicts(api: &Api, start: &str, end: &str, suggest: usize) -> Result<Option<Conflict>, Box<dyn Error>> {
+    let parse = |s: &str| slots::parse_datetime(s).ok_or_else(|| format!("Invalid time `{}` (use YYYY-MM-DDTHH:MM)", s));
+    let wanted = Interval::new(parse(start)?, parse(end)?);
+    if wanted.end <= wanted.start {
+        return Err("End time must be after start time.".into());
+    }
+    let busy = slots::blocking(&api.tasks().await?, crate::config::get().scheduling.all_day_busy);
+    let clashes: Vec<Busy> = slots::conflicts(&busy, &wanted).into_iter().cloned().collect();
+    if clashes.is_empty() {
+        return Ok(None);
+    }
+    let suggestions = if suggest > 0 {
+        slots::nearest_free(&crate::config::get().scheduling_availability(None, false, &[]), &busy, wanted, suggest)
+    } else {
+        Vec::new()
+    };
+    Ok(Some(Conflict { clashes, suggestions }))
+}
+
+pub struct FreeQuery {
+    pub duration: chrono::Duration,
+    pub range: Interval,
+    /// External calendars whose busy times also block a slot
+    pub participants: Vec<PathBuf>,
+}
+
+/// Gaps inside availability (minus quiet hours) not covered by our tasks or any participant's calendar
+pub async fn free_slots(api: &Api, query: &FreeQuery) -> Result<Vec<Interval>, Box<dyn Error>> {
+    let mut busy = slots::blocking(&api.tasks().await?, crate::config::get().scheduling.all_day_busy);
+    for p in &query.participants {
+        busy.extend(ics::busy_from_file(p, &query.range)?);
+    }
+    let cfg = crate::config::get();
+    let step = cfg.scheduling.slot_minutes;
+    // Gaps start on the slot grid so a meeting can be booked at the time shown
+    Ok(slots::free_gaps(&cfg.scheduling_availability(None, false, &[]), &busy, query.range, query.duration)
+        .into_iter()
+        .map(|g| Interval::new(slots::align_up(g.start, step), g.end))
+        .filter(|g| g.duration() >= query.duration)
+        .collect())
+}
+
+pub struct TodoInput {
+    pub title: String,
+    pub estimate: i32,
+    /// A phrase like "next friday", or a time already resolved by `check_deadline`, sent as it is
+    pub deadline: String,
+    pub priority: i32,
+    pub description: Option<String>,
+    /// A name, "me" or "unassigned"
+    pub assignee: Option<String>,
+    pub optional: bool,
+}
+
+/// Says which day an ambiguous deadline ("next friday", "3/4/25") was taken as, or with `strict`
+/// refuses it; the chosen reading comes back
+pub fn check_deadline(deadline: &str, strict: bool) -> Result<String, Box<dyn Error>> {
+    let parsed = parse_deadline(deadline).map_err(|e| format!("Error parsing deadline `{}`: {}", deadline, e))?;
+    if parsed.alternatives.is_empty() {
+        return Ok(parsed.chosen);
+    }
+    let day = |iso: &str| slots::parse_datetime(iso).map(|d| d.format("%a %b %-d").to_string()).unwrap_or_else(|| iso.to_string());
+    let others = parsed.alternatives.iter().map(|a| day(a)).collect::<Vec<_>>().join(" or ");
+    if strict {
+        return Err(format!("Deadline `{}` is ambiguous: {} or {}; give a date like {}", deadline, day(&parsed.chosen), others, &parsed.chosen[..10]).into());
+    }
+    eprintln!("`{}` could also mean {}; interpreting as {}; use --deadline-strict to error on ambiguity", deadline, others, day(&parsed.chosen));
+    Ok(parsed.chosen)
+}
+
+/// Refuses a deadline or event end that has already passed; with `allow_past` it only warns
+pub fn check_past(what: &str, iso: &str, allow_past: bool) -> Result<(), Box<dyn Error>> {
+    let Some(at) = slots::parse_datetime(iso) else { return Ok(()) };
+    if at >= crate::status::now() {
+        return Ok(());
+    }
+    let when = at.format("%a %b %-d %H:%M");
+    if !allow_past {
+        return Err(format!("The {} ({}) is in the past; pass --allow-past to create it anyway.", what, when).into());
+    }
+    eprintln!("Warning: the {} ({}) is in the past", what, when);
+    Ok(())
+}
+
+/// An event must end after it starts, and by default not already be over
+pub fn check_event_times(start: &str, end: &str, allow_past: bool) -> Result<(), Box<dyn Error>> {
+    if let (Some(s), Some(e)) = (slots::parse_datetime(start), slots::parse_datetime(end)) {
+        if e <= s {
+            return Err(format!("The event ends ({}) before it starts ({}).", e.format("%a %b %-d %H:%M"), s.format("%a %b %-d %H:%M")).into());
+        }
+    }
+    check_past("event end", end, allow_past)
+}
+
+/// Parse the natural-language deadline, then create the todo
+pub async fn create_todo(api: &Api, input: TodoInput) -> Result<Task, Box<dyn Error>> {
+    // Parsing a phrase again could land on another day than the one already warned about
+    let iso_deadline = match stamp::parse(&input.deadline) {
+        Some(at) => stamp::keep(Some(&input.deadline), at),
+        None => parse_deadline(&input.deadline).map_err(|e| format!("Error parsing deadline `{}`: {}", input.deadline, e))?.chosen,
+    };
+    // "Buy milk @errands" is filed under the errands context
+    let (title, contexts) = context::split_title(&input.title);
+    if title.is_empty() {
+        return Err("The title can't be only @contexts.".into());
+    }
+    let mut payload = Map::new();
+    payload.insert("title".into(), Value::String(title));
+    payload.insert("type".into(), Value::String("todo".into()));
+    if !contexts.is_empty() {
+        payload.insert("contexts".into(), Value::from(contexts));
+    }
+    payload.insert("estimate".into(), Value::Number(input.estimate.into()));
+    payload.insert("deadline".into(), Value::String(iso_deadline));
+    payload.insert("priority".into(), Value::Number(input.priority.into()));
+    if let Some(desc) = input.description {
+        payload.insert("description".into(), Value::String(desc));
+    }
+    insert_assignee(&mut payload, input.assignee.as_deref())?;
+    if input.optional {
+        payload.insert("optional".into(), Value::Bool(true));
+    }
+    api.create_task("create-todo", payload).await
+}
+
+#[derive(Default)]
+pub struct TaskChanges {
+    pub status: Option<String>,
+    pub title: Option<String>,
+    pub priority: Option<i32>,
+    pub show_as: Option<ShowAs>,
+    /// Replaces the task's contexts
+    pub contexts: Option<Vec<String>>,
+    /// Already in the backend's ISO format
+    pub deadline: Option<String>,
+    pub description: Option<String>,
+    /// A name, "me" or "unassigned"
+    pub assignee: Option<String>,
+    pub optional: Option<bool>,
+}
+
+/// Resolve "me"/"unassigned" and add the assignee, if any; an empty one unassigns
+fn insert_assignee(payload: &mut Map<String, Value>, who: Option<&str>) -> Result<(), Box<dyn Error>> {
+    if let Some(who) = who {
+        payload.insert("assignee".into(), Value::String(crate::config::get().assignee(who)?));
+    }
+    Ok(())
+}
+
+pub async fn update_task(api: &Api, task_id: i32, changes: TaskChanges) -> Result<Task, Box<dyn Error>> {
+    let mut payload = Map::new();
+    if let Some(s) = changes.status {
+        payload.insert("status".into(), Value::String(s));
+    }
+    if let Some(tl) = changes.title {
+        payload.insert("title".into(), Value::String(tl));
+    }
+    if let Some(p) = changes.priority {
+        payload.insert("priority".into(), Value::Number(p.into()));
+    }
+    if let Some(a) = changes.show_as {
+        payload.insert("transparency".into(), Value::String(a.as_str().into()));
+    }
+    if let Some(c) = changes.contexts {
+        payload.insert("contexts".into(), Value::from(c));
+    }
+    if let Some(d) = changes.deadline {
+        payload.insert("deadline".into(), Value::String(d));
+    }
+    if let Some(d) = changes.description {
+        payload.insert("description".into(), Value::String(d));
+    }
+    insert_assignee(&mut payload, changes.assignee.as_deref())?;
+    if let Some(o) = changes.optional {
+        payload.insert("optional".into(), Value::Bool(o));
+    }
+    if payload.is_empty() {
+        return Err("No updates provided.".into());
+    }
+    api.update_task(task_id, payload).await
+}
+
+/// A `--color` value as stored: "auto" picks one unlike `others`' colors
+pub fn category_color(input: &str, others: &[Category]) -> Result<String, String> {
+    match input.trim().eq_ignore_ascii_case("auto") {
+        true => Ok(crate::color::distinct(&others.iter().map(|c| c.color.clone()).collect::<Vec<_>>())),
+        false => crate::color::parse(input),
+    }
+}
+
+#[derive(Default)]
+pub struct CategoryChanges {
+    pub name: Option<String>,
+    /// Anything `category_color` takes
+    pub color: Option<String>,
+    /// An empty one removes the icon
+    pub icon: Option<String>,
+}
+
+/// Change the category with this ID or name
+pub async fn update_category(api: &Api, category: &str, changes: CategoryChanges) -> Result<Category, Box<dyn Error>> {
+    let all = api.categories().await?;
+    let wanted = category.trim();
+    let c = all
+        .iter()
+        .find(|c| c.id.to_string() == wanted || c.name.eq_ignore_ascii_case(wanted))
+        .ok_or_else(|| format!("No category '{}'", wanted))?;
+    let mut payload = Map::new();
+    if let Some(n) = changes.name {
+        payload.insert("name".into(), Value::String(n));
+    }
+    if let Some(color) = changes.color {
+        let others: Vec<Category> = all.iter().filter(|o| o.id != c.id).cloned().collect();
+        payload.insert("color".into(), Value::String(category_color(&color, &others)?));
+    }
+    if let Some(icon) = changes.icon {
+        payload.insert("icon".into(), Value::String(icon.trim().to_string()));
+    }
+    if payload.is_empty() {
+        return Err("No updates provided.".into());
+    }
+    api.update_category(c.id, payload).await
+}
+
+/// Copies of `tasks` with each todo's estimate replaced by the time it has left, for planning
+pub fn with_remaining(tasks: &[Task], meta: &MetaMap) -> Vec<Task> {
+    tasks
+        .iter()
+        .cloned()
+        .map(|mut t| {
+            if let Some(left) = crate::meta::remaining(&t, meta).filter(|_| t.kind == "todo") {
+                t.estimate = Some(left);
+            }
+            t
+        })
+        .collect()
+}
+
+/// Take `spent` off a todo's remaining time (its estimate until now); stored on the backend when
+/// it keeps `remaining`, locally otherwise. Returns the task and the minutes left
+pub async fn log_progress(api: &Api, task_id: i32, spent: chrono::Duration) -> Result<(Task, i32), Box<dyn Error>> {
+    let t = api.task(task_id).await?;
+    if t.kind != "todo" {
+        return Err(format!("Task {} is an event; progress is only tracked on todos.", task_id).into());
+    }
+    let meta = crate::meta::load()?;
+    let before = crate::meta::remaining(&t, &meta).or(t.estimate).ok_or_else(|| format!("Task {} has no estimate to count down from.", task_id))?;
+    let left = (before - spent.num_minutes() as i32).max(0);
+    let mut payload = Map::new();
+    payload.insert("remaining".into(), Value::from(left));
+    let t = api.update_task(task_id, payload).await?;
+    let local = (t.remaining != Some(left)).then_some(left);
+    crate::meta::update(task_id, |m| m.remaining_minutes = local)?;
+    Ok((t, left))
+}
+
+/// When the task was last changed (or created, for older backends)
+pub fn last_touched(t: &Task) -> Option<NaiveDateTime> {
+    t.updated_at.as_deref().or(t.created_at.as_deref()).and_then(slots::parse_datetime)
+}
+
+/// IDs a single-task-or-`--where` command acts on; `None` when the user backed out of a bulk
+/// change. `ask` adds a yes/no question even under the mass-change limit
+pub async fn targets(
+    api: &Api,
+    task: Option<TaskRef>,
+    filter: Option<&Filter>,
+    verb: &str,
+    confirm_count: Option<usize>,
+    ask: bool,
+) -> Result<Option<Vec<i32>>, Box<dyn Error>> {
+    let Some(filter) = filter else {
+        return Ok(Some(vec![rows::resolve(task.ok_or("Name a task or pass --where")?)?]));
+    };
+    let matched = filter.select(api.tasks().await?, &crate::meta::load()?, crate::status::now());
+    if matched.is_empty() {
+        println!("No tasks match.");
+        return Ok(None);
+    }
+    let titles: Vec<String> = matched.iter().map(|t| format!("[{}] {}", t.id, t.title)).collect();
+    Ok(prompt::approve_bulk(verb, &titles, confirm_count, ask)?.then(|| matched.iter().map(|t| t.id).collect()))
+}
+
+/// Open tasks untouched for at least `days`, oldest first
+pub fn stale(tasks: Vec<Task>, now: NaiveDateTime, days: i64) -> Vec<Task> {
+    let cutoff = now - chrono::Duration::days(days);
+    let mut out: Vec<Task> = tasks
+        .into_iter()
+        .filter(|t| t.status.as_deref() != Some("done") && last_touched(t).is_some_and(|at| at <= cutoff))
+        .collect();
+    out.sort_by_key(last_touched);
+    out
+}
+
+/// The fewest days `todo cleanup` waits before removing tasks that are on the calendar
+pub const CLEANUP_MIN_DAYS: i64 = 30;
+
+/// Done tasks unchanged for `days`, oldest first; with `pushed_only`, just those already on
+/// the calendar
+pub fn finished(tasks: Vec<Task>, now: NaiveDateTime, days: i64, pushed_only: bool) -> Vec<Task> {
+    let cutoff = now - chrono::Duration::days(days);
+    let mut out: Vec<Task> = tasks
+        .into_iter()
+        .filter(|t| t.status.as_deref() == Some("done") && last_touched(t).is_some_and(|at| at <= cutoff))
+        .filter(|t| !pushed_only || t.external_id.as_deref().is_some_and(|id| !id.is_empty()))
+        .collect();
+    out.sort_by_key(last_touched);
+    out
+}
+
+/// Todos with a block on `day` that aren't done, earliest block first
+pub fn slipped(tasks: Vec<Task>, day: NaiveDate) -> Vec<Task> {
+    let start = |t: &Task| t.start_time.as_deref().and_then(slots::parse_datetime);
+    let mut out: Vec<Task> = tasks
+        .into_iter()
+        .filter(|t| t.kind == "todo" && t.status.as_deref() != Some("done") && start(t).is_some_and(|s| s.date() == day))
+        .collect();
+    out.sort_by_key(start);
+    out
+}
+
+/// Actionable on `now`'s day: scheduled that day, due that day or earlier, or in progress
+pub fn on_today(t: &Task, now: NaiveDateTime) -> bool {
+    let at = |s: &Option<String>| s.as_deref().and_then(slots::parse_datetime);
+    let midnight = now.date().and_time(NaiveTime::MIN);
+    let today = Interval::new(midnight, midnight + chrono::Duration::days(1));
+    let scheduled = match (at(&t.start_time), at(&t.end_time)) {
+        (Some(s), Some(e)) => s.date() == now.date() || Interval::new(s, e.max(s)).overlaps(&today),
+        _ => false,
+    };
+    scheduled || at(&t.deadline).is_some_and(|d| d.date() <= now.date()) || t.status.as_deref() == Some("in-progress")
+}
+
+/// `todo today`: open tasks from `on_today`, in progress first, then by start or deadline
+pub fn today(tasks: Vec<Task>, now: NaiveDateTime) -> Vec<Task> {
+    let when = |t: &Task| t.start_time.as_deref().or(t.deadline.as_deref()).and_then(slots::parse_datetime);
+    let mut out: Vec<Task> = tasks.into_iter().filter(|t| t.status.as_deref() != Some("done") && on_today(t, now)).collect();
+    out.sort_by_key(|t| (t.status.as_deref() != Some("in-progress"), when(t).is_none(), when(t)));
+    out
+}
+
+pub struct Rollover {
+    /// As they were before moving, priorities included
+    pub slipped: Vec<Task>,
+    pub plan: Plan,
+}
+
+/// Move today's unfinished blocks to tomorrow onwards with the local engine, leaving later
+/// placements alone; `bump` raises each slipped task's priority by one (up to 10) first
+pub async fn rollover(api: &Api, today: NaiveDate, bump: bool, dry_run: bool) -> Result<Rollover, Box<dyn Error>> {
+    let mut tasks = api.tasks().await?;
+    let slipped = slipped(tasks.clone(), today);
+    if slipped.is_empty() {
+        return Ok(Rollover { slipped, plan: Plan::default() });
+    }
+    if bump && !dry_run {
+        for t in &slipped {
+            let priority = (t.priority.unwrap_or(0) + 1).min(10);
+            let updated = update_task(api, t.id, TaskChanges { priority: Some(priority), ..Default::default() }).await?;
+            if let Some(slot) = tasks.iter_mut().find(|x| x.id == t.id) {
+                *slot = updated;
+            }
+        }
+    }
+    let tomorrow = (today + chrono::Duration::days(1)).and_time(NaiveTime::MIN);
+    let plan = place(api, &tasks, tomorrow, dry_run, Some(1.0)).await?.plan;
+    Ok(Rollover { slipped, plan })
+}
+
+/// Tasks in the "waiting" state, longest-waiting first
+pub fn waiting(tasks: Vec<Task>, meta: &MetaMap) -> Vec<Task> {
+    let mut out: Vec<Task> = tasks.into_iter().filter(|t| t.status.as_deref() == Some("waiting")).collect();
+    let since = |t: &Task| meta.get(&t.id).and_then(|m| m.waiting_since.clone());
+    out.sort_by_key(|t| (since(t).is_none(), since(t)));
+    out
+}
+
+/// The day to chase a waiting task up: `days` after it was handed off
+pub fn follow_up_on(meta: &TaskMeta, days: i64) -> Option<NaiveDate> {
+    let since = NaiveDate::parse_from_str(meta.waiting_since.as_deref()?, "%Y-%m-%d").ok()?;
+    Some(since + chrono::Duration::days(days))
+}
+
+/// Waiting tasks due a follow-up by `today`, longest-waiting first
+pub fn follow_ups(tasks: Vec<Task>, meta: &MetaMap, days: i64, today: NaiveDate) -> Vec<Task> {
+    let due = |t: &Task| meta.get(&t.id).and_then(|m| follow_up_on(m, days)).is_some_and(|d| d <= today);
+    waiting(tasks, meta).into_iter().filter(due).collect()
+}
+
+/// Use the given config file or the configured availability; `stability` requests an incremental run
+pub async fn auto_schedule(api: &Api, config_file: Option<&str>, stability: Option<f64>) -> Result<AutoScheduleResult, Box<dyn Error>> {
+    let mut payload = match config_file {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => crate::config::get().schedule_payload(),
+    };
+    if let Some(s) = stability {
+        crate::config::set_incremental(&mut payload, s);
+    }
+    let _progress = Progress::spinner("Submitting schedule request");
+    api.auto_schedule(payload).await
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Shift {
+    /// Had no day, has one now
+    Placed,
+    Moved,
+    /// Had a day, lost it
+    Evicted,
+}
+
+/// How one todo's `scheduled_for` changed across a scheduling run
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub task_id: i32,
+    pub title: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub shift: Shift,
+    /// The eviction policy the run was asked to use, on evicted todos
+    pub policy: Option<Eviction>,
+}
+
+/// Open todos whose `scheduled_for` differs between two snapshots, in `after` order; evicted
+/// ones carry `policy`
+pub fn schedule_diff(before: &[Task], after: &[Task], policy: Option<Eviction>) -> Vec<Change> {
+    after
+        .iter()
+        .filter(|t| t.kind == "todo" && t.status.as_deref() != Some("done"))
+        .filter_map(|t| {
+            let was = before.iter().find(|b| b.id == t.id).and_then(|b| b.scheduled_for.clone());
+            let shift = match (&was, &t.scheduled_for) {
+                (None, Some(_)) => Shift::Placed,
+                (Some(_), None) => Shift::Evicted,
+                (Some(a), Some(b)) if a != b => Shift::Moved,
+                _ => return None,
+            };
+            let policy = policy.filter(|_| shift == Shift::Evicted);
+            Some(Change { task_id: t.id, title: t.title.clone(), before: was, after: t.scheduled_for.clone(), shift, policy })
+        })
+        .collect()
+}
+
+pub struct LocalSchedule {
+    pub plan: Plan,
+    /// Tasks whose placement differed from the server's and was written back
+    pub updated: Vec<i32>,
+}
+
+/// Plan every todo locally, then PATCH the ones whose slot changed (unless `dry_run`);
+/// `stability` makes the run incremental
+pub async fn local_schedule(api: &Api, dry_run: bool, stability: Option<f64>) -> Result<LocalSchedule, Box<dyn Error>> {
+    let tasks = api.tasks().await?;
+    place(api, &tasks, chrono::Local::now().naive_local(), dry_run, stability).await
+}
+
+/// Plan `tasks` as of `now` and write back the placements that changed
+async fn place(api: &Api, tasks: &[Task], now: NaiveDateTime, dry_run: bool, stability: Option<f64>) -> Result<LocalSchedule, Box<dyn Error>> {
+    let meta = crate::meta::load()?;
+    let opts = engine::Options { stability, meta: &meta, ..engine::Options::new(crate::config::get(), now) };
+    let left = with_remaining(tasks, &meta);
+    let calibrated;
+    let tasks = match crate::config::get().scheduling.calibrate {
+        true => {
+            calibrated = crate::calibrate::apply(&left, &meta);
+            &calibrated[..]
+        }
+        false => &left[..],
+    };
+    let plan = engine::plan(tasks, &opts);
+    let changed: Vec<&engine::Placement> = plan
+        .placed
+        .iter()
+        .filter(|p| {
+            let current = tasks.iter().find(|t| t.id == p.task_id);
+            current.and_then(|t| t.start_time.as_deref()).and_then(slots::parse_datetime) != Some(p.slot.start)
+                || current.and_then(|t| t.end_time.as_deref()).and_then(slots::parse_datetime) != Some(p.slot.end)
+        })
+        .collect();
+    if dry_run {
+        return Ok(LocalSchedule { updated: changed.iter().map(|p| p.task_id).collect(), plan });
+    }
+
+    let progress = Progress::bar(changed.len() as u64, "Updating placements");
+    for p in &changed {
+        let mut payload = Map::new();
+        payload.insert("start_time".into(), Value::String(stamp::format(p.slot.start)));
+        payload.insert("end_time".into(), Value::String(stamp::format(p.slot.end)));
+        payload.insert("scheduled_for".into(), Value::String(p.slot.start.format("%Y-%m-%d").to_string()));
+        api.update_task(p.task_id, payload).await?;
+        progress.inc(1);
+        progress.note_throttle(api.throttle_stats());
+    }
+    let updated = changed.iter().map(|p| p.task_id).collect();
+    Ok(LocalSchedule { plan, updated })
+}
+
+pub enum Explained {
+    /// Free-form answer from the backend's explain endpoint
+    Server(Value),
+    Local(engine::Explanation),
+}
+
+/// Ask the backend first (unless `local_only`), then fall back to replaying the local engine
+pub async fn explain(api: &Api, task_id: i32, local_only: bool) -> Result<Explained, Box<dyn Error>> {
+    if !local_only {
+        if let Some(v) = api.explain(task_id).await? {
+            return Ok(Explained::Server(v));
+        }
+    }
+    let tasks = api.tasks().await?;
+    let cfg = crate::config::get();
+    let meta = crate::meta::load()?;
+    let opts = engine::Options {
+        stability: cfg.scheduling.incremental_stability(),
+        meta: &meta,
+        ..engine::Options::new(cfg, chrono::Local::now().naive_local())
+    };
+    match engine::explain(&with_remaining(&tasks, &meta), &opts, task_id) {
+        Some(e) => Ok(Explained::Local(e)),
+        None if tasks.iter().any(|t| t.id == task_id) => {
+            Err(format!("Task {} isn't a pending todo, so the scheduler doesn't place it.", task_id).into())
+        }
+        None => Err(format!("Task {} not found.", task_id).into()),
+    }
+}
+
+/// The local planner's options for an infeasible deadline; see `negotiate::offer`
+pub async fn negotiate(api: &Api, task_id: i32) -> Result<(Task, crate::negotiate::Offer), Box<dyn Error>> {
+    let tasks = api.tasks().await?;
+    let meta = crate::meta::load()?;
+    let opts = engine::Options { meta: &meta, ..engine::Options::new(crate::config::get(), crate::status::now()) };
+    let offer = crate::negotiate::offer(&with_remaining(&tasks, &meta), &opts, task_id)?;
+    let task = tasks.into_iter().find(|t| t.id == task_id).ok_or("task vanished")?;
+    Ok((task, offer))
+}
+
+/// Cut `task` down to `keep` minutes and file the other `rest` as "<title> (part 2)" in the same
+/// category and contexts, due at `due` if given
+pub async fn split_task(api: &Api, task: &Task, keep: i32, rest: i32, due: Option<NaiveDateTime>) -> Result<(Task, Task), Box<dyn Error>> {
+    let mut payload = Map::new();
+    payload.insert("estimate".into(), Value::from(keep));
+    let first = api.update_task(task.id, payload).await?;
+    let mut payload = Map::new();
+    payload.insert("title".into(), Value::String(format!("{} (part 2)", task.title)));
+    payload.insert("type".into(), Value::String("todo".into()));
+    payload.insert("estimate".into(), Value::from(rest));
+    payload.insert("priority".into(), Value::from(task.priority.unwrap_or(0)));
+    if let Some(d) = due {
+        payload.insert("deadline".into(), Value::String(stamp::format(d)));
+    }
+    if let Some(c) = &task.category {
+        payload.insert("category_id".into(), Value::from(c.id));
+    }
+    if !task.contexts().is_empty() {
+        payload.insert("contexts".into(), Value::from(task.contexts().to_vec()));
+    }
+    insert_assignee(&mut payload, task.assignee.as_deref().filter(|a| !a.is_empty()))?;
+    let second = api.create_task("create-todo", payload).await?;
+    Ok((first, second))
+}
+
+/// Audit entries, optionally for one task, keeping only the newest `limit`
+pub fn history(task: Option<i32>, limit: Option<usize>) -> Result<Vec<audit::Entry>, Box<dyn Error>> {
+    let mut entries = match task {
+        Some(id) => audit::for_task(id)?,
+        None => audit::read_all()?,
+    };
+    if let Some(n) = limit {
+        let skip = entries.len().saturating_sub(n);
+        entries.drain(..skip);
+    }
+    Ok(entries)
+}
+
+pub struct TimelineEntry {
+    pub timestamp: String,
+    /// `timestamp` in local time, when it reads as one
+    pub at: Option<NaiveDateTime>,
+    pub source: &'static str,
+    pub activity: String,
+}
+
+pub struct TaskLog {
+    pub entries: Vec<TimelineEntry>,
+    /// Set when the server history couldn't be fetched at all
+    pub server_error: Option<String>,
+}
+
+/// A server history time in local time; one without an offset is UTC, as the backend writes them
+fn server_time(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(t.with_timezone(&chrono::Local).naive_local());
+    }
+    let utc = stamp::parse(s)?;
+    Some(chrono::Utc.from_utc_datetime(&utc).with_timezone(&chrono::Local).naive_local())
+}
+
+/// Local audit entries merged with the server's history endpoint (if it has one)
+pub async fn task_log(api: &Api, task_id: i32) -> Result<TaskLog, Box<dyn Error>> {
+    let mut entries: Vec<TimelineEntry> = audit::for_task(task_id)?
+        .iter()
+        .map(|e| TimelineEntry { timestamp: e.timestamp.clone(), at: stamp::parse(&e.timestamp), source: "local", activity: audit::describe(e) })
+        .collect();
+
+    let mut server_error = None;
+    match api.task_history(task_id).await {
+        Ok(Some(events)) => {
+            for ev in events {
+                let activity = match ev.detail {
+                    Some(d) => format!("{}: {}", ev.event, d),
+                    None => ev.event,
+                };
+                // Shown in local time like the audit entries, when it reads
+                let at = server_time(&ev.timestamp);
+                let timestamp = at.map(stamp::format).unwrap_or(ev.timestamp);
+                entries.push(TimelineEntry { timestamp, at, source: "server", activity });
+            }
+        }
+        Ok(None) => {}
+        Err(e) => server_error = Some(e.to_string()),
+    }
+    entries.sort_by(|a, b| match (a.at, b.at) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        _ => a.timestamp.cmp(&b.timestamp),
+    });
+    Ok(TaskLog { entries, server_error })
+}
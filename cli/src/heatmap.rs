@@ -0,0 +1,194 @@
+use crate::audit::Entry;
+use crate::config::Week;
+use crate::insights::{at, marks_done};
+use crate::meta::MetaMap;
+use chrono::{Datelike, Duration, NaiveDate};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// What got finished on one day
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct Day {
+    pub tasks: usize,
+    /// Time recorded with `done --took`, else the task's estimate
+    pub minutes: i64,
+}
+
+/// Completions per day from the audit log; a task marked done twice on one day counts once
+pub fn completed_by_day(entries: &[Entry], meta: &MetaMap) -> BTreeMap<NaiveDate, Day> {
+    let mut seen = BTreeSet::new();
+    let mut days: BTreeMap<NaiveDate, Day> = BTreeMap::new();
+    for e in entries.iter().filter(|e| e.ok && marks_done(e)) {
+        let (Some(id), Some(when)) = (e.task_id, at(e)) else { continue };
+        if !seen.insert((when.date(), id)) {
+            continue;
+        }
+        let took = meta.get(&id).and_then(|m| m.actual_minutes).map(i64::from);
+        let day = days.entry(when.date()).or_default();
+        day.tasks += 1;
+        day.minutes += took.or_else(|| e.result.get("estimate").and_then(Value::as_i64)).unwrap_or(0);
+    }
+    days
+}
+
+/// A calendar year, or the 52 weeks up to `today`
+pub fn span(year: Option<i32>, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    match year.and_then(|y| Some((NaiveDate::from_ymd_opt(y, 1, 1)?, NaiveDate::from_ymd_opt(y, 12, 31)?))) {
+        Some(span) => span,
+        None => (today - Duration::weeks(52) + Duration::days(1), today),
+    }
+}
+
+fn value(d: Option<&Day>, hours: bool) -> i64 {
+    d.map_or(0, |d| if hours { d.minutes } else { d.tasks as i64 })
+}
+
+/// 0 for nothing, then 1–4 in quarters of the busiest day
+fn level(v: i64, max: i64) -> usize {
+    match v {
+        0 => 0,
+        v => ((v * 4 + max - 1) / max.max(1)).clamp(1, 4) as usize,
+    }
+}
+
+/// Every week column from the one holding `first` to the one holding `last`, as its first day
+fn weeks(first: NaiveDate, last: NaiveDate, week: &Week) -> Vec<NaiveDate> {
+    let start = week.start_of(first);
+    (0..).map(|w| start + Duration::weeks(w)).take_while(|d| *d <= last).collect()
+}
+
+/// Totals line: "42 tasks done, 31h tracked, on 20 days; longest run 6 days"
+pub fn summary(days: &BTreeMap<NaiveDate, Day>, first: NaiveDate, last: NaiveDate) -> String {
+    let within: Vec<(&NaiveDate, &Day)> = days.range(first..=last).filter(|(_, d)| d.tasks > 0).collect();
+    let tasks: usize = within.iter().map(|(_, d)| d.tasks).sum();
+    let minutes: i64 = within.iter().map(|(_, d)| d.minutes).sum();
+    let (mut best, mut run, mut prev) = (0, 0, None::<NaiveDate>);
+    for (date, _) in &within {
+        run = if prev.is_some_and(|p| p + Duration::days(1) == **date) { run + 1 } else { 1 };
+        best = best.max(run);
+        prev = Some(**date);
+    }
+    format!(
+        "{} tasks done, {} tracked, on {} days; longest run {} days",
+        tasks,
+        crate::display::format_minutes(minutes as i32),
+        within.len(),
+        best
+    )
+}
+
+const GLYPHS: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+const GREENS: [u8; 5] = [238, 22, 28, 34, 40];
+
+/// Weekday rows by week columns, month names above; `color` shades the cells green
+pub fn render(days: &BTreeMap<NaiveDate, Day>, first: NaiveDate, last: NaiveDate, week: &Week, hours: bool, color: bool) -> String {
+    let cols = weeks(first, last, week);
+    let max = days.range(first..=last).map(|(_, d)| value(Some(d), hours)).max().unwrap_or(0);
+    let mut months = vec![' '; 4 + 2 * cols.len() + 3];
+    let mut free_from = 0;
+    for (x, w) in cols.iter().enumerate() {
+        let starts = (0..7).map(|i| *w + Duration::days(i)).find(|d| d.day() == 1 && (first..=last).contains(d));
+        let Some(month) = starts.or((x == 0).then_some(first)) else { continue };
+        // Skip a label that would run into the previous one
+        let at = 4 + 2 * x;
+        if at >= free_from {
+            for (i, c) in month.format("%b").to_string().chars().enumerate() {
+                months[at + i] = c;
+            }
+            free_from = at + 4;
+        }
+    }
+    let months: String = months.into_iter().collect();
+    let mut out = format!("{}\n", months.trim_end());
+    for row in 0..7 {
+        let day = cols.first().map(|w| *w + Duration::days(row)).unwrap_or(first);
+        // Label every other row, like a contribution graph
+        let label = if row % 2 == 1 { day.format("%a").to_string() } else { String::new() };
+        let mut line = format!("{:<4}", label);
+        for w in &cols {
+            let d = *w + Duration::days(row);
+            if d < first || d > last {
+                line.push_str("  ");
+                continue;
+            }
+            let l = level(value(days.get(&d), hours), max);
+            match color {
+                true => line.push_str(&format!("\x1b[38;5;{}m{}\x1b[0m ", GREENS[l], if l == 0 { "·" } else { "■" })),
+                false => line.push_str(&format!("{} ", GLYPHS[l])),
+            }
+        }
+        out.push_str(&format!("{}\n", line.trim_end()));
+    }
+    let legend: Vec<String> = match color {
+        true => GREENS.iter().enumerate().map(|(l, c)| format!("\x1b[38;5;{}m{}\x1b[0m", c, if l == 0 { "·" } else { "■" })).collect(),
+        false => GLYPHS.iter().map(|g| g.to_string()).collect(),
+    };
+    out.push_str(&format!("\n{}Less {} More ({})\n", " ".repeat(4), legend.join(" "), if hours { "hours" } else { "tasks" }));
+    out
+}
+
+/// `--accessible`: one sentence per month instead of a grid
+pub fn render_accessible(days: &BTreeMap<NaiveDate, Day>, first: NaiveDate, last: NaiveDate) -> String {
+    let mut months: BTreeMap<(i32, u32), Vec<(NaiveDate, Day)>> = BTreeMap::new();
+    for (d, day) in days.range(first..=last).filter(|(_, d)| d.tasks > 0) {
+        months.entry((d.year(), d.month())).or_default().push((*d, *day));
+    }
+    if months.is_empty() {
+        return "No tasks finished in this period.\n".into();
+    }
+    months
+        .values()
+        .map(|list| {
+            let tasks: usize = list.iter().map(|(_, d)| d.tasks).sum();
+            let (busiest, top) = list.iter().max_by_key(|(d, day)| (day.tasks, std::cmp::Reverse(*d))).copied().unwrap_or_default();
+            format!("{}: {} tasks on {} days, busiest {} with {}.\n", list[0].0.format("%B %Y"), tasks, list.len(), busiest.format("%a %b %-d"), top.tasks)
+        })
+        .collect()
+}
+
+const FILLS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+/// The same grid as an SVG, one square per day with a tooltip
+pub fn svg(days: &BTreeMap<NaiveDate, Day>, first: NaiveDate, last: NaiveDate, week: &Week, hours: bool) -> String {
+    let (cell, pitch, left, top) = (11, 13, 30, 20);
+    let cols = weeks(first, last, week);
+    let max = days.range(first..=last).map(|(_, d)| value(Some(d), hours)).max().unwrap_or(0);
+    let (width, height) = (left + cols.len() * pitch, top + 7 * pitch + 4);
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"10\">\n",
+        width, height
+    );
+    for (x, w) in cols.iter().enumerate() {
+        if let Some(d) = (0..7).map(|i| *w + Duration::days(i)).find(|d| d.day() == 1 && (first..=last).contains(d)) {
+            out.push_str(&format!("  <text x=\"{}\" y=\"12\" fill=\"#767676\">{}</text>\n", left + x * pitch, d.format("%b")));
+        }
+    }
+    for row in (1..7).step_by(2) {
+        let name = cols.first().map(|w| (*w + Duration::days(row as i64)).format("%a").to_string()).unwrap_or_default();
+        out.push_str(&format!("  <text x=\"0\" y=\"{}\" fill=\"#767676\">{}</text>\n", top + row * pitch + 9, name));
+    }
+    for (x, w) in cols.iter().enumerate() {
+        for row in 0..7 {
+            let d = *w + Duration::days(row as i64);
+            if d < first || d > last {
+                continue;
+            }
+            let day = days.get(&d).copied().unwrap_or_default();
+            let tip = match hours {
+                true => format!("{}: {}", d.format("%Y-%m-%d"), crate::display::format_minutes(day.minutes as i32)),
+                false => format!("{}: {} tasks", d.format("%Y-%m-%d"), day.tasks),
+            };
+            out.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" fill=\"{}\"><title>{}</title></rect>\n",
+                left + x * pitch,
+                top + row * pitch,
+                cell,
+                cell,
+                FILLS[level(value(Some(&day), hours), max)],
+                tip
+            ));
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
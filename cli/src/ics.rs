@@ -0,0 +1,482 @@
+use crate::commands::{ShowAs, Task};
+use crate::recurrence::{Freq, Rule};
+use crate::reminders::{end_of_day, Reminder};
+use crate::slots::{self, Busy, Interval};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// One property line with its parameters, e.g. `DTSTART;TZID=Europe/Berlin:20300506T100000`
+struct Prop<'a> {
+    name: &'a str,
+    params: &'a str,
+    value: &'a str,
+}
+
+fn split_prop(line: &str) -> Option<Prop<'_>> {
+    let (head, value) = line.split_once(':')?;
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some(Prop { name, params, value })
+}
+
+/// Undo RFC 5545 line folding (continuation lines start with a space or tab)
+fn unfold(raw: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for l in raw.lines() {
+        match (l.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(prev)) => prev.push_str(rest),
+            _ => lines.push(l.trim_end().to_string()),
+        }
+    }
+    lines
+}
+
+/// UTC times become local; TZID and floating times are taken as local wall-clock time
+pub fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let t = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(DateTime::<Utc>::from_naive_utc_and_offset(t, Utc).with_timezone(&Local).naive_local());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y%m%d").ok()?.and_hms_opt(0, 0, 0))
+}
+
+/// "PT1H30M", "P1D" → Duration
+fn parse_duration(value: &str) -> Option<Duration> {
+    let rest = value.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut num = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => num.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = num.parse().ok()?;
+                num.clear();
+                total += match unit {
+                    'W' => Duration::weeks(n),
+                    'D' => Duration::days(n),
+                    'H' => Duration::hours(n),
+                    'M' => Duration::minutes(n),
+                    'S' => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(total)
+}
+
+fn utc_to_local(t: NaiveDateTime) -> NaiveDateTime {
+    DateTime::<Utc>::from_naive_utc_and_offset(t, Utc).with_timezone(&Local).naive_local()
+}
+
+/// "+0100", "-0500", "+053000" → seconds east of UTC
+fn parse_offset(value: &str) -> Option<i64> {
+    let (sign, digits) = match value.split_at_checked(1)? {
+        ("+", d) => (1, d),
+        ("-", d) => (-1, d),
+        _ => return None,
+    };
+    let part = |r: std::ops::Range<usize>| digits.get(r).map_or(Some(0), |p| p.parse::<i64>().ok());
+    Some(sign * (part(0..2)? * 3600 + part(2..4)? * 60 + part(4..6)?))
+}
+
+/// A VTIMEZONE: each STANDARD/DAYLIGHT part puts its offset in force from its onset, yearly when it has an RRULE
+#[derive(Default)]
+struct Zone {
+    parts: Vec<(NaiveDateTime, Option<Rule>, i64)>,
+}
+
+impl Zone {
+    /// Seconds east of UTC at wall-clock time `t`
+    fn offset_at(&self, t: NaiveDateTime) -> i64 {
+        let onsets = self.parts.iter().filter_map(|(start, rule, offset)| {
+            let onset = match rule {
+                Some(r) => {
+                    // Outlook starts its rules in 1601; only the last year or two matter
+                    let start = match (r.freq, r.interval, r.count) {
+                        (Freq::Yearly, 1, None) if start.year() < t.year() - 1 => start.with_year(t.year() - 1).unwrap_or(*start),
+                        _ => *start,
+                    };
+                    r.occurrences(start, t + Duration::seconds(1)).pop()
+                }
+                None => Some(*start).filter(|s| *s <= t),
+            };
+            Some((onset?, *offset))
+        });
+        onsets.max_by_key(|(onset, _)| *onset).map_or(0, |(_, offset)| offset)
+    }
+}
+
+/// Which clock a time was written in
+#[derive(Clone, Copy, Default)]
+enum Clock<'a> {
+    /// Floating, or a TZID without a VTIMEZONE: taken as our own wall clock
+    #[default]
+    Local,
+    Utc,
+    Zone(&'a Zone),
+}
+
+impl Clock<'_> {
+    fn to_local(self, t: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Clock::Local => t,
+            Clock::Utc => utc_to_local(t),
+            Clock::Zone(z) => utc_to_local(t - Duration::seconds(z.offset_at(t))),
+        }
+    }
+
+    /// This clock's reading at UTC time `t`
+    fn reading_at_utc(self, t: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Clock::Local => utc_to_local(t),
+            Clock::Utc => t,
+            Clock::Zone(z) => t + Duration::seconds(z.offset_at(t)),
+        }
+    }
+}
+
+fn param<'a>(params: &'a str, key: &str) -> Option<&'a str> {
+    params.split(';').find_map(|p| p.strip_prefix(key)?.strip_prefix('=')).map(|v| v.trim_matches('"'))
+}
+
+type Zones = BTreeMap<String, Zone>;
+
+/// VTIMEZONE definitions by TZID
+fn parse_zones(lines: &[String]) -> Zones {
+    let mut zones = Zones::new();
+    let (mut tzid, mut zone) = (String::new(), Zone::default());
+    let (mut start, mut rule, mut offset) = (None, None, None);
+    for line in lines {
+        match line.as_str() {
+            "BEGIN:VTIMEZONE" => (tzid, zone) = (String::new(), Zone::default()),
+            "END:VTIMEZONE" => {
+                zones.insert(std::mem::take(&mut tzid), std::mem::take(&mut zone));
+            }
+            "BEGIN:STANDARD" | "BEGIN:DAYLIGHT" => (start, rule, offset) = (None, None, None),
+            "END:STANDARD" | "END:DAYLIGHT" => {
+                if let (Some(s), Some(o)) = (start, offset) {
+                    zone.parts.push((s, rule.take(), o));
+                }
+            }
+            l => match split_prop(l) {
+                Some(p) if p.name == "TZID" => tzid = p.value.to_string(),
+                Some(p) if p.name == "DTSTART" => start = NaiveDateTime::parse_from_str(p.value, "%Y%m%dT%H%M%S").ok(),
+                Some(p) if p.name == "RRULE" => rule = Rule::parse(p.value),
+                Some(p) if p.name == "TZOFFSETTO" => offset = parse_offset(p.value),
+                _ => {}
+            },
+        }
+    }
+    zones
+}
+
+/// A DTSTART-like value with the clock it's in; date-only values are whole (floating) days
+fn parse_stamp<'z>(p: &Prop, zones: &'z Zones) -> Option<(NaiveDateTime, Clock<'z>, bool)> {
+    let date_only = param(p.params, "VALUE") == Some("DATE") || p.value.len() == 8;
+    if date_only {
+        return Some((NaiveDate::parse_from_str(p.value, "%Y%m%d").ok()?.and_hms_opt(0, 0, 0)?, Clock::Local, true));
+    }
+    let (value, clock) = match p.value.strip_suffix('Z') {
+        Some(v) => (v, Clock::Utc),
+        None => (p.value, param(p.params, "TZID").and_then(|id| zones.get(id)).map_or(Clock::Local, Clock::Zone)),
+    };
+    Some((NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?, clock, false))
+}
+
+/// One occurrence of a calendar event, in local time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub uid: String,
+    pub summary: String,
+    pub interval: Interval,
+    pub all_day: bool,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub show_as: ShowAs,
+}
+
+/// A VEVENT as written, before its recurrences are expanded
+#[derive(Default)]
+struct RawEvent<'z> {
+    uid: String,
+    summary: String,
+    location: Option<String>,
+    description: Option<String>,
+    start: Option<(NaiveDateTime, Clock<'z>)>,
+    end: Option<(NaiveDateTime, Clock<'z>)>,
+    duration: Option<Duration>,
+    all_day: bool,
+    rule: Option<Rule>,
+    /// Local times of skipped occurrences
+    exdates: Vec<NaiveDateTime>,
+    /// Local start of the occurrence this one replaces
+    recurrence_id: Option<NaiveDateTime>,
+    transparent: bool,
+    status: String,
+}
+
+impl RawEvent<'_> {
+    fn show_as(&self) -> ShowAs {
+        match (self.transparent, self.status.as_str()) {
+            (true, _) => ShowAs::Free,
+            (false, "TENTATIVE") => ShowAs::Tentative,
+            _ => ShowAs::Busy,
+        }
+    }
+
+    /// Local start times within reach of `range`, minus EXDATEs and replaced occurrences
+    fn occurrences(&self, range: &Interval, replaced: &BTreeSet<(&str, NaiveDateTime)>) -> Vec<Interval> {
+        let Some((start, clock)) = self.start else { return vec![] };
+        let first = clock.to_local(start);
+        let end = match (self.end, self.duration) {
+            (Some((e, c)), _) => c.to_local(e),
+            (None, Some(d)) => first + d,
+            (None, None) => first + Duration::days(1),
+        };
+        let length = end - first;
+        let starts = match &self.rule {
+            Some(r) if self.recurrence_id.is_none() => {
+                let mut r = r.clone();
+                if r.until_utc {
+                    r.until = r.until.map(|u| clock.reading_at_utc(u));
+                }
+                // Wall-clock times elsewhere can be a day either side of ours
+                r.occurrences(start, range.end + Duration::days(2)).into_iter().map(|s| clock.to_local(s)).collect()
+            }
+            _ => vec![first],
+        };
+        starts
+            .into_iter()
+            .filter(|s| !self.exdates.contains(s) && (self.recurrence_id.is_some() || !replaced.contains(&(self.uid.as_str(), *s))))
+            .map(|s| Interval::new(s, s + length))
+            .filter(|i| i.overlaps(range))
+            .collect()
+    }
+}
+
+/// Every VEVENT occurrence overlapping `range`: RRULEs expanded, EXDATEs and RECURRENCE-ID
+/// replacements honoured, VTIMEZONE times converted to local; cancelled events are left out
+pub fn parse_events(raw: &str, range: &Interval) -> Vec<Event> {
+    let lines = unfold(raw);
+    let zones = parse_zones(&lines);
+    let mut raws: Vec<RawEvent> = Vec::new();
+    let mut stack: Vec<&str> = Vec::new();
+    for line in &lines {
+        if let Some(component) = line.strip_prefix("BEGIN:") {
+            if component == "VEVENT" {
+                raws.push(RawEvent::default());
+            }
+            stack.push(component);
+            continue;
+        }
+        if line.starts_with("END:") {
+            stack.pop();
+            continue;
+        }
+        // Alarms nest inside events and have their own DESCRIPTION and DURATION
+        let (Some(&"VEVENT"), Some(ev), Some(p)) = (stack.last(), raws.last_mut(), split_prop(line)) else { continue };
+        let local = |p: &Prop| parse_stamp(p, &zones).map(|(t, c, _)| c.to_local(t));
+        match p.name {
+            "UID" => ev.uid = p.value.to_string(),
+            "SUMMARY" => ev.summary = unescape(p.value),
+            "LOCATION" => ev.location = Some(unescape(p.value)).filter(|l| !l.is_empty()),
+            "DESCRIPTION" => ev.description = Some(unescape(p.value)).filter(|d| !d.trim().is_empty()),
+            "DTSTART" => {
+                if let Some((t, c, all_day)) = parse_stamp(&p, &zones) {
+                    (ev.start, ev.all_day) = (Some((t, c)), all_day);
+                }
+            }
+            "DTEND" => ev.end = parse_stamp(&p, &zones).map(|(t, c, _)| (t, c)),
+            "DURATION" => ev.duration = parse_duration(p.value),
+            "RRULE" => ev.rule = Rule::parse(p.value),
+            "EXDATE" => {
+                for v in p.value.split(',') {
+                    ev.exdates.extend(local(&Prop { value: v, ..p }));
+                }
+            }
+            "RECURRENCE-ID" => ev.recurrence_id = local(&p),
+            "TRANSP" => ev.transparent = p.value == "TRANSPARENT",
+            "STATUS" => ev.status = p.value.to_string(),
+            _ => {}
+        }
+    }
+
+    let replaced: BTreeSet<(&str, NaiveDateTime)> = raws.iter().filter_map(|r| Some((r.uid.as_str(), r.recurrence_id?))).collect();
+    let mut events = Vec::new();
+    for r in raws.iter().filter(|r| r.status != "CANCELLED") {
+        for interval in r.occurrences(range, &replaced) {
+            events.push(Event {
+                uid: r.uid.clone(),
+                summary: r.summary.clone(),
+                interval,
+                all_day: r.all_day,
+                location: r.location.clone(),
+                description: r.description.clone(),
+                show_as: r.show_as(),
+            });
+        }
+    }
+    events.sort_by_key(|e| e.interval.start);
+    events
+}
+
+/// Busy periods from VEVENTs (skipping cancelled/transparent ones) and VFREEBUSY entries
+pub fn parse_busy(raw: &str, range: &Interval) -> Vec<Busy> {
+    let mut busy: Vec<Busy> = parse_events(raw, range)
+        .into_iter()
+        .filter(|e| e.show_as != ShowAs::Free && e.interval.end > e.interval.start)
+        .map(|e| Busy { label: if e.summary.is_empty() { "busy".to_string() } else { e.summary }, interval: e.interval })
+        .collect();
+    for line in unfold(raw) {
+        let Some(p) = split_prop(&line) else { continue };
+        if p.name != "FREEBUSY" || p.params.contains("FBTYPE=FREE") {
+            continue;
+        }
+        for period in p.value.split(',') {
+            let Some((a, b)) = period.split_once('/') else { continue };
+            let Some(s) = parse_time(a) else { continue };
+            let Some(e) = parse_time(b).or_else(|| parse_duration(b).map(|d| s + d)) else { continue };
+            let i = Interval::new(s, e);
+            if i.overlaps(range) {
+                busy.push(Busy { interval: i, label: "busy".into() });
+            }
+        }
+    }
+    busy
+}
+
+/// Load busy periods from an .ics file, labelled with the file name
+pub fn busy_from_file(path: &Path, range: &Interval) -> Result<Vec<Busy>, Box<dyn Error>> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let who = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    Ok(parse_busy(&raw, range)
+        .into_iter()
+        .map(|b| Busy { label: format!("{}: {}", who, b.label), ..b })
+        .collect())
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", "\n").replace("\\N", "\n").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+/// VTODOs (Reminders and most task apps export these), listed under the calendar's X-WR-CALNAME;
+/// a date-only DUE means by the end of that day
+pub fn parse_todos(raw: &str) -> Vec<Reminder> {
+    let mut todos = Vec::new();
+    let mut calendar = String::new();
+    let mut current: Option<Reminder> = None;
+    let mut in_sub = false;
+    for line in unfold(raw) {
+        match (line.as_str(), current.as_mut()) {
+            ("BEGIN:VTODO", _) => {
+                current = Some(Reminder { list: calendar.clone(), ..Reminder::default() });
+                continue;
+            }
+            ("END:VTODO", Some(_)) => {
+                todos.extend(current.take().filter(|r| !r.title.is_empty()));
+                continue;
+            }
+            (l, Some(_)) if l.starts_with("BEGIN:") => in_sub = true,
+            (l, Some(_)) if l.starts_with("END:") => in_sub = false,
+            _ => {}
+        }
+        let Some(p) = split_prop(&line) else { continue };
+        match current.as_mut() {
+            None if p.name == "X-WR-CALNAME" => calendar = unescape(p.value),
+            Some(r) if !in_sub => match p.name {
+                "UID" => r.id = p.value.to_string(),
+                "SUMMARY" => r.title = unescape(p.value).trim().to_string(),
+                "DESCRIPTION" => r.notes = Some(unescape(p.value)).filter(|n| !n.trim().is_empty()),
+                "DUE" => {
+                    let date_only = p.params.contains("VALUE=DATE") || p.value.len() == 8;
+                    r.due = parse_time(p.value).map(|t| if date_only { end_of_day(t.date()) } else { t });
+                }
+                "STATUS" => r.completed = p.value == "COMPLETED",
+                "COMPLETED" => r.completed = true,
+                "PRIORITY" => r.priority = p.value.parse().unwrap_or(0),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    todos
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+/// Fold at 75 octets (continuation lines start with a space) and end with CRLF
+fn fold(line: &str, out: &mut String) {
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn cal_address(email: &str) -> String {
+    format!("mailto:{}", email.trim_start_matches("mailto:"))
+}
+
+/// VCALENDAR with one VEVENT per task that has a start and end; times are written as floating local time
+pub fn to_ics(tasks: &[Task]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec!["BEGIN:VCALENDAR".to_string(), "VERSION:2.0".into(), "PRODID:-//importly//todo//EN".into()];
+    for t in tasks {
+        let (Some(start), Some(end)) = (
+            t.start_time.as_deref().and_then(slots::parse_datetime),
+            t.end_time.as_deref().and_then(slots::parse_datetime),
+        ) else {
+            continue;
+        };
+        lines.push("BEGIN:VEVENT".into());
+        lines.push(format!("UID:task-{}@todo", t.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        if t.is_all_day() {
+            lines.push(format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")));
+            // DTEND is exclusive, so it has to be at least the next day
+            let last = end.date().max(start.date() + Duration::days(1));
+            lines.push(format!("DTEND;VALUE=DATE:{}", last.format("%Y%m%d")));
+        } else {
+            lines.push(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+            lines.push(format!("DTEND:{}", end.format("%Y%m%dT%H%M%S")));
+        }
+        lines.push(format!("SUMMARY:{}", escape(&t.title)));
+        if let Some(loc) = &t.location {
+            lines.push(format!("LOCATION:{}", escape(loc)));
+        }
+        if let Some(url) = &t.meeting_url {
+            lines.push(format!("URL:{}", url));
+        }
+        match t.transparency.as_deref() {
+            Some("free") => lines.push("TRANSP:TRANSPARENT".into()),
+            Some("tentative") => lines.push("STATUS:TENTATIVE".into()),
+            _ => {}
+        }
+        if let Some(o) = &t.organizer {
+            lines.push(format!("ORGANIZER:{}", cal_address(o)));
+        }
+        for a in t.attendees.iter().flatten() {
+            lines.push(format!("ATTENDEE;ROLE=REQ-PARTICIPANT:{}", cal_address(a)));
+        }
+        lines.push("END:VEVENT".into());
+    }
+    lines.push("END:VCALENDAR".into());
+    let mut out = String::new();
+    for l in &lines {
+        fold(l, &mut out);
+    }
+    out
+}
@@ -0,0 +1,126 @@
+use crate::agenda::{Day, Kind};
+use crate::config::Config;
+use crate::slots;
+use chrono::{NaiveTime, Timelike};
+
+const GUTTER: usize = 44;
+const COLUMN: usize = 150;
+const HOUR: usize = 40;
+const ALL_DAY: usize = 18;
+
+/// Fill and text colour per kind of entry
+fn colors(kind: Kind) -> (&'static str, &'static str) {
+    match kind {
+        Kind::Event => ("#4a7bd0", "#ffffff"),
+        Kind::Todo => ("#3fa34d", "#ffffff"),
+        Kind::Quiet => ("#e4e4e4", "#767676"),
+        Kind::Break => ("#f3e6c8", "#7a6a45"),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// `s` cut to `width` characters, with "…" when it didn't fit
+fn fit(s: &str, width: usize) -> String {
+    match s.chars().count() > width {
+        true => format!("{}…", s.chars().take(width.saturating_sub(1)).collect::<String>()),
+        false => s.to_string(),
+    }
+}
+
+fn minutes(t: NaiveTime) -> usize {
+    (t.hour() * 60 + t.minute()) as usize
+}
+
+/// Whole hours covering every event, todo block and availability window; 08–18 when the days are empty
+fn hours(days: &[Day], cfg: &Config) -> (usize, usize) {
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    for d in days {
+        let windows = slots::windows_on(&cfg.availability, d.date);
+        let scheduled = d.entries.iter().filter(|e| matches!(e.kind, Kind::Event | Kind::Todo)).map(|e| e.interval);
+        for i in windows.into_iter().chain(scheduled) {
+            let end = if i.end.date() > i.start.date() { 24 * 60 } else { minutes(i.end.time()) };
+            spans.push((minutes(i.start.time()), end));
+        }
+    }
+    let first = spans.iter().map(|s| s.0).min().map_or(8, |m| m / 60);
+    let last = spans.iter().map(|s| s.1).max().map_or(18, |m| m.div_ceil(60));
+    (first, last.max(first + 1))
+}
+
+/// The days side by side as an SVG: hours down the left, one column per day, all-day events in
+/// a strip under the dates; quiet hours and breaks show in the background
+pub fn week_svg(days: &[Day], cfg: &Config) -> String {
+    let (first, last) = hours(days, cfg);
+    let strip = days.iter().map(|d| d.all_day.len()).max().unwrap_or(0) * ALL_DAY;
+    let top = 36 + strip;
+    let y = |m: usize| top + (m.clamp(first * 60, last * 60) - first * 60) * HOUR / 60;
+    let (width, height) = (GUTTER + days.len() * COLUMN + 1, y(last * 60) + 1);
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"11\">\n\
+         \x20 <rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+        width, height, width, height
+    );
+    for h in first..=last {
+        out.push_str(&format!("  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#e0e0e0\"/>\n", GUTTER, y(h * 60), width, y(h * 60)));
+        if h < last {
+            out.push_str(&format!("  <text x=\"4\" y=\"{}\" fill=\"#767676\">{:02}:00</text>\n", y(h * 60) + 11, h));
+        }
+    }
+    for (i, d) in days.iter().enumerate() {
+        let x = GUTTER + i * COLUMN;
+        let header = if d.weekend { format!("{} (weekend)", d.date.format("%a %b %-d")) } else { d.date.format("%a %b %-d").to_string() };
+        out.push_str(&format!("  <text x=\"{}\" y=\"22\" font-weight=\"bold\">{}</text>\n", x + 6, header));
+        out.push_str(&format!("  <line x1=\"{}\" y1=\"0\" x2=\"{}\" y2=\"{}\" stroke=\"#e0e0e0\"/>\n", x, x, height));
+        // Time outside availability is shaded, like a calendar's off-hours
+        let open = slots::windows_on(&cfg.availability, d.date);
+        let mut from = first * 60;
+        for w in open.iter().map(|w| (minutes(w.start.time()), minutes(w.end.time()))).chain([(last * 60, last * 60)]) {
+            if w.0 > from {
+                out.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#f5f5f5\"/>\n", x + 1, y(from), COLUMN - 1, y(w.0) - y(from)));
+            }
+            from = from.max(w.1);
+        }
+        for (n, label) in d.all_day.iter().enumerate() {
+            let ay = 32 + n * ALL_DAY;
+            out.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"3\" fill=\"#8e7cc3\"><title>{}</title></rect>\n",
+                x + 3,
+                ay,
+                COLUMN - 6,
+                ALL_DAY - 3,
+                escape(label)
+            ));
+            out.push_str(&format!("  <text x=\"{}\" y=\"{}\" fill=\"#ffffff\">{}</text>\n", x + 7, ay + 11, escape(&fit(label, 22))));
+        }
+        for e in &d.entries {
+            let (start, end) = (minutes(e.interval.start.time()), if e.interval.end.date() > d.date { 24 * 60 } else { minutes(e.interval.end.time()) });
+            let (y0, y1) = (y(start), y(end));
+            if y1 <= y0 {
+                continue;
+            }
+            let (fill, ink) = if e.optional { ("#b5dcbb", "#2d5a34") } else { colors(e.kind) };
+            let span = format!("{}–{}", e.interval.start.format("%H:%M"), e.interval.end.format("%H:%M"));
+            out.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"3\" fill=\"{}\"><title>{} {}</title></rect>\n",
+                x + 3,
+                y0 + 1,
+                COLUMN - 6,
+                (y1 - y0).saturating_sub(2).max(1),
+                fill,
+                span,
+                escape(&e.label)
+            ));
+            if y1 - y0 >= 16 {
+                out.push_str(&format!("  <text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>\n", x + 7, y0 + 13, ink, escape(&fit(&e.label, 22))));
+            }
+            if y1 - y0 >= 30 {
+                out.push_str(&format!("  <text x=\"{}\" y=\"{}\" fill=\"{}\" font-size=\"10\">{}</text>\n", x + 7, y0 + 26, ink, span));
+            }
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
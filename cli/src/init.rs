@@ -0,0 +1,176 @@
+use crate::config::{self, Availability, Block, Config, Window};
+use crate::platform::Host;
+use crate::prompt;
+use clap_complete::{generate, Shell};
+use std::error::Error;
+use std::fs;
+use std::time::Duration;
+
+/// Candidates probed when no URL is given
+const CANDIDATE_URLS: [&str; 2] = [config::DEFAULT_API_URL, "http://localhost:8000"];
+
+/// True if the API answers at `url`
+pub async fn reachable(client: &reqwest::Client, url: &str) -> bool {
+    client
+        .get(format!("{}/categories/", url))
+        .timeout(Duration::from_secs(2))
+        .send()
+        .await
+        .map(|r| r.status().is_success())
+        .unwrap_or(false)
+}
+
+/// Parse "09:00-12:00, 13:00-17:00" (or "off") into windows
+fn parse_windows(input: &str) -> Result<Vec<Window>, String> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("off") || input == "-" {
+        return Ok(Vec::new());
+    }
+    input.split(',').map(str::parse).collect()
+}
+
+fn format_windows(windows: &[Window]) -> String {
+    if windows.is_empty() {
+        return "off".into();
+    }
+    windows
+        .iter()
+        .map(|w| String::from(w.clone()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Keep asking until the answer parses
+fn ask_windows(question: &str, current: &[Window]) -> Result<Vec<Window>, Box<dyn Error>> {
+    loop {
+        match parse_windows(&prompt::ask(question, &format_windows(current))?) {
+            Ok(w) => return Ok(w),
+            Err(e) if prompt::no_input() => return Err(e.into()),
+            Err(e) => eprintln!("  {}", e),
+        }
+    }
+}
+
+/// Write `shell`'s completion script (detected when not given) where the shell looks for it,
+/// and hook it into the shell's profile when the shell needs that
+pub fn install_completions(app: &mut clap::Command, shell: Option<Shell>) -> Result<(), Box<dyn Error>> {
+    let host = Host::current();
+    let Some(shell) = shell.or_else(|| host.shell()) else {
+        println!("Couldn't detect your shell; see `todo completions --help`.");
+        return Ok(());
+    };
+    let Some(target) = host.completions(shell) else {
+        println!("No usual place for {} completions; write them with `todo completions {}` and load that file.", shell, shell);
+        return Ok(());
+    };
+    if !prompt::confirm(&format!("Install {} completions to {}?", shell, target.script.display()), true)? {
+        return Ok(());
+    }
+    if let Some(dir) = target.script.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = fs::File::create(&target.script)?;
+    generate(shell, app, "todo", &mut file);
+    println!("Installed completions to {}", target.script.display());
+    if let Some((profile, line)) = &target.profile {
+        let existing = fs::read_to_string(profile).unwrap_or_default();
+        if !existing.lines().any(|l| l.trim() == line) {
+            if let Some(dir) = profile.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let sep = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+            fs::write(profile, format!("{}{}{}\n", existing, sep, line))?;
+            println!("Loaded from {}; open a new shell to use them.", profile.display());
+        }
+    }
+    if shell == Shell::Zsh {
+        println!("Make sure ~/.zfunc is on your fpath: fpath=(~/.zfunc $fpath); autoload -U compinit; compinit");
+    }
+    Ok(())
+}
+
+/// Interactive first-run setup
+pub async fn run(
+    client: &reqwest::Client,
+    api_url: Option<String>,
+    app: &mut clap::Command,
+) -> Result<(), Box<dyn Error>> {
+    let path = config::path();
+    let mut cfg: Config = if path.exists() {
+        println!("Existing config found at {}", path.display());
+        if !prompt::confirm("Update it?", true)? {
+            return Ok(());
+        }
+        config::load()?
+    } else {
+        Config::default()
+    };
+
+    // 1. API URL: use the flag, else probe the usual local addresses
+    let mut suggested = api_url.clone().unwrap_or_else(|| cfg.api_url.clone());
+    if api_url.is_none() {
+        for candidate in CANDIDATE_URLS {
+            if reachable(client, candidate).await {
+                println!("Found scheduler API at {}", candidate);
+                suggested = candidate.to_string();
+                break;
+            }
+        }
+    }
+    loop {
+        let url = prompt::ask("Scheduler API URL", &suggested)?;
+        let url = url.trim_end_matches('/').to_string();
+        if reachable(client, &url).await {
+            println!("  \u{2714} Connected to {}", url);
+            cfg.api_url = url;
+            break;
+        }
+        println!("  \u{2718} Could not reach {}", url);
+        if !prompt::confirm("Try another URL?", false)? {
+            cfg.api_url = url;
+            break;
+        }
+        suggested = url;
+    }
+
+    // 2. Availability
+    println!("Availability windows (e.g. 09:00-12:00, 13:00-17:00, or 'off'):");
+    let a = &cfg.availability;
+    let weekend_days = cfg.week.weekend_days();
+    let names = |weekend: bool| {
+        let start = cfg.week.first_day();
+        let days: Vec<&str> = (start..start + 7).map(|i| i % 7).filter(|i| weekend_days.contains(i) == weekend).map(|i| config::DAY_NAMES[i]).collect();
+        days.join(", ")
+    };
+    let workday_windows = (0..7).find(|i| !weekend_days.contains(i)).map_or(&a.mon, |i| a.days()[i]);
+    let weekend_windows = weekend_days.first().map_or(&a.sat, |&i| a.days()[i]);
+    let weekday = ask_windows(&format!("  Workdays ({})", names(false)), workday_windows)?;
+    let weekend = match weekend_days.is_empty() {
+        true => Vec::new(),
+        false => ask_windows(&format!("  Weekends ({})", names(true)), weekend_windows)?,
+    };
+    let daily_breaks: Vec<Window> = a.breaks.iter().filter(|b| b.days.is_empty()).map(|b| b.window.clone()).collect();
+    let breaks = ask_windows("  Daily breaks, e.g. lunch 12:00-13:00", &daily_breaks)?;
+    cfg.availability = Availability {
+        // Day-specific breaks can only be set in the file, so keep them
+        breaks: a
+            .breaks
+            .iter()
+            .filter(|b| !b.days.is_empty())
+            .cloned()
+            .chain(breaks.into_iter().map(|window| Block { days: Vec::new(), window }))
+            .collect(),
+        focus: a.focus.clone(),
+        max_hours_per_day: a.max_hours_per_day,
+        ..Availability::split_week(&weekday, &weekend, &weekend_days)
+    };
+
+    // 3. Write config
+    let written = config::save(&cfg)?;
+    println!("Wrote {}", written.display());
+
+    // 4. Completions
+    install_completions(app, None)?;
+    println!("All set! Try `todo list-tasks`.");
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use crate::audit::Entry;
+use chrono::{Duration, NaiveDateTime, Timelike};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Audit actions that bring a task into existence
+const CREATES: [&str; 3] = ["create-todo", "create-event", "repeat"];
+
+/// What the audit log says about how you work; nothing here leaves the machine
+#[derive(Default, Debug)]
+pub struct Insights {
+    /// Action and how often it succeeded, most used first
+    pub actions: Vec<(String, usize)>,
+    /// Tasks created in each hour of the day (local time)
+    pub created_by_hour: [usize; 24],
+    /// Tasks seen both created and marked done, and the mean time in between
+    pub completed: usize,
+    pub mean_to_done: Option<Duration>,
+    /// First entry's timestamp
+    pub since: Option<String>,
+}
+
+/// When the entry was written, local time
+pub fn at(e: &Entry) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(&e.timestamp, "%Y-%m-%dT%H:%M:%S").ok()
+}
+
+/// An update that sets the status to done
+pub fn marks_done(e: &Entry) -> bool {
+    e.action == "update-task" && e.payload.get("status").and_then(Value::as_str) == Some("done")
+}
+
+pub fn compute(entries: &[Entry]) -> Insights {
+    let ok: Vec<&Entry> = entries.iter().filter(|e| e.ok).collect();
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut created: BTreeMap<i32, NaiveDateTime> = BTreeMap::new();
+    let mut done: BTreeMap<i32, NaiveDateTime> = BTreeMap::new();
+    let mut by_hour = [0; 24];
+    for e in &ok {
+        *counts.entry(&e.action).or_default() += 1;
+        let (Some(id), Some(when)) = (e.task_id, at(e)) else {
+            continue;
+        };
+        if CREATES.contains(&e.action.as_str()) {
+            by_hour[when.hour() as usize] += 1;
+            created.entry(id).or_insert(when);
+        } else if marks_done(e) && created.get(&id).is_some_and(|c| *c <= when) {
+            done.entry(id).or_insert(when);
+        }
+    }
+    let spans: Vec<Duration> = done.iter().map(|(id, d)| *d - created[id]).collect();
+    let mean_to_done = (!spans.is_empty()).then(|| spans.iter().fold(Duration::zero(), |a, b| a + *b) / spans.len() as i32);
+    let mut actions: Vec<(String, usize)> = counts.into_iter().map(|(a, n)| (a.to_string(), n)).collect();
+    actions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Insights { actions, created_by_hour: by_hour, completed: spans.len(), mean_to_done, since: entries.first().map(|e| e.timestamp.clone()) }
+}
+
+/// "2d 5h", "3h 20m", "45m"
+pub fn format_span(d: Duration) -> String {
+    match d.num_days() {
+        0 => crate::display::format_minutes(d.num_minutes().max(1) as i32),
+        days => format!("{}d {}h", days, d.num_hours() % 24),
+    }
+}
+
+/// One bar per hour that saw any task creation, scaled to `width`
+pub fn hour_chart(by_hour: &[usize; 24], width: usize) -> Vec<String> {
+    let max = by_hour.iter().copied().max().unwrap_or(0).max(1);
+    by_hour
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| **n > 0)
+        .map(|(h, n)| format!("{:02}:00 {:<w$} {}", h, "█".repeat((n * width).div_ceil(max)), n, w = width))
+        .collect()
+}
@@ -0,0 +1,2 @@
+//! Snippets that hook todo into other tools
+pub mod shell;
@@ -0,0 +1,70 @@
+/// Shells we can print a quick-add keybinding for
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Target {
+    Zsh,
+    Fish,
+    Bash,
+}
+
+const ZSH: &str = r#"# todo quick-add: {{KEY}} captures a todo without leaving the prompt
+# Install: eval "$(todo integration zsh-widget)"
+_todo_quick_add() {
+  local line
+  zle -I
+  read -r "line?todo add: " </dev/tty || return
+  [[ -n $line ]] && todo add -- "$line"
+  zle reset-prompt
+}
+zle -N _todo_quick_add
+bindkey '{{KEY}}' _todo_quick_add
+"#;
+
+const FISH: &str = r#"# todo quick-add: {{KEY}} captures a todo without leaving the prompt
+# Install: todo integration fish-binding | source
+function __todo_quick_add
+    read -P 'todo add: ' line
+    and test -n "$line"
+    and todo add -- "$line"
+    commandline -f repaint
+end
+# Quoted like any other argument; unescaped so \e-style keys still work
+bind (string unescape -- '{{KEY}}') __todo_quick_add
+"#;
+
+const BASH: &str = r#"# todo quick-add: {{KEY}} captures a todo without leaving the prompt
+# Install: eval "$(todo integration bash-binding)"
+_todo_quick_add() {
+  local line
+  read -r -e -p "todo add: " line </dev/tty && [ -n "$line" ] && todo add -- "$line"
+}
+bind -x '"{{KEY}}": _todo_quick_add'
+"#;
+
+impl Target {
+    /// Alt-A, in each shell's own key notation
+    pub fn default_key(self) -> &'static str {
+        match self {
+            Target::Zsh => "^[a",
+            Target::Fish => "\\ea",
+            Target::Bash => "\\ea",
+        }
+    }
+
+    fn template(self) -> &'static str {
+        match self {
+            Target::Zsh => ZSH,
+            Target::Fish => FISH,
+            Target::Bash => BASH,
+        }
+    }
+}
+
+/// The keybinding script for `target`, bound to `key` (in the shell's notation) or Alt-A
+pub fn binding(target: Target, key: Option<&str>) -> Result<String, String> {
+    let key = key.unwrap_or(target.default_key());
+    // The key lands inside quotes in every template
+    if key.is_empty() || key.contains(['\'', '"', '\n']) {
+        return Err(format!("Unusable key sequence `{}`", key));
+    }
+    Ok(target.template().replace("{{KEY}}", key))
+}
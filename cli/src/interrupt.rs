@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Catch Ctrl-C from here on: the first press only asks the running operation to stop after
+/// the item in hand, so it can save its progress; a second press quits at once
+pub fn watch() {
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            request();
+            eprintln!("\nStopping after the current item… (Ctrl-C again to quit now)");
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
+            }
+        }
+    });
+}
+
+pub fn request() {
+    REQUESTED.store(true, Ordering::SeqCst);
+}
+
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Forget an earlier request, e.g. between runs in one process
+pub fn clear() {
+    REQUESTED.store(false, Ordering::SeqCst);
+}
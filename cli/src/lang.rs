@@ -0,0 +1,168 @@
+use crate::config::Config;
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use regex::Regex;
+
+/// Deadline phrases in a language besides English; English keeps working alongside
+pub trait LanguagePack {
+    /// Language code the pack serves, e.g. "zh"
+    fn code(&self) -> &'static str;
+    /// The day a phrase names and the time, if it gives one
+    fn read(&self, phrase: &str, today: NaiveDate, cfg: &Config) -> Option<(NaiveDate, Option<NaiveTime>)>;
+}
+
+/// Vocabulary for a language that writes dates as "<week><weekday><part><hour>" with no spaces
+pub struct Words {
+    pub code: &'static str,
+    /// "tomorrow" and friends, as days from today
+    pub days: &'static [(&'static str, i64)],
+    /// Names for Monday through Sunday
+    pub weekdays: [&'static [&'static str]; 7],
+    /// "next week" and friends before a weekday, as weeks from this one; longest first
+    pub weeks: &'static [(&'static str, i64)],
+    /// Suffixes for "N days later", with how many days a unit is
+    pub later: &'static [(&'static str, i64)],
+    /// Parts of the day, as `[day_parts]` names, and whether an hour after them is p.m.
+    pub parts: &'static [(&'static str, &'static str, bool)],
+    /// Hour and half-hour markers ("3点半")
+    pub hour: &'static str,
+    pub half: &'static str,
+}
+
+pub const CHINESE: Words = Words {
+    code: "zh",
+    days: &[("大后天", 3), ("后天", 2), ("明天", 1), ("今天", 0), ("昨天", -1)],
+    weekdays: [
+        &["周一", "星期一", "礼拜一"],
+        &["周二", "星期二", "礼拜二"],
+        &["周三", "星期三", "礼拜三"],
+        &["周四", "星期四", "礼拜四"],
+        &["周五", "星期五", "礼拜五"],
+        &["周六", "星期六", "礼拜六"],
+        &["周日", "周天", "星期日", "星期天", "礼拜天"],
+    ],
+    weeks: &[("下下", 2), ("下", 1), ("这", 0), ("本", 0)],
+    later: &[("个星期后", 7), ("星期后", 7), ("天之后", 1), ("天后", 1), ("周后", 7)],
+    parts: &[("早上", "morning", false), ("上午", "morning", false), ("中午", "afternoon", false), ("下午", "afternoon", true), ("晚上", "evening", true), ("夜里", "night", true)],
+    hour: "点",
+    half: "半",
+};
+
+pub const JAPANESE: Words = Words {
+    code: "ja",
+    days: &[("明後日", 2), ("あさって", 2), ("明日", 1), ("あした", 1), ("今日", 0), ("昨日", -1)],
+    weekdays: [
+        &["月曜日", "月曜"],
+        &["火曜日", "火曜"],
+        &["水曜日", "水曜"],
+        &["木曜日", "木曜"],
+        &["金曜日", "金曜"],
+        &["土曜日", "土曜"],
+        &["日曜日", "日曜"],
+    ],
+    weeks: &[("再来週の", 2), ("再来週", 2), ("来週の", 1), ("来週", 1), ("今週の", 0), ("今週", 0)],
+    later: &[("週間後", 7), ("週後", 7), ("日後", 1)],
+    parts: &[("午前", "morning", false), ("朝", "morning", false), ("午後", "afternoon", true), ("夕方", "evening", true), ("夜", "night", true)],
+    hour: "時",
+    half: "半",
+};
+
+/// Packs to choose from with `locale`
+pub const PACKS: &[&dyn LanguagePack] = &[&CHINESE, &JAPANESE];
+
+/// The pack for a locale like "zh", "zh-CN" or "ja_JP"
+pub fn pack(locale: &str) -> Option<&'static dyn LanguagePack> {
+    let code = locale.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    PACKS.iter().copied().find(|p| p.code() == code)
+}
+
+/// "3", "十", "二十三" or "两"
+fn number(s: &str) -> Option<i64> {
+    if let Ok(n) = s.parse() {
+        return Some(n);
+    }
+    let digit = |c: char| "〇一二三四五六七八九".chars().position(|d| d == c).map(|d| d as i64).or((c == '两').then_some(2));
+    match s.split_once('十') {
+        Some((tens, ones)) => {
+            let tens = if tens.is_empty() { 1 } else { digit(tens.chars().next()?).filter(|_| tens.chars().count() == 1)? };
+            let ones = if ones.is_empty() { 0 } else { digit(ones.chars().next()?).filter(|_| ones.chars().count() == 1)? };
+            Some(tens * 10 + ones)
+        }
+        None if s.chars().count() == 1 => digit(s.chars().next()?),
+        None => None,
+    }
+}
+
+impl Words {
+    /// Split off a trailing time: "15:00", "3点半", "下午3点" or just "晚上"
+    fn time<'a>(&self, phrase: &'a str, cfg: &Config) -> Option<(&'a str, Option<NaiveTime>)> {
+        let clock = Regex::new(r"(\d{1,2})[:：](\d{2})$").ok()?;
+        if let Some(c) = clock.captures(phrase) {
+            let t = NaiveTime::from_hms_opt(c[1].parse().ok()?, c[2].parse().ok()?, 0)?;
+            return Some((&phrase[..c.get(0)?.start()], Some(t)));
+        }
+        let num = r"\d{1,2}|[〇一二两三四五六七八九十]{1,3}";
+        let spoken = Regex::new(&format!(r"({num}){}(?:({})|({num})分)?$", self.hour, self.half)).ok()?;
+        let (rest, hour) = match spoken.captures(phrase) {
+            Some(c) => {
+                let h = number(&c[1])?;
+                let m = if c.get(2).is_some() { 30 } else { c.get(3).map_or(Some(0), |m| number(m.as_str()))? };
+                (&phrase[..c.get(0)?.start()], Some((h, m)))
+            }
+            None => (phrase, None),
+        };
+        let part = self.parts.iter().find(|(word, _, _)| rest.ends_with(word));
+        let rest = part.map_or(rest, |(word, _, _)| &rest[..rest.len() - word.len()]);
+        let time = match (hour, part) {
+            (Some((h, m)), Some((_, _, true))) if h < 12 => NaiveTime::from_hms_opt(h as u32 + 12, m as u32, 0),
+            (Some((h, m)), _) => NaiveTime::from_hms_opt(h as u32, m as u32, 0),
+            (None, Some((_, name, _))) => cfg.day_parts.span(name).map(|s| s.0),
+            (None, None) => None,
+        };
+        match (hour, time) {
+            (Some(_), None) => None,
+            _ => Some((rest, time)),
+        }
+    }
+
+    fn date(&self, phrase: &str, today: NaiveDate, cfg: &Config) -> Option<NaiveDate> {
+        if let Some((_, n)) = self.days.iter().find(|(word, _)| *word == phrase) {
+            return Some(today + Duration::days(*n));
+        }
+        for (suffix, unit) in self.later {
+            if let Some(n) = phrase.strip_suffix(suffix).and_then(number) {
+                return today.checked_add_signed(Duration::try_days(n.checked_mul(*unit)?)?);
+            }
+        }
+        let (weeks, name) = match self.weeks.iter().find(|(prefix, _)| phrase.starts_with(prefix)) {
+            Some((prefix, n)) => (Some(*n), &phrase[prefix.len()..]),
+            None => (None, phrase),
+        };
+        let index = self.weekdays.iter().position(|names| names.contains(&name))?;
+        let wd = Weekday::try_from(index as u8).ok()?;
+        match weeks {
+            // Within the configured week, counted from this one
+            Some(n) => {
+                let start = cfg.week.start_of(today) + Duration::weeks(n);
+                Some(start + Duration::days((wd.num_days_from_monday() as i64 - cfg.week.first_day() as i64).rem_euclid(7)))
+            }
+            // The coming one, today included
+            None => Some(today + Duration::days((wd.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64).rem_euclid(7))),
+        }
+    }
+}
+
+impl LanguagePack for Words {
+    fn code(&self) -> &'static str {
+        self.code
+    }
+
+    fn read(&self, phrase: &str, today: NaiveDate, cfg: &Config) -> Option<(NaiveDate, Option<NaiveTime>)> {
+        let phrase: String = phrase.split_whitespace().collect();
+        let (day, time) = self.time(&phrase, cfg)?;
+        let date = match day {
+            "" if time.is_some() => today,
+            day => self.date(day, today, cfg)?,
+        };
+        Some((date, time))
+    }
+}
@@ -0,0 +1,64 @@
+// src/lib.rs
+pub mod agenda;
+pub mod alias;
+pub mod api;
+pub mod archive;
+pub mod attach;
+pub mod audit;
+pub mod backup;
+pub mod batch;
+pub mod board;
+pub mod briefing;
+pub mod calibrate;
+pub mod capture;
+pub mod cassette;
+pub mod color;
+pub mod commands;
+pub mod config;
+pub mod context;
+pub mod daemon;
+pub mod db;
+pub mod date_parser;
+pub mod days;
+pub mod dedupe;
+pub mod display;
+pub mod doctor;
+pub mod engine;
+pub mod filter;
+pub mod forecast;
+pub mod habits;
+pub mod handlers;
+pub mod heatmap;
+pub mod ics;
+pub mod image;
+pub mod init;
+pub mod insights;
+pub mod integrations;
+pub mod interrupt;
+pub mod lang;
+pub mod logfile;
+pub mod matrix;
+pub mod meta;
+pub mod negotiate;
+pub mod paths;
+pub mod platform;
+pub mod progress;
+pub mod prompt;
+pub mod push;
+pub mod qr;
+pub mod ratelimit;
+pub mod receipt;
+pub mod recurrence;
+pub mod reminders;
+pub mod report;
+pub mod rows;
+pub mod search;
+pub mod share;
+pub mod slots;
+pub mod sort;
+pub mod stamp;
+pub mod status;
+pub mod store;
+pub mod theme;
+pub mod vault;
+pub mod views;
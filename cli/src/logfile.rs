@@ -0,0 +1,107 @@
+use crate::config::{self, LogFormat};
+use crate::store;
+use chrono::Local;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Flags whose values are never written to the log, matched anywhere in the flag's name
+const SECRET_FLAGS: &[&str] = &["password", "secret", "token"];
+
+pub fn path() -> PathBuf {
+    crate::paths::state_dir().join("todo.log")
+}
+
+/// `todo.log.1` is the newest rotated file
+fn rotated(n: usize) -> PathBuf {
+    let mut p = path().into_os_string();
+    p.push(format!(".{}", n));
+    p.into()
+}
+
+/// The log and its rotated files that exist, newest first
+pub fn files() -> Vec<PathBuf> {
+    let keep = config::get().log.keep;
+    std::iter::once(path()).chain((1..=keep.max(1)).map(rotated)).filter(|p| p.exists()).collect()
+}
+
+/// Shift todo.log → todo.log.1 → … once it has `max_bytes`, keeping `keep` old files
+pub fn rotate_if_full(max_bytes: u64, keep: usize) -> std::io::Result<()> {
+    let full = fs::metadata(path()).is_ok_and(|m| m.len() >= max_bytes);
+    if !full {
+        return Ok(());
+    }
+    if keep == 0 {
+        return fs::remove_file(path());
+    }
+    let _ = fs::remove_file(rotated(keep));
+    for n in (1..keep).rev() {
+        if rotated(n).exists() {
+            fs::rename(rotated(n), rotated(n + 1))?;
+        }
+    }
+    fs::rename(path(), rotated(1))
+}
+
+/// One line as configured: a JSON object, or "time kind key=value …"
+pub fn format(format: LogFormat, at: &str, kind: &str, fields: &Value) -> String {
+    match format {
+        LogFormat::Json => {
+            let mut line = json!({ "at": at, "kind": kind });
+            if let (Some(out), Some(extra)) = (line.as_object_mut(), fields.as_object()) {
+                out.extend(extra.clone());
+            }
+            line.to_string()
+        }
+        LogFormat::Pretty => {
+            let pairs = fields.as_object().into_iter().flatten().map(|(k, v)| match v {
+                Value::String(s) => format!("{}={}", k, s),
+                v => format!("{}={}", k, v),
+            });
+            std::iter::once(format!("{} {:<7}", at, kind)).chain(pairs).collect::<Vec<_>>().join(" ")
+        }
+    }
+}
+
+/// `user:password@` in a URL anywhere in an argument
+fn credentials() -> &'static regex::Regex {
+    static RE: OnceLock<regex::Regex> = OnceLock::new();
+    RE.get_or_init(|| regex::Regex::new(r"([a-zA-Z][a-zA-Z0-9+.-]*://[^/:@\s]*):[^/@\s]*@").unwrap())
+}
+
+/// Command-line arguments fit for the log: passwords in URLs and the values of secret flags
+/// (`--token x` or `--token=x`) become `***`
+pub fn redact(args: &[String]) -> Vec<String> {
+    let secret = |flag: &str| flag.starts_with("--") && SECRET_FLAGS.iter().any(|s| flag.to_lowercase().contains(s));
+    let mut out = Vec::with_capacity(args.len());
+    let mut hide_next = false;
+    for arg in args {
+        if std::mem::take(&mut hide_next) {
+            out.push("***".to_string());
+            continue;
+        }
+        match arg.split_once('=') {
+            Some((flag, _)) if secret(flag) => out.push(format!("{}=***", flag)),
+            None if secret(arg) => {
+                hide_next = true;
+                out.push(arg.clone());
+            }
+            _ => out.push(credentials().replace_all(arg, "$1:***@").into_owned()),
+        }
+    }
+    out
+}
+
+/// Append an entry when `log.enabled` is set; logging never makes a command fail
+pub fn event(kind: &str, fields: Value) {
+    let cfg = &config::get().log;
+    if !cfg.enabled {
+        return;
+    }
+    let line = format(cfg.format, &Local::now().format("%Y-%m-%dT%H:%M:%S%.3f").to_string(), kind, &fields);
+    let written = rotate_if_full(cfg.max_kb * 1024, cfg.keep).map_err(|e| e.to_string()).and_then(|_| store::append_line(&path(), &line).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        eprintln!("Warning: could not write {}: {}", path().display(), e);
+    }
+}
@@ -1,73 +1,101 @@
 // src/main.rs
-mod commands;
-mod date_parser;
-
-use clap::{CommandFactory, Parser};
-use commands::{Category, Commands, SyncResult, Task, AutoScheduleResult, PushTaskResult, PushAllResult, Shell as CliShell};
-use prettytable::{Table, row};
-use chrono::{NaiveDateTime, Local, Duration as ChronoDuration};
-use tokio::time::{sleep, Duration};
-use reqwest;
-use serde_json::{json, Value};
-use std::fs;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use clap_complete::generate;
-use crate::date_parser::parse_deadline;
-
-const API_URL: &str = "http://127.0.0.1:8000";
-
-fn humanize_datetime(s: &str) -> String {
-    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
-        let now = Local::now().naive_local();
-        let date = dt.date();
-        let time_str = dt.format("%H:%M").to_string();
-        if date == now.date() {
-            return format!("today at {}", time_str);
-        }
-        if date == now.date() + ChronoDuration::days(1) {
-            return format!("tomorrow at {}", time_str);
-        }
-        if date == now.date() + ChronoDuration::days(2) {
-            return format!("day after tomorrow at {}", time_str);
-        }
-        if date <= now.date() + ChronoDuration::days(7) {
-            return format!("{} at {}", dt.format("%A"), time_str);
-        }
-        dt.format("%Y-%m-%d %H:%M").to_string()
-    } else {
-        s.to_string()
-    }
-}
-
-fn format_minutes(mins: i32) -> String {
-    if mins <= 0 {
-        return "-".into();
-    }
-    let hours = mins / 60;
-    let minutes = mins % 60;
-    if hours > 0 {
-        if minutes > 0 {
-            format!("{}h {}m", hours, minutes)
-        } else {
-            format!("{}h", hours)
-        }
-    } else {
-        format!("{}m", minutes)
-    }
-}
+use prettytable::{Table, row};
+use std::path::PathBuf;
+use todo::api::{Api, ApiError};
+use todo::cassette::Cassette;
+use todo::progress::Progress;
+use todo::commands::{self, BackupCommand, Commands, ConfigCommand, ContextCommand, DaemonCommand, DbCommand, DayCommand, ExportCommand, ImportCommand, IndexCommand, IntegrationCommand, ListFormat, ReportCommand, Shell as CliShell, ShowAs, Task, VaultCommand, ViewCommand};
+use todo::integrations::shell;
+use todo::handlers::{self, CategoryChanges, EventInput, Explained, FreeQuery, PollOptions, TaskChanges, TodoInput};
+use todo::slots::{self, Interval};
+use todo::push::{self, Outcome};
+use todo::{agenda, alias, archive, attach, audit, backup, batch, board, briefing, calibrate, capture, color, config, context, daemon, date_parser, db, days, dedupe, display, filter, doctor, forecast, habits, heatmap, ics, image, insights, interrupt, init, logfile, matrix, meta, paths, prompt, qr, receipt, reminders, report, rows, search, share, status, theme, vault, views};
 
 #[derive(Parser)]
 #[command(name = "todo", about = "CLI for scheduler")]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Override the config directory
+    #[arg(long, global = true, value_name = "DIR", env = "TODO_CONFIG_DIR")]
+    config_dir: Option<PathBuf>,
+
+    /// Override the data directory (also holds cache and state)
+    #[arg(long, global = true, value_name = "DIR", env = "TODO_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Record every API interaction to a cassette file (for bug reports)
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Answer API calls from a recorded cassette instead of the network
+    #[arg(long, global = true, value_name = "FILE")]
+    replay: Option<PathBuf>,
+
+    /// Maximum API requests per second (overrides network.requests_per_second)
+    #[arg(long, global = true, value_name = "N")]
+    rps: Option<f64>,
+
+    /// Never prompt: take each prompt's default, or fail when it has none (for cron and CI)
+    #[arg(long, global = true, env = "TODO_NONINTERACTIVE", value_parser = clap::builder::BoolishValueParser::new())]
+    no_input: bool,
+
+    /// Screen-reader friendly output: labelled lines instead of tables, no colour-only cues
+    #[arg(long, global = true, env = "TODO_ACCESSIBLE", value_parser = clap::builder::BoolishValueParser::new())]
+    accessible: bool,
+
+    /// Lenient dates for dictation: "next thursday at five thirty in the afternoon"
+    #[arg(long, global = true, env = "TODO_FUZZY_DATES", value_parser = clap::builder::BoolishValueParser::new())]
+    fuzzy_dates: bool,
+
+    /// Color preset: default, solarized, high-contrast or deuteranopia-safe (overrides theme in the config)
+    #[arg(long, global = true, env = "TODO_THEME", value_name = "NAME")]
+    theme: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
+    let result = run().await;
+    if let Err(e) = &result {
+        logfile::event("error", serde_json::json!({ "error": e.to_string() }));
+    }
+    result
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    // The config holds per-command default flags, so find it before the real parse
+    let early = Cli::command().ignore_errors(true).get_matches();
+    paths::init(early.get_one::<PathBuf>("config_dir").cloned(), early.get_one::<PathBuf>("data_dir").cloned());
+    let loaded = config::init();
+    let args = match &loaded {
+        Ok(()) => {
+            let expanded = alias::expand(&Cli::command(), std::env::args_os().collect(), &config::get().alias)?;
+            commands::with_defaults(expanded, &config::get().defaults)
+        }
+        Err(_) => std::env::args_os().collect(),
+    };
+    // Later occurrences of a flag replace earlier ones, so typed flags beat configured defaults
+    let matches = Cli::command().mut_subcommands(|c| c.args_override_self(true)).get_matches_from(args);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    prompt::set_no_input(cli.no_input);
+    display::set_accessible(cli.accessible);
+    date_parser::set_fuzzy(cli.fuzzy_dates);
+    if let Some(name) = &cli.theme {
+        theme::set(name)?;
+    }
+    logfile::event("command", serde_json::json!({ "args": logfile::redact(&std::env::args().skip(1).collect::<Vec<_>>()) }));
+    // doctor reports a broken config itself instead of refusing to start
+    if let Err(e) = loaded {
+        if !matches!(cli.command, Commands::Doctor { .. }) {
+            return Err(e);
+        }
+    }
 
     // handle completions:
-    if let Commands::Completions { shell } = &cli.command {
+    if let Commands::Completions { shell, install } = &cli.command {
         // Convert our CmdShell enum into clap_complete::Shell
         let mut app = Cli::command();
         let generator = match shell {
@@ -77,254 +105,1580 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             CliShell::PowerShell  => clap_complete::Shell::PowerShell,
             CliShell::Elvish      => clap_complete::Shell::Elvish,
         };
-        generate(generator, &mut app, "todo", &mut std::io::stdout());
+        match install {
+            true => init::install_completions(&mut app, Some(generator))?,
+            false => generate(generator, &mut app, "todo", &mut std::io::stdout()),
+        }
         return Ok(());
     }
-    
-    let client = reqwest::Client::new();
+
+    if !matches!(cli.command, Commands::MigrateData { .. }) && paths::has_legacy_data() {
+        eprintln!("Note: found data in {}; run `todo migrate-data` to move it.", paths::legacy_dir().display());
+    }
+
+    let net = &config::get().network;
+    let rps = cli.rps.unwrap_or(net.requests_per_second);
+    let mut api = Api::new(&config::get().api_url).with_rate_limit(rps, net.burst.min(rps.max(1.0)));
+    if let Some(path) = &cli.record {
+        api = api.with_cassette(Cassette::record(path));
+    } else if let Some(path) = &cli.replay {
+        api = api.with_cassette(Cassette::replay(path)?);
+    }
     match cli.command {
         Commands::ListCategories => {
-            let resp = client.get(format!("{}/categories/", API_URL)).send().await?;
-            resp.error_for_status_ref()?;
-            let cats: Vec<Category> = resp.json().await?;
-            for c in cats {
-                println!("[{}] {} (color={})", c.id, c.name, c.color);
-            }
-        }
-
-        Commands::CreateCategory { name, color } => {
-            let payload = json!({ "name": name, "color": color });
-            let resp = client.post(format!("{}/categories/", API_URL))
-                .json(&payload)
-                .send()
-                .await?;
-            resp.error_for_status_ref()?;
-            let c: Category = resp.json().await?;
-            println!("Created category [ID {}] {}", c.id, c.name);
-        }
-
-        Commands::ListTasks => {
-            // Trigger auto-scheduling with default config before listing
-            let payload = json!({
-                "availability": {
-                    "0": [{ "start": "09:00", "end": "17:00" }],
-                    "1": [{ "start": "09:00", "end": "17:00" }],
-                    "2": [{ "start": "09:00", "end": "17:00" }],
-                    "3": [{ "start": "09:00", "end": "17:00" }],
-                    "4": [{ "start": "09:00", "end": "17:00" }],
-                    "5": [{ "start": "10:00", "end": "14:00" }],
-                    "6": [{ "start": "10:00", "end": "14:00" }]
-                },
-                "weights": { "priority": 1.0, "deadline": 100.0 }
-            });
-            let resp_sched = client.post(format!("{}/auto-schedule/", API_URL))
-                .json(&payload)
-                .send()
-                .await?;
-            resp_sched.error_for_status_ref()?;
-
-            // Wait briefly for background scheduler to complete
-            // Poll tasks until no TODOs remain unscheduled or timeout
-            for _ in 0..10 {
-                let resp = client.get(format!("{}/tasks/", API_URL)).send().await?;
-                resp.error_for_status_ref()?;
-                let tasks_check: Vec<Task> = resp.json().await?;
-                let pending = tasks_check
-                    .iter()
-                    .filter(|t| t.kind == "todo" && t.scheduled_for.is_none())
-                    .count();
-                if pending == 0 { break; }
-                sleep(Duration::from_millis(200)).await;
-            }
-
-            // Fetch ordered tasks
-            let resp = client.get(format!("{}/taskslist/", API_URL)).send().await?;
-            resp.error_for_status_ref()?;
-            let mut tasks: Vec<Task> = resp.json().await?;
-
-            // Sort by due date (start_time or deadline)
-            tasks.sort_by_key(|t| {
-                t.deadline
-                    .as_ref()
-                    .or(t.start_time.as_ref())
-                    .and_then(|d| NaiveDateTime::parse_from_str(d, "%Y-%m-%dT%H:%M:%S").ok())
-            });
-
-            let mut table = Table::new();
-            table.add_row(row!["ID","Task Name", "Due", "Est", "Priority", "Status", "Tags"]);
-            for t in tasks {
-                let due_raw = t.deadline
-                    .as_ref()
-                    .or(t.start_time.as_ref())
-                    .cloned();
-                let due_str = due_raw.as_deref().map(humanize_datetime).unwrap_or_else(|| "-".to_string());
-                let est_min = if t.kind == "event" {
-                    t.duration.unwrap_or(0)
-                } else {
-                    t.estimate.unwrap_or(0)
-                };
-                let est_str = format_minutes(est_min);
-                let prio = match t.priority.unwrap_or(0) {
-                    p if p >= 7 => "High",
-                    p if p >= 4 => "Medium",
-                    p if p > 0  => "Low",
-                    _ => "Low",
-                };
-                let status = t.status.clone().unwrap_or_default();
-                let tag = t.category.as_ref().map(|c| c.name.clone()).unwrap_or_default();
-                table.add_row(row![t.id, t.title, due_str, est_str, prio, status, tag]);
+            let swatches = agenda::use_color() && !display::accessible();
+            for c in api.categories().await? {
+                let swatch = if swatches { format!("{} ", color::swatch(&theme::current().category(&c.color))) } else { String::new() };
+                let icon = c.icon.as_deref().filter(|i| !i.is_empty()).map(|i| format!(" icon={}", i)).unwrap_or_default();
+                println!("{}[{}] {} (color={}{})", swatch, c.id, c.name, c.color, icon);
             }
-            table.printstd();
         }
 
-        Commands::CreateEvent { title, start, end, description } => {
-            let mut payload = serde_json::Map::new();
-            payload.insert("title".into(), Value::String(title));
-            payload.insert("type".into(), Value::String("event".into()));
-            payload.insert("start_time".into(), Value::String(start));
-            payload.insert("end_time".into(), Value::String(end));
-            if let Some(desc) = description {
-                payload.insert("description".into(), Value::String(desc));
-            }
-            let resp = client.post(format!("{}/tasks/", API_URL))
-                .json(&payload)
-                .send()
-                .await?;
-            resp.error_for_status_ref()?;
-            let t: Task = resp.json().await?;
-            println!("Created event task [ID {}] {}", t.id, t.title);
-        }
-
-        Commands::CreateTodo { title, estimate, deadline, priority, description } => {
-
-            let iso_deadline = parse_deadline(&deadline).map_err(|e| format!("Error parsing deadline `{}`: {}", deadline, e))?;
-            let mut payload = serde_json::Map::new();
-            
-            println!("Parsed deadline: {}", iso_deadline);
-            
-            payload.insert("title".into(), Value::String(title));
-            payload.insert("type".into(), Value::String("todo".into()));
-            payload.insert("estimate".into(), Value::Number(estimate.into()));
-            payload.insert("deadline".into(), Value::String(iso_deadline));
-            payload.insert("priority".into(), Value::Number(priority.into()));
-            if let Some(desc) = description {
-                payload.insert("description".into(), Value::String(desc));
-            }
-            let resp = client.post(format!("{}/tasks/", API_URL))
-                .json(&payload)
-                .send()
-                .await?;
-            resp.error_for_status_ref()?;
-            let t: Task = resp.json().await?;
-            println!("Created todo task [ID {}] {}", t.id, t.title);
-        }
-
-        Commands::UpdateTask { task_id, status, title, priority } => {
-            let mut payload = serde_json::Map::new();
-            if let Some(s) = status {
-                payload.insert("status".into(), Value::String(s));
-            }
-            if let Some(tl) = title {
-                payload.insert("title".into(), Value::String(tl));
-            }
-            if let Some(p) = priority {
-                payload.insert("priority".into(), Value::Number(p.into()));
-            }
-            if payload.is_empty() {
-                eprintln!("No updates provided.");
-                std::process::exit(1);
-            }
-            let resp = client.patch(format!("{}/tasks/{}", API_URL, task_id))
-                .json(&payload)
-                .send()
-                .await?;
-            resp.error_for_status_ref()?;
-            let t: Task = resp.json().await?;
-            println!(
-                "Updated task [ID {}] status={} priority={}",
-                t.id,
-                t.status.unwrap_or_default(),
-                t.priority.unwrap_or(0)
-            );
+        Commands::CreateCategory { name, color, icon } => {
+            let existing = if color.trim().eq_ignore_ascii_case("auto") { api.categories().await? } else { Vec::new() };
+            let color = handlers::category_color(&color, &existing)?;
+            let c = api.create_category(&name, &color, icon.as_deref().map(str::trim).filter(|i| !i.is_empty())).await?;
+            println!("Created category [ID {}] {} (color={})", c.id, c.name, c.color);
+        }
+
+        Commands::UpdateCategory { category, name, color, icon } => {
+            let c = handlers::update_category(&api, &category, CategoryChanges { name, color, icon }).await?;
+            let icon = c.icon.as_deref().filter(|i| !i.is_empty()).map(|i| format!(" icon={}", i)).unwrap_or_default();
+            println!("Updated category [ID {}] {} (color={}{})", c.id, c.name, c.color, icon);
         }
 
-        Commands::DeleteTask { task_id } => {
-            let resp = client.delete(format!("{}/tasks/{}", API_URL, task_id))
-                .send()
-                .await?;
-            if resp.status() == reqwest::StatusCode::NO_CONTENT {
-                println!("Deleted task ID {}", task_id);
+        Commands::ListTasks { contexts, filter, columns, assignee, format, sort, created_since } => {
+            // Habits whose day went by move on before they're listed
+            habits::catch_up(&api, status::now().date()).await?;
+            let payload = config::get().schedule_payload();
+            let listing = handlers::list_tasks(&api, &payload, &PollOptions::default()).await?;
+            let wanted: Vec<String> = contexts.iter().map(|c| context::normalize(c)).collect();
+            let now = status::now();
+            let all_meta = meta::load()?;
+            let owner = assignee.map(|a| config::get().assignee(&a)).transpose()?;
+            let since = match created_since {
+                Some(s) => Some(slots::parse_datetime(&date_parser::parse_deadline(&s)?.chosen).ok_or("Invalid --created-since date")?.date()),
+                None => None,
+            };
+            let mut shown: Vec<&Task> = listing
+                .tasks
+                .iter()
+                .filter(|t| wanted.is_empty() || context::has_any(t, &wanted))
+                .filter(|t| owner.as_deref().is_none_or(|o| t.assignee.as_deref().unwrap_or("").eq_ignore_ascii_case(o)))
+                .filter(|t| filter.as_ref().is_none_or(|f| f.matches(t, &all_meta, now)))
+                .filter(|t| since.is_none_or(|d| meta::created(t, &all_meta).is_some_and(|c| c.date() >= d)))
+                .collect();
+            sort.apply(&mut shown, &all_meta);
+
+            let badges = habits::badges(&habits::load()?, now.date());
+            if format == ListFormat::Alfred {
+                println!("{}", display::alfred_items(&shown));
+            } else if display::accessible() {
+                let extra = display::extra_columns(&columns)?;
+                println!("{} tasks.", shown.len());
+                for (i, t) in shown.iter().enumerate() {
+                    println!("{}", display::task_sentence(t, i + 1, &extra, &all_meta, badges.get(&t.id)));
+                }
             } else {
-                resp.error_for_status_ref()?;
+                display::task_table(&shown, &display::extra_columns(&columns)?, &all_meta, &badges).printstd();
+            }
+            rows::remember(&shown.iter().map(|t| t.id).collect::<Vec<_>>())?;
+            let today = chrono::Local::now().date_naive();
+            for t in handlers::follow_ups(listing.tasks.clone(), &all_meta, config::get().waiting.follow_up_days, today) {
+                let m = all_meta.get(&t.id).cloned().unwrap_or_default();
+                let who = m.waiting_on.unwrap_or_else(|| "someone".into());
+                eprintln!("Follow up: [{}] {} (waiting on {} since {})", t.id, t.title, who, m.waiting_since.unwrap_or_default());
+            }
+            let stale = &config::get().stale;
+            if let Some(limit) = stale.warn_above {
+                let now = chrono::Utc::now().naive_utc();
+                let count = handlers::stale(listing.tasks, now, stale.days).len();
+                if count > limit {
+                    eprintln!("Note: {} tasks untouched for {}+ days; run `todo stale` to review them.", count, stale.days);
+                }
+            }
+        }
+
+        Commands::CreateEvent { title, start, end, length, all_day, description, location, attendees, organizer, meeting_url, show_as, assignee, force, suggest, allow_past } => {
+            let (start, end) = match all_day {
+                true => handlers::all_day_span(&start, end.as_deref())?,
+                false => handlers::timed_span(&start, end.as_deref(), length.as_deref())?,
+            };
+            handlers::check_event_times(&start, &end, allow_past)?;
+            // All-day events only clash with anything when they're configured to block time, free and tentative ones never
+            let check = (!all_day || config::get().scheduling.all_day_busy) && show_as.is_none_or(|a| a == ShowAs::Busy);
+            let conflict = match check {
+                true => handlers::event_conflicts(&api, &start, &end, if suggest { 3 } else { 0 }).await?,
+                false => None,
+            };
+            if let Some(c) = conflict {
+                for b in &c.clashes {
+                    eprintln!("Warning: overlaps {} ({})", b.label, display::format_interval(&b.interval));
+                }
+                if suggest {
+                    if c.suggestions.is_empty() {
+                        println!("No free slot of the same length within a week.");
+                    } else {
+                        println!("Nearest free slots:");
+                    }
+                    for s in &c.suggestions {
+                        println!("  {}", display::format_interval(s));
+                    }
+                }
+                if !force {
+                    return Err("Event conflicts with existing tasks; pass --force to create it anyway.".into());
+                }
+            }
+            let t = handlers::create_event(&api, EventInput { title, start, end, all_day, description, location, attendees, organizer, meeting_url, show_as, assignee }).await?;
+            println!("{}", display::reference("Created event", t.id, Some(&t.title)));
+        }
+
+        Commands::CreateTodo { title, estimate, deadline, deadline_strict, allow_past, priority, description, assignee, optional } => {
+            let due = handlers::check_deadline(&deadline, deadline_strict)?;
+            handlers::check_past("deadline", &due, allow_past)?;
+            let t = handlers::create_todo(&api, TodoInput { title, estimate, deadline: due, priority, description, assignee, optional }).await?;
+            println!("Parsed deadline: {}", t.deadline.clone().unwrap_or_default());
+            println!("{}", display::reference("Created todo", t.id, Some(&t.title)));
+        }
+
+        Commands::Capture { text, from_clipboard, title, estimate, deadline, deadline_strict, allow_past, priority, no_fetch } => {
+            let due = handlers::check_deadline(&deadline, deadline_strict)?;
+            handlers::check_past("deadline", &due, allow_past)?;
+            let text = match text {
+                Some(t) => t,
+                None if from_clipboard => capture::read_clipboard()?,
+                None => return Err("Nothing to capture".into()),
+            };
+            let captured = capture::prepare(api.client(), &text, !no_fetch).await;
+            let title = title.unwrap_or(captured.title);
+            let input = TodoInput { title, estimate, deadline: due, priority, description: captured.description, assignee: None, optional: false };
+            let t = handlers::create_todo(&api, input).await?;
+            println!("{}", display::reference("Captured todo", t.id, Some(&t.title)));
+        }
+
+        Commands::Show { task_id, qr } => {
+            let task_id = rows::resolve(task_id)?;
+            let t = api.task(task_id).await?;
+            if qr {
+                let ics = qr::payload(&t).ok_or_else(|| format!("Task {} has no start and end to share.", t.id))?;
+                println!("{}", qr::render(&ics)?);
+                println!("[ID {}] {}: scan to add it to your calendar", t.id, t.title);
+                return Ok(());
+            }
+            let m = meta::get(task_id)?;
+            if display::accessible() {
+                println!("Task {}, title {}, type {}.", t.id, t.title, t.kind);
+                for (label, value) in display::task_details(&t, &m).into_iter().chain(m.fields.iter().map(|(n, v)| (n.as_str(), v.clone()))) {
+                    println!("{}: {}", label, value.replace("\n  ", ", "));
+                }
+                for c in &m.comments {
+                    println!("Comment from {}: {}", c.at, c.text);
+                }
+                return Ok(());
+            }
+            println!("[ID {}] {} ({})", t.id, t.title, t.kind);
+            for (label, value) in display::task_details(&t, &m) {
+                println!("  {:<12} {}", format!("{}:", label), value);
+            }
+            for (name, value) in &m.fields {
+                println!("  {:<12} {}", format!("{}:", name), value);
+            }
+            if !m.comments.is_empty() {
+                println!("  Comments:");
+                for c in &m.comments {
+                    println!("    {}  {}", c.at, c.text);
+                }
+            }
+        }
+
+        Commands::UpdateTask { task_id, filter, confirm_count, status, title, priority, show_as, waiting_on, assignee, optional } => {
+            let Some(ids) = handlers::targets(&api, task_id, filter.as_ref(), "Update", confirm_count, false).await? else {
+                return Ok(());
+            };
+            let status = if waiting_on.is_some() { Some("waiting".to_string()) } else { status };
+            let today = chrono::Local::now().date_naive().format("%Y-%m-%d").to_string();
+            for task_id in ids {
+                let changes = TaskChanges { status: status.clone(), title: title.clone(), priority, show_as, assignee: assignee.clone(), optional, ..Default::default() };
+                let t = handlers::update_task(&api, task_id, changes).await?;
+                if let Some(who) = &waiting_on {
+                    meta::update(task_id, |m| (m.waiting_on, m.waiting_since) = (Some(who.clone()), Some(today.clone())))?;
+                } else if status.is_some() {
+                    meta::update(task_id, |m| (m.waiting_on, m.waiting_since) = (None, None))?;
+                }
+                println!(
+                    "{}: status={} priority={}",
+                    display::reference("Updated", t.id, Some(&t.title)),
+                    t.status.as_deref().unwrap_or_default(),
+                    t.priority.unwrap_or(0)
+                );
+            }
+        }
+
+        Commands::SetField { task_id, name, value } => {
+            let task_id = rows::resolve(task_id)?;
+            let name = name.to_lowercase();
+            if !filter::is_field_name(&name) || filter::is_reserved(&name) {
+                return Err(format!("'{}' can't be a field name; use letters, digits and _ and avoid built-ins like status or due", name).into());
+            }
+            let m = meta::update(task_id, |m| match value {
+                Some(v) => { m.fields.insert(name.clone(), v); }
+                None => { m.fields.remove(&name); }
+            })?;
+            match m.fields.get(&name) {
+                Some(v) => println!("{}: {} = {}", display::reference("Updated", task_id, None), name, v),
+                None => println!("{}: {} cleared", display::reference("Updated", task_id, None), name),
+            }
+        }
+
+        Commands::Comment { task_id, text } => {
+            let task_id = rows::resolve(task_id)?;
+            let added = text.is_some();
+            let thread = match text {
+                Some(text) => meta::add_comment(task_id, &text, status::now())?,
+                None => meta::get(task_id)?.comments,
+            };
+            if thread.is_empty() {
+                println!("No comments on task [ID {}].", task_id);
+            }
+            for c in &thread {
+                println!("{}  {}", c.at, c.text);
+            }
+            if added {
+                println!("{}", display::reference("Commented on", task_id, None));
+            }
+        }
+
+        Commands::Done { task_id, filter, confirm_count, took } => {
+            let took = match took {
+                Some(d) => Some(i32::try_from(date_parser::parse_duration(&d)?.num_minutes()).map_err(|_| format!("--took '{}' is too long", d))?),
+                None => None,
+            };
+            let Some(ids) = handlers::targets(&api, task_id, filter.as_ref(), "Finish", confirm_count, false).await? else {
+                return Ok(());
+            };
+            for task_id in ids {
+                let t = handlers::update_task(&api, task_id, TaskChanges { status: Some("done".into()), ..Default::default() }).await?;
+                meta::update(task_id, |m| {
+                    (m.waiting_on, m.waiting_since) = (None, None);
+                    if let Some(minutes) = took {
+                        m.actual_minutes = Some(minutes);
+                    }
+                })?;
+                println!("{}", display::reference("Finished", t.id, Some(&t.title)));
+                if let Some(next) = habits::completed(&api, &t, status::now().date()).await? {
+                    let due = display::humanize_datetime(next.deadline.as_deref().unwrap_or_default());
+                    println!("{}: next one, due {}", display::reference("Created todo", next.id, Some(&next.title)), due);
+                }
+            }
+        }
+
+        Commands::Progress { task_id, done } => {
+            let task_id = rows::resolve(task_id)?;
+            let spent = date_parser::parse_duration(&done)?;
+            let (t, left) = handlers::log_progress(&api, task_id, spent).await?;
+            let est = t.estimate.unwrap_or(0);
+            println!("{}: {} / {} done, {} left", display::reference("Updated", t.id, Some(&t.title)), display::format_minutes(est - left), display::format_minutes(est), if left == 0 { "nothing".into() } else { display::format_minutes(left) });
+        }
+
+        Commands::DeleteTask { task_id, filter, confirm_count, yes } => {
+            let Some(ids) = handlers::targets(&api, task_id, filter.as_ref(), "Delete", confirm_count, !yes).await? else {
+                return Ok(());
+            };
+            for task_id in ids {
+                api.delete_task(task_id).await?;
+                println!("{}", display::reference("Deleted", task_id, None));
             }
         }
 
         Commands::SyncCalendar => {
-            let resp = client.post(format!("{}/calendar/sync", API_URL))
-                .send()
-                .await?;
-            resp.error_for_status_ref()?;
-            let result: SyncResult = resp.json().await?;
+            let result = {
+                let _spinner = Progress::spinner("Syncing Google Calendar");
+                // The backend imports in one request, so there is nothing to journal; it's safe to repeat
+                tokio::select! {
+                    r = api.sync_calendar() => r?,
+                    _ = tokio::signal::ctrl_c() => {
+                        return Err("Interrupted; the backend may still finish this sync. Run sync-calendar again to be sure, it's safe to repeat".into());
+                    }
+                }
+            };
             println!(
                 "Imported {} events from Google Calendar.",
                 result.imported.unwrap_or(0)
             );
         }
 
-        Commands::AutoSchedule { config } => {
-            // Use provided config file or default JSON
-            let payload = if let Some(path) = config {
-                let content = fs::read_to_string(&path)?;
-                serde_json::from_str(&content)?
+        Commands::Import(ImportCommand::GoogleTasks { list, completed }) => {
+            let result = {
+                let _spinner = Progress::spinner("Importing Google Tasks");
+                api.import_google_tasks(list.as_deref(), completed).await?
+            };
+            println!(
+                "Imported {} new and updated {} tasks from {}.",
+                result.imported,
+                result.updated,
+                match result.lists.len() {
+                    1 => format!("list '{}'", result.lists[0]),
+                    n => format!("{} lists", n),
+                }
+            );
+            if result.skipped > 0 {
+                println!("Skipped {} without a due date; give them one in Google Tasks to bring them in.", result.skipped);
+            }
+        }
+
+        Commands::Import(ImportCommand::Ics { file, until }) => {
+            let today = status::now().date();
+            let last = match until {
+                Some(u) => slots::parse_datetime(&date_parser::parse_deadline(&u)?.chosen).ok_or("Invalid --until date")?.date(),
+                None => today + chrono::Duration::weeks(12),
+            };
+            let range = Interval::new(today.and_hms_opt(0, 0, 0).unwrap(), (last + chrono::Duration::days(1)).and_hms_opt(0, 0, 0).unwrap());
+            let raw = std::fs::read_to_string(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+            let events = ics::parse_events(&raw, &range);
+            let r = handlers::import_events(&api, &events).await?;
+            println!("Imported {} events up to {}.", r.created, last);
+            if r.skipped > 0 {
+                println!("Skipped {} already in the calendar.", r.skipped);
+            }
+            for f in &r.failed {
+                eprintln!("Failed: {}", f);
+            }
+        }
+
+        Commands::Import(ImportCommand::Reminders { file, list, completed }) => {
+            let found = match file {
+                Some(f) => reminders::from_file(&f)?,
+                #[cfg(feature = "reminders-bridge")]
+                None => reminders::from_app()?,
+                #[cfg(not(feature = "reminders-bridge"))]
+                None => return Err("Pass a Reminders export (.ics or .json); reading Reminders.app directly needs a build with --features reminders-bridge".into()),
+            };
+            let r = reminders::import(&api, &found, list.as_deref(), completed).await?;
+            println!("Imported {} reminders; created {} categories.", r.tasks.len(), r.categories);
+            if r.skipped > 0 {
+                println!("Skipped {} imported before.", r.skipped);
+            }
+            for f in &r.failed {
+                eprintln!("Failed: {}", f);
+            }
+            for (id, title) in &r.tasks {
+                println!("{}", display::reference("Imported", *id, Some(title)));
+            }
+        }
+
+        Commands::AutoSchedule { config, local, dry_run, incremental, stability } => {
+            let scheduling = &config::get().scheduling;
+            if stability.is_some_and(|s| !(0.0..=1.0).contains(&s)) {
+                return Err("--stability must be between 0 and 1".into());
+            }
+            let stability = if incremental || stability.is_some() {
+                Some(stability.unwrap_or(scheduling.stability))
             } else {
-                // default availability & weights for full week
-                json!({
-                    "availability": {
-                        "0": [{ "start": "09:00", "end": "17:00" }],
-                        "1": [{ "start": "09:00", "end": "17:00" }],
-                        "2": [{ "start": "09:00", "end": "17:00" }],
-                        "3": [{ "start": "09:00", "end": "17:00" }],
-                        "4": [{ "start": "09:00", "end": "17:00" }],
-                        "5": [{ "start": "10:00", "end": "14:00" }],
-                        "6": [{ "start": "10:00", "end": "14:00" }]
-                    },
-                    "weights": { "priority": 1.0, "deadline": 100.0 }
-                })
+                scheduling.incremental_stability()
             };
-            let resp = client.post(format!("{}/auto-schedule/", API_URL))
-                .json(&payload)
-                .send()
-                .await?;
-            resp.error_for_status_ref()?;
-            let result: AutoScheduleResult = resp.json().await?;
-            println!("Auto-schedule status: {}", result.status.unwrap_or_default());
+            if !local {
+                let before = api.tasks().await?;
+                let result = handlers::auto_schedule(&api, config.as_deref(), stability).await?;
+                println!("Auto-schedule status: {}", result.status.unwrap_or_default());
+                handlers::wait_for_schedule(&api, &PollOptions::default()).await?;
+                let after = api.tasks().await?;
+                let changes = handlers::schedule_diff(&before, &after, scheduling.eviction);
+                if changes.is_empty() {
+                    println!("No todo changed day.");
+                } else {
+                    let mut table = Table::new();
+                    table.add_row(row!["ID", "Task", "Before", "After", ""]);
+                    for c in &changes {
+                        let mut shift = format!("{:?}", c.shift).to_lowercase();
+                        if let Some(policy) = c.policy {
+                            shift = format!("{} ({})", shift, policy.name());
+                        }
+                        table.add_row(row![c.task_id, c.title, c.before.as_deref().unwrap_or("-"), c.after.as_deref().unwrap_or("-"), shift]);
+                    }
+                    table.printstd();
+                }
+                if forecast::enabled(config::get()) {
+                    for w in forecast::warnings(&after, config::get(), status::now()) {
+                        eprintln!("Warning: {}", w);
+                    }
+                }
+                for c in &changes {
+                    println!("{}", display::reference("Rescheduled", c.task_id, Some(&c.title)));
+                }
+                return Ok(());
+            }
+
+            let result = handlers::local_schedule(&api, dry_run, stability).await?;
+            if let Some(policy) = scheduling.eviction {
+                println!("Eviction policy: {}", policy.name());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["ID", "Task", "Slot", ""]);
+            for p in &result.plan.placed {
+                let mut notes = Vec::new();
+                if p.kept {
+                    notes.push("kept".to_string());
+                }
+                if let Some(from) = p.shrunk_from {
+                    notes.push(format!("shrunk from {}", display::format_minutes(from as i32)));
+                }
+                if p.late {
+                    notes.push("past deadline".to_string());
+                }
+                table.add_row(row![p.task_id, p.title, display::format_interval(&p.slot), notes.join(", ")]);
+            }
+            table.printstd();
+            for p in result.plan.placed.iter().filter(|p| p.late) {
+                eprintln!("Warning: [{}] {} is scheduled to end after its deadline.", p.task_id, p.title);
+            }
+            for u in &result.plan.unplaced {
+                println!("Not scheduled: [{}] {} ({})", u.task_id, u.title, u.reason);
+            }
+            if dry_run {
+                println!("Dry run: {} task(s) would move.", result.updated.len());
+                return Ok(());
+            }
+            println!("Updated {} task(s).", result.updated.len());
+            if forecast::enabled(config::get()) {
+                for w in forecast::warnings(&api.tasks().await?, config::get(), status::now()) {
+                    eprintln!("Warning: {}", w);
+                }
+            }
+            for p in result.plan.placed.iter().filter(|p| result.updated.contains(&p.task_id)) {
+                println!("{}", display::reference("Scheduled", p.task_id, Some(&p.title)));
+            }
         }
 
         Commands::PushTask { task_id } => {
-            let resp = client.post(format!("{}/calendar/push/{}", API_URL, task_id))
-                .send()
-                .await?;
-            resp.error_for_status_ref()?;
-            let result: PushTaskResult = resp.json().await?;
-            println!(
-                "Pushed task [ID {}] to Google Calendar as {}",
-                task_id,
-                result.google_event_id.unwrap_or_default()
-            );
+            let task_id = rows::resolve(task_id)?;
+            let result = api.push_task(task_id, config::get().category_icons).await?;
+            println!("{}: Google Calendar event {}", display::reference("Pushed", task_id, None), result.google_event_id.unwrap_or_default());
         }
 
-        Commands::PushAll => {
-            let resp = client.post(format!("{}/calendar/push-all", API_URL))
-                .send()
-                .await?;
-            resp.error_for_status_ref()?;
-            let result: PushAllResult = resp.json().await?;
-            println!(
-                "Pushed {} new and updated {} existing events.",
-                result.pushed.unwrap_or(0),
-                result.updated.unwrap_or(0)
-            );
+        Commands::PushAll { client_side, concurrency, resume, filter } => {
+            let mut client_side = client_side || resume || filter.is_some();
+            if !client_side {
+                let result = {
+                    let _spinner = Progress::spinner("Pushing scheduled tasks to Google Calendar");
+                    api.push_all(config::get().category_icons).await
+                };
+                match result {
+                    Ok(r) => println!(
+                        "Pushed {} new and updated {} existing events.",
+                        r.pushed.unwrap_or(0),
+                        r.updated.unwrap_or(0)
+                    ),
+                    // Older backends have no bulk endpoint
+                    Err(e) if e.downcast_ref::<ApiError>().is_some_and(|a| matches!(a.status, 404 | 405)) => {
+                        eprintln!("Backend has no push-all ({}); pushing task by task.", e);
+                        client_side = true;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            if client_side {
+                let only = if let Some(f) = &filter {
+                    Some(f.select(api.tasks().await?, &meta::load()?, status::now()).iter().map(|t| t.id).collect::<Vec<_>>())
+                } else if resume {
+                    let journal = push::load_journal()?;
+                    if journal.is_empty() {
+                        println!("Nothing to resume; the last push finished without failures.");
+                        return Ok(());
+                    }
+                    Some(journal.remaining())
+                } else {
+                    None
+                };
+                interrupt::watch();
+                let reports = push::push_all(&api, only.as_deref(), concurrency).await?;
+
+                let mut table = Table::new();
+                table.add_row(row!["ID", "Task", "Result", "Detail"]);
+                let (mut ok, mut failed, mut skipped, mut not_reached) = (0, 0, 0, 0);
+                for r in &reports {
+                    let (result, detail) = match &r.outcome {
+                        Outcome::Pushed(id) => { ok += 1; ("pushed", id.as_str()) }
+                        Outcome::Failed(why) => { failed += 1; ("failed", why.as_str()) }
+                        Outcome::Skipped(why) => { skipped += 1; ("skipped", why.as_str()) }
+                        Outcome::NotReached => { not_reached += 1; ("interrupted", "") }
+                    };
+                    table.add_row(row![r.task_id, r.title, result, detail]);
+                }
+                table.printstd();
+                println!("{} pushed, {} failed, {} skipped.", ok, failed, skipped);
+                if not_reached > 0 {
+                    return Err(format!("interrupted with {} tasks not pushed yet; rerun with --resume to continue", not_reached).into());
+                }
+                if failed > 0 {
+                    return Err("some tasks failed to push; rerun with --resume to retry them".into());
+                }
+            }
+        }
+
+        Commands::Explain { task_id, local } => match handlers::explain(&api, rows::resolve(task_id)?, local).await? {
+            Explained::Server(v) => println!("{}", serde_json::to_string_pretty(&v)?),
+            Explained::Local(e) => {
+                match (&e.slot, &e.unplaced) {
+                    (Some(s), _) => {
+                        let how = if e.kept { " (kept from the previous run)" } else { "" };
+                        println!("[{}] {} \u{2192} {}{}", e.task_id, e.title, display::format_interval(s), how);
+                    }
+                    (None, reason) => {
+                        println!("[{}] {} could not be scheduled: {}", e.task_id, e.title, reason.as_deref().unwrap_or("unknown"));
+                    }
+                }
+                let total: f64 = e.score.iter().map(|p| p.value).sum();
+                println!("Score {:.2} (scheduled {} of {})", total, e.rank, e.of);
+                for p in &e.score {
+                    println!("  {:<10} {:>8.2}  {}", p.name, p.value, p.detail);
+                }
+                if !e.competitors.is_empty() {
+                    println!("Placed ahead of it:");
+                    for c in &e.competitors {
+                        println!("  [{}] {}  {}  (score {:.2})", c.task_id, c.title, display::format_interval(&c.slot), c.score);
+                    }
+                }
+                if !e.skipped_days.is_empty() {
+                    println!("Days passed over:");
+                    for (day, why) in &e.skipped_days {
+                        println!("  {}  {}", day.format("%a %Y-%m-%d"), why);
+                    }
+                }
+            }
+        },
+
+        Commands::Negotiate { task_id } => {
+            let (task, offer) = handlers::negotiate(&api, rows::resolve(task_id)?).await?;
+            if let Some(slot) = offer.fits {
+                println!("[{}] {} already fits before its deadline: {}", offer.task_id, offer.title, display::format_interval(&slot));
+                return Ok(());
+            }
+            let when = |t: chrono::NaiveDateTime| t.format("%a %Y-%m-%d %H:%M").to_string();
+            println!("[{}] {} can't be done by {}.", offer.task_id, offer.title, when(offer.deadline));
+            let mut keys = Vec::new();
+            if let Some(e) = offer.earliest {
+                println!("  d  Move the deadline to {}, the earliest it could be done", when(e));
+                keys.push('d');
+            }
+            if let Some((p, evicted)) = &offer.raise {
+                let cost = match evicted.is_empty() {
+                    true => "nothing else loses its slot".to_string(),
+                    false => format!("unschedules {}", evicted.iter().map(|(id, t)| format!("[{}] {}", id, t)).collect::<Vec<_>>().join(", ")),
+                };
+                println!("  p  Raise the priority to {}; {}", p, cost);
+                keys.push('p');
+            }
+            if let Some((keep, rest)) = offer.split {
+                let due = offer.earliest.map(|e| format!(", due {}", when(e))).unwrap_or_default();
+                println!("  s  Split: {} by the deadline, {} as a new todo{}", display::format_minutes(keep), display::format_minutes(rest), due);
+                keys.push('s');
+            }
+            if keys.is_empty() {
+                println!("Nothing in the current plan makes room for it; try a smaller estimate or more availability.");
+                return Ok(());
+            }
+            if !prompt::interactive() {
+                println!("Run this in a terminal to pick one.");
+                return Ok(());
+            }
+            println!("  q  Leave it");
+            let key = loop {
+                match console::Term::stdout().read_char()? {
+                    c if keys.contains(&c) || c == 'q' => break c,
+                    _ => continue,
+                }
+            };
+            match (key, offer.earliest, &offer.raise, offer.split) {
+                ('d', Some(e), _, _) => {
+                    let changes = TaskChanges { deadline: Some(todo::stamp::format(e)), ..Default::default() };
+                    let t = handlers::update_task(&api, task.id, changes).await?;
+                    println!("{}: deadline moved to {}", display::reference("Updated", t.id, Some(&t.title)), when(e));
+                }
+                ('p', _, Some((p, _)), _) => {
+                    let t = handlers::update_task(&api, task.id, TaskChanges { priority: Some(*p), ..Default::default() }).await?;
+                    println!("Run `todo auto-schedule` to re-plan.");
+                    println!("{}: priority raised to {}", display::reference("Updated", t.id, Some(&t.title)), p);
+                }
+                ('s', _, _, Some((keep, rest))) => {
+                    let (first, second) = handlers::split_task(&api, &task, keep, rest, offer.earliest).await?;
+                    println!("Run `todo auto-schedule` to re-plan.");
+                    println!("{}: split off the rest", display::reference("Updated", first.id, Some(&first.title)));
+                    println!("{}", display::reference("Created todo", second.id, Some(&second.title)));
+                }
+                _ => println!("Left as it is."),
+            }
+        }
+
+        Commands::Free { duration, within, participants } => {
+            let duration = date_parser::parse_duration(&duration)?;
+            let (start, end) = date_parser::parse_range(&within)?;
+            let query = FreeQuery { duration, range: Interval::new(start, end), participants };
+            let gaps = handlers::free_slots(&api, &query).await?;
+            if gaps.is_empty() {
+                println!("No free slot of {} within {}.", display::format_minutes(duration.num_minutes() as i32), within);
+                return Ok(());
+            }
+            for g in &gaps {
+                println!("{}  ({})", display::format_interval(g), display::format_minutes(g.duration().num_minutes() as i32));
+            }
+        }
+
+        Commands::Backup(BackupCommand::Create { output }) => {
+            let now = status::now();
+            let b = backup::create(&api, &now.format("%Y-%m-%dT%H:%M:%S").to_string()).await?;
+            let output = output.unwrap_or_else(|| format!("todo-backup-{}.tar.zst", now.format("%Y-%m-%d")).into());
+            backup::write(&output, &b)?;
+            println!("Backed up {} tasks and {} categories to {}", b.tasks.len(), b.categories.len(), output.display());
+            if vault::is_locked() {
+                eprintln!("Note: the backup file is not encrypted; keep it somewhere safe.");
+            }
+        }
+
+        Commands::Daemon(DaemonCommand::Run) => daemon::run(&api, &config::get().daemon).await?,
+
+        Commands::Daemon(action) => {
+            let exe = std::env::current_exe()?;
+            let service = daemon::service(&todo::platform::Host::current(), &exe, &paths::state_dir().join("daemon.log")).ok_or("Couldn't find your home directory")?;
+            match action {
+                DaemonCommand::Install => {
+                    service.install()?;
+                    if let Some((path, _)) = &service.file {
+                        println!("Wrote {}", path.display());
+                    }
+                    println!("The daemon is running and starts at every login; check on it with `todo daemon status`.");
+                }
+                DaemonCommand::Status => {
+                    if !service.installed() {
+                        println!("Not installed; set it up with `todo daemon install`.");
+                        return Ok(());
+                    }
+                    println!("{}", daemon::exec(&service.status)?.1);
+                }
+                DaemonCommand::Uninstall => {
+                    if !service.installed() {
+                        println!("Not installed.");
+                        return Ok(());
+                    }
+                    for problem in service.uninstall()? {
+                        eprintln!("Note: {}", problem);
+                    }
+                    println!("Removed {}.", daemon::NAME);
+                }
+                DaemonCommand::Run => unreachable!(),
+            }
+        }
+
+        Commands::Config(ConfigCommand::Export { output, secrets }) => {
+            let path = config::path();
+            if !path.exists() {
+                return Err(format!("No config file at {}; nothing to export", path.display()).into());
+            }
+            let exported = config::export(&std::fs::read_to_string(&path)?, secrets)?;
+            match output {
+                Some(out) => {
+                    std::fs::write(&out, exported)?;
+                    println!("Exported {} to {}", path.display(), out.display());
+                }
+                None => print!("{}", exported),
+            }
+        }
+
+        Commands::Config(ConfigCommand::Import { file, replace }) => {
+            let path = config::path();
+            let current = match path.exists() && !replace {
+                true => Some(std::fs::read_to_string(&path)?),
+                false => None,
+            };
+            let text = config::import(&std::fs::read_to_string(&file)?, current.as_deref())?;
+            if path.exists() {
+                let kept = path.with_extension("toml.bak");
+                std::fs::copy(&path, &kept)?;
+                println!("Previous config kept in {}", kept.display());
+            }
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, text)?;
+            println!("Imported {} into {}", file.display(), path.display());
+        }
+
+        Commands::Config(ConfigCommand::Diff) => {
+            let lines = config::diff(&config::load()?)?;
+            if lines.is_empty() {
+                println!("Everything is at its default.");
+            }
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+
+        Commands::Db(DbCommand::Check) => {
+            let problems = db::check()?;
+            if !problems.is_empty() {
+                for problem in &problems {
+                    println!("{}", problem);
+                }
+                return Err(format!("{} problems in {}; run `todo db repair`", problems.len(), db::path().display()).into());
+            }
+            println!("{} is healthy.", db::path().display());
+        }
+
+        Commands::Db(DbCommand::Repair) => {
+            let done = db::repair()?;
+            if done.is_empty() {
+                println!("Nothing to repair.");
+            }
+            for line in done {
+                println!("{}", line);
+            }
+        }
+
+        Commands::Backup(BackupCommand::Snapshot) => {
+            let now = status::now();
+            let b = backup::create(&api, &now.format("%Y-%m-%dT%H:%M:%S").to_string()).await?;
+            let pruned = backup::snapshot(&b, now)?;
+            println!("Snapshot {} saved ({} tasks); pruned {}", backup::snapshot_path(now).display(), b.tasks.len(), pruned);
+        }
+
+        Commands::Backup(BackupCommand::List) => {
+            let all = backup::snapshots()?;
+            if all.is_empty() {
+                println!("No snapshots yet; take one with `todo backup snapshot`.");
+            }
+            for at in all {
+                println!("{}", backup::snapshot_path(at).file_stem().unwrap_or_default().to_string_lossy());
+            }
+        }
+
+        Commands::Backup(BackupCommand::Diff { a, b }) => {
+            let lines = backup::diff(&backup::open(&a)?, &backup::open(&b)?);
+            if lines.is_empty() {
+                println!("No task changes.");
+            }
+            for l in lines {
+                println!("{}", l);
+            }
+        }
+
+        Commands::Backup(BackupCommand::Restore { file, force }) => {
+            let b = backup::open(&file)?;
+            let r = backup::restore(&api, &b, force).await?;
+            println!("Restored {} of {} tasks; created {} categories.", r.tasks.len(), b.tasks.len(), r.categories);
+            if let Some(p) = r.config_copy {
+                println!("Your config was kept; the backed-up one is at {}", p.display());
+            }
+            for f in &r.failed {
+                eprintln!("Failed: {}", f);
+            }
+            for (id, title) in &r.tasks {
+                println!("{}", display::reference("Restored", *id, Some(title)));
+            }
+            if !r.failed.is_empty() {
+                return Err(format!("{} tasks could not be restored", r.failed.len()).into());
+            }
+        }
+
+        Commands::Share { week, within, readonly: _, output } => {
+            let (start, end) = date_parser::parse_range(if week { "this week" } else { &within })?;
+            let bundle = share::build(&api.tasks().await?, config::get(), Interval::new(start, end), status::now());
+            let page = share::write(&output, &bundle)?;
+            println!("Wrote {} ({} slots); share the folder or host it anywhere static.", page.display(), bundle.slots.len());
+        }
+
+        Commands::Export(ExportCommand::Image { week: _, within, output }) => {
+            if output.extension().is_some_and(|e| !e.eq_ignore_ascii_case("svg")) {
+                return Err(format!("Can only write SVG; use a .svg name and convert it (e.g. `rsvg-convert -o week.png {}`)", output.display()).into());
+            }
+            let today = status::now().date();
+            let (from, days) = match within {
+                Some(w) => {
+                    let (start, end) = date_parser::parse_range(&w)?;
+                    (start.date(), ((end - chrono::Duration::seconds(1)).date() - start.date()).num_days() + 1)
+                }
+                None => (config::get().week.start_of(today), 7),
+            };
+            let days = agenda::build(&api.tasks().await?, config::get(), from, days);
+            std::fs::write(&output, image::week_svg(&days, config::get()))?;
+            println!("Wrote {} ({} to {})", output.display(), from.format("%a %Y-%m-%d"), days.last().map_or(from, |d| d.date).format("%a %Y-%m-%d"));
+        }
+
+        Commands::Export(ExportCommand::Receipt { date, width, output }) => {
+            if width < receipt::MIN_WIDTH {
+                return Err(format!("--width must be at least {}", receipt::MIN_WIDTH).into());
+            }
+            let day = slots::parse_datetime(&date_parser::parse_deadline(&date)?.chosen).ok_or("Invalid date")?.date();
+            let card = agenda::build(&api.tasks().await?, config::get(), day, 1).pop().map(|d| receipt::render(&d, width)).unwrap_or_default();
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, card)?;
+                    println!("Wrote {} ({})", path.display(), day.format("%a %Y-%m-%d"));
+                }
+                None => print!("{}", card),
+            }
+        }
+
+        Commands::ExportIcs { output, within, filter, sort } => {
+            let mut tasks = api.tasks().await?;
+            if let Some(f) = &filter {
+                tasks = f.select(tasks, &meta::load()?, status::now());
+            }
+            if let Some(w) = within {
+                let range = date_parser::parse_range(&w).map(|(s, e)| Interval::new(s, e))?;
+                tasks.retain(|t| slots::busy_from_tasks(std::slice::from_ref(t)).iter().any(|b| b.interval.overlaps(&range)));
+            }
+            sort.apply(&mut tasks, &meta::load()?);
+            let calendar = ics::to_ics(&tasks);
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, calendar)?;
+                    println!("Wrote {}", path.display());
+                }
+                None => print!("{}", calendar),
+            }
+        }
+
+        Commands::Repeat { task_id, every, stop } => {
+            let task_id = rows::resolve(task_id)?;
+            let mut all = habits::load()?;
+            if stop {
+                let first = habits::for_task(&mut all, task_id).map(|(first, _)| *first).ok_or(format!("Task {} doesn't repeat", task_id))?;
+                let title = all.remove(&first).map(|h| h.title).unwrap_or_default();
+                habits::save(&all)?;
+                println!("'{}' no longer repeats; its history is gone too.", title);
+                return Ok(());
+            }
+            let every = every.ok_or("Say how often with --every day|weekday|week, or --stop")?;
+            let t = api.task(task_id).await?;
+            if t.kind != "todo" {
+                return Err("Only todos can repeat".into());
+            }
+            let due = t.deadline.as_deref().and_then(slots::parse_datetime).map(|d| d.date()).unwrap_or_else(|| status::now().date());
+            match habits::for_task(&mut all, task_id) {
+                Some((_, h)) => h.every = every,
+                None => {
+                    all.insert(task_id, habits::Habit::new(&t.title, every, task_id, due));
+                }
+            }
+            habits::save(&all)?;
+            println!("Finishing it creates the next one.");
+            println!("{}: repeats every {} from {}", display::reference("Updated", t.id, Some(&t.title)), every.name(), due);
+        }
+
+        Commands::Calibrate => {
+            let all = calibrate::bias(&api.tasks().await?, &meta::load()?);
+            if all.is_empty() {
+                println!("Nothing to go on yet; record how long tasks take with `todo done <task> --took 1h30m`.");
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["Category", "Tasks", "Estimated", "Actual", "Factor"]);
+            for (name, b) in &all {
+                table.add_row(row![name, b.samples, display::format_minutes(b.estimated), display::format_minutes(b.actual), format!("{:.2}", b.ratio())]);
+            }
+            table.printstd();
+            for (name, b) in all.iter().filter(|(_, b)| b.samples >= calibrate::MIN_SAMPLES) {
+                println!("{}.", calibrate::describe(name, b));
+            }
+            if !config::get().scheduling.calibrate {
+                println!("Set `calibrate = true` under [scheduling] to plan with these factors (categories with {}+ tasks).", calibrate::MIN_SAMPLES);
+            }
+        }
+
+        Commands::Report(ReportCommand::Week) => {
+            let (first, rows) = report::week(&api.tasks().await?, config::get(), status::now());
+            println!("Week of {}", first.format("%a %Y-%m-%d"));
+            if rows.is_empty() {
+                println!("Nothing booked with a category this week; set weekly targets under [quotas], e.g. work = \"max 40h\".");
+                return Ok(());
+            }
+            if display::accessible() {
+                for r in &rows {
+                    println!("{}", report::describe(r));
+                }
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["Category", "Done", "Scheduled", "Total", "Quota", ""]);
+            for r in &rows {
+                let quota = r.quota.map(String::from).unwrap_or_default();
+                table.add_row(row![r.category, report::amount(r.actual), report::amount(r.scheduled), report::amount(r.total()), quota, r.verdict()]);
+            }
+            table.printstd();
+        }
+
+        Commands::Day(cmd) => {
+            let date = |d: &str| -> Result<chrono::NaiveDate, Box<dyn std::error::Error>> {
+                Ok(slots::parse_datetime(&date_parser::parse_deadline(d)?.chosen).ok_or("Invalid date")?.date())
+            };
+            let hours = |windows: &[config::Window]| match windows {
+                [] => "no hours".to_string(),
+                _ => windows.iter().cloned().map(String::from).collect::<Vec<_>>().join(", "),
+            };
+            match cmd {
+                DayCommand::Set { date: d, template } => {
+                    let d = date(&d)?;
+                    let name = days::set(d, &template, config::get())?;
+                    let windows = config::get().day_template(&name).map(|(_, t)| t.effective_windows()).unwrap_or_default();
+                    println!("{}: {} day, {}.", d.format("%a %Y-%m-%d"), name, hours(&windows));
+                }
+                DayCommand::Clear { date: d } => {
+                    let d = date(&d)?;
+                    match days::clear(d)? {
+                        true => println!("{} is back to its usual hours.", d.format("%a %Y-%m-%d")),
+                        false => println!("{} had no day template.", d.format("%a %Y-%m-%d")),
+                    }
+                }
+                DayCommand::List => {
+                    let cfg = config::get();
+                    if cfg.day_templates.is_empty() {
+                        println!("No day templates; add one under [day_templates.office] with windows and buffers.");
+                        return Ok(());
+                    }
+                    for (name, t) in &cfg.day_templates {
+                        let mut about = hours(&t.windows);
+                        if t.buffer_before > 0 || t.buffer_after > 0 {
+                            let (before, after) = (display::format_minutes(t.buffer_before as i32), display::format_minutes(t.buffer_after as i32));
+                            about.push_str(&format!(" (buffers: {} before, {} after)", before, after));
+                        }
+                        println!("{:<12} {}", name, about);
+                    }
+                    let today = status::now().date();
+                    let upcoming: Vec<_> = days::load()?.into_iter().filter(|(d, _)| *d >= today).collect();
+                    if !upcoming.is_empty() {
+                        println!();
+                    }
+                    for (d, name) in upcoming {
+                        let unknown = if cfg.day_template(&name).is_none() { "  (no such template; usual hours apply)" } else { "" };
+                        println!("{}  {}{}", d.format("%a %Y-%m-%d"), name, unknown);
+                    }
+                }
+            }
+        }
+
+        Commands::Heatmap { year, hours, svg } => {
+            let days = heatmap::completed_by_day(&audit::read_all()?, &meta::load()?);
+            let (first, last) = heatmap::span(year, status::now().date());
+            let week = &config::get().week;
+            if let Some(file) = svg {
+                std::fs::write(&file, heatmap::svg(&days, first, last, week, hours))?;
+                println!("Wrote {}", file.display());
+                return Ok(());
+            }
+            match display::accessible() {
+                true => print!("{}", heatmap::render_accessible(&days, first, last)),
+                false => print!("{}", heatmap::render(&days, first, last, week, hours, agenda::use_color())),
+            }
+            println!("{}", heatmap::summary(&days, first, last));
+        }
+
+        Commands::Insights { top } => {
+            let i = insights::compute(&audit::read_all()?);
+            let Some(since) = &i.since else {
+                println!("The audit log is empty; insights build up as you use todo.");
+                return Ok(());
+            };
+            println!("Since {}\n", since);
+            let mut table = Table::new();
+            table.add_row(row!["Action", "Times"]);
+            for (action, n) in i.actions.iter().take(top) {
+                table.add_row(row![action, n]);
+            }
+            table.printstd();
+            let chart = insights::hour_chart(&i.created_by_hour, 30);
+            if !chart.is_empty() {
+                println!("\nWhen you add tasks:");
+                for line in chart {
+                    println!("  {}", line);
+                }
+            }
+            match i.mean_to_done {
+                Some(d) => println!("\nFrom creation to done: {} on average, over {} tasks.", insights::format_span(d), i.completed),
+                None => println!("\nNo task has been both created and finished since the log began."),
+            }
+        }
+
+        Commands::Habits => {
+            let today = status::now().date();
+            habits::catch_up(&api, today).await?;
+            let all = habits::load()?;
+            if all.is_empty() {
+                println!("No repeating tasks; start one with `todo repeat <task> --every day`.");
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["Habit", "Every", "Streak", "Best", "Done", "Missed", "Next due", "Task"]);
+            for h in all.values() {
+                let done = h.history.len() - h.misses();
+                table.add_row(row![h.title, h.every.name(), h.badge(today).unwrap_or_else(|| "-".into()), h.best(), done, h.misses(), h.due, h.task_id]);
+            }
+            table.printstd();
+        }
+
+        Commands::Briefing => {
+            let now = status::now();
+            let b = briefing::build(&api.tasks().await?, config::get().user.as_deref(), now);
+            print!("{}", briefing::render(&b, now, |id| config::get().task_link(id)));
+        }
+
+        Commands::Matrix { urgent_days, important } => {
+            let tasks = api.tasks().await?;
+            let th = matrix::Thresholds { urgent_within: chrono::Duration::days(urgent_days), important_from: important };
+            print!("{}", matrix::render(&matrix::build(&tasks, status::now(), &th)));
+        }
+
+        Commands::Agenda { days, location } => {
+            let today = chrono::Local::now().date_naive();
+            habits::catch_up(&api, today).await?;
+            let mut tasks = api.tasks().await?;
+            if let Some(wanted) = location {
+                tasks.retain(|t| agenda::at_location(t, &wanted));
+            }
+            let days = agenda::build(&tasks, config::get(), today, days);
+            match display::accessible() {
+                true => print!("{}", agenda::render_accessible(&days)),
+                false => print!("{}", agenda::render(&days, agenda::use_color())),
+            }
+        }
+
+        Commands::Today => {
+            let now = status::now();
+            let shown = handlers::today(api.tasks().await?, now);
+            if shown.is_empty() {
+                println!("Nothing scheduled or due today.");
+                return Ok(());
+            }
+            let shown: Vec<&Task> = shown.iter().collect();
+            let all_meta = meta::load()?;
+            let badges = habits::badges(&habits::load()?, now.date());
+            match display::accessible() {
+                true => {
+                    println!("{} tasks today.", shown.len());
+                    for (i, t) in shown.iter().enumerate() {
+                        println!("{}", display::task_sentence(t, i + 1, &[], &all_meta, badges.get(&t.id)));
+                    }
+                }
+                false => display::task_table(&shown, &[], &all_meta, &badges).printstd(),
+            }
+            rows::remember(&shown.iter().map(|t| t.id).collect::<Vec<_>>())?;
+        }
+
+        Commands::Board { interactive, done, today } => {
+            let mut tasks = api.tasks().await?;
+            if today {
+                let now = status::now();
+                tasks.retain(|t| handlers::on_today(t, now));
+            }
+            if interactive {
+                if !prompt::interactive() {
+                    return Err("--interactive needs a terminal".into());
+                }
+                board::interactive(&api, tasks, done).await?;
+                return Ok(());
+            }
+            let cols = board::columns(&tasks, done);
+            match display::accessible() {
+                true => print!("{}", board::render_accessible(&cols)),
+                false => print!("{}", board::render(&cols, console::Term::stdout().size().1 as usize, None, agenda::use_color())),
+            }
+        }
+
+        Commands::QuietHours { task_id, ignore, add, reset } => {
+            let task_id = rows::resolve(task_id)?;
+            let changed = reset || ignore || !add.is_empty();
+            let current = if reset {
+                meta::update(task_id, |m| {
+                    m.ignore_quiet_hours = false;
+                    m.quiet_hours.clear();
+                })?
+            } else if ignore || !add.is_empty() {
+                meta::update(task_id, |m| {
+                    m.ignore_quiet_hours |= ignore;
+                    m.quiet_hours.extend(add);
+                })?
+            } else {
+                meta::get(task_id)?
+            };
+            let blocks: Vec<String> = current.quiet_hours.into_iter().map(String::from).collect();
+            match (current.ignore_quiet_hours, blocks.is_empty()) {
+                (false, true) => println!("Task {} follows the global quiet hours.", task_id),
+                (ignore, _) => {
+                    if ignore {
+                        println!("Task {} may be scheduled during quiet hours.", task_id);
+                    }
+                    if !blocks.is_empty() {
+                        println!("Task {} is also kept out of: {}", task_id, blocks.join("; "));
+                    }
+                }
+            }
+            if changed {
+                println!("{}", display::reference("Updated", task_id, None));
+            }
+        }
+
+        Commands::Attach { task_id, target } => {
+            let task_id = rows::resolve(task_id)?;
+            let added = target.is_some();
+            let attachments = match target {
+                Some(t) => attach::add(task_id, &t)?,
+                None => meta::get(task_id)?.attachments,
+            };
+            if attachments.is_empty() {
+                println!("Task {} has no attachments.", task_id);
+            }
+            for (i, a) in attachments.iter().enumerate() {
+                println!("{}. {}", i + 1, a);
+            }
+            if added {
+                println!("{}", display::reference("Attached to", task_id, None));
+            }
+        }
+
+        Commands::Open { task_id, index } => {
+            let task_id = rows::resolve(task_id)?;
+            let attachments = meta::get(task_id)?.attachments;
+            let target = index
+                .checked_sub(1)
+                .and_then(|i| attachments.get(i))
+                .ok_or_else(|| format!("Task {} has {} attachment(s); nothing at {}", task_id, attachments.len(), index))?;
+            attach::open(target)?;
+        }
+
+        Commands::Url { task_id, open } => {
+            let task_id = rows::resolve(task_id)?;
+            let url = config::get()
+                .task_link(task_id)
+                .ok_or("No web UI configured; set task_url in the config, e.g. task_url = \"https://tasks.example.com/tasks/{id}\"")?;
+            match open {
+                true => attach::open(&url)?,
+                false => println!("{}", url),
+            }
+        }
+
+        Commands::Waiting { followed_up } => {
+            let days = config::get().waiting.follow_up_days;
+            let today = chrono::Local::now().date_naive();
+            if let Some(r) = followed_up {
+                let task_id = rows::resolve(r)?;
+                meta::update(task_id, |m| m.waiting_since = Some(today.format("%Y-%m-%d").to_string()))?;
+                let next = (today + chrono::Duration::days(days)).format("%a %Y-%m-%d");
+                println!("{}: next follow-up on {}", display::reference("Followed up on", task_id, None), next);
+                return Ok(());
+            }
+            let all_meta = meta::load()?;
+            let tasks = handlers::waiting(api.tasks().await?, &all_meta);
+            if tasks.is_empty() {
+                println!("Nothing is waiting on anyone.");
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["#", "ID", "Task", "Waiting on", "Since", "Follow up"]);
+            for (i, t) in tasks.iter().enumerate() {
+                let m = all_meta.get(&t.id).cloned().unwrap_or_default();
+                let follow_up = match handlers::follow_up_on(&m, days) {
+                    Some(d) if d <= today => "now".to_string(),
+                    Some(d) => d.format("%a %Y-%m-%d").to_string(),
+                    None => "-".into(),
+                };
+                let since = m.waiting_since.unwrap_or_else(|| "-".into());
+                table.add_row(row![format!("%{}", i + 1), t.id, t.title, m.waiting_on.unwrap_or_else(|| "-".into()), since, follow_up]);
+            }
+            table.printstd();
+            rows::remember(&tasks.iter().map(|t| t.id).collect::<Vec<_>>())?;
+        }
+
+        Commands::Rollover { bump, dry_run } => {
+            let r = handlers::rollover(&api, status::now().date(), bump, dry_run).await?;
+            if r.slipped.is_empty() {
+                println!("Nothing slipped today.");
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["ID", "Task", "Was", "Now"]);
+            for t in &r.slipped {
+                let was = t.start_time.as_deref().map(display::humanize_datetime).unwrap_or_default();
+                let now = match (r.plan.placed.iter().find(|p| p.task_id == t.id), r.plan.unplaced.iter().find(|u| u.task_id == t.id)) {
+                    (Some(p), _) => display::format_interval(&p.slot),
+                    (None, Some(u)) => format!("not scheduled: {}", u.reason),
+                    (None, None) => "-".into(),
+                };
+                table.add_row(row![t.id, t.title, was, now]);
+            }
+            table.printstd();
+            let minutes: i32 = r.slipped.iter().filter_map(|t| t.estimate).sum();
+            let verb = if dry_run { "Would roll over" } else { "Rolled over" };
+            println!("{} {} task(s), {} of work.", verb, r.slipped.len(), display::format_minutes(minutes));
+            if !dry_run {
+                for t in &r.slipped {
+                    println!("{}", display::reference("Rolled over", t.id, Some(&t.title)));
+                }
+            }
+        }
+
+        Commands::Stale { days, later, delete, bump, yes, confirm_count } => {
+            let days = days.unwrap_or(config::get().stale.days);
+            // The backend stamps tasks in UTC
+            let now = chrono::Utc::now().naive_utc();
+            let tasks = handlers::stale(api.tasks().await?, now, days);
+            if tasks.is_empty() {
+                println!("No tasks untouched for {}+ days.", days);
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["#", "ID", "Task", "Status", "Untouched"]);
+            for (i, t) in tasks.iter().enumerate() {
+                let idle = handlers::last_touched(t).map(|at| format!("{}d", (now - at).num_days())).unwrap_or_default();
+                table.add_row(row![format!("%{}", i + 1), t.id, t.title, t.status.clone().unwrap_or_default(), idle]);
+            }
+            table.printstd();
+            rows::remember(&tasks.iter().map(|t| t.id).collect::<Vec<_>>())?;
+            if !(later || delete || bump) {
+                return Ok(());
+            }
+            let (verb, done) = if later {
+                ("Defer", "Deferred")
+            } else if delete {
+                ("Delete", "Deleted")
+            } else {
+                ("Bump", "Bumped")
+            };
+            let titles: Vec<String> = tasks.iter().map(|t| format!("[{}] {}", t.id, t.title)).collect();
+            if !prompt::approve_bulk(verb, &titles, confirm_count, (later || delete) && !yes)? {
+                return Ok(());
+            }
+            for t in &tasks {
+                if delete {
+                    api.delete_task(t.id).await?;
+                    push::forget(&[t.id])?;
+                } else {
+                    let changes = if later {
+                        TaskChanges { status: Some("later".into()), ..Default::default() }
+                    } else {
+                        TaskChanges { priority: Some((t.priority.unwrap_or(0) + 1).min(10)), ..Default::default() }
+                    };
+                    handlers::update_task(&api, t.id, changes).await?;
+                }
+                println!("{}", display::reference(done, t.id, Some(&t.title)));
+            }
+        }
+
+        Commands::Cleanup { days, archive, delete, include_unpushed, yes, confirm_count } => {
+            // The backend stamps tasks in UTC
+            let now = chrono::Utc::now().naive_utc();
+            let tasks = handlers::finished(api.tasks().await?, now, days, !include_unpushed);
+            if tasks.is_empty() {
+                let which = if include_unpushed { "" } else { " already on the calendar" };
+                println!("No done tasks{} older than {} days.", which, days);
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["#", "ID", "Task", "Type", "Done for", "On calendar"]);
+            for (i, t) in tasks.iter().enumerate() {
+                let idle = handlers::last_touched(t).map(|at| format!("{}d", (now - at).num_days())).unwrap_or_default();
+                let pushed = if t.external_id.as_deref().is_some_and(|id| !id.is_empty()) { "yes" } else { "no" };
+                table.add_row(row![format!("%{}", i + 1), t.id, t.title, t.kind, idle, pushed]);
+            }
+            table.printstd();
+            rows::remember(&tasks.iter().map(|t| t.id).collect::<Vec<_>>())?;
+            if !(archive || delete) {
+                println!("Keep a copy and remove them with --archive, or remove them outright with --delete.");
+                return Ok(());
+            }
+            // Deleting a task leaves its calendar event behind, so only long-finished ones go
+            let pushed = tasks.iter().filter(|t| t.external_id.as_deref().is_some_and(|id| !id.is_empty())).count();
+            if pushed > 0 && days < handlers::CLEANUP_MIN_DAYS {
+                return Err(format!(
+                    "{} of these are on the calendar, where their events would stay; use --days {} or more",
+                    pushed,
+                    handlers::CLEANUP_MIN_DAYS
+                )
+                .into());
+            }
+            let (verb, done) = if archive { ("Archive", "Archived") } else { ("Delete", "Deleted") };
+            let titles: Vec<String> = tasks.iter().map(|t| format!("[{}] {}", t.id, t.title)).collect();
+            if !prompt::approve_bulk(verb, &titles, confirm_count, !yes)? {
+                return Ok(());
+            }
+            if archive {
+                println!("Copies go to the archive in {}.", db::path().display());
+            }
+            let mut all_meta = meta::load()?;
+            for t in &tasks {
+                if archive {
+                    archive::append(t, all_meta.get(&t.id), status::now())?;
+                }
+                api.delete_task(t.id).await?;
+                push::forget(&[t.id])?;
+                // Saved as we go, so stopping halfway leaves no notes for tasks already gone
+                if all_meta.remove(&t.id).is_some() {
+                    meta::save(&all_meta)?;
+                }
+                println!("{}", display::reference(done, t.id, Some(&t.title)));
+            }
+        }
+
+        Commands::Dedupe { auto, threshold, confirm_count } => {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err("--threshold must be between 0 and 1".into());
+            }
+            let groups = dedupe::clusters(&api.tasks().await?, threshold);
+            if groups.is_empty() {
+                println!("No duplicates found.");
+                return Ok(());
+            }
+            if auto {
+                let doomed: Vec<String> = groups.iter().flat_map(|g| &g[1..]).map(|t| format!("[{}] {}", t.id, t.title)).collect();
+                if !prompt::approve_bulk("Delete", &doomed, confirm_count, false)? {
+                    return Ok(());
+                }
+            }
+            let mut merged = 0;
+            for group in &groups {
+                let plan = dedupe::plan_merge(group);
+                println!("Possible duplicates:");
+                for t in group {
+                    let due = t.deadline.as_deref().map(display::humanize_datetime).unwrap_or_else(|| "-".into());
+                    println!("  [{}] {} (due {}, priority {})", t.id, t.title, due, t.priority.unwrap_or(0));
+                }
+                if !auto && !prompt::confirm(&format!("Merge into [{}]?", plan.keep), false)? {
+                    continue;
+                }
+                let changes = TaskChanges { priority: plan.priority, deadline: plan.deadline, description: plan.description, ..Default::default() };
+                handlers::update_task(&api, plan.keep, changes).await?;
+                meta::merge(plan.keep, &plan.remove)?;
+                for id in &plan.remove {
+                    api.delete_task(*id).await?;
+                    push::forget(&[*id])?;
+                }
+                println!("Merged {} duplicate(s) into [{}].", plan.remove.len(), plan.keep);
+                for id in &plan.remove {
+                    println!("{}", display::reference("Deleted", *id, None));
+                }
+                println!("{}", display::reference("Updated", plan.keep, None));
+                merged += 1;
+            }
+            if merged == 0 {
+                println!("No duplicates merged.");
+            }
+        }
+
+        Commands::History { task, limit } => {
+            let entries = handlers::history(task, limit)?;
+            if entries.is_empty() {
+                println!("No history recorded.");
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["When", "Action", "Task", "Result", "Details"]);
+            for e in &entries {
+                let when = e.timestamp.replace('T', " ");
+                let task = e.task_id.map(|i| i.to_string()).unwrap_or_else(|| "-".into());
+                let result = if e.ok { "ok" } else { "failed" };
+                table.add_row(row![when, e.action, task, result, audit::summarize(e)]);
+            }
+            table.printstd();
+        }
+
+        Commands::Log { task_id } => {
+            let task_id = rows::resolve(task_id)?;
+            let log = handlers::task_log(&api, task_id).await?;
+            if let Some(e) = &log.server_error {
+                eprintln!("Server history unavailable: {}", e);
+            }
+            if log.entries.is_empty() {
+                println!("No activity recorded for task {}.", task_id);
+                return Ok(());
+            }
+            let mut table = Table::new();
+            table.add_row(row!["When", "Source", "Activity"]);
+            for e in log.entries {
+                table.add_row(row![e.timestamp.replace('T', " "), e.source, e.activity]);
+            }
+            table.printstd();
+        }
+
+        Commands::Search { query, full_text, filter, sort } => {
+            let mut hits = if full_text {
+                let index = if vault::is_locked() {
+                    search::Index::in_memory(&api.tasks().await?)?
+                } else {
+                    let index = search::Index::open()?;
+                    match search::built_at()? {
+                        Some(at) => {
+                            let age = slots::parse_datetime(&at).map(|at| status::now() - at);
+                            if age.is_some_and(|a| a > chrono::Duration::days(1)) {
+                                eprintln!("Note: index built {}; run `todo index rebuild` to pick up changes made outside this CLI.", display::humanize_datetime(&at));
+                            }
+                        }
+                        None => {
+                            index.rebuild(&api.tasks().await?, status::now())?;
+                        }
+                    }
+                    index
+                };
+                index.search(&query)?
+            } else {
+                let words = search::tokenize(&query);
+                api.tasks()
+                    .await?
+                    .into_iter()
+                    .filter(|t| {
+                        let title = search::tokenize(&t.title);
+                        !words.is_empty() && words.iter().all(|w| title.contains(w))
+                    })
+                    .map(|t| search::Hit { id: t.id, title: t.title, snippet: None })
+                    .collect()
+            };
+            if let Some(f) = &filter {
+                let keep: Vec<i32> = f.select(api.tasks().await?, &meta::load()?, status::now()).iter().map(|t| t.id).collect();
+                hits.retain(|h| keep.contains(&h.id));
+            }
+            if let Some(spec) = &sort {
+                let tasks = api.tasks().await?;
+                let mut found: Vec<&Task> = tasks.iter().filter(|t| hits.iter().any(|h| h.id == t.id)).collect();
+                spec.apply(&mut found, &meta::load()?);
+                // Hits from a stale index whose task is gone go last
+                hits.sort_by_key(|h| found.iter().position(|t| t.id == h.id).unwrap_or(usize::MAX));
+            }
+            if hits.is_empty() {
+                println!("No tasks match '{}'.", query);
+            }
+            for (i, h) in hits.iter().enumerate() {
+                println!("%{:<3} [{}] {}", i + 1, h.id, h.title);
+                if let Some(s) = &h.snippet {
+                    println!("       {}", s);
+                }
+            }
+            rows::remember(&hits.iter().map(|h| h.id).collect::<Vec<_>>())?;
+        }
+
+        Commands::View { action: Some(action), .. } => match action {
+            ViewCommand::Save { name, filter } => {
+                views::save(&name, &filter)?;
+                println!("Saved view '{}'; show it with `todo view {}`.", name, name);
+            }
+            ViewCommand::List => {
+                let saved = views::load()?;
+                if saved.is_empty() {
+                    println!("No saved views.");
+                }
+                for (name, filter) in saved {
+                    println!("{:<16} {}", name, filter);
+                }
+            }
+            ViewCommand::Delete { name } => match views::remove(&name)? {
+                true => println!("Deleted view '{}'.", name),
+                false => return Err(format!("No view '{}'", name).into()),
+            },
+        },
+
+        Commands::View { action: None, name } => {
+            let name = name.ok_or("Name a view, or use `todo view list`")?;
+            let filter = views::get(&name)?;
+            let tasks = api.tasks().await?;
+            let now = status::now();
+            let all_meta = meta::load()?;
+            let shown: Vec<&Task> = tasks.iter().filter(|t| filter.matches(t, &all_meta, now)).collect();
+            display::task_table(&shown, &[], &all_meta, &habits::badges(&habits::load()?, now.date())).printstd();
+            rows::remember(&shown.iter().map(|t| t.id).collect::<Vec<_>>())?;
+        }
+
+        Commands::Index(IndexCommand::Rebuild) => {
+            if vault::is_locked() {
+                return Err("No index is kept while the vault is locked; `todo search --full-text` builds one each time".into());
+            }
+            let count = search::Index::open()?.rebuild(&api.tasks().await?, status::now())?;
+            println!("Indexed {} tasks.", count);
+        }
+
+        Commands::Context(cmd) => match &cmd {
+            ContextCommand::List => {
+                let counts = context::in_use(&api.tasks().await?);
+                let windows = &config::get().scheduling.context_windows;
+                let names: std::collections::BTreeSet<&String> = counts.keys().chain(windows.keys()).collect();
+                if names.is_empty() {
+                    println!("No contexts yet; add one with `todo context add <task> @errands`.");
+                }
+                for name in names {
+                    let n = counts.get(name).copied().unwrap_or(0);
+                    let when = windows.get(name).map(|w| w.iter().cloned().map(String::from).collect::<Vec<_>>().join(", "));
+                    println!("@{:<14} {:>3} open  {}", name, n, when.unwrap_or_else(|| "any time".into()));
+                }
+            }
+            ContextCommand::Add { task_id, contexts } | ContextCommand::Remove { task_id, contexts } => {
+                let adding = matches!(cmd, ContextCommand::Add { .. });
+                let task_id = rows::resolve(*task_id)?;
+                let mut current = api.task(task_id).await?.contexts().to_vec();
+                for c in contexts.iter().map(|c| context::normalize(c)) {
+                    match adding {
+                        true if !current.contains(&c) => current.push(c),
+                        false => current.retain(|x| *x != c),
+                        _ => {}
+                    }
+                }
+                let t = handlers::update_task(&api, task_id, TaskChanges { contexts: Some(current), ..Default::default() }).await?;
+                let tags: Vec<String> = t.contexts().iter().map(|c| format!("@{}", c)).collect();
+                let tags = if tags.is_empty() { "none".into() } else { tags.join(" ") };
+                println!("{}: contexts {}", display::reference("Updated", t.id, Some(&t.title)), tags);
+            }
+        },
+
+        Commands::Batch { resume } => {
+            interrupt::watch();
+            let failed = batch::run(&api, std::io::stdin().lock(), std::io::stdout().lock(), resume).await?;
+            if failed > 0 {
+                return Err(format!("{} batch operation(s) failed", failed).into());
+            }
+        }
+
+        Commands::Status { format, refresh } => {
+            let max_age = if refresh { chrono::Duration::zero() } else { chrono::Duration::seconds(config::get().status.refresh_seconds) };
+            let now = status::now();
+            println!("{}", status::render(&status::current(&api, max_age, now).await?, format, now));
+        }
+
+        Commands::Integration(cmd) => {
+            let (target, key) = match cmd {
+                IntegrationCommand::ZshWidget { key } => (shell::Target::Zsh, key),
+                IntegrationCommand::FishBinding { key } => (shell::Target::Fish, key),
+                IntegrationCommand::BashBinding { key } => (shell::Target::Bash, key),
+            };
+            print!("{}", shell::binding(target, key.as_deref())?);
+        }
+
+        Commands::Vault(cmd) => match cmd {
+            VaultCommand::Lock => {
+                let n = vault::lock()?;
+                println!("Vault locked; encrypted {} store(s).", n);
+            }
+            VaultCommand::Unlock => {
+                let n = vault::unlock()?;
+                println!("Vault unlocked; decrypted {} store(s).", n);
+            }
+            VaultCommand::Status => println!("{}", vault::status()),
+        },
+
+        Commands::MigrateData { dry_run } => {
+            println!("Config: {}", paths::config_dir().display());
+            if let Some(p) = config::system_path() {
+                println!("System: {}", p.display());
+            }
+            println!("Data:   {}", paths::data_dir().display());
+            println!("Cache:  {}", paths::cache_dir().display());
+            println!("State:  {}", paths::state_dir().display());
+            let report = paths::migrate(dry_run)?;
+            if report.is_empty() {
+                println!("Nothing to migrate.");
+            }
+            for line in report {
+                println!("{}", line);
+            }
         }
+
+        Commands::Init { api_url } => {
+            init::run(api.client(), api_url, &mut Cli::command()).await?;
+        }
+
+        Commands::Doctor { schema } => doctor::run(api.client(), api.base_url(), schema).await?,
         _ => unreachable!(), // we've already returned on Completions
     }
 
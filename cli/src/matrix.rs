@@ -0,0 +1,92 @@
+use crate::commands::Task;
+use crate::display::{humanize_datetime, priority_label, titled};
+use crate::slots::parse_datetime;
+use chrono::{Duration, NaiveDateTime};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Quadrant {
+    DoFirst,
+    Schedule,
+    Delegate,
+    Drop,
+}
+
+pub const QUADRANTS: [Quadrant; 4] = [Quadrant::DoFirst, Quadrant::Schedule, Quadrant::Delegate, Quadrant::Drop];
+
+impl Quadrant {
+    pub fn of(urgent: bool, important: bool) -> Quadrant {
+        match (urgent, important) {
+            (true, true) => Quadrant::DoFirst,
+            (false, true) => Quadrant::Schedule,
+            (true, false) => Quadrant::Delegate,
+            (false, false) => Quadrant::Drop,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Quadrant::DoFirst => "Urgent and important: do first",
+            Quadrant::Schedule => "Important, not urgent: schedule",
+            Quadrant::Delegate => "Urgent, not important: delegate",
+            Quadrant::Drop => "Neither: drop or defer",
+        }
+    }
+}
+
+/// What counts as urgent and important
+pub struct Thresholds {
+    /// Deadlines this close (or already past) are urgent
+    pub urgent_within: Duration,
+    /// Priorities from here up are important
+    pub important_from: i32,
+}
+
+impl Default for Thresholds {
+    /// Due within two days; medium priority or higher
+    fn default() -> Self {
+        Thresholds { urgent_within: Duration::days(2), important_from: 4 }
+    }
+}
+
+/// Open todos by quadrant, in `QUADRANTS` order; each list soonest deadline first, then
+/// highest priority
+pub fn build<'a>(tasks: &'a [Task], now: NaiveDateTime, th: &Thresholds) -> [Vec<&'a Task>; 4] {
+    let mut out: [Vec<&Task>; 4] = Default::default();
+    for t in tasks.iter().filter(|t| t.kind == "todo" && t.status.as_deref() != Some("done")) {
+        let urgent = t.deadline.as_deref().and_then(parse_datetime).is_some_and(|d| d <= now + th.urgent_within);
+        let important = t.priority.unwrap_or(0) >= th.important_from;
+        let q = Quadrant::of(urgent, important);
+        out[QUADRANTS.iter().position(|x| *x == q).unwrap_or(3)].push(t);
+    }
+    let due = |t: &Task| t.deadline.as_deref().and_then(parse_datetime);
+    for list in &mut out {
+        list.sort_by_key(|t| (due(t).is_none(), due(t), -t.priority.unwrap_or(0)));
+    }
+    out
+}
+
+/// "[12] Pay rent (due Friday at 21:00, priority 4 Medium)"
+fn line(t: &Task) -> String {
+    let mut about: Vec<String> = t.deadline.as_deref().map(|d| format!("due {}", humanize_datetime(d))).into_iter().collect();
+    let p = t.priority.unwrap_or(0);
+    about.push(format!("priority {} {}", p, priority_label(Some(p))));
+    format!("[{}] {} ({})", t.id, titled(t), about.join(", "))
+}
+
+/// The four quadrants as headed lists, each with its count
+pub fn render(quadrants: &[Vec<&Task>; 4]) -> String {
+    let mut out = String::new();
+    for (q, list) in QUADRANTS.iter().zip(quadrants) {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("{} ({})\n", q.title(), list.len()));
+        if list.is_empty() {
+            out.push_str("  nothing\n");
+        }
+        for t in list {
+            out.push_str(&format!("  {}\n", line(t)));
+        }
+    }
+    out
+}
@@ -0,0 +1,154 @@
+use crate::commands::Task;
+use crate::config::Block;
+use crate::db;
+use chrono::{NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Per-task data the backend has no fields for, kept only on this machine
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct TaskMeta {
+    /// Let the scheduler use quiet hours for this task
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_quiet_hours: bool,
+    /// Extra do-not-schedule blocks for this task only
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub quiet_hours: Vec<Block>,
+    /// URLs and absolute file paths, in the order they were attached
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<String>,
+    /// Who a delegated task is waiting on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waiting_on: Option<String>,
+    /// Day it was handed off, or last chased up (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waiting_since: Option<String>,
+    /// User-defined fields, e.g. effort_points = "5"
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    /// Minutes it really took, recorded with `todo done --took`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_minutes: Option<i32>,
+    /// Minutes left after `todo progress`, when the backend doesn't keep `remaining`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_minutes: Option<i32>,
+    /// Notes added with `todo comment`, oldest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<Comment>,
+    /// When it was created (UTC), for backends that don't send `created_at`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+impl TaskMeta {
+    /// Fold a duplicate's metadata into this one: lists are joined, and this task's own
+    /// values win where both have one
+    pub fn absorb(&mut self, other: TaskMeta) {
+        self.ignore_quiet_hours |= other.ignore_quiet_hours;
+        for b in other.quiet_hours {
+            if !self.quiet_hours.contains(&b) {
+                self.quiet_hours.push(b);
+            }
+        }
+        for a in other.attachments {
+            if !self.attachments.contains(&a) {
+                self.attachments.push(a);
+            }
+        }
+        if self.waiting_on.is_none() {
+            (self.waiting_on, self.waiting_since) = (other.waiting_on, other.waiting_since);
+        }
+        for (k, v) in other.fields {
+            self.fields.entry(k).or_insert(v);
+        }
+        self.actual_minutes = self.actual_minutes.or(other.actual_minutes);
+        self.remaining_minutes = self.remaining_minutes.or(other.remaining_minutes);
+        for c in other.comments {
+            if !self.comments.contains(&c) {
+                self.comments.push(c);
+            }
+        }
+        self.comments.sort_by(|a, b| a.at.cmp(&b.at));
+        self.created_at = match (self.created_at.take(), other.created_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+/// A timestamped note on a task
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Comment {
+    /// Local time, YYYY-MM-DD HH:MM
+    pub at: String,
+    pub text: String,
+}
+
+pub type MetaMap = BTreeMap<i32, TaskMeta>;
+
+pub const STORE: &str = "task_meta";
+
+pub fn load() -> Result<MetaMap, Box<dyn Error>> {
+    Ok(db::read_doc(STORE)?.unwrap_or_default())
+}
+
+pub fn save(map: &MetaMap) -> Result<(), Box<dyn Error>> {
+    db::write_doc(STORE, map)
+}
+
+/// One task's metadata, or the defaults
+pub fn get(task_id: i32) -> Result<TaskMeta, Box<dyn Error>> {
+    Ok(load()?.remove(&task_id).unwrap_or_default())
+}
+
+/// Append a note stamped `at`; returns the whole thread
+pub fn add_comment(task_id: i32, text: &str, at: NaiveDateTime) -> Result<Vec<Comment>, Box<dyn Error>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("A comment needs some text".into());
+    }
+    let comment = Comment { at: at.format("%Y-%m-%d %H:%M").to_string(), text: text.to_string() };
+    Ok(update(task_id, |m| m.comments.push(comment))?.comments)
+}
+
+/// Minutes left on `t`: the backend's `remaining`, else the locally kept figure; `None` until
+/// progress is logged
+pub fn remaining(t: &Task, meta: &MetaMap) -> Option<i32> {
+    t.remaining.or_else(|| meta.get(&t.id)?.remaining_minutes)
+}
+
+/// When `t` was created, in local time: the backend's `created_at` (UTC), else the one stamped
+/// here when it was made
+pub fn created(t: &Task, meta: &MetaMap) -> Option<NaiveDateTime> {
+    let utc = t.created_at.as_deref().or_else(|| meta.get(&t.id)?.created_at.as_deref()).and_then(crate::slots::parse_datetime)?;
+    Some(chrono::Local.from_utc_datetime(&utc).naive_local())
+}
+
+/// Move the metadata of `from` (duplicates about to be deleted) onto `keep`
+pub fn merge(keep: i32, from: &[i32]) -> Result<TaskMeta, Box<dyn Error>> {
+    let mut map = load()?;
+    let mut merged = map.remove(&keep).unwrap_or_default();
+    for id in from {
+        merged.absorb(map.remove(id).unwrap_or_default());
+    }
+    if merged != TaskMeta::default() {
+        map.insert(keep, merged.clone());
+    }
+    save(&map)?;
+    Ok(merged)
+}
+
+/// Change one task's metadata, dropping the entry once it's back to defaults
+pub fn update(task_id: i32, change: impl FnOnce(&mut TaskMeta)) -> Result<TaskMeta, Box<dyn Error>> {
+    let mut map = load()?;
+    let entry = map.entry(task_id).or_default();
+    change(entry);
+    let updated = entry.clone();
+    if updated == TaskMeta::default() {
+        map.remove(&task_id);
+    }
+    save(&map)?;
+    Ok(updated)
+}
@@ -0,0 +1,80 @@
+use crate::commands::Task;
+use crate::engine::{self, Options, Plan};
+use crate::slots;
+use chrono::NaiveDateTime;
+
+/// Ways to make an infeasible deadline work, each found by re-running the local planner
+pub struct Offer {
+    pub task_id: i32,
+    pub title: String,
+    pub deadline: NaiveDateTime,
+    /// It already fits: where it goes
+    pub fits: Option<slots::Interval>,
+    /// When it could be finished with no deadline at all
+    pub earliest: Option<NaiveDateTime>,
+    /// The lowest priority that gets it in on time, and the todos that would then lose their slot
+    pub raise: Option<(i32, Vec<(i32, String)>)>,
+    /// Minutes that fit before the deadline, and the rest
+    pub split: Option<(i32, i32)>,
+}
+
+fn slot_of(plan: &Plan, task_id: i32) -> Option<slots::Interval> {
+    plan.placed.iter().find(|p| p.task_id == task_id).map(|p| p.slot)
+}
+
+/// Plan with the task changed by `change`; its slot if it then lands before `deadline`
+fn try_with(tasks: &[Task], opts: &Options, task_id: i32, change: impl Fn(&mut Task)) -> (Plan, Option<slots::Interval>) {
+    let changed: Vec<Task> = tasks
+        .iter()
+        .cloned()
+        .map(|mut t| {
+            if t.id == task_id {
+                change(&mut t);
+            }
+            t
+        })
+        .collect();
+    let plan = engine::plan(&changed, opts);
+    let slot = slot_of(&plan, task_id);
+    (plan, slot)
+}
+
+/// What could be done about `task_id`'s deadline; an error if it isn't a todo with a deadline
+/// and an estimate
+pub fn offer(tasks: &[Task], opts: &Options, task_id: i32) -> Result<Offer, String> {
+    let task = tasks.iter().find(|t| t.id == task_id).ok_or_else(|| format!("Task {} not found.", task_id))?;
+    let deadline = task.deadline.as_deref().and_then(slots::parse_datetime).filter(|_| task.kind == "todo");
+    let deadline = deadline.ok_or_else(|| format!("Task {} isn't a todo with a deadline.", task_id))?;
+    let est = task.estimate.filter(|e| *e > 0).ok_or_else(|| format!("Task {} has no estimate to plan with.", task_id))?;
+    let mut offer = Offer { task_id, title: task.title.clone(), deadline, fits: None, earliest: None, raise: None, split: None };
+
+    let base = engine::plan(tasks, opts);
+    offer.fits = slot_of(&base, task_id);
+    if offer.fits.is_some() {
+        return Ok(offer);
+    }
+    offer.earliest = try_with(tasks, opts, task_id, |t| t.deadline = None).1.map(|s| s.end);
+
+    // Only as far as one above everything else: past that, priority changes nothing
+    let top = tasks.iter().filter(|t| t.kind == "todo").filter_map(|t| t.priority).max().unwrap_or(0) + 1;
+    let from = task.priority.unwrap_or(0) + 1;
+    offer.raise = (from..=top.max(from)).find_map(|p| {
+        let (plan, slot) = try_with(tasks, opts, task_id, |t| t.priority = Some(p));
+        slot?;
+        let evicted = base
+            .placed
+            .iter()
+            .filter(|b| b.task_id != task_id && slot_of(&plan, b.task_id).is_none())
+            .map(|b| (b.task_id, b.title.clone()))
+            .collect();
+        Some((p, evicted))
+    });
+
+    let step = (opts.config.scheduling.slot_minutes as i32).max(1);
+    offer.split = (1..=(est - 1) / step)
+        .rev()
+        .map(|n| n * step)
+        .find(|part| try_with(tasks, opts, task_id, |t| t.estimate = Some(*part)).1.is_some())
+        .map(|part| (part, est - part));
+    Ok(offer)
+}
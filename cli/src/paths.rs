@@ -0,0 +1,141 @@
+use crate::platform::Host;
+use directories::ProjectDirs;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Where each kind of local file lives
+pub struct Layout {
+    pub config: PathBuf,
+    pub data: PathBuf,
+    pub cache: PathBuf,
+    pub state: PathBuf,
+    /// Machine-wide config read under the user's; left out when `--config-dir` is given
+    pub system_config: Option<PathBuf>,
+}
+
+static LAYOUT: OnceLock<Layout> = OnceLock::new();
+
+/// Resolve the XDG (or platform) layout, honoring `--config-dir`/`--data-dir`.
+/// A data-dir override also hosts cache and state so tests stay self-contained.
+pub fn init(config_dir: Option<PathBuf>, data_dir: Option<PathBuf>) {
+    let dirs = ProjectDirs::from("", "", "todo");
+    let fallback = legacy_dir();
+    let pick = |f: fn(&ProjectDirs) -> PathBuf| dirs.as_ref().map(f).unwrap_or_else(|| fallback.clone());
+
+    let system_config = config_dir.is_none().then(|| Host::current().system_config_dir());
+    let config = config_dir.unwrap_or_else(|| pick(|d| d.config_dir().to_path_buf()));
+    let layout = match data_dir {
+        Some(d) => Layout {
+            config,
+            system_config,
+            cache: d.join("cache"),
+            state: d.join("state"),
+            data: d,
+        },
+        None => Layout {
+            config,
+            system_config,
+            data: pick(|d| d.data_dir().to_path_buf()),
+            cache: pick(|d| d.cache_dir().to_path_buf()),
+            // macOS/Windows have no state dir; keep state next to data there
+            state: pick(|d| d.state_dir().unwrap_or_else(|| d.data_dir()).to_path_buf()),
+        },
+    };
+    let _ = LAYOUT.set(layout);
+}
+
+fn layout() -> &'static Layout {
+    LAYOUT.get_or_init(|| {
+        // Only reached if a code path runs before `init`
+        let base = legacy_dir();
+        Layout { config: base.clone(), data: base.clone(), cache: base.clone(), state: base, system_config: None }
+    })
+}
+
+pub fn config_dir() -> &'static Path {
+    &layout().config
+}
+
+pub fn system_config_dir() -> Option<&'static Path> {
+    layout().system_config.as_deref()
+}
+
+pub fn data_dir() -> &'static Path {
+    &layout().data
+}
+
+pub fn cache_dir() -> &'static Path {
+    &layout().cache
+}
+
+pub fn state_dir() -> &'static Path {
+    &layout().state
+}
+
+/// Pre-XDG location of all local files (`~/.todo`)
+pub fn legacy_dir() -> PathBuf {
+    Host::current().home().unwrap_or_else(|| ".".into()).join(".todo")
+}
+
+/// Legacy file name → new location
+fn legacy_files() -> Vec<(&'static str, PathBuf)> {
+    vec![
+        ("audit.jsonl", data_dir().join("audit.jsonl")),
+        ("vault.locked", state_dir().join("vault.locked")),
+    ]
+}
+
+/// True if anything is still waiting in `~/.todo`
+pub fn has_legacy_data() -> bool {
+    let legacy = legacy_dir();
+    legacy_files().iter().any(|(name, _)| legacy.join(name).exists())
+}
+
+/// Move legacy files into the XDG layout; returns a line per action taken
+pub fn migrate(dry_run: bool) -> Result<Vec<String>, Box<dyn Error>> {
+    let legacy = legacy_dir();
+    let mut report = Vec::new();
+    for (name, dest) in legacy_files() {
+        let src = legacy.join(name);
+        if !src.exists() {
+            continue;
+        }
+        if src == dest {
+            continue;
+        }
+        let append = dest.exists() && name.ends_with(".jsonl");
+        if dest.exists() && !append {
+            report.push(format!("skip {} (already exists at {})", name, dest.display()));
+            continue;
+        }
+        report.push(format!(
+            "{} {} -> {}",
+            if append { "merge" } else { "move" },
+            src.display(),
+            dest.display()
+        ));
+        if dry_run {
+            continue;
+        }
+        if let Some(dir) = dest.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if append {
+            // Legacy lines are older, so they go first to keep the log chronological
+            let mut merged = fs::read_to_string(&src)?;
+            merged.push_str(&fs::read_to_string(&dest)?);
+            fs::write(&dest, merged)?;
+            fs::remove_file(&src)?;
+        } else if fs::rename(&src, &dest).is_err() {
+            // rename fails across filesystems
+            fs::copy(&src, &dest)?;
+            fs::remove_file(&src)?;
+        }
+    }
+    if !dry_run && legacy.exists() && fs::read_dir(&legacy)?.next().is_none() {
+        fs::remove_dir(&legacy)?;
+    }
+    Ok(report)
+}
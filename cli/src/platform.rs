@@ -0,0 +1,154 @@
+use clap_complete::Shell;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Os {
+    /// Linux and the BSDs: XDG directories, Secret Service
+    Linux,
+    MacOs,
+    Windows,
+}
+
+impl Os {
+    pub fn current() -> Os {
+        if cfg!(windows) {
+            Os::Windows
+        } else if cfg!(target_os = "macos") {
+            Os::MacOs
+        } else {
+            Os::Linux
+        }
+    }
+}
+
+/// The machine we run on, as far as files, shells and credentials go; tests build one for an OS
+/// with their own environment variables
+pub struct Host {
+    pub os: Os,
+    pub env: fn(&str) -> Option<OsString>,
+}
+
+/// Where `todo completions --install` puts a shell's script, and the line a profile needs to load
+/// it when the shell doesn't pick scripts up from a directory by itself
+#[derive(PartialEq, Debug)]
+pub struct Completions {
+    pub script: PathBuf,
+    pub profile: Option<(PathBuf, String)>,
+}
+
+impl Host {
+    pub fn current() -> Host {
+        Host { os: Os::current(), env: |name| std::env::var_os(name) }
+    }
+
+    fn var(&self, name: &str) -> Option<PathBuf> {
+        (self.env)(name).filter(|v| !v.is_empty()).map(PathBuf::from)
+    }
+
+    /// %USERPROFILE% on Windows (HOME is rarely set there), $HOME elsewhere
+    pub fn home(&self) -> Option<PathBuf> {
+        match self.os {
+            Os::Windows => self.var("USERPROFILE").or_else(|| {
+                // "C:" and "\Users\ana"; joining them as paths would drop the drive off Windows
+                let mut home = self.var("HOMEDRIVE")?.into_os_string();
+                home.push(self.var("HOMEPATH")?);
+                Some(home.into())
+            }),
+            _ => self.var("HOME"),
+        }
+    }
+
+    /// Settings shared by every user of the machine, read before the user's own config:
+    /// %ProgramData%\todo, /Library/Application Support/todo, or the first of $XDG_CONFIG_DIRS
+    pub fn system_config_dir(&self) -> PathBuf {
+        match self.os {
+            Os::Windows => self.var("ProgramData").unwrap_or_else(|| r"C:\ProgramData".into()).join("todo"),
+            Os::MacOs => PathBuf::from("/Library/Application Support/todo"),
+            Os::Linux => {
+                let dirs = (self.env)("XDG_CONFIG_DIRS").unwrap_or_default();
+                let first = dirs.to_string_lossy().split(':').find(|d| !d.is_empty()).map(PathBuf::from);
+                first.unwrap_or_else(|| "/etc/xdg".into()).join("todo")
+            }
+        }
+    }
+
+    /// Program and leading arguments that hand a URL or file to the desktop's default handler;
+    /// on Windows that's ShellExecute, since `cmd /C start` would run whatever follows a `&`
+    pub fn opener(&self) -> (&'static str, &'static [&'static str]) {
+        match self.os {
+            Os::Windows => ("rundll32", &["url.dll,FileProtocolHandler"]),
+            Os::MacOs => ("open", &[]),
+            Os::Linux => ("xdg-open", &[]),
+        }
+    }
+
+    /// What the OS keyring is called, for messages
+    pub fn keyring_name(&self) -> &'static str {
+        match self.os {
+            Os::Windows => "Windows Credential Manager",
+            Os::MacOs => "macOS Keychain",
+            Os::Linux => "Secret Service keyring",
+        }
+    }
+
+    /// The shell we were started from: $SHELL where it's set, else PowerShell on Windows
+    pub fn shell(&self) -> Option<Shell> {
+        let Some(path) = self.var("SHELL") else {
+            return (self.os == Os::Windows).then_some(Shell::PowerShell);
+        };
+        let name = path.file_stem()?.to_string_lossy().to_lowercase();
+        match name.as_str() {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            "elvish" => Some(Shell::Elvish),
+            "pwsh" | "powershell" => Some(Shell::PowerShell),
+            _ => None,
+        }
+    }
+
+    /// %APPDATA% on Windows, $XDG_CONFIG_HOME or ~/.config elsewhere
+    pub fn config_home(&self) -> Option<PathBuf> {
+        match self.os {
+            Os::Windows => self.var("APPDATA"),
+            _ => self.var("XDG_CONFIG_HOME").or_else(|| Some(self.home()?.join(".config"))),
+        }
+    }
+
+    pub fn completions(&self, shell: Shell) -> Option<Completions> {
+        let home = self.home()?;
+        let script = |script: PathBuf| Some(Completions { script, profile: None });
+        match shell {
+            Shell::Bash => {
+                let data = self.var("XDG_DATA_HOME").unwrap_or_else(|| home.join(".local").join("share"));
+                script(data.join("bash-completion").join("completions").join("todo"))
+            }
+            Shell::Zsh => script(home.join(".zfunc").join("_todo")),
+            Shell::Fish => script(self.config_home()?.join("fish").join("completions").join("todo.fish")),
+            Shell::PowerShell => {
+                // PowerShell 7 keeps its profile under Documents on Windows, in the config dir elsewhere
+                let dir = match self.os {
+                    Os::Windows => home.join("Documents").join("PowerShell"),
+                    _ => self.config_home()?.join("powershell"),
+                };
+                let script = dir.join("Completions").join("todo.ps1");
+                let line = format!(". \"{}\"", script.display());
+                Some(Completions { profile: Some((dir.join("Microsoft.PowerShell_profile.ps1"), line)), script })
+            }
+            Shell::Elvish => {
+                let dir = self.config_home()?.join("elvish");
+                Some(Completions { script: dir.join("lib").join("todo.elv"), profile: Some((dir.join("rc.elv"), "use todo".into())) })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether to write ANSI colors to stdout: only on a terminal, never with NO_COLOR, and on
+/// Windows only once the console has agreed to interpret them (asked the first time)
+pub fn ansi_colors() -> bool {
+    static ON: OnceLock<bool> = OnceLock::new();
+    *ON.get_or_init(|| console::Term::stdout().features().colors_supported())
+}
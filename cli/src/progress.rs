@@ -0,0 +1,85 @@
+use crate::ratelimit::ThrottleStats;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// Set to hide progress output even on a terminal
+const DISABLE_ENV: &str = "TODO_NO_PROGRESS";
+
+/// Progress only makes sense when a human is watching (and screen readers would read every tick)
+pub fn enabled() -> bool {
+    std::io::stdout().is_terminal() && std::io::stderr().is_terminal() && std::env::var_os(DISABLE_ENV).is_none() && !crate::display::accessible()
+}
+
+/// Spinner or bar that silently does nothing when output isn't a TTY
+pub struct Progress {
+    bar: Option<ProgressBar>,
+}
+
+impl Progress {
+    /// Indeterminate work (single slow request, polling)
+    pub fn spinner(msg: &str) -> Self {
+        if !enabled() {
+            return Progress { bar: None };
+        }
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(ProgressStyle::with_template("{spinner} {msg} {prefix}").unwrap_or_else(|_| ProgressStyle::default_spinner()));
+        bar.set_message(msg.to_string());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        Progress { bar: Some(bar) }
+    }
+
+    /// Counted work (one step per request)
+    pub fn bar(len: u64, msg: &str) -> Self {
+        if !enabled() {
+            return Progress { bar: None };
+        }
+        let bar = ProgressBar::new(len);
+        bar.set_style(
+            ProgressStyle::with_template("{msg} [{bar:30}] {pos}/{len} {prefix}")
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars("=> "),
+        );
+        bar.set_message(msg.to_string());
+        Progress { bar: Some(bar) }
+    }
+
+    pub fn inc(&self, n: u64) {
+        if let Some(b) = &self.bar {
+            b.inc(n);
+        }
+    }
+
+    pub fn set_message(&self, msg: &str) {
+        if let Some(b) = &self.bar {
+            b.set_message(msg.to_string());
+        }
+    }
+
+    /// Show "throttled: …" next to the bar once the limiter has kicked in
+    pub fn note_throttle(&self, stats: &ThrottleStats) {
+        if let (Some(b), Some(s)) = (&self.bar, stats.summary()) {
+            b.set_prefix(format!("({})", s));
+        }
+    }
+
+    /// Print a line without tearing the bar
+    pub fn println(&self, line: &str) {
+        match &self.bar {
+            Some(b) => b.println(line),
+            None => eprintln!("{}", line),
+        }
+    }
+
+    pub fn finish(&self) {
+        if let Some(b) = &self.bar {
+            b.finish_and_clear();
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
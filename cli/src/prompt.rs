@@ -0,0 +1,104 @@
+use std::error::Error;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+
+/// `--no-input` / `TODO_NONINTERACTIVE`: every prompt takes its default, and one without a
+/// default fails instead of waiting
+pub fn set_no_input(on: bool) {
+    NO_INPUT.store(on, Ordering::SeqCst);
+}
+
+pub fn no_input() -> bool {
+    NO_INPUT.load(Ordering::SeqCst)
+}
+
+/// Someone can answer: input is allowed and stdin is a terminal
+pub fn interactive() -> bool {
+    !no_input() && io::stdin().is_terminal()
+}
+
+/// Ask for a line of input; empty input (or EOF) yields the default
+pub fn ask(question: &str, default: &str) -> Result<String, Box<dyn Error>> {
+    if no_input() {
+        if default.is_empty() {
+            return Err(format!("'{}' needs an answer, but input is disabled (--no-input)", question).into());
+        }
+        eprintln!("{} [{}]: taking the default (--no-input)", question, default);
+        return Ok(default.to_string());
+    }
+    if default.is_empty() {
+        print!("{}: ", question);
+    } else {
+        print!("{} [{}]: ", question, default);
+    }
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    Ok(match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => default.to_string(),
+        Ok(_) => {
+            let answer = line.trim();
+            if answer.is_empty() { default.to_string() } else { answer.to_string() }
+        }
+    })
+}
+
+/// Yes/no question
+pub fn confirm(question: &str, default: bool) -> Result<bool, Box<dyn Error>> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    if no_input() {
+        eprintln!("{} ({}): {} (--no-input)", question, hint, if default { "yes" } else { "no" });
+        return Ok(default);
+    }
+    let answer = ask(&format!("{} ({})", question, hint), "")?;
+    Ok(match answer.to_lowercase().as_str() {
+        "" => default,
+        a => a.starts_with('y'),
+    })
+}
+
+/// Whether a filter-based change may touch this many tasks
+#[derive(PartialEq, Debug)]
+pub enum MassChange {
+    Allowed,
+    /// Over the limit: show a sample and have the user type the count
+    Confirm,
+    Refused(String),
+}
+
+/// Changes to more than `limit` tasks need `--confirm-count` matching the real count, or an
+/// interactive confirmation, so an overly broad filter can't rewrite the whole backlog
+pub fn mass_change(count: usize, limit: usize, confirm_count: Option<usize>, interactive: bool) -> MassChange {
+    match confirm_count {
+        _ if count <= limit => MassChange::Allowed,
+        Some(n) if n == count => MassChange::Allowed,
+        Some(n) => MassChange::Refused(format!("--confirm-count {} doesn't match the {} tasks this would change", n, count)),
+        None if interactive => MassChange::Confirm,
+        None => MassChange::Refused(format!("This would change {} tasks (limit {}); pass --confirm-count {} to go ahead", count, limit, count)),
+    }
+}
+
+/// Show a few of the affected titles and ask for the count to be typed back
+pub fn confirm_mass_change(verb: &str, titles: &[String]) -> Result<bool, Box<dyn Error>> {
+    const SAMPLE: usize = 5;
+    println!("This would {} {} tasks, including:", verb.to_lowercase(), titles.len());
+    for t in titles.iter().take(SAMPLE) {
+        println!("  {}", t);
+    }
+    if titles.len() > SAMPLE {
+        println!("  … and {} more", titles.len() - SAMPLE);
+    }
+    Ok(ask(&format!("Type {} to go ahead", titles.len()), "")? == titles.len().to_string())
+}
+
+/// The whole mass-change check for a bulk command: refuse, ask for the count, or (under the
+/// limit) ask a plain yes/no when `ask` is set; true means go ahead
+pub fn approve_bulk(verb: &str, titles: &[String], confirm_count: Option<usize>, ask: bool) -> Result<bool, Box<dyn Error>> {
+    match mass_change(titles.len(), crate::config::get().bulk.confirm_above, confirm_count, interactive()) {
+        MassChange::Refused(why) => Err(why.into()),
+        MassChange::Confirm => confirm_mass_change(verb, titles),
+        MassChange::Allowed if confirm_count.is_some() || !ask => Ok(true),
+        MassChange::Allowed => confirm(&format!("{} {} tasks?", verb, titles.len()), false),
+    }
+}
@@ -0,0 +1,126 @@
+use crate::api::Api;
+use crate::commands::Task;
+use crate::db;
+use crate::progress::Progress;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Outcome {
+    Pushed(String),
+    Failed(String),
+    Skipped(String),
+    /// Not attempted because the run was interrupted
+    NotReached,
+}
+
+pub struct PushReport {
+    pub task_id: i32,
+    pub title: String,
+    pub outcome: Outcome,
+}
+
+/// What the last client-side run left undone, consumed by `--resume`
+#[derive(Serialize, Deserialize, Default)]
+pub struct Journal {
+    pub failed: Vec<i32>,
+    /// Never attempted because the run was interrupted
+    #[serde(default)]
+    pub not_reached: Vec<i32>,
+}
+
+impl Journal {
+    pub fn is_empty(&self) -> bool {
+        self.failed.is_empty() && self.not_reached.is_empty()
+    }
+
+    /// Everything a resumed run should push
+    pub fn remaining(&self) -> Vec<i32> {
+        self.failed.iter().chain(&self.not_reached).copied().collect()
+    }
+}
+
+pub const JOURNAL: &str = "push_journal";
+
+pub fn load_journal() -> Result<Journal, Box<dyn Error>> {
+    Ok(db::read_doc(JOURNAL)?.unwrap_or_default())
+}
+
+/// Drop deleted tasks from the journal so `--resume` doesn't try to push them
+pub fn forget(ids: &[i32]) -> Result<(), Box<dyn Error>> {
+    let mut journal = load_journal()?;
+    if journal.is_empty() {
+        return Ok(());
+    }
+    journal.failed.retain(|id| !ids.contains(id));
+    journal.not_reached.retain(|id| !ids.contains(id));
+    match journal.is_empty() {
+        true => db::remove(JOURNAL),
+        false => db::write_doc(JOURNAL, &journal),
+    }
+}
+
+/// Same rule as the backend's push-all: events always, todos only once placed
+pub fn skip_reason(t: &Task) -> Option<String> {
+    if t.kind != "event" && t.kind != "todo" {
+        return Some(format!("unsupported type '{}'", t.kind));
+    }
+    if t.start_time.is_none() || t.end_time.is_none() {
+        return Some(if t.kind == "todo" { "not scheduled yet".into() } else { "missing start/end".into() });
+    }
+    None
+}
+
+/// Push each eligible task concurrently; `only` restricts to specific IDs (resume). After
+/// an interrupt (see `interrupt::watch`) requests in flight finish and the rest are journalled
+pub async fn push_all(api: &Api, only: Option<&[i32]>, concurrency: usize) -> Result<Vec<PushReport>, Box<dyn Error>> {
+    let mut tasks = api.tasks().await?;
+    if let Some(ids) = only {
+        tasks.retain(|t| ids.contains(&t.id));
+    }
+
+    let mut reports = Vec::new();
+    let mut eligible = Vec::new();
+    for t in tasks {
+        match skip_reason(&t) {
+            Some(reason) => reports.push(PushReport { task_id: t.id, title: t.title, outcome: Outcome::Skipped(reason) }),
+            None => eligible.push(t),
+        }
+    }
+
+    let progress = Progress::bar(eligible.len() as u64, "Pushing");
+    let pushed: Vec<PushReport> = stream::iter(eligible.clone())
+        .take_while(|_| futures::future::ready(!crate::interrupt::requested()))
+        .map(|t| {
+            let progress = &progress;
+            async move {
+                let outcome = match api.push_task(t.id, crate::config::get().category_icons).await {
+                    Ok(r) => Outcome::Pushed(r.google_event_id.unwrap_or_default()),
+                    Err(e) => Outcome::Failed(e.to_string()),
+                };
+                progress.inc(1);
+                progress.note_throttle(api.throttle_stats());
+                PushReport { task_id: t.id, title: t.title, outcome }
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+    progress.finish();
+
+    for t in eligible.into_iter().filter(|t| !pushed.iter().any(|r| r.task_id == t.id)) {
+        reports.push(PushReport { task_id: t.id, title: t.title, outcome: Outcome::NotReached });
+    }
+    reports.extend(pushed);
+    reports.sort_by_key(|r| r.task_id);
+
+    let with = |wanted: fn(&Outcome) -> bool| reports.iter().filter(|r| wanted(&r.outcome)).map(|r| r.task_id).collect::<Vec<_>>();
+    let journal = Journal { failed: with(|o| matches!(o, Outcome::Failed(_))), not_reached: with(|o| *o == Outcome::NotReached) };
+    if journal.is_empty() {
+        db::remove(JOURNAL)?;
+    } else {
+        db::write_doc(JOURNAL, &journal)?;
+    }
+    Ok(reports)
+}
@@ -0,0 +1,18 @@
+use crate::commands::Task;
+use crate::ics;
+use crate::slots::parse_datetime;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::{EcLevel, QrCode};
+
+/// The task as a one-event calendar, for a phone to add; None if it has no start and end
+pub fn payload(t: &Task) -> Option<String> {
+    t.start_time.as_deref().and_then(parse_datetime)?;
+    t.end_time.as_deref().and_then(parse_datetime)?;
+    Some(ics::to_ics(std::slice::from_ref(t)))
+}
+
+/// `data` as a QR code in half-block characters, light modules drawn so it scans off a dark terminal
+pub fn render(data: &str) -> Result<String, String> {
+    let code = QrCode::with_error_correction_level(data, EcLevel::L).map_err(|e| format!("Can't make a QR code: {}", e))?;
+    Ok(code.render::<Dense1x2>().dark_color(Dense1x2::Light).light_color(Dense1x2::Dark).quiet_zone(true).build())
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration, Instant};
+
+/// Client-side token bucket: `rate` requests per second with bursts up to `burst`
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    pub fn new(rate: f64, burst: f64) -> Self {
+        let burst = burst.max(1.0);
+        RateLimiter { rate: rate.max(0.01), burst, state: Mutex::new((burst, Instant::now())) }
+    }
+
+    /// Take a token, sleeping until one is available; returns how long we waited
+    pub async fn acquire(&self) -> Duration {
+        let wait = {
+            let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+            let (tokens, last) = &mut *state;
+            let now = Instant::now();
+            *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * self.rate).min(self.burst);
+            *last = now;
+            // Going negative reserves a future token, so concurrent callers queue fairly
+            *tokens -= 1.0;
+            if *tokens >= 0.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(-*tokens / self.rate)
+            }
+        };
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+        wait
+    }
+}
+
+/// Counters surfaced to bulk operations
+#[derive(Default)]
+pub struct ThrottleStats {
+    /// Requests delayed by the local limiter
+    pub delayed: AtomicU64,
+    /// Retries after a 429 from the server
+    pub retried: AtomicU64,
+}
+
+impl ThrottleStats {
+    /// e.g. "throttled: 4 delayed, 1 retried after 429"; `None` when never throttled
+    pub fn summary(&self) -> Option<String> {
+        let delayed = self.delayed.load(Ordering::Relaxed);
+        let retried = self.retried.load(Ordering::Relaxed);
+        if delayed == 0 && retried == 0 {
+            return None;
+        }
+        Some(format!("throttled: {} delayed, {} retried after 429", delayed, retried))
+    }
+}
+
+/// Delay requested by a Retry-After header (seconds or HTTP-date)
+pub fn retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let when = DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc);
+    Some((when - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
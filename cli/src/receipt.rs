@@ -0,0 +1,79 @@
+use crate::agenda::{Day, Kind};
+
+/// Narrowest card that still fits a time, a checkbox and a few words
+pub const MIN_WIDTH: usize = 20;
+
+fn centered(text: &str, width: usize) -> String {
+    let pad = width.saturating_sub(text.chars().count()) / 2;
+    format!("{}{}", " ".repeat(pad), text)
+}
+
+/// "T U E S D A Y" when it fits, else just upper case
+fn large(text: &str, width: usize) -> String {
+    let spaced = text.to_uppercase().chars().map(String::from).collect::<Vec<_>>().join(" ");
+    match spaced.chars().count() <= width {
+        true => spaced,
+        false => text.to_uppercase(),
+    }
+}
+
+/// `text` word-wrapped after `lead`, continuation lines indented to match; words longer than a
+/// line are cut
+fn wrapped(lead: &str, text: &str, width: usize, out: &mut String) {
+    let indent = lead.chars().count();
+    let room = width.saturating_sub(indent).max(1);
+    let mut line = String::new();
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > room {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            lines.push(word.drain(..room).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > room {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    for (i, l) in lines.iter().enumerate() {
+        let prefix = if i == 0 { lead.to_string() } else { " ".repeat(indent) };
+        out.push_str(format!("{}{}", prefix, l).trim_end());
+        out.push('\n');
+    }
+}
+
+/// The day as a fixed-width card for a thermal printer: a large header, then every event and
+/// todo block in order, todos with a checkbox to tick off. Quiet hours and breaks are left out
+pub fn render(day: &Day, width: usize) -> String {
+    let width = width.max(MIN_WIDTH);
+    let rule = |c: &str| format!("{}\n", c.repeat(width));
+    let mut out = rule("=");
+    out.push_str(&format!("{}\n", centered(&large(&day.date.format("%A").to_string(), width), width)));
+    out.push_str(&format!("{}\n", centered(&day.date.format("%Y-%m-%d").to_string(), width)));
+    out.push_str(&rule("="));
+    for label in &day.all_day {
+        wrapped("all day  ", label, width, &mut out);
+    }
+    let entries: Vec<_> = day.entries.iter().filter(|e| matches!(e.kind, Kind::Event | Kind::Todo)).collect();
+    if entries.is_empty() && day.all_day.is_empty() {
+        out.push_str(&format!("{}\n", centered("nothing planned", width)));
+    }
+    for e in entries {
+        let mark = match e.kind {
+            Kind::Todo => "[ ]",
+            _ => " - ",
+        };
+        wrapped(&format!("{} {} ", e.interval.start.format("%H:%M"), mark), &e.label, width, &mut out);
+    }
+    out.push_str(&rule("-"));
+    out
+}
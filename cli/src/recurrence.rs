@@ -0,0 +1,198 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Weekday};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// An iCalendar RRULE (the parts calendar exports use; WKST is taken as Monday)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub freq: Freq,
+    pub interval: u32,
+    pub count: Option<usize>,
+    /// Wall-clock time as written; `until_utc` when it ended in Z
+    pub until: Option<NaiveDateTime>,
+    pub until_utc: bool,
+    /// Weekday with its position in the month (0 for every one), e.g. 2TU, -1FR, MO
+    pub by_day: Vec<(i32, Weekday)>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    Some(match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn numbers<T: std::str::FromStr>(list: &str) -> Vec<T> {
+    list.split(',').filter_map(|n| n.trim_start_matches('+').parse().ok()).collect()
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let next = if month == 12 { NaiveDate::from_ymd_opt(year + 1, 1, 1) } else { NaiveDate::from_ymd_opt(year, month + 1, 1) };
+    next.map(|n| n.pred_opt().unwrap().day()).unwrap_or(31)
+}
+
+/// `n`th entry counting from 1, or from the end when negative
+fn nth<T: Copy>(items: &[T], n: i32) -> Option<T> {
+    match n {
+        0 => None,
+        n if n > 0 => items.get(n as usize - 1).copied(),
+        n => items.len().checked_sub(n.unsigned_abs() as usize).and_then(|i| items.get(i).copied()),
+    }
+}
+
+impl Rule {
+    /// "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;UNTIL=20301231T235959Z"; `None` for frequencies we can't expand
+    pub fn parse(rrule: &str) -> Option<Rule> {
+        let mut rule = Rule { freq: Freq::Daily, interval: 1, count: None, until: None, until_utc: false, by_day: vec![], by_month_day: vec![], by_month: vec![], by_set_pos: vec![] };
+        let mut freq = None;
+        for part in rrule.split(';') {
+            let Some((key, value)) = part.split_once('=') else { continue };
+            match key {
+                "FREQ" => {
+                    freq = Some(match value {
+                        "DAILY" => Freq::Daily,
+                        "WEEKLY" => Freq::Weekly,
+                        "MONTHLY" => Freq::Monthly,
+                        "YEARLY" => Freq::Yearly,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => rule.interval = value.parse().unwrap_or(1).max(1),
+                "COUNT" => rule.count = value.parse().ok(),
+                "UNTIL" => {
+                    rule.until_utc = value.ends_with('Z');
+                    let v = value.trim_end_matches('Z');
+                    rule.until = NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S")
+                        .ok()
+                        .or_else(|| NaiveDate::parse_from_str(v, "%Y%m%d").ok()?.and_hms_opt(23, 59, 59));
+                }
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .filter_map(|d| {
+                            // The last two characters, which needn't be two bytes in imported data
+                            let (at, _) = d.char_indices().rev().nth(1)?;
+                            let (pos, code) = d.split_at(at);
+                            Some((if pos.is_empty() { 0 } else { pos.trim_start_matches('+').parse().ok()? }, weekday(code)?))
+                        })
+                        .collect()
+                }
+                "BYMONTHDAY" => rule.by_month_day = numbers(value),
+                "BYMONTH" => rule.by_month = numbers(value),
+                "BYSETPOS" => rule.by_set_pos = numbers(value),
+                _ => {}
+            }
+        }
+        rule.freq = freq?;
+        Some(rule)
+    }
+
+    /// Days in one month matching BYMONTHDAY or BYDAY, else `default_day`
+    fn month_days(&self, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+        let len = days_in_month(year, month);
+        let date = |d: u32| NaiveDate::from_ymd_opt(year, month, d);
+        if !self.by_month_day.is_empty() {
+            return self
+                .by_month_day
+                .iter()
+                .filter_map(|&d| date(if d > 0 { d as u32 } else { (len as i32 + 1 + d).max(0) as u32 }))
+                .collect();
+        }
+        if self.by_day.is_empty() {
+            return date(default_day).into_iter().collect();
+        }
+        let mut out = Vec::new();
+        for &(pos, wd) in &self.by_day {
+            let all: Vec<NaiveDate> = (1..=len).filter_map(date).filter(|d| d.weekday() == wd).collect();
+            match pos {
+                0 => out.extend(all),
+                p => out.extend(nth(&all, p)),
+            }
+        }
+        out
+    }
+
+    /// The `k`th period's first day and its candidate dates
+    fn period(&self, first: NaiveDate, k: i64) -> (NaiveDate, Vec<NaiveDate>) {
+        let step = k * self.interval as i64;
+        let (start, mut dates) = match self.freq {
+            Freq::Daily => {
+                let d = first + Duration::days(step);
+                let keep = (self.by_day.is_empty() || self.by_day.iter().any(|&(_, w)| w == d.weekday()))
+                    && (self.by_month_day.is_empty() || self.by_month_day.contains(&(d.day() as i32)));
+                (d, if keep { vec![d] } else { vec![] })
+            }
+            Freq::Weekly => {
+                let monday = first - Duration::days(first.weekday().num_days_from_monday() as i64) + Duration::weeks(step);
+                let days = match self.by_day.is_empty() {
+                    true => vec![first.weekday()],
+                    false => self.by_day.iter().map(|&(_, w)| w).collect(),
+                };
+                (monday, days.into_iter().map(|w| monday + Duration::days(w.num_days_from_monday() as i64)).collect())
+            }
+            Freq::Monthly => {
+                let index = first.year() as i64 * 12 + first.month0() as i64 + step;
+                let (year, month) = (index.div_euclid(12) as i32, index.rem_euclid(12) as u32 + 1);
+                let start = NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(first);
+                (start, self.month_days(year, month, first.day()))
+            }
+            Freq::Yearly => {
+                let year = first.year() + step as i32;
+                let months = match self.by_month.is_empty() {
+                    true => vec![first.month()],
+                    false => self.by_month.clone(),
+                };
+                let start = NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or(first);
+                (start, months.into_iter().flat_map(|m| self.month_days(year, m, first.day())).collect())
+            }
+        };
+        if !self.by_month.is_empty() {
+            dates.retain(|d| self.by_month.contains(&d.month()));
+        }
+        dates.sort();
+        dates.dedup();
+        if !self.by_set_pos.is_empty() {
+            dates = self.by_set_pos.iter().filter_map(|&p| nth(&dates, p)).collect();
+            dates.sort();
+        }
+        (start, dates)
+    }
+
+    /// Start times from `first` (itself included when it matches) up to, not including, `limit`;
+    /// COUNT and UNTIL (compared as written) apply
+    pub fn occurrences(&self, first: NaiveDateTime, limit: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut out = Vec::new();
+        for k in 0.. {
+            let (start, dates) = self.period(first.date(), k);
+            if start.and_time(first.time()) >= limit && start > first.date() {
+                break;
+            }
+            for d in dates {
+                let t = d.and_time(first.time());
+                if t < first {
+                    continue;
+                }
+                if t >= limit || self.until.is_some_and(|u| t > u) || self.count.is_some_and(|c| out.len() >= c) {
+                    return out;
+                }
+                out.push(t);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,198 @@
+use crate::api::Api;
+use crate::db;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+
+/// One Apple Reminders item, from an .ics export or the JSON bridge
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Reminder {
+    /// Stable across exports; remembered so importing again skips it
+    pub id: String,
+    /// The Reminders list, which becomes the category
+    pub list: String,
+    pub title: String,
+    pub notes: Option<String>,
+    pub due: Option<NaiveDateTime>,
+    pub completed: bool,
+    /// iCalendar scale: 0 none, 1 highest … 9 lowest
+    pub priority: u8,
+}
+
+/// A due date without a time
+pub fn end_of_day(day: NaiveDate) -> NaiveDateTime {
+    day.and_time(NaiveTime::from_hms_opt(23, 59, 0).unwrap())
+}
+
+/// Reminders' high/medium/low (1/5/9) onto our 0–10 scale
+pub fn priority(ical: u8) -> i32 {
+    match ical {
+        1..=4 => 8,
+        5 => 5,
+        6..=9 => 2,
+        _ => 0,
+    }
+}
+
+/// "2030-05-31" (end of day), local "2030-05-31T17:00:00", or RFC 3339 with an offset
+fn parse_due(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t.with_timezone(&Local).naive_local());
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok().map(end_of_day))
+}
+
+#[derive(Deserialize)]
+struct JsonReminder {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    list: String,
+    #[serde(alias = "name")]
+    title: String,
+    #[serde(default, alias = "body")]
+    notes: Option<String>,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    completed: bool,
+    #[serde(default)]
+    priority: u8,
+}
+
+/// The bridge's format: an array of `{id, list, title, notes, due, completed, priority}`
+pub fn from_json(raw: &str) -> Result<Vec<Reminder>, Box<dyn Error>> {
+    let items: Vec<JsonReminder> = serde_json::from_str(raw).map_err(|e| format!("Not a Reminders JSON export: {}", e))?;
+    items
+        .into_iter()
+        .filter(|r| !r.title.trim().is_empty())
+        .map(|r| {
+            let due = match r.due.as_deref().filter(|d| !d.is_empty()) {
+                Some(d) => Some(parse_due(d).ok_or_else(|| format!("'{}': unrecognised due date", d))?),
+                None => None,
+            };
+            Ok(Reminder { id: r.id, list: r.list, title: r.title.trim().to_string(), notes: r.notes.filter(|n| !n.trim().is_empty()), due, completed: r.completed, priority: r.priority })
+        })
+        .collect()
+}
+
+/// An export file, .ics or JSON by its contents
+pub fn from_file(path: &Path) -> Result<Vec<Reminder>, Box<dyn Error>> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    match raw.trim_start().starts_with("BEGIN:VCALENDAR") {
+        true => Ok(crate::ics::parse_todos(&raw)),
+        false => from_json(&raw),
+    }
+}
+
+/// Reads every list straight from Reminders.app in the bridge's JSON format
+#[cfg(feature = "reminders-bridge")]
+const BRIDGE: &str = r#"
+const app = Application('Reminders');
+const day = d => `${d.getFullYear()}-${String(d.getMonth() + 1).padStart(2, '0')}-${String(d.getDate()).padStart(2, '0')}`;
+const out = [];
+app.lists().forEach(l => {
+  const r = l.reminders;
+  const [ids, names, bodies, dues, allDay, done, prios] = [r.id(), r.name(), r.body(), r.dueDate(), r.alldayDueDate(), r.completed(), r.priority()];
+  ids.forEach((id, i) => out.push({
+    id, list: l.name(), title: names[i], notes: bodies[i], completed: done[i], priority: prios[i],
+    due: allDay[i] ? day(allDay[i]) : (dues[i] ? dues[i].toISOString() : null),
+  }));
+});
+JSON.stringify(out);
+"#;
+
+/// Everything in Reminders.app, via `osascript` (macOS asks for permission the first time)
+#[cfg(feature = "reminders-bridge")]
+pub fn from_app() -> Result<Vec<Reminder>, Box<dyn Error>> {
+    if !cfg!(target_os = "macos") {
+        return Err("The Reminders bridge only works on macOS; import an exported .ics or .json file instead".into());
+    }
+    let out = std::process::Command::new("osascript").args(["-l", "JavaScript", "-e", BRIDGE]).output()?;
+    if !out.status.success() {
+        return Err(format!("osascript: {}", String::from_utf8_lossy(&out.stderr).trim()).into());
+    }
+    from_json(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Reminder id → task id for everything imported so far
+pub const STORE: &str = "reminders";
+
+/// What an import did
+#[derive(Default, Debug)]
+pub struct Imported {
+    /// The new tasks' IDs and titles
+    pub tasks: Vec<(i32, String)>,
+    pub categories: usize,
+    /// Imported on an earlier run
+    pub skipped: usize,
+    pub failed: Vec<String>,
+}
+
+fn payload(r: &Reminder, category_id: i32) -> Map<String, Value> {
+    let mut payload = Map::new();
+    payload.insert("title".into(), Value::from(r.title.clone()));
+    payload.insert("type".into(), Value::from("todo"));
+    payload.insert("category_id".into(), Value::from(category_id));
+    payload.insert("priority".into(), Value::from(priority(r.priority)));
+    if let Some(notes) = &r.notes {
+        payload.insert("description".into(), Value::from(notes.clone()));
+    }
+    if let Some(due) = r.due {
+        payload.insert("deadline".into(), Value::from(crate::stamp::format(due)));
+    }
+    if r.completed {
+        payload.insert("status".into(), Value::from("done"));
+    }
+    payload
+}
+
+/// Create a todo per reminder, filing each under a category named after its list
+/// (made if missing); only `list` when given, and completed ones only with `completed`
+pub async fn import(api: &Api, reminders: &[Reminder], list: Option<&str>, completed: bool) -> Result<Imported, Box<dyn Error>> {
+    let mut seen: BTreeMap<String, i32> = db::read_doc(STORE)?.unwrap_or_default();
+    let mut categories = api.categories().await?;
+    let mut done = Imported::default();
+    let wanted = reminders
+        .iter()
+        .filter(|r| list.is_none_or(|l| r.list.eq_ignore_ascii_case(l)))
+        .filter(|r| completed || !r.completed);
+    for r in wanted {
+        if !r.id.is_empty() && seen.contains_key(&r.id) {
+            done.skipped += 1;
+            continue;
+        }
+        let name = if r.list.is_empty() { "Reminders" } else { r.list.as_str() };
+        let category_id = match categories.iter().find(|c| c.name.eq_ignore_ascii_case(name)) {
+            Some(c) => c.id,
+            None => match api.create_category(name, "#CCCCCC", None).await {
+                Ok(c) => {
+                    done.categories += 1;
+                    categories.push(c);
+                    categories.last().map(|c| c.id).unwrap_or_default()
+                }
+                // Carry on, so what was imported is still recorded below
+                Err(e) => {
+                    done.failed.push(format!("{}: couldn't create category '{}': {}", r.title, name, e));
+                    continue;
+                }
+            },
+        };
+        match api.create_task("import-reminders", payload(r, category_id)).await {
+            Ok(t) => {
+                done.tasks.push((t.id, t.title));
+                if !r.id.is_empty() {
+                    seen.insert(r.id.clone(), t.id);
+                }
+            }
+            Err(e) => done.failed.push(format!("{}: {}", r.title, e)),
+        }
+    }
+    db::write_doc(STORE, &seen)?;
+    Ok(done)
+}
@@ -0,0 +1,73 @@
+use crate::commands::Task;
+use crate::config::{Config, Quota};
+use crate::display::format_minutes;
+use crate::slots::{self, Interval};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+
+/// Minutes as "2h 30m", or "0h" for none
+pub fn amount(minutes: i64) -> String {
+    match minutes {
+        m if m <= 0 => "0h".into(),
+        m => format_minutes(m as i32),
+    }
+}
+
+/// One category's week: time already spent and time still booked
+pub struct Row {
+    pub category: String,
+    pub quota: Option<Quota>,
+    /// Booked time before now
+    pub actual: i64,
+    /// Booked time from now to the end of the week
+    pub scheduled: i64,
+}
+
+impl Row {
+    pub fn total(&self) -> i64 {
+        self.actual + self.scheduled
+    }
+
+    /// "3h under min", "2h over max" or "ok"; empty without a quota
+    pub fn verdict(&self) -> String {
+        let Some(q) = self.quota else { return String::new() };
+        match (q.min_minutes, q.max_minutes) {
+            (Some(min), _) if self.total() < min => format!("{} under min", amount(min - self.total())),
+            (_, Some(max)) if self.total() > max => format!("{} over max", amount(self.total() - max)),
+            _ => "ok".into(),
+        }
+    }
+}
+
+/// Per-category time for the configured week holding `now`, from events and placed todo blocks;
+/// categories with a quota are listed even when nothing is booked. Returns the week's first day too
+pub fn week(tasks: &[Task], cfg: &Config, now: NaiveDateTime) -> (NaiveDate, Vec<Row>) {
+    let first = cfg.week.start_of(now.date());
+    let start = first.and_hms_opt(0, 0, 0).unwrap_or(now);
+    let span = Interval::new(start, start + Duration::weeks(1));
+    let mut rows: BTreeMap<String, Row> = BTreeMap::new();
+    for (name, q) in &cfg.quotas {
+        rows.insert(name.to_lowercase(), Row { category: name.clone(), quota: Some(*q), actual: 0, scheduled: 0 });
+    }
+    let mine = tasks.iter().filter(|t| t.blocks_time() && !t.is_all_day() && !t.belongs_to_other(cfg.user.as_deref()));
+    for t in mine {
+        let (Some(c), Some(b)) = (t.category.as_ref(), slots::busy_from_tasks(std::slice::from_ref(t)).pop()) else { continue };
+        if !b.interval.overlaps(&span) {
+            continue;
+        }
+        let (s, e) = (b.interval.start.max(span.start), b.interval.end.min(span.end));
+        let row = rows.entry(c.name.to_lowercase()).or_insert_with(|| Row { category: c.name.clone(), quota: None, actual: 0, scheduled: 0 });
+        row.actual += (e.min(now) - s).num_minutes().max(0);
+        row.scheduled += (e - s.max(now)).num_minutes().max(0);
+    }
+    (first, rows.into_values().collect())
+}
+
+/// `--accessible` line: "work: 31h done, 6h scheduled, 37h of max 40h, ok."
+pub fn describe(r: &Row) -> String {
+    let mut out = format!("{}: {} done, {} scheduled", r.category, amount(r.actual), amount(r.scheduled));
+    if let Some(q) = r.quota {
+        out.push_str(&format!(", {} of {}, {}", amount(r.total()), String::from(q), r.verdict()));
+    }
+    format!("{}.", out)
+}
@@ -0,0 +1,42 @@
+use crate::db;
+use std::error::Error;
+use std::str::FromStr;
+
+/// A task named on the command line: its server ID, or `%N` for row N of the last listing
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TaskRef {
+    Id(i32),
+    Row(usize),
+}
+
+impl FromStr for TaskRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('%') {
+            Some(n) => match n.parse() {
+                Ok(row) if row > 0 => Ok(TaskRef::Row(row)),
+                _ => Err(format!("'{}' is not a row number (use %1, %2, …)", s)),
+            },
+            None => s.parse().map(TaskRef::Id).map_err(|_| format!("'{}' is not a task ID or %row", s)),
+        }
+    }
+}
+
+pub const STORE: &str = "last_rows";
+
+/// Keep the IDs of the rows just printed so `%N` can refer to them
+pub fn remember(ids: &[i32]) -> Result<(), Box<dyn Error>> {
+    db::write_doc(STORE, &ids)
+}
+
+pub fn resolve(r: TaskRef) -> Result<i32, Box<dyn Error>> {
+    let row = match r {
+        TaskRef::Id(id) => return Ok(id),
+        TaskRef::Row(row) => row,
+    };
+    let ids: Vec<i32> = db::read_doc(STORE)?.ok_or("No listing to refer to; run list-tasks first")?;
+    ids.get(row - 1)
+        .copied()
+        .ok_or_else(|| format!("No row %{} in the last listing (it had {} rows)", row, ids.len()).into())
+}
@@ -0,0 +1,149 @@
+use crate::commands::Task;
+use crate::{db, vault};
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const SNIPPET_WIDTH: usize = 60;
+
+/// Lowercased words: "Q3 OKR-review" → ["q3", "okr", "review"]
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// When the database's index was last built from scratch
+#[derive(Serialize, Deserialize)]
+struct Built {
+    /// YYYY-MM-DDTHH:MM:SS
+    built_at: String,
+}
+
+/// A matching task with the bit of text that matched
+#[derive(PartialEq, Debug)]
+pub struct Hit {
+    pub id: i32,
+    pub title: String,
+    pub snippet: Option<String>,
+}
+
+pub const STORE: &str = "search_index";
+
+/// An FTS5 table over task titles and descriptions, by task ID
+pub struct Index {
+    conn: Connection,
+}
+
+impl Index {
+    /// The database's, kept in step as tasks are created, changed and deleted
+    pub fn open() -> Result<Index, Box<dyn Error>> {
+        Ok(Index { conn: db::open()? })
+    }
+
+    /// A throwaway one over `tasks`, for when the vault is locked: the database's can't be encrypted
+    pub fn in_memory(tasks: &[Task]) -> Result<Index, Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        // The same table the database's schema has
+        conn.execute_batch("CREATE VIRTUAL TABLE search USING fts5(title, text, tokenize = 'unicode61 remove_diacritics 0');")?;
+        let index = Index { conn };
+        index.fill(tasks)?;
+        Ok(index)
+    }
+
+    /// Replace everything in the table with `tasks`
+    fn fill(&self, tasks: &[Task]) -> Result<usize, Box<dyn Error>> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM search", [])?;
+        for t in tasks {
+            tx.execute("INSERT INTO search (rowid, title, text) VALUES (?1, ?2, ?3)", params![t.id, t.title, t.description.as_deref().unwrap_or("")])?;
+        }
+        tx.commit()?;
+        Ok(tasks.len())
+    }
+
+    /// Index `tasks` from scratch and note when; returns how many went in
+    pub fn rebuild(&self, tasks: &[Task], built_at: NaiveDateTime) -> Result<usize, Box<dyn Error>> {
+        let count = self.fill(tasks)?;
+        db::write_doc(STORE, &Built { built_at: built_at.format("%Y-%m-%dT%H:%M:%S").to_string() })?;
+        Ok(count)
+    }
+
+    /// Tasks containing every word of `query`; exact phrase matches come first
+    pub fn search(&self, query: &str) -> Result<Vec<Hit>, Box<dyn Error>> {
+        let words = tokenize(query);
+        let Some(first) = words.first() else {
+            return Ok(Vec::new());
+        };
+        // Each word quoted on its own: FTS5 wants them all, and reads none of them as syntax
+        let matching: Vec<String> = words.iter().map(|w| format!("\"{}\"", w)).collect();
+        let mut stmt = self.conn.prepare("SELECT rowid, title, text FROM search WHERE search MATCH ?1")?;
+        let rows: Vec<(i32, String, String)> = stmt.query_map([matching.join(" ")], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?.collect::<Result<_, _>>()?;
+        let phrase = words.join(" ");
+        let mut hits: Vec<(bool, Hit)> = rows
+            .into_iter()
+            .map(|(id, title, text)| {
+                let exact = [&title, &text].iter().any(|s| tokenize(s).join(" ").contains(&phrase));
+                (exact, Hit { id, snippet: snippet(&text, first), title })
+            })
+            .collect();
+        hits.sort_by_key(|(exact, h)| (!exact, h.id));
+        Ok(hits.into_iter().map(|(_, h)| h).collect())
+    }
+}
+
+/// The line of `text` holding `word`, cut to a readable width around it
+fn snippet(text: &str, word: &str) -> Option<String> {
+    let line = text.lines().find(|l| tokenize(l).iter().any(|w| w == word))?.trim();
+    let lower = line.to_lowercase();
+    let at = lower.find(word).filter(|_| lower.len() == line.len()).unwrap_or(0);
+    // Start at the first whole word within half a snippet before the match
+    let from = at.saturating_sub(SNIPPET_WIDTH / 2);
+    let start = match from {
+        0 => 0,
+        _ => line.char_indices().map(|(i, _)| i).find(|&i| i >= from && line[..i].ends_with(char::is_whitespace)).unwrap_or(at),
+    };
+    let cut: String = line[start..].chars().take(SNIPPET_WIDTH).collect();
+    let lead = if start > 0 { "…" } else { "" };
+    let tail = if cut.len() < line.len() - start { "…" } else { "" };
+    Some(format!("{}{}{}", lead, cut.trim(), tail))
+}
+
+/// When the database's index was built; `None` if it never was (or was cleared)
+pub fn built_at() -> Result<Option<String>, Box<dyn Error>> {
+    Ok(db::read_doc::<Built>(STORE)?.map(|b| b.built_at))
+}
+
+/// Whether the database's index is worth keeping in step: built, and not behind a locked vault
+fn kept() -> Result<bool, Box<dyn Error>> {
+    Ok(!vault::is_locked() && built_at()?.is_some())
+}
+
+/// Re-index a task that was just created or changed
+pub fn update(t: &Task) -> Result<(), Box<dyn Error>> {
+    if !kept()? {
+        return Ok(());
+    }
+    let conn = db::open()?;
+    conn.execute("DELETE FROM search WHERE rowid = ?1", [t.id])?;
+    conn.execute("INSERT INTO search (rowid, title, text) VALUES (?1, ?2, ?3)", params![t.id, t.title, t.description.as_deref().unwrap_or("")])?;
+    Ok(())
+}
+
+/// Drop a deleted task from the index
+pub fn forget(task_id: i32) -> Result<(), Box<dyn Error>> {
+    if !kept()? {
+        return Ok(());
+    }
+    db::open()?.execute("DELETE FROM search WHERE rowid = ?1", [task_id])?;
+    Ok(())
+}
+
+/// Empty the database's index, so nothing is left in plaintext once the vault locks
+pub fn clear() -> Result<(), Box<dyn Error>> {
+    db::open()?.execute("DELETE FROM search", [])?;
+    db::remove(STORE)
+}
@@ -0,0 +1,109 @@
+use crate::commands::Task;
+use crate::config::Config;
+use crate::slots::{self, Interval};
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ISO: &str = "%Y-%m-%dT%H:%M";
+
+/// A stretch of time with nothing about what fills it
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Slot {
+    pub start: String,
+    pub end: String,
+    /// "busy", "tentative" or "free"
+    pub state: &'static str,
+}
+
+/// What collaborators get: times only, no titles, locations or attendees
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Bundle {
+    pub generated_at: String,
+    pub from: String,
+    pub to: String,
+    pub slots: Vec<Slot>,
+}
+
+fn slot(i: Interval, state: &'static str) -> Slot {
+    Slot { start: i.start.format(ISO).to_string(), end: i.end.format(ISO).to_string(), state }
+}
+
+/// Overlapping or touching intervals joined, so the number of meetings doesn't show either
+fn merged(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.sort_by_key(|i| i.start);
+    let mut out: Vec<Interval> = Vec::new();
+    for i in intervals {
+        match out.last_mut() {
+            Some(last) if i.start <= last.end => last.end = last.end.max(i.end),
+            _ => out.push(i),
+        }
+    }
+    out
+}
+
+pub fn build(tasks: &[Task], cfg: &Config, range: Interval, now: NaiveDateTime) -> Bundle {
+    let open: Vec<Task> = tasks.iter().filter(|t| t.status.as_deref() != Some("done")).cloned().collect();
+    let clip = |i: Interval| i.overlaps(&range).then(|| Interval::new(i.start.max(range.start), i.end.min(range.end)));
+    let blocking = slots::blocking(&open, cfg.scheduling.all_day_busy);
+    let busy = merged(blocking.iter().filter_map(|b| clip(b.interval)).collect());
+    let tentative: Vec<Task> = open.iter().filter(|t| t.transparency.as_deref() == Some("tentative") && !t.is_all_day()).cloned().collect();
+    let tentative_busy = slots::busy_from_tasks(&tentative);
+    let tentative = merged(tentative_busy.iter().filter_map(|b| clip(b.interval)).collect());
+    let min = Duration::minutes(cfg.scheduling.slot_minutes as i64);
+    let taken: Vec<slots::Busy> = blocking.iter().chain(&tentative_busy).cloned().collect();
+    let free = slots::free_gaps(&cfg.availability, &taken, range, min);
+
+    let mut out: Vec<(Interval, &'static str)> = busy.into_iter().map(|i| (i, "busy")).collect();
+    out.extend(tentative.into_iter().map(|i| (i, "tentative")));
+    out.extend(free.into_iter().map(|i| (i, "free")));
+    out.sort_by_key(|(i, _)| i.start);
+    Bundle {
+        generated_at: now.format(ISO).to_string(),
+        from: range.start.format(ISO).to_string(),
+        to: range.end.format(ISO).to_string(),
+        slots: out.into_iter().map(|(i, state)| slot(i, state)).collect(),
+    }
+}
+
+/// A standalone page listing the slots day by day
+pub fn html(b: &Bundle) -> String {
+    let mut body = String::new();
+    let mut day = String::new();
+    for s in &b.slots {
+        let (date, start) = s.start.split_once('T').unwrap_or((&s.start, ""));
+        if date != day {
+            if !day.is_empty() {
+                body.push_str("</ul>\n");
+            }
+            let label = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map(|d| d.format("%A %Y-%m-%d").to_string()).unwrap_or_default();
+            body.push_str(&format!("<h2>{}</h2>\n<ul>\n", label));
+            day = date.to_string();
+        }
+        let end = s.end.split_once('T').map_or("", |(_, t)| t);
+        body.push_str(&format!("<li class=\"{0}\">{1}–{2} {0}</li>\n", s.state, start, end));
+    }
+    if !day.is_empty() {
+        body.push_str("</ul>\n");
+    }
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Availability</title>\n\
+         <style>body{{font-family:sans-serif}} .busy{{color:#a33}} .tentative{{color:#a70}} .free{{color:#383}}</style>\n\
+         </head><body>\n<h1>Availability {} to {}</h1>\n{}<p><small>Generated {}</small></p>\n</body></html>\n",
+        b.from.replace('T', " "),
+        b.to.replace('T', " "),
+        body,
+        b.generated_at.replace('T', " ")
+    )
+}
+
+/// Write index.html and schedule.json into `dir`; returns the page's path
+pub fn write(dir: &Path, b: &Bundle) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join("schedule.json"), serde_json::to_string_pretty(b)?)?;
+    let page = dir.join("index.html");
+    fs::write(&page, html(b))?;
+    Ok(page)
+}
@@ -0,0 +1,154 @@
+use crate::commands::Task;
+use crate::config::{Availability, Block};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Interval {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl Interval {
+    pub fn new(start: NaiveDateTime, end: NaiveDateTime) -> Self {
+        Interval { start, end }
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Pieces split at each midnight, so overnight and multi-day spans can be shown day by day
+    pub fn by_day(&self) -> Vec<Interval> {
+        let mut pieces = Vec::new();
+        let mut start = self.start;
+        while start < self.end {
+            let end = (start.date() + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap().min(self.end);
+            pieces.push(Interval::new(start, end));
+            start = end;
+        }
+        pieces
+    }
+}
+
+/// Something already occupying the calendar
+#[derive(Clone)]
+pub struct Busy {
+    pub interval: Interval,
+    pub label: String,
+}
+
+/// Accepts the backend's ISO format, with or without seconds
+pub fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    crate::stamp::parse(s)
+}
+
+/// Events and placed todo blocks
+pub fn busy_from_tasks(tasks: &[Task]) -> Vec<Busy> {
+    tasks
+        .iter()
+        .filter(|t| t.kind == "event" || t.kind == "todo")
+        .filter_map(|t| {
+            let start = parse_datetime(t.start_time.as_deref()?)?;
+            let end = parse_datetime(t.end_time.as_deref()?)?;
+            Some(Busy { interval: Interval::new(start, end), label: format!("[{}] {}", t.id, t.title) })
+        })
+        .collect()
+}
+
+/// What blocks scheduling: like `busy_from_tasks`, minus free and tentative events, and all-day events only when `all_day_busy`
+pub fn blocking(tasks: &[Task], all_day_busy: bool) -> Vec<Busy> {
+    let relevant: Vec<Task> = tasks.iter().filter(|t| t.blocks_time() && (all_day_busy || !t.is_all_day())).cloned().collect();
+    busy_from_tasks(&relevant)
+}
+
+pub fn conflicts<'a>(busy: &'a [Busy], slot: &Interval) -> Vec<&'a Busy> {
+    busy.iter().filter(|b| b.interval.overlaps(slot)).collect()
+}
+
+/// Availability windows falling on one date, minus breaks and focus blocks
+pub fn windows_on(availability: &Availability, date: NaiveDate) -> Vec<Interval> {
+    availability
+        .open_windows_on(date)
+        .iter()
+        .map(|w| {
+            let (start, end) = w.times();
+            Interval::new(date.and_time(start), date.and_time(end))
+        })
+        .collect()
+}
+
+/// Free gaps inside `range` that fall within availability and are at least `min` long
+pub fn free_gaps(availability: &Availability, busy: &[Busy], range: Interval, min: Duration) -> Vec<Interval> {
+    let mut taken: Vec<Interval> = busy.iter().map(|b| b.interval).filter(|i| i.overlaps(&range)).collect();
+    taken.sort_by_key(|i| i.start);
+
+    let mut gaps = Vec::new();
+    let mut day = range.start.date();
+    while day <= range.end.date() {
+        for w in windows_on(availability, day) {
+            let mut cursor = w.start.max(range.start);
+            let end = w.end.min(range.end);
+            for t in &taken {
+                if t.end <= cursor || t.start >= end {
+                    continue;
+                }
+                if t.start - cursor >= min {
+                    gaps.push(Interval::new(cursor, t.start));
+                }
+                cursor = cursor.max(t.end);
+            }
+            if end - cursor >= min {
+                gaps.push(Interval::new(cursor, end));
+            }
+        }
+        day += Duration::days(1);
+    }
+    gaps
+}
+
+/// Up to `n` free slots as long as `wanted`, closest to it first (searching a week either side)
+pub fn nearest_free(availability: &Availability, busy: &[Busy], wanted: Interval, n: usize) -> Vec<Interval> {
+    let len = wanted.duration();
+    let range = Interval::new(wanted.start - Duration::days(7), wanted.end + Duration::days(7));
+    let mut slots: Vec<Interval> = free_gaps(availability, busy, range, len)
+        .into_iter()
+        .map(|g| {
+            // Place as close to the requested start as the gap allows
+            let start = wanted.start.clamp(g.start, g.end - len);
+            Interval::new(start, start + len)
+        })
+        .collect();
+    slots.sort_by_key(|s| (s.start - wanted.start).num_minutes().abs());
+    slots.truncate(n);
+    slots
+}
+
+/// Where recurring blocks fall on one date
+pub fn blocks_on(blocks: &[Block], date: NaiveDate) -> Vec<Interval> {
+    let day = date.weekday().num_days_from_monday() as usize;
+    blocks
+        .iter()
+        .filter(|b| b.applies_to(day))
+        .map(|b| {
+            let (start, end) = b.window.times();
+            Interval::new(date.and_time(start), date.and_time(end))
+        })
+        .collect()
+}
+
+/// First multiple of `minutes` past the hour at or after `t`
+pub fn align_up(t: NaiveDateTime, minutes: u32) -> NaiveDateTime {
+    let step = minutes.max(1) as i64 * 60;
+    let secs = t.and_utc().timestamp();
+    t + Duration::seconds((step - secs.rem_euclid(step)) % step)
+}
+
+/// Nearest multiple of `minutes` past the hour
+pub fn round_to(t: NaiveDateTime, minutes: u32) -> NaiveDateTime {
+    let half = Duration::seconds(minutes.max(1) as i64 * 30);
+    align_up(t - half + Duration::seconds(1), minutes)
+}
@@ -0,0 +1,115 @@
+use crate::commands::Task;
+use crate::meta::MetaMap;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Key {
+    Created,
+    Deadline,
+    /// The deadline, or the start for events
+    Due,
+    Estimate,
+    Id,
+    Priority,
+    Title,
+}
+
+const KEYS: &[(&str, Key)] = &[
+    ("created", Key::Created),
+    ("deadline", Key::Deadline),
+    ("due", Key::Due),
+    ("estimate", Key::Estimate),
+    ("id", Key::Id),
+    ("priority", Key::Priority),
+    ("title", Key::Title),
+];
+
+impl Key {
+    /// Newest and most important first; everything else smallest or soonest first
+    fn descending_by_default(self) -> bool {
+        matches!(self, Key::Created | Key::Priority)
+    }
+}
+
+/// A sort order like `deadline,priority:desc,title`: keys are compared in turn, each `:asc` or
+/// `:desc` (default: newest `created` and highest `priority` first, the rest ascending). Tasks
+/// missing a key's value go after those that have one, whichever the direction, and tasks that
+/// tie on every key stay in id order so the listing doesn't shuffle between runs
+#[derive(Clone, PartialEq, Debug)]
+pub struct SortSpec {
+    keys: Vec<(Key, bool)>,
+}
+
+impl Default for SortSpec {
+    /// Soonest due first, as `list-tasks` has always shown them
+    fn default() -> Self {
+        SortSpec { keys: vec![(Key::Due, false)] }
+    }
+}
+
+impl FromStr for SortSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut keys = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, direction) = part.split_once(':').map_or((part, None), |(n, d)| (n.trim(), Some(d.trim())));
+            let key = KEYS
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, key)| *key)
+                .ok_or_else(|| format!("Unknown sort key '{}' (use {})", name, KEYS.iter().map(|(k, _)| *k).collect::<Vec<_>>().join(", ")))?;
+            let descending = match direction.map(str::to_lowercase).as_deref() {
+                None => key.descending_by_default(),
+                Some("asc") => false,
+                Some("desc") => true,
+                Some(d) => return Err(format!("Unknown sort direction '{}' for {} (use asc or desc)", d, name)),
+            };
+            keys.push((key, descending));
+        }
+        if keys.is_empty() {
+            return Err("Give at least one sort key, e.g. deadline,priority:desc".into());
+        }
+        Ok(SortSpec { keys })
+    }
+}
+
+/// Present values in the given direction, missing ones last
+fn present<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn compare(key: Key, descending: bool, a: &Task, b: &Task, meta: &MetaMap) -> Ordering {
+    let when = |s: Option<&String>| s.and_then(|s| crate::slots::parse_datetime(s));
+    match key {
+        Key::Created => present(crate::meta::created(a, meta), crate::meta::created(b, meta), descending),
+        Key::Deadline => present(when(a.deadline.as_ref()), when(b.deadline.as_ref()), descending),
+        Key::Due => present(when(crate::display::due_raw(a)), when(crate::display::due_raw(b)), descending),
+        Key::Estimate => present(a.estimate, b.estimate, descending),
+        Key::Id => present(Some(a.id), Some(b.id), descending),
+        Key::Priority => present(a.priority, b.priority, descending),
+        Key::Title => present(Some(a.title.to_lowercase()), Some(b.title.to_lowercase()), descending),
+    }
+}
+
+impl SortSpec {
+    /// Order `tasks` in place; `meta` supplies locally stamped creation times
+    pub fn apply<T: Borrow<Task>>(&self, tasks: &mut [T], meta: &MetaMap) {
+        tasks.sort_by(|a, b| {
+            let (a, b) = (a.borrow(), b.borrow());
+            self.keys
+                .iter()
+                .map(|&(key, descending)| compare(key, descending, a, b, meta))
+                .find(|o| o.is_ne())
+                .unwrap_or_else(|| a.id.cmp(&b.id))
+        });
+    }
+}
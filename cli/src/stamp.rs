@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+
+/// How the backend stores times: local wall-clock, no offset
+pub const FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// A stored time, with or without seconds or a fraction of one; a trailing Z is read as the
+/// wall-clock time it shows, the way the backend wrote it
+pub fn parse(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim().trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M"))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S"))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M"))
+        .ok()
+}
+
+/// The form every command sends
+pub fn format(t: NaiveDateTime) -> String {
+    t.format(FORMAT).to_string()
+}
+
+/// `stored` as it is when it still reads as `t`, so writing back an unchanged time never
+/// alters it; otherwise `t` in the canonical form
+pub fn keep(stored: Option<&str>, t: NaiveDateTime) -> String {
+    match stored {
+        Some(s) if parse(s) == Some(t) => s.to_string(),
+        _ => format(t),
+    }
+}
+
+/// `new` differs from what's stored, comparing times rather than text
+pub fn changed(stored: Option<&str>, new: &str) -> bool {
+    match (stored.and_then(parse), parse(new)) {
+        (Some(a), Some(b)) => a != b,
+        _ => stored != Some(new),
+    }
+}
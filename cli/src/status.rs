@@ -0,0 +1,119 @@
+use crate::api::Api;
+use crate::commands::Task;
+use crate::db;
+use crate::slots;
+use chrono::{Duration, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::error::Error;
+
+const TITLE_WIDTH: usize = 30;
+const ISO: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// What a status bar shows
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Summary {
+    /// Label and start (YYYY-MM-DDTHH:MM:SS) of the current or next scheduled item
+    pub next: Option<(String, String)>,
+    /// Open todos due today
+    pub due_today: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Cached {
+    fetched_at: String,
+    summary: Summary,
+}
+
+pub const STORE: &str = "status";
+
+pub fn summarize(tasks: &[Task], now: NaiveDateTime) -> Summary {
+    let open = |t: &&Task| t.status.as_deref() != Some("done");
+    let next = slots::busy_from_tasks(&tasks.iter().filter(open).filter(|t| !t.is_all_day()).cloned().collect::<Vec<_>>())
+        .into_iter()
+        .filter(|b| b.interval.end > now)
+        .min_by_key(|b| b.interval.start)
+        .map(|b| (b.label, b.interval.start.format(ISO).to_string()));
+    let due_today = tasks
+        .iter()
+        .filter(open)
+        .filter(|t| t.kind == "todo" && t.deadline.as_deref().and_then(slots::parse_datetime).is_some_and(|d| d.date() == now.date()))
+        .count();
+    Summary { next, due_today }
+}
+
+/// The cached summary while it's younger than `max_age`, otherwise a fresh one; a stale
+/// cache beats an error so a bar polling every few seconds never flickers
+pub async fn current(api: &Api, max_age: Duration, now: NaiveDateTime) -> Result<Summary, Box<dyn Error>> {
+    let cached: Option<Cached> = db::read_doc(STORE).ok().flatten();
+    let fetched_at = cached.as_ref().and_then(|c| slots::parse_datetime(&c.fetched_at));
+    if fetched_at.is_some_and(|at| at <= now && now - at < max_age) {
+        return Ok(cached.map(|c| c.summary).unwrap_or_default());
+    }
+    match api.tasks().await {
+        Ok(tasks) => {
+            let summary = summarize(&tasks, now);
+            db::write_doc(STORE, &Cached { fetched_at: now.format(ISO).to_string(), summary: summary.clone() })?;
+            Ok(summary)
+        }
+        Err(e) => cached.map(|c| c.summary).ok_or(e),
+    }
+}
+
+fn shorten(title: &str) -> String {
+    match title.char_indices().nth(TITLE_WIDTH) {
+        Some((i, _)) => format!("{}…", title[..i].trim_end()),
+        None => title.to_string(),
+    }
+}
+
+/// "14:00 [3] Review" today, "Tue 09:00 [3] Review" later, "now [3] Review" once started
+fn next_text(s: &Summary, now: NaiveDateTime) -> Option<String> {
+    let (title, start) = s.next.as_ref()?;
+    let start = slots::parse_datetime(start)?;
+    let when = if start <= now {
+        "now".to_string()
+    } else if start.date() == now.date() {
+        start.format("%H:%M").to_string()
+    } else {
+        start.format("%a %H:%M").to_string()
+    };
+    Some(format!("{} {}", when, shorten(title)))
+}
+
+fn line(s: &Summary, now: NaiveDateTime) -> String {
+    let due = (s.due_today > 0).then(|| format!("{} due", s.due_today));
+    let parts: Vec<String> = next_text(s, now).into_iter().chain(due).collect();
+    if parts.is_empty() {
+        "nothing scheduled".into()
+    } else {
+        parts.join(" · ")
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    Plain,
+    Tmux,
+    /// JSON for a waybar custom module (`"return-type": "json"`)
+    Waybar,
+    Polybar,
+}
+
+/// One line for the chosen bar, with its markup characters escaped
+pub fn render(s: &Summary, format: Format, now: NaiveDateTime) -> String {
+    let text = line(s, now);
+    match format {
+        Format::Plain => text,
+        Format::Tmux => text.replace('#', "##"),
+        Format::Polybar => text.replace('%', "%%"),
+        Format::Waybar => {
+            let class = if s.due_today > 0 { "due" } else { "idle" };
+            json!({ "text": text, "tooltip": format!("{} todo(s) due today", s.due_today), "class": class }).to_string()
+        }
+    }
+}
+
+pub fn now() -> NaiveDateTime {
+    Local::now().naive_local()
+}
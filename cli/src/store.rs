@@ -0,0 +1,94 @@
+use crate::vault;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Files outside the database that may hold personal data: snapshots and logs
+pub fn known_stores() -> Vec<PathBuf> {
+    let mut stores: Vec<PathBuf> = crate::backup::snapshots().unwrap_or_default().into_iter().map(crate::backup::snapshot_path).collect();
+    stores.extend(crate::logfile::files());
+    stores
+}
+
+fn ensure_parent(path: &Path) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// Encrypt a line when the vault is locked, otherwise pass it through
+pub fn encode(line: &str) -> Result<String, Box<dyn Error>> {
+    if vault::is_locked() {
+        vault::seal(line)
+    } else {
+        Ok(line.to_string())
+    }
+}
+
+/// Read all non-empty lines, transparently decrypting sealed ones
+pub fn read_lines(path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(decode)
+        .collect()
+}
+
+/// A stored line as it was written, decrypting it if it was sealed
+pub fn decode(line: &str) -> Result<String, Box<dyn Error>> {
+    if vault::is_sealed(line) {
+        vault::open(line)
+    } else {
+        Ok(line.to_string())
+    }
+}
+
+/// Append one line without touching existing content
+pub fn append_line(path: &Path, line: &str) -> Result<(), Box<dyn Error>> {
+    ensure_parent(path)?;
+    let encoded = encode(line)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", encoded)?;
+    Ok(())
+}
+
+/// Replace a store's content, writing to a temp file first so a crash can't truncate it
+pub fn write_lines(path: &Path, lines: &[String]) -> Result<(), Box<dyn Error>> {
+    ensure_parent(path)?;
+    let mut out = String::new();
+    for l in lines {
+        out.push_str(&encode(l)?);
+        out.push('\n');
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, out)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Load a single-document store, if present
+pub fn read_doc<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, Box<dyn Error>> {
+    match read_lines(path)?.first() {
+        Some(line) => Ok(Some(serde_json::from_str(line)?)),
+        None => Ok(None),
+    }
+}
+
+/// Save a single-document store as one (possibly encrypted) line
+pub fn write_doc<T: Serialize>(path: &Path, doc: &T) -> Result<(), Box<dyn Error>> {
+    write_lines(path, &[serde_json::to_string(doc)?])
+}
+
+pub fn remove(path: &Path) -> Result<(), Box<dyn Error>> {
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How a theme marks text on the terminal
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Ink {
+    Plain,
+    /// SGR parameters as they are, e.g. "90" or "1;91", for the terminal's own palette
+    Sgr(&'static str),
+    /// "#RRGGBB" in truecolor
+    Hex(&'static str),
+}
+
+impl Ink {
+    pub fn paint(self, text: &str) -> String {
+        match self {
+            Ink::Plain => text.to_string(),
+            Ink::Sgr(code) => format!("\x1b[{}m{}\x1b[0m", code, text),
+            Ink::Hex(hex) => match crate::color::rgb(hex) {
+                Some((r, g, b)) => format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text),
+                None => text.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Theme {
+    pub name: &'static str,
+    /// High, medium and low priority labels
+    pub priority: [Ink; 3],
+    /// Agenda lines for events and todo blocks
+    pub event: Ink,
+    pub todo: Ink,
+    /// Quiet hours, breaks and optional todos in the agenda
+    pub muted: Ink,
+    /// Category colors are drawn as the nearest of these, so categories stay apart for the
+    /// eyes the theme is for; empty draws them as they were set
+    pub palette: &'static [&'static str],
+}
+
+pub const DEFAULT: Theme = Theme {
+    name: "default",
+    priority: [Ink::Hex("#E15759"), Ink::Hex("#F28E2B"), Ink::Plain],
+    event: Ink::Plain,
+    todo: Ink::Plain,
+    muted: Ink::Sgr("90"),
+    palette: &[],
+};
+
+pub const SOLARIZED: Theme = Theme {
+    name: "solarized",
+    priority: [Ink::Hex("#DC322F"), Ink::Hex("#B58900"), Ink::Hex("#93A1A1")],
+    event: Ink::Hex("#268BD2"),
+    todo: Ink::Hex("#859900"),
+    muted: Ink::Hex("#586E75"),
+    palette: &["#B58900", "#CB4B16", "#DC322F", "#D33682", "#6C71C4", "#268BD2", "#2AA198", "#859900"],
+};
+
+/// Bold, bright and no dim greys: for low vision and washed-out screens
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    priority: [Ink::Sgr("1;91"), Ink::Sgr("1;93"), Ink::Plain],
+    event: Ink::Sgr("96"),
+    todo: Ink::Sgr("97"),
+    muted: Ink::Sgr("37"),
+    palette: &["#FFFFFF", "#FFFF00", "#00FFFF", "#FF00FF", "#00FF00", "#FF8000"],
+};
+
+/// Okabe–Ito colors, told apart by blue against orange and by lightness rather than red
+/// against green
+pub const DEUTERANOPIA: Theme = Theme {
+    name: "deuteranopia-safe",
+    priority: [Ink::Sgr("1;38;2;213;94;0"), Ink::Hex("#56B4E9"), Ink::Plain],
+    event: Ink::Hex("#56B4E9"),
+    todo: Ink::Hex("#E69F00"),
+    muted: Ink::Sgr("90"),
+    palette: &["#E69F00", "#56B4E9", "#009E73", "#F0E442", "#0072B2", "#D55E00", "#CC79A7", "#999999"],
+};
+
+/// Themes `--theme` and `theme` in the config choose from
+pub const PRESETS: &[&Theme] = &[&DEFAULT, &SOLARIZED, &HIGH_CONTRAST, &DEUTERANOPIA];
+
+/// Index into PRESETS picked with `--theme`; past the end means none was
+static CHOSEN: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+pub fn find(name: &str) -> Result<&'static Theme, String> {
+    PRESETS.iter().copied().find(|t| t.name.eq_ignore_ascii_case(name.trim())).ok_or_else(|| {
+        let known: Vec<&str> = PRESETS.iter().map(|t| t.name).collect();
+        format!("Unknown theme '{}' (known: {})", name, known.join(", "))
+    })
+}
+
+/// `--theme` / `TODO_THEME`, which beats the config
+pub fn set(name: &str) -> Result<(), String> {
+    let theme = find(name)?;
+    CHOSEN.store(PRESETS.iter().position(|t| std::ptr::eq(*t, theme)).unwrap_or_default(), Ordering::SeqCst);
+    Ok(())
+}
+
+/// The theme from `--theme`, else the config's, else the default
+pub fn current() -> &'static Theme {
+    if let Some(t) = PRESETS.get(CHOSEN.load(Ordering::SeqCst)) {
+        return t;
+    }
+    crate::config::get().theme.as_deref().and_then(|n| find(n).ok()).unwrap_or(&DEFAULT)
+}
+
+impl Theme {
+    /// `label` in the color for `priority`, banded as `display::priority_label` does
+    pub fn priority(&self, priority: Option<i32>, label: &str) -> String {
+        let band = match priority.unwrap_or(0) {
+            p if p >= 7 => 0,
+            p if p >= 4 => 1,
+            _ => 2,
+        };
+        self.priority[band].paint(label)
+    }
+
+    /// A category's color as this theme draws it
+    pub fn category(&self, color: &str) -> String {
+        match self.palette.is_empty() {
+            true => color.to_string(),
+            false => crate::color::nearest(color, self.palette),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+/// Prefix marking an encrypted store line
+const SEALED_PREFIX: &str = "enc1:";
+const KEYRING_SERVICE: &str = "todo-cli";
+const KEYRING_USER: &str = "vault";
+/// Overrides the OS keyring, e.g. on headless machines
+pub const KEY_ENV: &str = "TODO_VAULT_KEY";
+
+/// Marker file whose presence means new writes are encrypted
+fn marker_path() -> PathBuf {
+    crate::paths::state_dir().join("vault.locked")
+}
+
+pub fn is_locked() -> bool {
+    marker_path().exists()
+}
+
+pub fn is_sealed(line: &str) -> bool {
+    line.starts_with(SEALED_PREFIX)
+}
+
+fn keyring_error(e: keyring::Error) -> Box<dyn Error> {
+    format!("{} unavailable ({}); set {} instead", crate::platform::Host::current().keyring_name(), e, KEY_ENV).into()
+}
+
+fn keyring_entry() -> Result<keyring::Entry, Box<dyn Error>> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(keyring_error)
+}
+
+fn decode_key(encoded: &str) -> Result<Key, Box<dyn Error>> {
+    let bytes = B64.decode(encoded.trim())?;
+    if bytes.len() != 32 {
+        return Err("Vault key must be 32 bytes (base64)".into());
+    }
+    Ok(*Key::from_slice(&bytes))
+}
+
+/// Key from `TODO_VAULT_KEY` or the OS keyring, if any
+fn load_key() -> Result<Option<Key>, Box<dyn Error>> {
+    if let Ok(v) = std::env::var(KEY_ENV) {
+        return decode_key(&v).map(Some);
+    }
+    match keyring_entry()?.get_password() {
+        Ok(v) => decode_key(&v).map(Some),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(keyring_error(e)),
+    }
+}
+
+fn require_key() -> Result<Key, Box<dyn Error>> {
+    load_key()?.ok_or_else(|| {
+        format!("Local data is encrypted but no key is available (OS keyring or {})", KEY_ENV).into()
+    })
+}
+
+/// Existing key, or a freshly generated one saved to the keyring
+fn load_or_create_key() -> Result<Key, Box<dyn Error>> {
+    if let Some(k) = load_key()? {
+        return Ok(k);
+    }
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    keyring_entry()?.set_password(&B64.encode(key)).map_err(keyring_error)?;
+    Ok(key)
+}
+
+fn seal_with(key: &Key, plaintext: &str) -> Result<String, Box<dyn Error>> {
+    let cipher = ChaCha20Poly1305::new(key);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ct = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| "Encryption failed")?;
+    let mut blob = nonce.to_vec();
+    blob.extend_from_slice(&ct);
+    Ok(format!("{}{}", SEALED_PREFIX, B64.encode(blob)))
+}
+
+/// Encrypt one line with a fresh nonce
+pub fn seal(plaintext: &str) -> Result<String, Box<dyn Error>> {
+    seal_with(&require_key()?, plaintext)
+}
+
+/// Decrypt a line produced by `seal`
+pub fn open(line: &str) -> Result<String, Box<dyn Error>> {
+    let key = require_key()?;
+    let blob = B64.decode(line.trim_start_matches(SEALED_PREFIX))?;
+    if blob.len() < 12 {
+        return Err("Corrupt encrypted line".into());
+    }
+    let (nonce, ct) = blob.split_at(12);
+    let pt = ChaCha20Poly1305::new(&key)
+        .decrypt(Nonce::from_slice(nonce), ct)
+        .map_err(|_| "Decryption failed (wrong key?)")?;
+    Ok(String::from_utf8(pt)?)
+}
+
+/// Plaintext of every store: files by path, then the database by key
+type Contents = (Vec<(PathBuf, Vec<String>)>, Vec<(String, Vec<String>)>);
+
+fn read_all() -> Result<Contents, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for path in crate::store::known_stores() {
+        if path.exists() {
+            let lines = crate::store::read_lines(&path)?;
+            files.push((path, lines));
+        }
+    }
+    let mut keyed = Vec::new();
+    for key in crate::db::stores()? {
+        let lines = crate::db::read_lines(&key)?;
+        keyed.push((key, lines));
+    }
+    Ok((files, keyed))
+}
+
+/// Write everything back, sealed or not as the marker now says
+fn write_all((files, keyed): &Contents) -> Result<usize, Box<dyn Error>> {
+    for (path, lines) in files {
+        crate::store::write_lines(path, lines)?;
+    }
+    for (key, lines) in keyed {
+        crate::db::write_lines(key, lines)?;
+    }
+    Ok(files.len() + keyed.len())
+}
+
+/// Encrypt every store in place and keep encrypting new writes
+pub fn lock() -> Result<usize, Box<dyn Error>> {
+    load_or_create_key()?;
+    let plain = read_all()?;
+    if let Some(dir) = marker_path().parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(marker_path(), "")?;
+    // The search index is SQLite's own table, which can't be sealed; a locked vault searches without it
+    crate::search::clear()?;
+    write_all(&plain)
+}
+
+/// Decrypt every store back to plaintext and stop encrypting
+pub fn unlock() -> Result<usize, Box<dyn Error>> {
+    let decrypted = read_all()?;
+    if marker_path().exists() {
+        fs::remove_file(marker_path())?;
+    }
+    write_all(&decrypted)
+}
+
+/// Human-readable vault state
+pub fn status() -> String {
+    let key = match load_key() {
+        Ok(Some(_)) => "available",
+        Ok(None) => "not set",
+        Err(_) => "unavailable",
+    };
+    let state = if is_locked() { "locked (encrypting)" } else { "unlocked (plaintext)" };
+    format!("Vault {}; key {}", state, key)
+}
@@ -0,0 +1,41 @@
+use crate::db;
+use crate::filter::Filter;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Saved searches: name → filter expression
+pub type Views = BTreeMap<String, String>;
+
+pub const STORE: &str = "views";
+
+pub fn load() -> Result<Views, Box<dyn Error>> {
+    Ok(db::read_doc(STORE)?.unwrap_or_default())
+}
+
+/// Save (or replace) a view after checking its expression parses
+pub fn save(name: &str, expr: &str) -> Result<(), Box<dyn Error>> {
+    if name.is_empty() || name.starts_with('-') {
+        return Err(format!("'{}' is not a usable view name", name).into());
+    }
+    expr.parse::<Filter>().map_err(|e| format!("Invalid filter: {}", e))?;
+    let mut views = load()?;
+    views.insert(name.to_string(), expr.to_string());
+    db::write_doc(STORE, &views)
+}
+
+/// Returns whether the view existed
+pub fn remove(name: &str) -> Result<bool, Box<dyn Error>> {
+    let mut views = load()?;
+    let existed = views.remove(name).is_some();
+    db::write_doc(STORE, &views)?;
+    Ok(existed)
+}
+
+pub fn get(name: &str) -> Result<Filter, Box<dyn Error>> {
+    let views = load()?;
+    let expr = views.get(name).ok_or_else(|| match views.is_empty() {
+        true => format!("No view '{}'; save one with `todo view save {} '<filter>'`", name, name),
+        false => format!("No view '{}' (saved: {})", name, views.keys().cloned().collect::<Vec<_>>().join(", ")),
+    })?;
+    Ok(expr.parse::<Filter>().map_err(|e| format!("View '{}' is invalid: {}", name, e))?)
+}
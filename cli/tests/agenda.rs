@@ -0,0 +1,78 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::agenda::{self, Kind};
+use todo::commands::Task;
+use todo::config::{Block, Config};
+
+#[test]
+fn block_shorthands_cover_the_rest_of_the_day() {
+    let b: Block = "fri after 18:00".parse().unwrap();
+    assert_eq!((b.days.as_slice(), b.window.start.as_str(), b.window.end.as_str()), (&[4][..], "18:00", "23:59"));
+    assert_eq!(String::from("before 07:30".parse::<Block>().unwrap()), "00:00-07:30");
+}
+
+#[test]
+fn agenda_interleaves_tasks_with_quiet_hours_and_breaks() {
+    let cfg: Config = toml::from_str(
+        "[availability]\nbreaks = [\"12:00-13:00\"]\n[scheduling]\ndo_not_schedule = [\"after 20:00\"]",
+    )
+    .unwrap();
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Standup", "type": "event", "start_time": "2030-05-06T09:30:00", "end_time": "2030-05-06T09:45:00", "location": "Office" },
+        { "id": 5, "title": "Demo", "type": "event", "transparency": "tentative", "start_time": "2030-05-06T11:00:00", "end_time": "2030-05-06T11:30:00" },
+        { "id": 2, "title": "Report", "type": "todo", "optional": true, "start_time": "2030-05-07T14:00:00", "end_time": "2030-05-07T15:00:00" },
+        { "id": 3, "title": "Conference", "type": "event", "all_day": true, "start_time": "2030-05-07T00:00:00", "end_time": "2030-05-09T00:00:00" }
+    ]))
+    .unwrap();
+    let days = agenda::build(&tasks, &cfg, NaiveDate::from_ymd_opt(2030, 5, 6).unwrap(), 2);
+    let kinds: Vec<Vec<Kind>> = days.iter().map(|d| d.entries.iter().map(|e| e.kind).collect()).collect();
+    assert_eq!(kinds, vec![vec![Kind::Event, Kind::Event, Kind::Break, Kind::Quiet], vec![Kind::Break, Kind::Todo, Kind::Quiet]]);
+
+    assert_eq!(days[0].all_day.len(), 0);
+    assert_eq!(days[1].all_day, vec!["[3] Conference (day 1/2)".to_string()]);
+    assert!(agenda::at_location(&tasks[0], "office") && !agenda::at_location(&tasks[1], "office"));
+
+    let plain = agenda::render(&days, false);
+    assert!(plain.contains("  09:30-09:45  [1] Standup @ Office\n"), "{}", plain);
+    assert!(plain.contains("  11:00-11:30  [5] Demo (tentative)\n"), "{}", plain);
+    assert!(plain.contains("  20:00-23:59  quiet hours  ~"), "{}", plain);
+    assert!(plain.contains("Tue 2030-05-07\n  all day      [3] Conference (day 1/2)\n"), "{}", plain);
+    assert!(plain.contains("  14:00-15:00  [2] Report (optional)\n"), "{}", plain);
+    assert!(agenda::render(&days, true).contains("\x1b[90m  12:00-13:00  break\x1b[0m"));
+    assert!(agenda::render(&days, true).contains("\x1b[90m  14:00-15:00  [2] Report (optional)\x1b[0m"));
+
+    let spoken = agenda::render_accessible(&days);
+    assert!(spoken.starts_with("Monday 2030-05-06:\n09:30 to 09:45, event: task 1, Standup @ Office\n"), "{}", spoken);
+    assert!(spoken.contains("12:00 to 13:00, break, nothing gets scheduled\n"), "{}", spoken);
+    assert!(spoken.contains("All day, event: task 3, Conference (day 1/2)\n"), "{}", spoken);
+    assert!(!spoken.contains('~') && !spoken.contains('\x1b'));
+}
+
+#[test]
+fn overnight_events_are_split_at_midnight() {
+    let cfg: Config = toml::from_str("").unwrap();
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 4, "title": "Night shift", "type": "event", "start_time": "2030-05-06T22:00:00", "end_time": "2030-05-07T06:00:00" }
+    ]))
+    .unwrap();
+    let days = agenda::build(&tasks, &cfg, NaiveDate::from_ymd_opt(2030, 5, 6).unwrap(), 2);
+    let plain = agenda::render(&days, false);
+    assert!(plain.contains("Mon 2030-05-06\n  22:00-24:00  [4] Night shift (day 1/2)\n"), "{}", plain);
+    assert!(plain.contains("Tue 2030-05-07\n  00:00-06:00  [4] Night shift (day 2/2)\n"), "{}", plain);
+}
+
+#[test]
+fn week_start_and_weekend_follow_the_config() {
+    let cfg = todo::config::parse("[week]\nstarts_on = \"sun\"\nweekend = [\"fri\", \"sat\"]\n[availability]\nmon = [\"08:00-12:00\"]").unwrap();
+    let thu = NaiveDate::from_ymd_opt(2030, 5, 9).unwrap();
+    assert_eq!(cfg.week.start_of(thu), NaiveDate::from_ymd_opt(2030, 5, 5).unwrap());
+    assert_eq!(cfg.week.last_workday(NaiveDate::from_ymd_opt(2030, 5, 10).unwrap()), thu);
+    let [mon, _, _, _, fri, sat, sun] = cfg.availability.days().map(|d| d.iter().map(|w| format!("{}-{}", w.start, w.end)).collect::<Vec<_>>());
+    assert_eq!((mon, fri, sat, sun), (vec!["08:00-12:00".to_string()], vec!["10:00-14:00".to_string()], vec!["10:00-14:00".to_string()], vec!["09:00-17:00".to_string()]));
+
+    let days = agenda::build(&[], &cfg, thu, 4);
+    let plain = agenda::render(&days, false);
+    assert!(plain.starts_with("Thu 2030-05-09\n  (nothing scheduled)\nFri 2030-05-10 (weekend)\n"), "{}", plain);
+    assert!(plain.contains("Sat 2030-05-11 (weekend)\n  (nothing scheduled)\n\nSun 2030-05-12\n"), "{}", plain);
+    assert!(agenda::render_accessible(&days).contains("New week.\nSunday 2030-05-12:\n"));
+}
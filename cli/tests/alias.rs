@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use todo::alias;
+
+fn argv(words: &[&str]) -> Vec<OsString> {
+    words.iter().map(OsString::from).collect()
+}
+
+fn cli() -> clap::Command {
+    clap::Command::new("todo").arg(clap::Arg::new("data_dir").long("data-dir").global(true))
+}
+
+#[test]
+fn templates_split_like_a_shell() {
+    assert_eq!(alias::split(r#"ct "Call mum" -d 'next week'  -p\ 4"#).unwrap(), vec!["ct", "Call mum", "-d", "next week", "-p 4"]);
+    assert!(alias::split("lt 'oops").is_err());
+}
+
+#[test]
+fn placeholders_take_arguments_and_the_rest_is_appended() {
+    let aliases = BTreeMap::from([
+        ("wait".to_string(), "ut $1 --waiting-on $2".to_string()),
+        ("errands".to_string(), "lt @errands".to_string()),
+    ]);
+    let out = alias::expand(&cli(), argv(&["todo", "--data-dir", "wait", "wait", "12", "Alice", "-p", "3"]), &aliases).unwrap();
+    assert_eq!(out, argv(&["todo", "--data-dir", "wait", "ut", "12", "--waiting-on", "Alice", "-p", "3"]));
+    assert_eq!(alias::expand(&cli(), argv(&["todo", "errands", "@home"]), &aliases).unwrap(), argv(&["todo", "lt", "@errands", "@home"]));
+    assert!(alias::expand(&cli(), argv(&["todo", "wait", "12"]), &aliases).is_err());
+}
@@ -0,0 +1,19 @@
+mod common;
+
+use todo::{attach, meta};
+
+#[test]
+fn attachments_are_normalized_and_deduplicated() {
+    common::isolate_paths();
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let abs = file.path().canonicalize().unwrap().to_string_lossy().to_string();
+
+    attach::add(5, "https://example.com/spec").unwrap();
+    attach::add(5, file.path().to_str().unwrap()).unwrap();
+    let all = attach::add(5, "https://example.com/spec").unwrap();
+    assert_eq!(all, vec!["https://example.com/spec".to_string(), abs]);
+    assert_eq!(meta::get(5).unwrap().attachments.len(), 2);
+
+    assert!(attach::add(5, "/no/such/file.pdf").is_err());
+    assert!(meta::get(6).unwrap().attachments.is_empty());
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use common::{backend, json};
+use serde_json::json as j;
+use std::collections::BTreeMap;
+use todo::backup::{self, Backup};
+use todo::meta;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+#[test]
+fn payload_drops_server_fields_and_remaps_category() {
+    let raw = j!({ "id": 40, "title": "Buy stamps", "type": "todo", "created_at": "2030-01-01T00:00:00",
+                   "category": { "id": 7, "name": "Errands", "color": "#FF9900" }, "category_id": 7,
+                   "estimate": 15, "location": null, "external_id": "gcal-9" });
+    let payload = backup::task_payload(&raw, &BTreeMap::from([(7, 3)]));
+    let keys: Vec<&str> = payload.keys().map(String::as_str).collect();
+    assert_eq!(keys, vec!["category_id", "estimate", "external_id", "title", "type"]);
+    assert_eq!(payload["category_id"], 3);
+}
+
+#[tokio::test]
+async fn restore_recreates_tasks_and_moves_metadata_to_new_ids() {
+    let b = backend().await;
+    Mock::given(method("GET")).and(path("/tasks/")).respond_with(ResponseTemplate::new(200).set_body_json(j!([]))).mount(&b.server).await;
+    b.stub("GET", "/categories/", "categories").await;
+    Mock::given(method("POST"))
+        .and(path("/categories/"))
+        .and(body_partial_json(j!({ "name": "Errands" })))
+        .respond_with(json(200, "category"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .and(body_partial_json(j!({ "title": "Buy stamps", "category_id": 3 })))
+        .respond_with(json(200, "task_todo"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let saved: Backup = serde_json::from_value(j!({
+        "format": 1, "created_at": "2030-05-01T12:00:00",
+        "categories": [{ "id": 7, "name": "Errands", "color": "#FF9900" }, { "id": 8, "name": "home", "color": "#CCCCCC" }],
+        "tasks": [{ "id": 40, "title": "Buy stamps", "type": "todo", "category": { "id": 7, "name": "Errands", "color": "#FF9900" } }],
+        "meta": { "40": { "fields": { "client": "acme" } } },
+        "views": {},
+        "config": null,
+        "push_journal": { "failed": [40, 41] }
+    }))
+    .unwrap();
+    let r = backup::restore(&b.api, &saved, false).await.unwrap();
+    assert_eq!((r.tasks.len(), r.categories), (1, 1));
+    assert!(r.failed.is_empty());
+    // task_todo comes back as #12
+    assert_eq!(meta::get(12).unwrap().fields["client"], "acme");
+    assert_eq!(todo::push::load_journal().unwrap().failed, vec![12]);
+}
+
+#[test]
+fn backups_are_compressed_and_old_json_ones_still_read() {
+    let dir = tempfile::tempdir().unwrap();
+    let saved: Backup = serde_json::from_value(j!({
+        "format": 1, "created_at": "2030-05-01T12:00:00", "categories": [], "views": { "work": "category=Work" }, "config": null,
+        "tasks": [{ "id": 40, "title": "Buy stamps", "type": "todo", "external_id": "gcal-9" }], "meta": {},
+        "push_journal": { "failed": [40] }
+    }))
+    .unwrap();
+    let file = dir.path().join("b.tar.zst");
+    backup::write(&file, &saved).unwrap();
+    assert!(std::fs::read(&file).unwrap().starts_with(&[0x28, 0xb5, 0x2f, 0xfd]));
+    let back = backup::read(&file).unwrap();
+    assert_eq!((back.tasks, back.views, back.push_journal.failed), (saved.tasks.clone(), saved.views.clone(), vec![40]));
+
+    let old = dir.path().join("b.json");
+    std::fs::write(&old, r#"{"format":1,"created_at":"","categories":[],"tasks":[],"meta":{},"views":{},"config":null}"#).unwrap();
+    assert!(backup::read(&old).unwrap().push_journal.is_empty());
+}
+
+#[tokio::test]
+async fn restore_refuses_a_backend_with_tasks() {
+    let b = backend().await;
+    b.stub("GET", "/tasks/", "tasks").await;
+    let saved: Backup = serde_json::from_value(j!({
+        "format": 1, "created_at": "2030-05-01T12:00:00", "categories": [], "tasks": [], "meta": {}, "views": {}, "config": null
+    }))
+    .unwrap();
+    let err = backup::restore(&b.api, &saved, false).await.unwrap_err().to_string();
+    assert!(err.contains("--force"), "{}", err);
+}
+
+#[test]
+fn retention_keeps_newest_per_day_then_per_week() {
+    // Two snapshots a day for six weeks, ending Sunday 2030-06-16
+    let start = chrono::NaiveDate::from_ymd_opt(2030, 5, 6).unwrap();
+    let all: Vec<_> = (0..42).flat_map(|d| [6, 18].map(|h| (start + chrono::Days::new(d)).and_hms_opt(h, 0, 0).unwrap())).collect();
+    let keep: Vec<String> = backup::retained(&all, 7, 4).iter().map(|t| t.format("%m-%d %H").to_string()).collect();
+    // Last seven evenings, plus the Sunday evenings of the three weeks before
+    assert_eq!(keep, vec!["05-26 18", "06-02 18", "06-09 18", "06-10 18", "06-11 18", "06-12 18", "06-13 18", "06-14 18", "06-15 18", "06-16 18"]);
+}
+
+#[test]
+fn diff_lists_added_removed_and_changed_tasks() {
+    let snap = |tasks| -> Backup {
+        serde_json::from_value(j!({ "format": 1, "created_at": "", "categories": [], "tasks": tasks, "meta": {}, "views": {}, "config": null })).unwrap()
+    };
+    let a = snap(j!([
+        { "id": 1, "title": "Report", "status": "pending", "updated_at": "a" },
+        { "id": 2, "title": "Call mum", "status": "pending" }
+    ]));
+    let b = snap(j!([
+        { "id": 1, "title": "Report", "status": "done", "updated_at": "b" },
+        { "id": 3, "title": "Dentist", "status": "pending" }
+    ]));
+    assert_eq!(backup::diff(&a, &b), vec!["~ [1] Report: status pending → done", "- [2] Call mum", "+ [3] Dentist"]);
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::{backend, json};
+use serde_json::Value;
+use todo::batch::{self, Op};
+use todo::meta;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::Mock;
+
+#[test]
+fn command_lines_and_json_parse_to_the_same_operation() {
+    assert_eq!(batch::parse("done 12").unwrap(), batch::parse(r#"{"op": "done", "id": 12}"#).unwrap());
+    assert_eq!(
+        batch::parse("ct 'Buy milk' -e 10 -d tomorrow").unwrap(),
+        Op::CreateTodo { title: "Buy milk".into(), estimate: 10, deadline: "tomorrow".into(), priority: 0, description: None, assignee: None, optional: false }
+    );
+    assert!(batch::parse("agenda").unwrap_err().contains("batch"));
+    assert!(batch::parse("ct 'Buy milk' -e 10 -d yesterday").unwrap_err().contains("--allow-past"));
+    assert!(batch::parse("ct 'Buy milk' -e 10 -d yesterday --allow-past").is_ok());
+}
+
+#[tokio::test]
+async fn runs_every_line_and_reports_each_result() {
+    let b = backend().await;
+    Mock::given(method("POST")).and(path("/tasks/")).respond_with(json(200, "task_todo")).expect(1).mount(&b.server).await;
+    Mock::given(method("PATCH"))
+        .and(path("/tasks/10"))
+        .and(body_partial_json(serde_json::json!({ "status": "done" })))
+        .respond_with(json(200, "task_updated"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let input = "# weekly review\nct 'Buy milk' -e 10 -d tomorrow\n{\"op\": \"done\", \"id\": 10}\n\nlist-categories\n{\"op\": \"attach\", \"id\": 10, \"target\": \"https://example.com/spec\"}\n";
+    let mut out = Vec::new();
+    let failed = batch::run(&b.api, input.as_bytes(), &mut out, false).await.unwrap();
+    let results: Vec<Value> = String::from_utf8(out).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+
+    assert_eq!(failed, 1);
+    assert_eq!(results.iter().map(|r| r["line"].as_u64().unwrap()).collect::<Vec<_>>(), vec![2, 3, 5, 6]);
+    assert_eq!(results.iter().map(|r| r["ok"].as_bool().unwrap()).collect::<Vec<_>>(), vec![true, true, false, true]);
+    assert_eq!(meta::get(10).unwrap().attachments, vec!["https://example.com/spec".to_string()]);
+}
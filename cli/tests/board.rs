@@ -0,0 +1,42 @@
+use serde_json::json;
+use todo::board::{self, Column};
+use todo::commands::Task;
+
+fn tasks() -> Vec<Task> {
+    serde_json::from_value(json!([
+        { "id": 1, "title": "Draft chapter", "type": "todo", "status": "in-progress", "deadline": "2030-05-09T17:00:00", "category": { "id": 1, "name": "Thesis", "color": "#3fa34d" } },
+        { "id": 2, "title": "Pay rent", "type": "todo", "status": "pending", "deadline": "2030-05-31T21:00:00" },
+        { "id": 3, "title": "Call the bank", "type": "todo", "status": "waiting", "deadline": "2030-05-20T12:00:00" },
+        { "id": 4, "title": "Old report", "type": "todo", "status": "done", "updated_at": "2030-05-01T09:00:00" },
+        { "id": 5, "title": "New report", "type": "todo", "status": "done", "updated_at": "2030-05-05T09:00:00" },
+        { "id": 6, "title": "Standup", "type": "event", "start_time": "2030-05-06T09:30:00", "end_time": "2030-05-06T09:45:00" }
+    ]))
+    .unwrap()
+}
+
+#[test]
+fn cards_are_grouped_by_status() {
+    let tasks = tasks();
+    let cols = board::columns(&tasks, 1);
+    let ids: Vec<Vec<i32>> = cols.iter().map(|c| c.iter().map(|t| t.id).collect()).collect();
+    assert_eq!(ids, vec![vec![3, 2], vec![1], vec![5]]);
+    assert_eq!((Column::Todo.step(1), Column::Todo.step(-1), Column::Done.step(1)), (Some(Column::InProgress), None, None));
+    assert_eq!(Column::InProgress.step(1).map(Column::status), Some("done"));
+
+    let plain = board::render(&cols, 120, Some((1, 0)), false);
+    let lines: Vec<&str> = plain.lines().collect();
+    assert!(lines[0].starts_with("TODO (2)") && lines[0].contains("IN PROGRESS (1)") && lines[0].contains("DONE (1)"), "{}", plain);
+    assert!(lines[2].contains(" [3] Call the bank") && lines[2].contains(">[1] Draft chapter"), "{}", plain);
+    assert!(plain.contains("Thesis") && plain.contains("waiting") && !plain.contains('\x1b'), "{}", plain);
+    assert!(board::render(&cols, 80, None, true).contains("\x1b[38;2;63;163;77m▌"));
+
+    let spoken = board::render_accessible(&cols);
+    assert!(spoken.starts_with("todo, 2 tasks.\nTask 3, title Call the bank, due"), "{}", spoken);
+    assert!(spoken.contains("in progress, 1 tasks.\nTask 1, title Draft chapter"), "{}", spoken);
+}
+
+#[test]
+fn saved_views_become_tabs() {
+    assert_eq!(board::tab_bar(&["urgent", "work"], 0), "[all]  urgent   work");
+    assert_eq!(board::tab_bar(&["urgent", "work"], 2), " all   urgent  [work]");
+}
@@ -0,0 +1,32 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::briefing;
+use todo::commands::Task;
+
+#[test]
+fn briefing_covers_today_and_flags_unplaced_work() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Standup", "type": "event", "start_time": "2030-05-06T09:30:00", "end_time": "2030-05-06T09:45:00" },
+        { "id": 2, "title": "Report", "type": "todo", "status": "pending", "priority": 5, "estimate": 120,
+          "deadline": "2030-05-06T17:00:00" },
+        { "id": 3, "title": "Slides", "type": "todo", "status": "pending", "priority": 8, "estimate": 60,
+          "deadline": "2030-05-09T17:00:00", "start_time": "2030-05-06T10:00:00", "end_time": "2030-05-06T11:00:00" },
+        { "id": 4, "title": "Tax return", "type": "todo", "status": "pending", "priority": 2, "deadline": "2030-05-01T17:00:00" },
+        { "id": 5, "title": "Their task", "type": "todo", "status": "pending", "priority": 9, "assignee": "sam" },
+        { "id": 6, "title": "Filed", "type": "todo", "status": "done", "priority": 10 }
+    ]))
+    .unwrap();
+    let now = NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(8, 0, 0).unwrap();
+    let b = briefing::build(&tasks, Some("alex"), now);
+    let ids = |ts: &[Task]| ts.iter().map(|t| t.id).collect::<Vec<_>>();
+    assert_eq!(ids(&b.events), vec![1]);
+    assert_eq!(ids(&b.blocks), vec![3]);
+    assert_eq!(ids(&b.top), vec![3, 2, 4]);
+    assert_eq!(ids(&b.overdue), vec![4]);
+    assert_eq!(b.at_risk.iter().map(|(t, m)| (t.id, *m)).collect::<Vec<_>>(), vec![(2, 120)]);
+    let text = briefing::render(&b, now, |_| None);
+    assert!(text.contains("[2] Report is due today with 2h not scheduled"), "{}", text);
+    assert!(!text.contains("https://"), "{}", text);
+    let linked = briefing::render(&b, now, |id| Some(format!("https://tasks.example.com/tasks/{}", id)));
+    assert!(linked.contains("[4] Tax return (due 2030-05-01T17:00:00)\n    https://tasks.example.com/tasks/4\n"), "{}", linked);
+}
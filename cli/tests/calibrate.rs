@@ -0,0 +1,29 @@
+use serde_json::json;
+use todo::calibrate;
+use todo::commands::Task;
+use todo::meta::{MetaMap, TaskMeta};
+
+fn done(id: i32, category: &str, estimate: i32) -> serde_json::Value {
+    json!({ "id": id, "title": format!("t{}", id), "type": "todo", "status": "done", "estimate": estimate,
+            "category": { "id": 1, "name": category, "color": "#000000" } })
+}
+
+#[test]
+fn ratios_scale_open_estimates_once_a_category_has_enough_samples() {
+    let mut raw = vec![done(1, "Coding", 60), done(2, "Coding", 60), done(3, "Coding", 30), done(4, "Admin", 30)];
+    raw.push(json!({ "id": 5, "title": "next feature", "type": "todo", "status": "pending", "estimate": 50,
+                     "category": { "id": 1, "name": "Coding", "color": "#000000" } }));
+    raw.push(json!({ "id": 6, "title": "expenses", "type": "todo", "status": "pending", "estimate": 20,
+                     "category": { "id": 2, "name": "Admin", "color": "#000000" } }));
+    let tasks: Vec<Task> = serde_json::from_value(json!(raw)).unwrap();
+    let meta: MetaMap = [(1, 90), (2, 120), (3, 40), (4, 60)].map(|(id, m)| (id, TaskMeta { actual_minutes: Some(m), ..Default::default() })).into();
+
+    let bias = calibrate::bias(&tasks, &meta);
+    assert_eq!(bias["Coding"].samples, 3);
+    assert!((bias["Coding"].ratio() - 1.666).abs() < 0.01);
+    assert_eq!(calibrate::describe("Coding", &bias["Coding"]), "Coding tasks take 1.7× your estimate");
+
+    let planned = calibrate::apply(&tasks, &meta);
+    // Admin has a single sample, so its estimate stays put
+    assert_eq!((planned[4].estimate, planned[5].estimate), (Some(83), Some(20)));
+}
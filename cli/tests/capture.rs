@@ -0,0 +1,31 @@
+use todo::capture::{self, Captured};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn links_are_titled_from_the_page() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ticket/42"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "<html><head><title>\n  Fix login &amp; signup\n</title></head></html>",
+        ))
+        .mount(&server)
+        .await;
+    let client = reqwest::Client::new();
+    let text = format!("look at {}/ticket/42.", server.uri());
+
+    let c = capture::prepare(&client, &text, true).await;
+    assert_eq!(c.title, "Fix login & signup");
+    assert_eq!(c.description.as_deref(), Some(text.as_str()));
+
+    // Without fetching, the words around the link make the title
+    assert_eq!(capture::prepare(&client, &text, false).await.title, "look at");
+}
+
+#[tokio::test]
+async fn plain_text_uses_its_first_line() {
+    let client = reqwest::Client::new();
+    let c = capture::prepare(&client, "Call the bank\nabout the card", true).await;
+    assert_eq!(c, Captured { title: "Call the bank".into(), description: Some("about the card".into()) });
+}
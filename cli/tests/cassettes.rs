@@ -0,0 +1,51 @@
+mod common;
+
+use common::{backend, isolate_paths};
+use std::path::PathBuf;
+use std::time::Duration;
+use todo::api::Api;
+use todo::cassette::Cassette;
+use todo::handlers::{self, PollOptions};
+
+fn cassette(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cassettes").join(name)
+}
+
+fn fast_poll() -> PollOptions {
+    PollOptions { attempts: 5, interval: Duration::from_millis(1) }
+}
+
+#[tokio::test]
+async fn replays_recorded_list_tasks_session() {
+    isolate_paths();
+    // Unroutable base URL: any live request would fail
+    let api = Api::new("http://127.0.0.1:9").with_cassette(Cassette::replay(&cassette("list_tasks.json")).unwrap());
+    let listing = handlers::list_tasks(&api, &serde_json::json!({}), &fast_poll()).await.unwrap();
+    assert_eq!(listing.poll.polls, 2);
+    assert_eq!(listing.tasks[0].scheduled_for.as_deref(), Some("2030-05-06"));
+}
+
+#[tokio::test]
+async fn replay_fails_when_tape_runs_out() {
+    isolate_paths();
+    let api = Api::new("http://127.0.0.1:9").with_cassette(Cassette::replay(&cassette("list_tasks.json")).unwrap());
+    let err = api.categories().await.err().unwrap();
+    assert!(err.to_string().contains("GET /categories/"));
+}
+
+#[tokio::test]
+async fn recorded_session_replays_identically() {
+    let b = backend().await;
+    b.stub("GET", "/categories/", "categories").await;
+    b.stub("POST", "/calendar/push-all", "push_all").await;
+    let tape = tempfile::NamedTempFile::new().unwrap();
+
+    let live = Api::new(&b.server.uri()).with_cassette(Cassette::record(tape.path()));
+    let cats = live.categories().await.unwrap();
+    live.push_all(true).await.unwrap();
+
+    let replay = Api::new("http://127.0.0.1:9").with_cassette(Cassette::replay(tape.path()).unwrap());
+    let replayed = replay.categories().await.unwrap();
+    assert_eq!(cats.len(), replayed.len());
+    assert_eq!(replay.push_all(true).await.unwrap().pushed, Some(2));
+}
@@ -0,0 +1,22 @@
+use todo::color;
+
+#[test]
+fn colors_are_normalized_and_bad_ones_rejected() {
+    assert_eq!(color::parse("#4e79a7").unwrap(), "#4E79A7");
+    assert_eq!(color::parse("f80").unwrap(), "#FF8800");
+    assert_eq!(color::parse("Teal").unwrap(), "#76B7B2");
+    assert!(color::parse("#12345").unwrap_err().contains("#RRGGBB"));
+    assert!(color::parse("blurple").is_err());
+    assert_eq!(color::swatch("#FF8800"), "\x1b[48;2;255;136;0m  \x1b[0m");
+}
+
+#[test]
+fn auto_picks_a_color_unlike_the_existing_ones() {
+    assert_eq!(color::distinct(&[]), color::PALETTE[0]);
+    let existing = vec!["#4E79A7".to_string(), "#3366CC".to_string(), "not a color".to_string()];
+    let picked = color::distinct(&existing);
+    assert!(!existing.contains(&picked));
+    // Far from both blues: not another blue
+    let (r, _, b) = color::rgb(&picked).unwrap();
+    assert!(r > b, "{}", picked);
+}
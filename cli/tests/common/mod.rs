@@ -0,0 +1,67 @@
+// Shared mock-backend harness for integration tests
+#![allow(dead_code)]
+
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Once;
+use todo::api::Api;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+static INIT: Once = Once::new();
+
+/// Point every local store at a throwaway directory (once per test binary)
+pub fn isolate_paths() {
+    INIT.call_once(|| {
+        let dir = tempfile::tempdir().expect("tempdir").keep();
+        todo::paths::init(Some(dir.join("config")), Some(dir.join("data")));
+    });
+}
+
+pub fn fixture(name: &str) -> Value {
+    let p = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(format!("{}.json", name));
+    let raw = std::fs::read_to_string(&p).unwrap_or_else(|e| panic!("fixture {}: {}", p.display(), e));
+    serde_json::from_str(&raw).unwrap_or_else(|e| panic!("fixture {}: {}", p.display(), e))
+}
+
+pub fn json(status: u16, name: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(fixture(name))
+}
+
+pub struct Backend {
+    pub server: MockServer,
+    pub api: Api,
+}
+
+impl Backend {
+    /// Respond to `verb path` with a fixture body
+    pub async fn stub(&self, verb: &str, route: &str, fixture_name: &str) {
+        Mock::given(method(verb))
+            .and(path(route))
+            .respond_with(json(200, fixture_name))
+            .mount(&self.server)
+            .await;
+    }
+}
+
+/// Fresh mock server plus a client pointed at it
+pub async fn backend() -> Backend {
+    isolate_paths();
+    let server = MockServer::start().await;
+    let api = Api::new(&server.uri());
+    Backend { server, api }
+}
+
+/// Stub every endpoint the CLI hits with its default fixture
+pub async fn full_backend() -> Backend {
+    let b = backend().await;
+    b.stub("GET", "/categories/", "categories").await;
+    b.stub("POST", "/categories/", "category").await;
+    b.stub("GET", "/tasks/", "tasks").await;
+    b.stub("GET", "/taskslist/", "taskslist").await;
+    b.stub("POST", "/auto-schedule/", "auto_schedule").await;
+    b.stub("POST", "/calendar/sync", "sync").await;
+    b.stub("POST", "/calendar/push-all", "push_all").await;
+    b.stub("GET", "/openapi.json", "openapi").await;
+    b
+}
@@ -0,0 +1,26 @@
+use serde_json::json;
+use todo::commands::Task;
+use todo::context;
+
+#[test]
+fn at_tags_are_pulled_out_of_titles() {
+    assert_eq!(
+        context::split_title("Buy milk @Errands and stamps @errands @home"),
+        ("Buy milk and stamps".to_string(), vec!["errands".to_string(), "home".to_string()])
+    );
+    // Email addresses and a lone "@" stay in the title
+    assert_eq!(context::split_title("Mail ann@example.com @ noon").1, Vec::<String>::new());
+}
+
+#[test]
+fn contexts_in_use_skip_finished_tasks() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Milk", "type": "todo", "status": "pending", "contexts": ["errands"] },
+        { "id": 2, "title": "Bank", "type": "todo", "status": "pending", "contexts": ["errands", "phone"] },
+        { "id": 3, "title": "Post", "type": "todo", "status": "done", "contexts": ["errands"] }
+    ]))
+    .unwrap();
+    let counts: Vec<(String, usize)> = context::in_use(&tasks).into_iter().collect();
+    assert_eq!(counts, vec![("errands".into(), 2), ("phone".into(), 1)]);
+    assert!(context::has_any(&tasks[1], &[context::normalize("@Phone")]));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{backend, full_backend};
+use std::path::{Path, PathBuf};
+use todo::config::Daemon;
+use todo::{daemon, meta};
+use todo::platform::{Host, Os};
+use todo::push;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+#[test]
+fn each_os_gets_its_own_service() {
+    let exe = Path::new("/opt/todo/bin/todo");
+    let log = Path::new("/tmp/daemon.log");
+
+    let linux = Host { os: Os::Linux, env: |name| (name == "HOME").then(|| "/home/ana".into()) };
+    let unit = daemon::service(&linux, exe, log).unwrap();
+    let (path, text) = unit.file.unwrap();
+    assert_eq!(path, PathBuf::from("/home/ana/.config/systemd/user/todo-daemon.service"));
+    assert!(text.contains("ExecStart=\"/opt/todo/bin/todo\" --no-input daemon run\n"), "{}", text);
+    assert_eq!(unit.enable.last().unwrap().join(" "), "systemctl --user enable --now todo-daemon.service");
+
+    let mac = Host { os: Os::MacOs, env: |name| (name == "HOME").then(|| "/Users/ana".into()) };
+    let agent = daemon::service(&mac, exe, log).unwrap();
+    let (path, text) = agent.file.unwrap();
+    assert_eq!(path, PathBuf::from("/Users/ana/Library/LaunchAgents/com.importly.todo.daemon.plist"));
+    assert!(text.contains("<string>com.importly.todo.daemon</string>") && text.contains("<string>/tmp/daemon.log</string>"), "{}", text);
+    assert_eq!(agent.status.join(" "), "launchctl list com.importly.todo.daemon");
+
+    let windows = Host { os: Os::Windows, env: |_| None };
+    let task = daemon::service(&windows, Path::new(r"C:\Program Files\todo\todo.exe"), log).unwrap();
+    assert_eq!(task.file, None);
+    assert!(task.enable[0].contains(&r#""C:\Program Files\todo\todo.exe" --no-input daemon run"#.to_string()));
+    assert_eq!(task.disable.last().unwrap().join(" "), "schtasks /Delete /TN todo-daemon /F");
+
+    assert_eq!(daemon::service(&Host { os: Os::Linux, env: |_| None }, exe, log), None);
+}
+
+#[tokio::test]
+async fn a_round_syncs_and_snapshots_once_a_day() {
+    let b = full_backend().await;
+    // Follow-ups have their own test; both would share the last-run days
+    let cfg = Daemon { follow_ups: false, ..Daemon::default() };
+    let now = NaiveDate::from_ymd_opt(2040, 3, 4).unwrap().and_hms_opt(9, 0, 0).unwrap();
+
+    let first = daemon::tick(&b.api, &cfg, now).await;
+    assert_eq!(first[0], "calendar synced; 4 events imported");
+    assert!(first[1].starts_with("snapshot saved"), "{:?}", first);
+    assert_eq!(daemon::tick(&b.api, &cfg, now + chrono::Duration::minutes(15)).await.len(), 1);
+
+    // Pushes a client-side push-all left behind are tried again
+    todo::db::write_doc(push::JOURNAL, &push::Journal { failed: vec![10], not_reached: vec![] }).unwrap();
+    let retried = daemon::tick(&b.api, &cfg, now + chrono::Duration::minutes(30)).await;
+    assert_eq!(retried.last().unwrap(), "retried 1 pushes; 0 pushed, 0 still failing");
+    assert!(push::load_journal().unwrap().is_empty());
+
+    let quiet = Daemon { sync_calendar: false, snapshot: false, retry_pushes: false, follow_ups: false, ..Daemon::default() };
+    assert!(daemon::tick(&b.api, &quiet, now).await.is_empty());
+}
+
+#[tokio::test]
+async fn follow_ups_and_rollover_run_once_a_day() {
+    let b = backend().await;
+    let tasks = serde_json::json!([{ "id": 7, "title": "Contract", "type": "todo", "status": "waiting" }]);
+    Mock::given(method("GET")).and(path("/tasks/")).respond_with(ResponseTemplate::new(200).set_body_json(tasks)).mount(&b.server).await;
+    meta::update(7, |m| {
+        m.waiting_on = Some("Alice".into());
+        m.waiting_since = Some("2041-01-01".into());
+    })
+    .unwrap();
+    let cfg = Daemon { sync_calendar: false, snapshot: false, retry_pushes: false, rollover_at: Some("18:00".into()), ..Daemon::default() };
+    let at = |d, h| NaiveDate::from_ymd_opt(2041, 1, d).unwrap().and_hms_opt(h, 0, 0).unwrap();
+
+    assert!(daemon::tick(&b.api, &cfg, at(2, 9)).await.is_empty());
+    assert_eq!(daemon::tick(&b.api, &cfg, at(2, 18)).await, ["rollover: nothing slipped today"]);
+    assert!(daemon::tick(&b.api, &cfg, at(2, 19)).await.is_empty());
+    assert_eq!(daemon::tick(&b.api, &cfg, at(10, 9)).await, ["follow up: [7] Contract (waiting on Alice since 2041-01-01)"]);
+    assert!(daemon::tick(&b.api, &cfg, at(10, 10)).await.is_empty());
+}
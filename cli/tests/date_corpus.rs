@@ -0,0 +1,51 @@
+//! Phrases and what they parse to, kept in tests/fixtures/date_phrases.txt so grammar changes
+//! can't quietly change old phrases. After a deliberate change, rewrite the file with
+//! `UPDATE_DATE_CORPUS=1 cargo test --test date_corpus` and review the diff.
+
+use chrono::NaiveDateTime;
+use std::path::PathBuf;
+use todo::date_parser::{parse_deadline_on, parse_range_at};
+
+fn corpus() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/date_phrases.txt")
+}
+
+/// What one corpus line's phrase gives now; "deadline:" and "range:" say which parser
+fn evaluate(kind: &str, phrase: &str, now: NaiveDateTime) -> String {
+    let out = match kind {
+        "range" => parse_range_at(phrase, now).map(|(s, e)| format!("{} .. {}", s.format("%Y-%m-%dT%H:%M"), e.format("%Y-%m-%dT%H:%M"))),
+        _ => parse_deadline_on(phrase, now.date()).map(|d| match d.alternatives.is_empty() {
+            true => d.chosen,
+            false => format!("{} | {}", d.chosen, d.alternatives.join(", ")),
+        }),
+    };
+    out.unwrap_or_else(|e| format!("error: {}", e))
+}
+
+#[test]
+fn every_phrase_in_the_corpus_still_parses_the_same() {
+    let text = std::fs::read_to_string(corpus()).unwrap();
+    let mut now: Option<NaiveDateTime> = None;
+    let mut rewritten = String::new();
+    let mut changed = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        if let Some(at) = line.strip_prefix("@ ") {
+            now = Some(NaiveDateTime::parse_from_str(at.split(" #").next().unwrap_or(at).trim(), "%Y-%m-%dT%H:%M").unwrap());
+        }
+        let Some((kind, rest)) = line.split_once(": ").filter(|(k, _)| ["deadline", "range"].contains(k)) else {
+            rewritten.push_str(&format!("{}\n", line));
+            continue;
+        };
+        let (phrase, expected) = rest.split_once(" => ").unwrap_or_else(|| panic!("line {}: no ' => '", n + 1));
+        let actual = evaluate(kind, phrase, now.unwrap_or_else(|| panic!("line {}: no '@ ' reference time above", n + 1)));
+        if actual != expected {
+            changed.push(format!("line {}: {} {:?}\n  expected {}\n  got      {}", n + 1, kind, phrase, expected, actual));
+        }
+        rewritten.push_str(&format!("{}: {} => {}\n", kind, phrase, actual));
+    }
+    if std::env::var_os("UPDATE_DATE_CORPUS").is_some() {
+        std::fs::write(corpus(), rewritten).unwrap();
+        return;
+    }
+    assert!(changed.is_empty(), "{} phrase(s) changed:\n{}", changed.len(), changed.join("\n"));
+}
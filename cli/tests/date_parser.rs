@@ -0,0 +1,148 @@
+use chrono::{Datelike, Local, NaiveDate};
+use todo::{config, slots};
+use todo::date_parser::{parse_deadline, parse_duration, parse_range, period_date, period_range};
+
+fn day(m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2030, m, d).unwrap()
+}
+
+#[test]
+fn named_periods_resolve_from_config() {
+    let cfg = config::parse(
+        "[periods]\nsprint = { start = \"2030-01-07\", length = \"2w\" }\nQ3 = \"07-01..09-30\"\n\"exam week\" = [\"2030-06-10..2030-06-14\", \"2030-12-09..2030-12-13\"]",
+    )
+    .unwrap();
+    let today = day(5, 8);
+    assert_eq!(period_date("end of sprint", today, &cfg).unwrap(), Some(day(5, 12)));
+    assert_eq!(period_date("start of next sprint", today, &cfg).unwrap(), Some(day(5, 13)));
+    assert_eq!(period_date("end of q3", today, &cfg).unwrap(), Some(day(9, 30)));
+    assert_eq!(period_date("before exam week", today, &cfg).unwrap(), Some(day(6, 9)));
+    assert_eq!(period_date("before exam week", day(6, 11), &cfg).unwrap(), Some(day(12, 8)));
+    assert_eq!(period_range("next exam week", today, &cfg).unwrap(), Some((day(12, 9), day(12, 13))));
+    assert_eq!(period_date("end of term", today, &cfg).unwrap(), None);
+    assert!(period_date("end of exam week", day(12, 20), &cfg).unwrap_err().contains("No upcoming"));
+
+    let bad = config::parse("[periods]\nsprint = { start = \"2030-01-07\", length = \"fortnight\" }").unwrap();
+    assert!(period_range("sprint", today, &bad).unwrap_err().contains("2w"));
+}
+
+#[test]
+fn ambiguous_phrases_list_their_other_readings() {
+    let d = parse_deadline("3/4/25 at 5 pm").unwrap();
+    assert_eq!((d.chosen.as_str(), d.alternatives), ("2025-03-04T17:00:00", vec!["2025-04-03T17:00:00".to_string()]));
+    assert!(parse_deadline("3/14/25").unwrap().alternatives.is_empty());
+    assert!(parse_deadline("4/4/25").unwrap().alternatives.is_empty());
+    let strict = todo::handlers::check_deadline("3/4/25", true).unwrap_err().to_string();
+    assert!(strict.contains("Tue Mar 4 or Thu Apr 3") && strict.contains("2025-03-04"), "{}", strict);
+}
+
+#[test]
+fn past_deadlines_and_backwards_events_are_refused() {
+    use todo::handlers::{check_event_times, check_past};
+    assert!(check_past("deadline", "2020-01-01T21:00:00", false).unwrap_err().to_string().contains("Wed Jan 1 21:00) is in the past"));
+    assert!(check_past("deadline", "2020-01-01T21:00:00", true).is_ok());
+    assert!(check_past("deadline", "2099-01-01T21:00:00", false).is_ok());
+    let backwards = check_event_times("2099-05-06T10:00:00", "2099-05-06T09:00:00", true).unwrap_err().to_string();
+    assert!(backwards.contains("ends (Wed May 6 09:00) before it starts"), "{}", backwards);
+    assert!(check_event_times("2020-05-06T10:00:00", "2020-05-06T11:00:00", false).is_err());
+
+    let today = Local::now().date_naive();
+    let name = today.format("%A").to_string().to_lowercase();
+    let on = |days: i64| format!("{}T21:00:00", (today + chrono::Duration::days(days)).format("%Y-%m-%d"));
+    let d = parse_deadline(&name).unwrap();
+    assert_eq!((d.chosen, d.alternatives), (on(0), vec![on(7)]));
+    let d = parse_deadline(&format!("next {}", name)).unwrap();
+    assert_eq!((d.chosen, d.alternatives), (on(7), vec![on(14)]));
+    let tomorrow = (today + chrono::Duration::days(1)).weekday().to_string();
+    assert!(parse_deadline(&format!("this {}", tomorrow)).unwrap().alternatives.is_empty());
+}
+
+#[test]
+fn day_parts_name_times_and_spans() {
+    let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
+    let at = |h: u32| tomorrow.and_hms_opt(h, 0, 0).unwrap();
+    assert_eq!(parse_deadline("tomorrow evening").unwrap().chosen, format!("{}T19:00:00", tomorrow));
+    assert_eq!(parse_deadline("tomorrow morning at 8am").unwrap().chosen, format!("{}T08:00:00", tomorrow));
+    assert_eq!(parse_range("tomorrow morning").unwrap(), (at(9), at(14)));
+    assert_eq!(parse_range("tomorrow night").unwrap(), (at(22), (tomorrow + chrono::Duration::days(1)).and_hms_opt(0, 0, 0).unwrap()));
+    let name = tomorrow.format("%A").to_string();
+    assert_eq!(parse_deadline(&format!("{} afternoon", name)).unwrap().chosen, format!("{}T14:00:00", tomorrow));
+
+    let cfg = config::parse("[day_parts]\nevening = \"18:30\"").unwrap();
+    assert_eq!(cfg.day_parts.span("afternoon").map(|s| s.1.map(|t| t.to_string())), Some(Some("18:30:00".into())));
+    assert!(cfg.day_parts.span("noon").is_none());
+}
+
+#[test]
+fn working_days_skip_weekends_and_holidays() {
+    let cfg = config::parse("[week]\nholidays = [\"2030-05-13\"]\ntomorrow_is_workday = true").unwrap();
+    // Fri 2030-05-10: the weekend, then the Monday holiday
+    assert_eq!(cfg.week.next_workday(day(5, 10)), day(5, 14));
+    assert_eq!(cfg.week.tomorrow(day(5, 10)), day(5, 14));
+    assert_eq!(cfg.week.tomorrow(day(5, 8)), day(5, 9));
+    assert_eq!(config::Config::default().week.tomorrow(day(5, 10)), day(5, 11));
+    // A Friday holiday moves "end of week" back to Thursday
+    let cfg = config::parse("[week]\nholidays = [\"2030-05-10\"]").unwrap();
+    assert_eq!(cfg.week.last_workday(day(5, 8)), day(5, 9));
+
+    let today = Local::now().date_naive();
+    let next = |name: &str| parse_deadline(&format!("next {}", name)).unwrap().chosen;
+    let after = parse_deadline("monday after next").unwrap().chosen;
+    let coming = slots::parse_datetime(&next("monday")).unwrap().date();
+    assert_eq!(after, format!("{}T21:00:00", coming + chrono::Duration::weeks(1)));
+    assert!(coming > today && parse_deadline("monday after next").unwrap().alternatives.is_empty());
+    assert!(parse_deadline("next workday").is_ok());
+}
+
+#[test]
+fn language_packs_read_native_phrases() {
+    use todo::lang;
+    let cfg = config::Config::default();
+    let zh = lang::pack("zh-CN").unwrap();
+    let ja = lang::pack("ja_JP").unwrap();
+    assert!(lang::pack("fr").is_none());
+    // Wed 2030-05-08; weeks start on Monday
+    let today = day(5, 8);
+    let at = |d: NaiveDate, h: u32, m: u32| Some((d, Some(chrono::NaiveTime::from_hms_opt(h, m, 0).unwrap())));
+    assert_eq!(zh.read("明天", today, &cfg), Some((day(5, 9), None)));
+    assert_eq!(zh.read("下周五", today, &cfg), Some((day(5, 17), None)));
+    assert_eq!(zh.read("周一", today, &cfg), Some((day(5, 13), None)));
+    assert_eq!(zh.read("3天后", today, &cfg), Some((day(5, 11), None)));
+    assert_eq!(zh.read("十天后", today, &cfg), Some((day(5, 18), None)));
+    assert_eq!(zh.read("明天下午3点半", today, &cfg), at(day(5, 9), 15, 30));
+    assert_eq!(zh.read("后天晚上", today, &cfg), at(day(5, 10), 19, 0));
+    assert_eq!(ja.read("来週の金曜日", today, &cfg), Some((day(5, 17), None)));
+    assert_eq!(ja.read("明日 午後2時", today, &cfg), at(day(5, 9), 14, 0));
+    assert_eq!(ja.read("3日後 15:00", today, &cfg), at(day(5, 11), 15, 0));
+    assert_eq!(zh.read("tomorrow", today, &cfg), None);
+    assert_eq!(ja.read("来週の何か", today, &cfg), None);
+    assert_eq!(zh.read("99999999999天后", today, &cfg), None);
+    assert_eq!(ja.read("9223372036854775807週間後", today, &cfg), None);
+}
+
+#[test]
+fn dictated_phrases_are_tidied_for_the_parser() {
+    use todo::date_parser::spoken;
+    assert_eq!(spoken("due next thursday at five thirty in the afternoon"), "next thursday at 5:30 pm");
+    assert_eq!(spoken("Tomorrow at half past nine"), "tomorrow at 9:30 am");
+    assert_eq!(spoken("friday at quarter to three"), "friday at 2:45 pm");
+    assert_eq!(spoken("by monday noon"), "monday at 12:00 pm");
+    assert_eq!(spoken("seven o'clock tonight"), "today at 7:00 pm");
+    assert_eq!(spoken("next friday in the evening"), "next friday evening");
+    assert_eq!(spoken("3/4/25 at 5 pm"), "3/4/25 at 5:00 pm");
+    assert_eq!(spoken("next three days"), "next 3 days");
+    assert_eq!(spoken("2030-05-06..2030-05-10"), "2030-05-06..2030-05-10");
+    assert_eq!(spoken("Friday, at 5.30 p.m."), "friday at 5:30 pm");
+    let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
+    assert_eq!(parse_deadline(&spoken("tomorrow at five thirty in the afternoon")).unwrap().chosen, format!("{}T17:30:00", tomorrow));
+}
+
+#[test]
+fn numbers_past_the_calendar_are_errors() {
+    assert_eq!(parse_duration("1h30m").unwrap(), chrono::Duration::minutes(90));
+    for huge in ["9223372036854775807", "99999999999999999h", "9999999999999999999999h5m"] {
+        assert!(parse_duration(huge).is_err(), "{}", huge);
+    }
+    assert!(parse_range("next 99999999999 days").unwrap_err().to_string().contains("past the end of the calendar"));
+    assert!(parse_range("2030-05-06..+262142-12-31").unwrap_err().to_string().contains("past the end of the calendar"));
+}
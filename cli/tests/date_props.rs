@@ -0,0 +1,86 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use proptest::prelude::*;
+use todo::date_parser::{parse_deadline_on, parse_range_at};
+use todo::slots::parse_datetime;
+
+/// Any day from 2000 to 2099, plus the days most likely to break date arithmetic
+fn reference_day() -> impl Strategy<Value = NaiveDate> {
+    let ymd = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    prop_oneof![
+        (0i64..36524).prop_map(|n| NaiveDate::from_ymd_opt(2000, 1, 1).unwrap() + Duration::days(n)),
+        // Leap days, month ends, year ends and DST changeovers
+        prop::sample::select(vec![
+            ymd(2028, 2, 28), ymd(2028, 2, 29), ymd(2029, 2, 28), ymd(2030, 1, 31), ymd(2030, 3, 30), ymd(2030, 3, 31),
+            ymd(2030, 10, 27), ymd(2030, 11, 3), ymd(2030, 12, 31), ymd(2099, 12, 31),
+        ]),
+    ]
+}
+
+fn day_of(chosen: &str) -> NaiveDate {
+    parse_datetime(chosen).unwrap().date()
+}
+
+const WEEKDAYS: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+proptest! {
+    // The parser builds its regexes per call, so keep the suite quick
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn relative_days_count_from_the_reference_day(today in reference_day()) {
+        let on = |p: &str| day_of(&parse_deadline_on(p, today).unwrap().chosen);
+        prop_assert_eq!(on("today"), today);
+        prop_assert_eq!(on("tomorrow"), today + Duration::days(1));
+        prop_assert_eq!(on("day after tomorrow"), today + Duration::days(2));
+        prop_assert_eq!(on("yesterday"), today - Duration::days(1));
+        prop_assert_eq!(on("next week"), today + Duration::weeks(1));
+        let t = parse_datetime(&parse_deadline_on("tomorrow at 5:30 pm", today).unwrap().chosen).unwrap();
+        prop_assert_eq!(t.time(), NaiveTime::from_hms_opt(17, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn weekdays_land_on_that_weekday_within_a_week(today in reference_day(), wd in 0usize..7) {
+        let name = WEEKDAYS[wd];
+        let plain = day_of(&parse_deadline_on(name, today).unwrap().chosen);
+        let next = day_of(&parse_deadline_on(&format!("next {}", name), today).unwrap().chosen);
+        let after = day_of(&parse_deadline_on(&format!("{} after next", name), today).unwrap().chosen);
+        let expected = Weekday::try_from(wd as u8).unwrap();
+        prop_assert_eq!((plain.weekday(), next.weekday(), after.weekday()), (expected, expected, expected));
+        prop_assert!(plain >= today && plain < today + Duration::weeks(1));
+        prop_assert!(next > today && next <= today + Duration::weeks(1));
+        prop_assert_eq!(after, next + Duration::weeks(1));
+    }
+
+    #[test]
+    fn months_clamp_to_their_last_day(today in reference_day()) {
+        let next = day_of(&parse_deadline_on("next month", today).unwrap().chosen);
+        prop_assert_eq!((next.year() * 12 + next.month0() as i32) - (today.year() * 12 + today.month0() as i32), 1);
+        prop_assert!(next.day() == today.day() || (next.day() < today.day() && (next + Duration::days(1)).day() == 1));
+        let end = day_of(&parse_deadline_on("end of month", today).unwrap().chosen);
+        prop_assert_eq!((end.year(), end.month()), (today.year(), today.month()));
+        prop_assert_eq!((end + Duration::days(1)).day(), 1);
+    }
+
+    #[test]
+    fn slash_dates_round_trip(day in (0i64..36524).prop_map(|n| NaiveDate::from_ymd_opt(2000, 1, 1).unwrap() + Duration::days(n))) {
+        let phrase = format!("{}/{}/{:02}", day.month(), day.day(), day.year() % 100);
+        let d = parse_deadline_on(&phrase, day).unwrap();
+        prop_assert_eq!(day_of(&d.chosen), day);
+        // The day-first reading is only offered when it's a real, different date
+        for alt in &d.alternatives {
+            let alt = day_of(alt);
+            prop_assert_eq!((alt.month(), alt.day()), (day.day(), day.month()));
+        }
+    }
+
+    #[test]
+    fn ranges_are_whole_days_from_midnight(today in reference_day(), hour in 0u32..24, n in 1i64..30) {
+        let now = today.and_hms_opt(hour, 15, 0).unwrap();
+        let midnight = |d: NaiveDate| d.and_hms_opt(0, 0, 0).unwrap();
+        prop_assert_eq!(parse_range_at("tomorrow", now).unwrap(), (midnight(today + Duration::days(1)), midnight(today + Duration::days(2))));
+        prop_assert_eq!(parse_range_at("today", now).unwrap(), (now, midnight(today + Duration::days(1))));
+        prop_assert_eq!(parse_range_at(&format!("next {} days", n), now).unwrap(), (now, midnight(today + Duration::days(n + 1))));
+        let (start, end) = parse_range_at("next week", now).unwrap();
+        prop_assert_eq!((end - start, start.time()), (Duration::weeks(1), NaiveTime::MIN));
+    }
+}
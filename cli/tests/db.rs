@@ -0,0 +1,43 @@
+mod common;
+
+use common::isolate_paths;
+use serde_json::Value;
+use std::fs;
+use todo::paths::data_dir;
+use todo::{audit, db, views};
+
+#[test]
+fn old_json_stores_move_in_and_damage_is_repaired() {
+    isolate_paths();
+    fs::create_dir_all(data_dir()).unwrap();
+    fs::write(data_dir().join("views.json"), "{\"work\":\"category:Work\"}\n").unwrap();
+    assert_eq!(views::load().unwrap().get("work").map(String::as_str), Some("category:Work"));
+    assert!(!data_dir().join("views.json").exists());
+
+    // A log brought back by `migrate-data` goes in before what's already there
+    audit::record("create", None, &Value::Null, true, &Value::Null);
+    let old = r#"{"timestamp":"2020-01-01T00:00:00","action":"delete","task_id":4,"payload":null,"ok":true,"result":null}"#;
+    fs::write(data_dir().join("audit.jsonl"), format!("{}\n", old)).unwrap();
+    let actions: Vec<String> = audit::read_all().unwrap().into_iter().map(|e| e.action).collect();
+    assert_eq!(actions, ["delete", "create"]);
+
+    // A torn line is found, quarantined, and the rest of the store still reads
+    db::append_line(audit::STORE, "{\"action\":").unwrap();
+    let problems = db::check().unwrap();
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].starts_with("audit line 2 isn't valid"), "{:?}", problems);
+    assert!(db::repair().unwrap()[0].starts_with("quarantined audit line 2"));
+    assert!(db::check().unwrap().is_empty());
+    assert!(db::repair().unwrap().is_empty());
+
+    // A file that isn't a database any more is moved aside and rebuilt
+    fs::write(db::path(), "overwritten by something else\n".repeat(200)).unwrap();
+    assert!(db::check().unwrap()[0].starts_with("Can't open"));
+    assert!(views::load().is_err());
+    let done = db::repair().unwrap();
+    assert!(done[0].starts_with("moved the damaged database"), "{:?}", done);
+    assert!(db::check().unwrap().is_empty());
+    assert!(views::load().unwrap().is_empty());
+    let aside = fs::read_dir(data_dir()).unwrap().filter_map(|e| e.ok()).any(|e| e.file_name().to_string_lossy().starts_with("todo.db.corrupt-"));
+    assert!(aside);
+}
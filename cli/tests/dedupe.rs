@@ -0,0 +1,54 @@
+use serde_json::json;
+use todo::commands::Task;
+use todo::dedupe;
+use todo::meta::{Comment, TaskMeta};
+
+fn tasks() -> Vec<Task> {
+    serde_json::from_value(json!([
+        { "id": 4, "title": "Call the dentist", "type": "todo", "status": "pending", "priority": 2,
+          "deadline": "2030-05-07T17:00:00", "description": "Ask about Tuesday" },
+        { "id": 9, "title": "call the dentist!", "type": "todo", "status": "pending", "priority": 5,
+          "deadline": "2030-05-07T09:00:00", "description": "Bring insurance card" },
+        { "id": 11, "title": "Call the dentists", "type": "todo", "status": "pending",
+          "deadline": "2030-05-07T12:00:00", "description": "Ask about Tuesday" },
+        { "id": 12, "title": "Call the dentist", "type": "todo", "status": "pending", "deadline": "2030-05-08T09:00:00" },
+        { "id": 13, "title": "Call the dentist", "type": "todo", "status": "done", "deadline": "2030-05-07T09:00:00" },
+        { "id": 14, "title": "Water plants", "type": "todo", "status": "pending", "deadline": "2030-05-07T09:00:00" }
+    ]))
+    .unwrap()
+}
+
+#[test]
+fn clusters_need_similar_titles_and_the_same_deadline_day() {
+    let ids: Vec<Vec<i32>> = dedupe::clusters(&tasks(), 0.85).iter().map(|c| c.iter().map(|t| t.id).collect()).collect();
+    assert_eq!(ids, vec![vec![4, 9, 11]]);
+    assert!(dedupe::clusters(&tasks(), 0.99).iter().all(|c| c.len() == 2));
+}
+
+#[test]
+fn merge_keeps_the_oldest_task_with_the_strongest_fields() {
+    let cluster = &dedupe::clusters(&tasks(), 0.85)[0];
+    let plan = dedupe::plan_merge(cluster);
+    assert_eq!(plan.keep, 4);
+    assert_eq!(plan.remove, vec![9, 11]);
+    assert_eq!(plan.deadline.as_deref(), Some("2030-05-07T09:00:00"));
+    assert_eq!(plan.priority, Some(5));
+    assert_eq!(plan.description.as_deref(), Some("Ask about Tuesday\n\nBring insurance card"));
+}
+
+#[test]
+fn merged_duplicates_bring_their_notes_and_fields() {
+    let comment = |at: &str, text: &str| Comment { at: at.into(), text: text.into() };
+    let mut kept = TaskMeta { fields: [("client".to_string(), "acme".to_string())].into(), comments: vec![comment("2030-05-06 10:00", "booked")], ..Default::default() };
+    kept.absorb(TaskMeta {
+        attachments: vec!["https://example.com/form".into()],
+        waiting_on: Some("Alice".into()),
+        fields: [("client".to_string(), "other".to_string()), ("room".to_string(), "4".to_string())].into(),
+        comments: vec![comment("2030-05-05 09:00", "called them")],
+        created_at: Some("2030-05-01T08:00:00".into()),
+        ..Default::default()
+    });
+    assert_eq!((kept.fields["client"].as_str(), kept.fields["room"].as_str()), ("acme", "4"));
+    assert_eq!(kept.comments.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(), ["called them", "booked"]);
+    assert_eq!((kept.attachments.len(), kept.waiting_on.as_deref(), kept.created_at.as_deref()), (1, Some("Alice"), Some("2030-05-01T08:00:00")));
+}
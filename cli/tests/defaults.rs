@@ -0,0 +1,18 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use todo::commands;
+
+fn argv(s: &str) -> Vec<OsString> {
+    s.split_whitespace().map(OsString::from).collect()
+}
+
+#[test]
+fn configured_flags_go_right_after_the_command_name() {
+    let defaults = BTreeMap::from([("create-todo".to_string(), vec!["-p".to_string(), "4".to_string()])]);
+    // Aliases find the same defaults, and global options before the command are left alone
+    assert_eq!(
+        commands::with_defaults(argv("todo --data-dir /tmp/x ct Milk -e 10 -d tomorrow -p 7"), &defaults),
+        argv("todo --data-dir /tmp/x ct -p 4 Milk -e 10 -d tomorrow -p 7")
+    );
+    assert_eq!(commands::with_defaults(argv("todo lt"), &defaults), argv("todo lt"));
+}
@@ -0,0 +1,43 @@
+use serde_json::json;
+use todo::commands::Task;
+use todo::display;
+
+#[test]
+fn accessible_lines_spell_out_each_field() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 42, "title": "Write report", "type": "todo", "status": "pending", "priority": 8, "contexts": ["desk"] }
+    ]))
+    .unwrap();
+    let streak = "🔥 3-day streak".to_string();
+    let line = display::task_sentence(&tasks[0], 2, &[], &Default::default(), Some(&streak));
+    assert_eq!(line, "Task 42, title Write report, priority high, status pending, tags @desk, 3-day streak, reference %2");
+}
+
+#[test]
+fn alfred_items_carry_the_task_id_as_argument() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 7, "title": "Buy milk", "type": "todo", "status": "pending", "priority": 8, "estimate": 90, "contexts": ["errands"] }
+    ]))
+    .unwrap();
+    let out = display::alfred_items(&tasks.iter().collect::<Vec<_>>());
+    let item = &out["items"][0];
+    assert_eq!((item["title"].as_str(), item["arg"].as_str(), item["uid"].as_str()), (Some("Buy milk"), Some("7"), Some("task-7")));
+    assert_eq!(item["subtitle"], "1h 30m · High · pending · @errands");
+}
+
+#[test]
+fn category_icons_go_before_the_title_when_on() {
+    let t: Task = serde_json::from_value(json!({
+        "id": 7, "title": "Literature review", "type": "todo", "category": { "id": 1, "name": "Thesis", "color": "#3366FF", "icon": "📚" }
+    }))
+    .unwrap();
+    assert_eq!(display::with_icon(&t, true), "📚 Literature review");
+    assert_eq!(display::with_icon(&t, false), "Literature review");
+    assert_eq!(display::task_row(&t)[1], "📚 Literature review");
+}
+
+#[test]
+fn references_put_the_id_where_scripts_can_find_it() {
+    assert_eq!(display::reference("Created todo", 42, Some("Write report")), "Created todo task [ID 42] Write report");
+    assert_eq!(display::reference("Deleted", 7, None), "Deleted task [ID 7]");
+}
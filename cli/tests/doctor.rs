@@ -0,0 +1,52 @@
+mod common;
+
+use common::{backend, fixture};
+use serde_json::json;
+use todo::commands::{Category, Task};
+use todo::doctor::{self, Status};
+use todo::push;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+#[test]
+fn model_fields_come_from_the_structs() {
+    let fields = doctor::fields_of::<Task>();
+    assert!(fields.contains(&"type") && fields.contains(&"deadline") && !fields.contains(&"kind"));
+    assert_eq!(doctor::fields_of::<Category>(), ["id", "name", "color", "icon"]);
+}
+
+#[tokio::test]
+async fn schema_drift_lists_missing_and_unknown_fields() {
+    let b = backend().await;
+    let mut task = fixture("task_todo");
+    let obj = task.as_object_mut().unwrap();
+    obj.insert("effort".into(), json!(3));
+    obj.insert("category".into(), json!({"id": 1, "name": "Thesis", "color": "#fff", "parent": 2}));
+    let tasks = json!([task]);
+    for route in ["/tasks/", "/taskslist/"] {
+        Mock::given(method("GET")).and(path(route)).respond_with(ResponseTemplate::new(200).set_body_json(&tasks)).mount(&b.server).await;
+    }
+    Mock::given(method("GET")).and(path("/tasks/12")).respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "twelve"}))).mount(&b.server).await;
+    b.stub("GET", "/categories/", "categories").await;
+
+    let checks = doctor::schema_checks(b.api.client(), b.api.base_url()).await;
+    let by_name = |n: &str| checks.iter().find(|c| c.name == n).unwrap();
+    let tasks = by_name("GET /tasks/");
+    assert!(tasks.status == Status::Warn);
+    // The fixture predates assignees and meeting details
+    assert!(tasks.detail.starts_with("missing description, ") && tasks.detail.contains("assignee"), "{}", tasks.detail);
+    assert!(tasks.detail.ends_with("not read by the CLI: effort, category.parent"), "{}", tasks.detail);
+    assert!(by_name("GET /categories/").status == Status::Pass);
+    let one = by_name("GET /tasks/{id}");
+    assert!(one.status == Status::Fail && one.detail.contains("doesn't parse"), "{}", one.detail);
+}
+
+#[tokio::test]
+async fn leftover_pushes_point_at_resume() {
+    let b = backend().await;
+    todo::db::write_doc(push::JOURNAL, &push::Journal { failed: vec![4], not_reached: vec![5, 6] }).unwrap();
+    let checks = doctor::run_checks(b.api.client(), b.api.base_url()).await;
+    let pushes = checks.iter().find(|c| c.name == "Pushes").unwrap();
+    assert!(pushes.status == Status::Warn && pushes.detail == "1 failed, 2 not reached by the last push-all", "{}", pushes.detail);
+    assert_eq!(pushes.hint.as_deref(), Some("Run `todo push-all --resume`"));
+}
@@ -0,0 +1,411 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+use serde_json::json;
+use todo::commands::Task;
+use todo::config::Config;
+use todo::engine::{self, Options};
+use todo::meta::{MetaMap, TaskMeta};
+
+fn at(day: u32, h: u32, m: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2030, 5, day).unwrap().and_hms_opt(h, m, 0).unwrap()
+}
+
+fn todo(id: i32, estimate: i32, deadline: &str, priority: i32) -> Task {
+    serde_json::from_value(json!({
+        "id": id, "title": format!("todo {}", id), "type": "todo", "status": "pending",
+        "priority": priority, "estimate": estimate, "deadline": deadline
+    }))
+    .unwrap()
+}
+
+fn placed_at(mut task: Task, start: &str, end: &str) -> Task {
+    task.start_time = Some(start.into());
+    task.end_time = Some(end.into());
+    task
+}
+
+fn in_category(mut task: Task, name: &str) -> Task {
+    task.category = Some(serde_json::from_value(json!({ "id": 1, "name": name, "color": "#000000" })).unwrap());
+    task
+}
+
+fn event(id: i32, start: &str, end: &str) -> Task {
+    serde_json::from_value(json!({
+        "id": id, "title": "meeting", "type": "event", "start_time": start, "end_time": end
+    }))
+    .unwrap()
+}
+
+fn config(toml_availability: &str) -> Config {
+    toml::from_str(&format!("[availability]\n{}", toml_availability)).unwrap()
+}
+
+// 2030-05-06 is a Monday
+#[test]
+fn skips_lunch_break() {
+    let cfg = config(r#"breaks = ["12:00-13:00"]"#);
+    let tasks = vec![
+        event(1, "2030-05-06T09:00:00", "2030-05-06T11:00:00"),
+        todo(2, 120, "2030-05-10T17:00:00", 5),
+    ];
+    let opts = Options::new(&cfg, at(6, 8, 0));
+    let plan = engine::plan(&tasks, &opts);
+    assert_eq!(plan.placed[0].slot.start, at(6, 13, 0));
+    assert_eq!(plan.placed[0].slot.end, at(6, 15, 0));
+}
+
+#[test]
+fn daily_cap_pushes_work_to_the_next_day() {
+    let cfg = config("max_hours_per_day = 2.5");
+    let tasks = vec![todo(1, 120, "2030-05-10T17:00:00", 9), todo(2, 120, "2030-05-10T17:00:00", 1)];
+    let opts = Options::new(&cfg, at(6, 8, 0));
+    let plan = engine::plan(&tasks, &opts);
+    let starts: Vec<_> = plan.placed.iter().map(|p| (p.task_id, p.slot.start)).collect();
+    assert_eq!(starts, vec![(1, at(6, 9, 0)), (2, at(7, 9, 0))]);
+}
+
+#[test]
+fn reports_tasks_that_cannot_fit() {
+    let cfg = config(r#"focus = ["mon 09:00-17:00"]"#);
+    let tasks = vec![todo(1, 60, "2030-05-06T17:00:00", 1), todo(2, 60, "2030-05-01T17:00:00", 1)];
+    let opts = Options::new(&cfg, at(6, 8, 0));
+    let plan = engine::plan(&tasks, &opts);
+    assert!(plan.placed.is_empty());
+    let reasons: Vec<_> = plan.unplaced.iter().map(|u| (u.task_id, u.reason.as_str())).collect();
+    assert!(reasons.contains(&(1, "no free slot before the deadline")));
+    assert!(reasons.contains(&(2, "deadline has passed")));
+}
+
+#[test]
+fn payload_cuts_breaks_and_focus_out_of_windows() {
+    let cfg = config(
+        r#"breaks = ["12:00-13:00"]
+focus = ["mon 09:00-10:30"]
+max_hours_per_day = 6"#,
+    );
+    let payload = cfg.schedule_payload();
+    assert_eq!(
+        payload["availability"]["0"],
+        json!([{ "start": "10:30", "end": "12:00" }, { "start": "13:00", "end": "17:00" }])
+    );
+    assert_eq!(
+        payload["availability"]["1"],
+        json!([{ "start": "09:00", "end": "12:00" }, { "start": "13:00", "end": "17:00" }])
+    );
+    assert_eq!(payload["max_hours_per_day"], json!(6.0));
+}
+
+#[test]
+fn category_share_spreads_work_across_days() {
+    let mut cfg = config("");
+    cfg.scheduling.max_category_share = Some(0.6);
+    let tasks: Vec<Task> = (1..=3).map(|i| in_category(todo(i, 180, "2030-05-10T17:00:00", 5), "Thesis")).collect();
+    let plan = engine::plan(&tasks, &Options::new(&cfg, at(6, 8, 0)));
+    // 60% of an 8h day is 4.8h, so a second 3h block has to move to the next day
+    let days: Vec<_> = plan.placed.iter().map(|p| p.slot.start.date()).collect();
+    assert_eq!(days, vec![at(6, 0, 0).date(), at(7, 0, 0).date(), at(8, 0, 0).date()]);
+}
+
+#[test]
+fn categories_follow_their_profile() {
+    let cfg: Config = toml::from_str(
+        r#"
+[profiles.personal]
+mon = ["18:00-21:00"]
+tue = ["18:00-21:00"]
+
+[category_profiles]
+Home = "personal"
+"#,
+    )
+    .unwrap();
+    let tasks = vec![
+        in_category(todo(1, 60, "2030-05-10T17:00:00", 5), "Thesis"),
+        in_category(todo(2, 60, "2030-05-10T21:00:00", 5), "Home"),
+    ];
+    let plan = engine::plan(&tasks, &Options::new(&cfg, at(6, 8, 0)));
+    let starts: Vec<_> = plan.placed.iter().map(|p| (p.task_id, p.slot.start)).collect();
+    assert_eq!(starts, vec![(1, at(6, 9, 0)), (2, at(6, 18, 0))]);
+
+    let payload = cfg.schedule_payload();
+    assert_eq!(payload["profiles"]["personal"]["0"], json!([{ "start": "18:00", "end": "21:00" }]));
+    assert_eq!(payload["category_profiles"]["Home"], json!("personal"));
+}
+
+#[test]
+fn uncategorized_tasks_use_global_table() {
+    let cfg: Config = toml::from_str("[profiles.personal]\nmon = []\n[category_profiles]\nHome = \"personal\"").unwrap();
+    assert!(cfg.availability_for(Some("Home")).mon.is_empty());
+    assert_eq!(cfg.availability_for(Some("Thesis")).mon.len(), 1);
+    assert_eq!(cfg.availability_for(None).mon.len(), 1);
+}
+
+#[test]
+fn incremental_keeps_valid_placements() {
+    let cfg = config("");
+    let tasks = vec![
+        placed_at(todo(1, 60, "2030-05-10T17:00:00", 1), "2030-05-08T14:00:00", "2030-05-08T15:00:00"),
+        // Estimate changed since it was placed, so it moves
+        placed_at(todo(2, 90, "2030-05-10T17:00:00", 1), "2030-05-08T10:00:00", "2030-05-08T11:00:00"),
+        todo(3, 60, "2030-05-10T17:00:00", 9),
+    ];
+    let full = engine::plan(&tasks, &Options::new(&cfg, at(6, 8, 0)));
+    assert!(full.placed.iter().all(|p| p.slot.start.date() == at(6, 0, 0).date()));
+
+    let plan = engine::plan(&tasks, &Options { stability: Some(1.0), ..Options::new(&cfg, at(6, 8, 0)) });
+    let slots: Vec<_> = plan.placed.iter().map(|p| (p.task_id, p.slot.start, p.kept)).collect();
+    assert_eq!(slots, vec![(3, at(6, 9, 0), false), (2, at(6, 10, 0), false), (1, at(8, 14, 0), true)]);
+}
+
+#[test]
+fn low_stability_lets_urgent_work_bump_placements() {
+    let cfg = config("");
+    let tasks = vec![
+        placed_at(todo(1, 480, "2030-05-10T17:00:00", 1), "2030-05-06T09:00:00", "2030-05-06T17:00:00"),
+        todo(2, 60, "2030-05-06T17:00:00", 9),
+    ];
+    let stiff = engine::plan(&tasks, &Options { stability: Some(1.0), ..Options::new(&cfg, at(6, 8, 0)) });
+    assert_eq!(stiff.unplaced[0].task_id, 2);
+
+    let loose = engine::plan(&tasks, &Options { stability: Some(0.5), ..Options::new(&cfg, at(6, 8, 0)) });
+    assert!(loose.unplaced.is_empty());
+    let slots: Vec<_> = loose.placed.iter().map(|p| (p.task_id, p.slot.start)).collect();
+    assert_eq!(slots, vec![(2, at(6, 9, 0)), (1, at(7, 9, 0))]);
+}
+
+#[test]
+fn explain_reports_score_competitors_and_skipped_days() {
+    let cfg = config("");
+    let tasks = vec![
+        event(1, "2030-05-06T09:00:00", "2030-05-06T11:00:00"),
+        todo(2, 120, "2030-05-07T17:00:00", 9),
+        todo(3, 300, "2030-05-08T17:00:00", 2),
+    ];
+    let opts = Options::new(&cfg, at(6, 8, 0));
+    let e = engine::explain(&tasks, &opts, 3).unwrap();
+    assert_eq!(e.slot.unwrap().start, at(7, 9, 0));
+    assert_eq!((e.rank, e.of), (2, 2));
+    assert_eq!(e.score[0].name, "priority");
+    assert_eq!(e.competitors.iter().map(|c| c.task_id).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(e.skipped_days.len(), 1);
+    assert!(e.skipped_days[0].1.contains("[2] todo 2"), "{}", e.skipped_days[0].1);
+
+    assert!(engine::explain(&tasks, &opts, 1).is_none());
+}
+
+#[test]
+fn quiet_hours_hold_unless_the_task_opts_out() {
+    let cfg: Config = toml::from_str("[scheduling]\ndo_not_schedule = [\"before 12:00\", \"after 16:00\"]").unwrap();
+    let tasks = vec![todo(1, 60, "2030-05-10T17:00:00", 5), todo(2, 60, "2030-05-10T17:00:00", 1)];
+    let plan = engine::plan(&tasks, &Options::new(&cfg, at(6, 8, 0)));
+    assert_eq!(plan.placed[0].slot.start, at(6, 12, 0));
+
+    let mut meta = MetaMap::new();
+    meta.insert(1, TaskMeta { ignore_quiet_hours: true, ..Default::default() });
+    meta.insert(2, TaskMeta { quiet_hours: vec!["mon 12:00-17:00".parse().unwrap()], ..Default::default() });
+    let plan = engine::plan(&tasks, &Options { meta: &meta, ..Options::new(&cfg, at(6, 8, 0)) });
+    let slots: Vec<_> = plan.placed.iter().map(|p| (p.task_id, p.slot.start)).collect();
+    assert_eq!(slots, vec![(1, at(6, 9, 0)), (2, at(7, 12, 0))]);
+}
+
+#[test]
+fn placements_snap_to_the_slot_grid() {
+    let tasks = vec![
+        event(1, "2030-05-06T09:00:00", "2030-05-06T09:20:00"),
+        todo(2, 15, "2030-05-10T17:00:00", 9),
+        todo(3, 15, "2030-05-10T17:00:00", 1),
+    ];
+    let slots = |cfg: &Config| -> Vec<_> {
+        engine::plan(&tasks, &Options::new(cfg, at(6, 8, 0))).placed.iter().map(|p| p.slot.start).collect()
+    };
+    assert_eq!(slots(&config("")), vec![at(6, 9, 30), at(6, 9, 45)]);
+    let fine: Config = toml::from_str("[scheduling]\nslot_minutes = 5").unwrap();
+    assert_eq!(slots(&fine), vec![at(6, 9, 20), at(6, 9, 35)]);
+    assert_eq!(fine.schedule_payload()["slot_minutes"], json!(5));
+}
+
+#[test]
+fn short_admin_tasks_are_batched() {
+    let cfg: Config = toml::from_str("[[scheduling.batching]]\ntags = [\"Email\"]").unwrap();
+    let mut tasks: Vec<Task> = (1..=5).map(|id| in_category(todo(id, 10, "2030-05-10T17:00:00", 5), "email")).collect();
+    tasks.push(todo(6, 60, "2030-05-10T17:00:00", 1));
+    tasks.push(in_category(todo(7, 30, "2030-05-10T17:00:00", 5), "email"));
+    let plan = engine::plan(&tasks, &Options::new(&cfg, at(6, 8, 0)));
+    let slots: BTreeMap<i32, NaiveDateTime> = plan.placed.iter().map(|p| (p.task_id, p.slot.start)).collect();
+    let batched: Vec<_> = (1..=5).map(|id| slots[&id]).collect();
+    assert_eq!(batched, vec![at(6, 9, 0), at(6, 9, 10), at(6, 9, 20), at(6, 9, 30), at(6, 16, 0)]);
+    // Too long to batch, so it's placed like any other task
+    assert_eq!(slots[&7], at(6, 9, 45));
+    assert_eq!(slots[&6], at(6, 10, 15));
+}
+
+#[test]
+fn all_day_events_block_only_when_configured() {
+    let mut birthday = event(1, "2030-05-06T00:00:00", "2030-05-07T00:00:00");
+    birthday.all_day = Some(true);
+    let tasks = vec![birthday, todo(2, 60, "2030-05-10T17:00:00", 1)];
+    let start = |cfg: &Config| engine::plan(&tasks, &Options::new(cfg, at(6, 8, 0))).placed[0].slot.start;
+    assert_eq!(start(&config("")), at(6, 9, 0));
+    let busy: Config = toml::from_str("[scheduling]\nall_day_busy = true").unwrap();
+    assert_eq!(start(&busy), at(7, 9, 0));
+}
+
+#[test]
+fn only_busy_events_hold_time() {
+    let mut optional = event(1, "2030-05-06T09:00:00", "2030-05-06T10:00:00");
+    optional.transparency = Some("tentative".into());
+    let mut lunch = event(2, "2030-05-06T10:00:00", "2030-05-06T11:00:00");
+    lunch.transparency = Some("busy".into());
+    let tasks = vec![optional, lunch, todo(3, 60, "2030-05-10T17:00:00", 1)];
+    let plan = engine::plan(&tasks, &Options::new(&config(""), at(6, 8, 0)));
+    assert_eq!(plan.placed[0].slot.start, at(6, 9, 0));
+}
+
+#[test]
+fn delegated_tasks_are_not_scheduled() {
+    let mut handed_off = todo(1, 60, "2030-05-10T17:00:00", 5);
+    handed_off.status = Some("waiting".into());
+    let plan = engine::plan(&[handed_off, todo(2, 60, "2030-05-10T17:00:00", 1)], &Options::new(&config(""), at(6, 8, 0)));
+    assert_eq!(plan.placed.iter().map(|p| p.task_id).collect::<Vec<_>>(), vec![2]);
+}
+
+#[test]
+fn errands_wait_for_their_context_window() {
+    let mut errand = todo(1, 60, "2030-05-12T17:00:00", 1);
+    errand.contexts = Some(vec!["errands".into()]);
+    let cfg: Config = toml::from_str("[scheduling.context_windows]\nerrands = [\"sat 10:00-12:00\"]").unwrap();
+    let plan = engine::plan(&[errand], &Options::new(&cfg, at(6, 8, 0)));
+    assert_eq!(plan.placed[0].slot.start, at(11, 10, 0));
+}
+
+#[test]
+fn only_my_tasks_use_my_availability() {
+    let mut theirs = todo(1, 60, "2030-05-10T17:00:00", 9);
+    theirs.assignee = Some("anna".into());
+    let mut their_meeting = event(2, "2030-05-06T09:00:00", "2030-05-06T10:00:00");
+    their_meeting.assignee = Some("Anna".into());
+    let mut mine = todo(3, 60, "2030-05-10T17:00:00", 1);
+    mine.assignee = Some("Ben".into());
+    let cfg: Config = toml::from_str("user = \"ben\"").unwrap();
+    let plan = engine::plan(&[theirs, their_meeting, mine, todo(4, 60, "2030-05-10T17:00:00", 0)], &Options::new(&cfg, at(6, 8, 0)));
+    let placed: Vec<(i32, NaiveDateTime)> = plan.placed.iter().map(|p| (p.task_id, p.slot.start)).collect();
+    assert_eq!(placed, vec![(3, at(6, 9, 0)), (4, at(6, 10, 0))]);
+    assert_eq!(cfg.assignee("me").unwrap(), "ben");
+    assert_eq!(cfg.assignee("unassigned").unwrap(), "");
+}
+
+#[test]
+fn weekly_quotas_are_soft_limits() {
+    let cfg: Config = toml::from_str("[quotas]\nWork = \"max 10h\"\nside = \"min 2h\"").unwrap();
+    let meeting = in_category(event(1, "2030-05-06T09:00:00", "2030-05-06T17:00:00"), "work");
+    let tasks = vec![
+        meeting.clone(),
+        in_category(todo(2, 180, "2030-05-20T17:00:00", 9), "work"),
+        in_category(todo(3, 180, "2030-05-08T17:00:00", 1), "work"),
+        in_category(todo(4, 120, "2030-05-20T17:00:00", 0), "side"),
+    ];
+    let plan = engine::plan(&tasks, &Options::new(&cfg, at(6, 8, 0)));
+    let start = |id: i32| plan.placed.iter().find(|p| p.task_id == id).map(|p| p.slot.start);
+    // 8h of meetings leave room for no 3h block this week, except one due before it ends
+    assert_eq!(start(2), Some(at(13, 9, 0)));
+    assert_eq!(start(3), Some(at(7, 11, 0)));
+    // Short of its minimum, side work goes ahead of higher-scoring todos
+    assert_eq!(start(4), Some(at(7, 9, 0)));
+    assert!(plan.unplaced.is_empty());
+
+    let booked = vec![meeting, placed_at(tasks[3].clone(), "2030-05-09T09:00:00", "2030-05-09T10:00:00")];
+    let (first, rows) = todo::report::week(&booked, &cfg, at(7, 12, 0));
+    assert_eq!(first, at(6, 0, 0).date());
+    let summary: Vec<(String, i64, i64, String)> = rows.iter().map(|r| (r.category.clone(), r.actual, r.scheduled, r.verdict())).collect();
+    assert_eq!(summary, vec![("side".into(), 0, 60, "1h under min".into()), ("Work".into(), 480, 0, "ok".into())]);
+    assert_eq!(todo::report::describe(&rows[1]), "Work: 8h done, 0h scheduled, 8h of max 10h, ok.");
+    assert!("max 40".parse::<todo::config::Quota>().is_ok() && "most 4h".parse::<todo::config::Quota>().is_err());
+}
+
+#[test]
+fn eviction_policies_make_room_for_todos_that_do_not_fit() {
+    // Shortest first, so the long important todo comes last and finds Monday taken
+    let with = |policy: &str| -> Config {
+        toml::from_str(&format!("[weights]\npriority = 1.0\nestimate = -1.0\n[scheduling]\neviction = \"{}\"", policy)).unwrap()
+    };
+    let now = at(6, 8, 0);
+    let tasks = vec![todo(1, 240, "2030-05-06T17:00:00", 1), todo(2, 480, "2030-05-06T17:00:00", 9)];
+
+    let cfg = with("drop-lowest-priority");
+    let plan = engine::plan(&tasks, &Options::new(&cfg, now));
+    assert_eq!(plan.placed.iter().map(|p| (p.task_id, p.slot.start)).collect::<Vec<_>>(), vec![(2, at(6, 9, 0))]);
+    assert_eq!(plan.unplaced[0].reason, "dropped to make room for [2] todo 2");
+
+    let cfg = with("overflow-past-deadline-with-warning");
+    let plan = engine::plan(&tasks, &Options::new(&cfg, now));
+    let late: Vec<_> = plan.placed.iter().filter(|p| p.late).map(|p| (p.task_id, p.slot.start)).collect();
+    assert_eq!(late, vec![(2, at(7, 9, 0))]);
+    assert!(plan.unplaced.is_empty());
+
+    let mut optional = todo(1, 240, "", 1);
+    optional.deadline = None;
+    let tasks = vec![optional, todo(2, 360, "2030-05-06T17:00:00", 9)];
+    let cfg = with("shrink-optional");
+    let plan = engine::plan(&tasks, &Options::new(&cfg, now));
+    let placed: Vec<_> = plan.placed.iter().map(|p| (p.task_id, p.slot.start, p.slot.end, p.shrunk_from)).collect();
+    assert_eq!(placed, vec![(1, at(6, 9, 0), at(6, 11, 0), Some(240)), (2, at(6, 11, 0), at(6, 17, 0), None)]);
+    // Without a policy the important todo is simply left out
+    let cfg: Config = toml::from_str("[weights]\npriority = 1.0\nestimate = -1.0").unwrap();
+    assert_eq!(engine::plan(&tasks, &Options::new(&cfg, now)).unplaced[0].task_id, 2);
+}
+
+#[test]
+fn optional_todos_give_way_to_required_ones() {
+    let now = at(6, 8, 0);
+    // Optional todos are planned last, so only one kept from an earlier run can be in the way
+    let mut optional = placed_at(todo(1, 240, "", 1), "2030-05-06T09:00:00", "2030-05-06T13:00:00");
+    (optional.deadline, optional.optional) = (None, Some(true));
+    let tasks = vec![optional, todo(2, 360, "2030-05-06T17:00:00", 9)];
+    let cfg: Config = toml::from_str("[weights]\npriority = 1.0\nestimate = -1.0\n[scheduling]\neviction = \"shrink-optional\"").unwrap();
+    let plan = engine::plan(&tasks, &Options { stability: Some(1.0), ..Options::new(&cfg, now) });
+    let placed: Vec<_> = plan.placed.iter().map(|p| (p.task_id, p.slot.start, p.slot.end, p.shrunk_from)).collect();
+    assert_eq!(placed, vec![(1, at(6, 9, 0), at(6, 11, 0), Some(240)), (2, at(6, 11, 0), at(6, 17, 0), None)]);
+    // Without a policy the optional todo is moved out of the way instead
+    let cfg: Config = toml::from_str("[weights]\npriority = 1.0\nestimate = -1.0").unwrap();
+    let plan = engine::plan(&tasks, &Options { stability: Some(1.0), ..Options::new(&cfg, now) });
+    let placed: Vec<_> = plan.placed.iter().map(|p| (p.task_id, p.slot.start)).collect();
+    assert_eq!(placed, vec![(2, at(6, 9, 0)), (1, at(7, 9, 0))]);
+}
+
+#[test]
+fn optional_todos_only_get_what_is_left() {
+    let cfg = Config::default();
+    let now = at(6, 8, 0);
+    let mut nice = todo(1, 120, "2030-05-06T17:00:00", 9);
+    nice.optional = Some(true);
+    let tasks = vec![nice, todo(2, 480, "2030-05-06T17:00:00", 1)];
+    let plan = engine::plan(&tasks, &Options::new(&cfg, now));
+    assert_eq!(plan.placed.iter().map(|p| p.task_id).collect::<Vec<_>>(), vec![2]);
+    assert!(plan.unplaced[0].reason.starts_with("optional, no slack left"));
+
+    let explained = engine::explain(&tasks, &Options::new(&cfg, now), 1).unwrap();
+    assert_eq!((explained.rank, explained.of), (2, 2));
+}
+
+#[test]
+fn day_templates_replace_the_hours_of_their_dates() {
+    let mut cfg: Config = toml::from_str(
+        "[day_templates.travel]\n[day_templates.office]\nwindows = [\"08:30-12:00\", \"13:00-17:30\"]\nbuffer_before = 45\nbuffer_after = 30",
+    )
+    .unwrap();
+    let office = &cfg.day_template("Office").unwrap().1.effective_windows();
+    assert_eq!(office.iter().cloned().map(String::from).collect::<Vec<_>>(), vec!["09:15-12:00", "13:00-17:00"]);
+    // Buffers longer than what's left of the day leave nothing, rather than wrapping around midnight
+    let late: Config = toml::from_str("[day_templates.late]\nwindows = [\"20:00-23:00\"]\nbuffer_before = 300").unwrap();
+    assert!(late.day_template("late").unwrap().1.effective_windows().is_empty());
+    let early: Config = toml::from_str("[day_templates.early]\nwindows = [\"01:00-03:00\"]\nbuffer_after = 300").unwrap();
+    assert!(early.day_template("early").unwrap().1.effective_windows().is_empty());
+
+    cfg.assign_days(&BTreeMap::from([
+        (NaiveDate::from_ymd_opt(2030, 5, 6).unwrap(), "travel".to_string()),
+        (NaiveDate::from_ymd_opt(2030, 5, 7).unwrap(), "office".to_string()),
+    ]));
+    let plan = engine::plan(&[todo(1, 60, "2030-05-10T17:00:00", 1)], &Options::new(&cfg, at(6, 8, 0)));
+    assert_eq!(plan.placed[0].slot.start, at(7, 9, 15));
+    assert_eq!(cfg.schedule_payload()["dates"]["2030-05-06"], json!([]));
+}
@@ -0,0 +1,67 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::json;
+use todo::commands::Task;
+use todo::filter::Filter;
+use todo::meta::{MetaMap, TaskMeta};
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(9, 0, 0).unwrap()
+}
+
+fn tasks() -> Vec<Task> {
+    serde_json::from_value(json!([
+        { "id": 1, "title": "Ship release", "type": "todo", "status": "pending", "priority": 8 },
+        { "id": 2, "title": "Pay rent", "type": "todo", "status": "pending", "priority": 2, "deadline": "2030-05-07T12:00:00" },
+        { "id": 3, "title": "Pay invoice", "type": "todo", "status": "done", "priority": 9, "deadline": "2030-05-06T17:00:00" },
+        { "id": 4, "title": "Plan trip", "type": "todo", "status": "pending", "deadline": "2030-05-20T09:00:00",
+          "contexts": ["home"], "category": { "id": 1, "name": "Personal", "color": "#00aa00" } }
+    ]))
+    .unwrap()
+}
+
+fn ids(expr: &str) -> Vec<i32> {
+    let f: Filter = expr.parse().unwrap();
+    let fields = |pairs: &[(&str, &str)]| TaskMeta {
+        fields: pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        ..Default::default()
+    };
+    let meta = MetaMap::from([(1, fields(&[("effort_points", "13")])), (2, fields(&[("effort_points", "5"), ("client", "Acme")]))]);
+    tasks().iter().filter(|t| f.matches(t, &meta, now())).map(|t| t.id).collect()
+}
+
+#[test]
+fn and_binds_tighter_than_or() {
+    assert_eq!(ids("status!=done AND (priority>=7 OR due<2d)"), vec![1, 2]);
+    assert_eq!(ids("status != done and priority >= 7 or due < 2d"), vec![1, 2, 3]);
+}
+
+#[test]
+fn dates_text_and_contexts() {
+    assert_eq!(ids("due=tomorrow"), vec![2]);
+    assert_eq!(ids("due>=2030-05-07"), vec![2, 4]);
+    assert_eq!(ids("title~pay AND status=done"), vec![3]);
+    assert_eq!(ids("context=@home OR category='personal'"), vec![4]);
+}
+
+#[test]
+fn mistakes_are_reported() {
+    for bad in ["priority>=high", "size>3", "stauts!=done", "+size-x>3", "status<done", "priority>=7 due<2d", "(status=done", "due<soon", "due<999999999999d", "due<99999999999d"] {
+        assert!(bad.parse::<Filter>().is_err(), "{} should not parse", bad);
+    }
+}
+
+#[test]
+fn not_and_period_keywords() {
+    // 2030-05-06 is a Monday, so the week ends at midnight going into the 13th
+    assert_eq!(ids("due<eow"), vec![2, 3]);
+    assert_eq!(ids("NOT status=done AND due<eom"), vec![2, 4]);
+    assert_eq!(ids("not (priority>=7 or context=home)"), vec![2]);
+}
+
+#[test]
+fn custom_fields_compare_as_numbers_or_text() {
+    assert_eq!(ids("+effort_points>=8"), vec![1]);
+    assert_eq!(ids("+effort_points<8 OR +client~acme"), vec![2]);
+    assert_eq!(ids("+client!=acme"), vec![1, 3, 4]);
+    assert!(todo::filter::is_reserved("due") && !todo::filter::is_reserved("effort_points"));
+}
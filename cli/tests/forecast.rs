@@ -0,0 +1,31 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::commands::Task;
+use todo::config::Config;
+use todo::forecast;
+
+// 2030-05-06 is a Monday; default availability adds up to 48h a week
+#[test]
+fn warns_about_long_days_and_an_overcommitted_week() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Workshop", "type": "event", "start_time": "2030-05-06T09:00:00", "end_time": "2030-05-06T12:00:00" },
+        { "id": 2, "title": "Report", "type": "todo", "status": "pending", "estimate": 390,
+          "start_time": "2030-05-06T12:00:00", "end_time": "2030-05-06T18:30:00" },
+        { "id": 3, "title": "Migration", "type": "todo", "status": "pending", "estimate": 3000, "deadline": "2030-05-10T17:00:00" },
+        { "id": 4, "title": "Old", "type": "todo", "status": "done", "start_time": "2030-05-07T09:00:00", "end_time": "2030-05-07T18:00:00" }
+    ]))
+    .unwrap();
+    let mut cfg = Config::default();
+    let now = NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(8, 0, 0).unwrap();
+    assert!(forecast::warnings(&tasks, &cfg, now).is_empty());
+
+    cfg.scheduling.max_daily_hours = Some(8.0);
+    cfg.scheduling.overcommit_percent = Some(10.0);
+    assert_eq!(
+        forecast::warnings(&tasks, &cfg, now),
+        vec![
+            "Mon 2030-05-06: 9h 30m scheduled, over your 8h a day",
+            "The next 7 days have 59h 30m committed against 48h available (24% over)",
+        ]
+    );
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{backend, fixture, json};
+use serde_json::json as j;
+use todo::commands::Task;
+use todo::habits::{self, Every, Habit};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::Mock;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2030, 5, d).unwrap()
+}
+
+#[test]
+fn weekdays_skip_the_weekend() {
+    // 2030-05-10 is a Friday
+    assert_eq!(Every::Weekday.next(day(10)), day(13));
+    assert_eq!(Every::Week.next(day(10)), day(17));
+}
+
+#[test]
+fn misses_break_the_streak() {
+    let mut h = Habit::new("Stretch", Every::Day, 1, day(1));
+    for d in 1..=3 {
+        h.complete(day(d));
+    }
+    assert_eq!(h.badge(day(3)).as_deref(), Some("🔥 3-day streak"));
+    // Nothing done on the 4th or 5th
+    assert_eq!(h.streak(day(6)), 0);
+    assert_eq!(h.catch_up(day(6)), Some(day(6)));
+    assert_eq!((h.misses(), h.best()), (2, 3));
+    h.complete(day(6));
+    assert_eq!((h.streak(day(7)), h.due), (1, day(7)));
+}
+
+#[tokio::test]
+async fn finishing_an_instance_creates_the_next() {
+    let b = backend().await;
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .and(body_partial_json(j!({ "title": "Pay rent", "deadline": "2030-06-01T21:00:00", "estimate": 15 })))
+        .respond_with(json(200, "task_updated"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    let done: Task = serde_json::from_value(fixture("task_todo")).unwrap();
+    let mut all = habits::Habits::new();
+    all.insert(done.id, Habit::new("Pay rent", Every::Day, done.id, day(31)));
+    habits::save(&all).unwrap();
+
+    let next = habits::completed(&b.api, &done, day(31)).await.unwrap().expect("next instance");
+    let h = habits::load().unwrap()[&done.id].clone();
+    assert_eq!((h.task_id, h.due, h.streak(day(31))), (next.id, NaiveDate::from_ymd_opt(2030, 6, 1).unwrap(), 1));
+}
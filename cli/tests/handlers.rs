@@ -0,0 +1,585 @@
+mod common;
+
+use common::{backend, fixture, full_backend, json};
+use chrono::NaiveDate;
+use serde_json::json as j;
+use std::path::PathBuf;
+use std::time::Duration;
+use todo::audit;
+use todo::commands::ShowAs;
+use todo::config::Eviction;
+use todo::handlers::{self, EventInput, FreeQuery, PollOptions, TaskChanges, TodoInput};
+use todo::rows::{self, TaskRef};
+use todo::slots::Interval;
+use wiremock::matchers::{body_json, body_partial_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+fn fast_poll(attempts: u32) -> PollOptions {
+    PollOptions { attempts, interval: Duration::from_millis(1) }
+}
+
+#[tokio::test]
+async fn lists_categories() {
+    let b = full_backend().await;
+    let cats = b.api.categories().await.unwrap();
+    assert_eq!(cats.len(), 2);
+    assert_eq!(cats[0].name, "Thesis");
+}
+
+#[tokio::test]
+async fn list_tasks_polls_until_everything_is_scheduled() {
+    let b = backend().await;
+    b.stub("POST", "/auto-schedule/", "auto_schedule").await;
+    b.stub("GET", "/taskslist/", "taskslist").await;
+    // First poll still sees an unscheduled todo, later polls see it placed
+    Mock::given(method("GET"))
+        .and(path("/tasks/"))
+        .respond_with(json(200, "tasks_unscheduled"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&b.server)
+        .await;
+    b.stub("GET", "/tasks/", "tasks").await;
+
+    let payload = todo::config::Config::default().schedule_payload();
+    let listing = handlers::list_tasks(&b.api, &payload, &fast_poll(10)).await.unwrap();
+    assert_eq!(listing.poll.polls, 2);
+    assert_eq!(listing.poll.unscheduled, 0);
+    // Sorted by due: the event (09:30) before the todo deadline
+    let ids: Vec<i32> = listing.tasks.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![11, 10]);
+}
+
+#[tokio::test]
+async fn list_tasks_gives_up_after_poll_budget() {
+    let b = backend().await;
+    b.stub("POST", "/auto-schedule/", "auto_schedule").await;
+    b.stub("GET", "/tasks/", "tasks_unscheduled").await;
+    b.stub("GET", "/taskslist/", "taskslist").await;
+
+    let payload = todo::config::Config::default().schedule_payload();
+    let listing = handlers::list_tasks(&b.api, &payload, &fast_poll(3)).await.unwrap();
+    assert_eq!(listing.poll.polls, 3);
+    assert_eq!(listing.poll.unscheduled, 1);
+}
+
+#[tokio::test]
+async fn list_tasks_sends_configured_availability() {
+    let b = backend().await;
+    Mock::given(method("POST"))
+        .and(path("/auto-schedule/"))
+        .and(body_partial_json(j!({
+            "availability": { "0": [{ "start": "09:00", "end": "17:00" }] },
+            "weights": { "priority": 1.0, "deadline": 100.0 }
+        })))
+        .respond_with(json(200, "auto_schedule"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    b.stub("GET", "/tasks/", "tasks").await;
+    b.stub("GET", "/taskslist/", "taskslist").await;
+
+    let payload = todo::config::Config::default().schedule_payload();
+    handlers::list_tasks(&b.api, &payload, &fast_poll(1)).await.unwrap();
+}
+
+#[tokio::test]
+async fn create_todo_sends_parsed_deadline() {
+    let b = backend().await;
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .and(body_partial_json(j!({
+            "title": "Pay rent", "type": "todo", "estimate": 15, "priority": 4,
+            "deadline": "2030-05-31T21:00:00"
+        })))
+        .respond_with(json(200, "task_todo"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let input = TodoInput {
+        title: "Pay rent".into(),
+        estimate: 15,
+        deadline: "5/31/30".into(),
+        priority: 4,
+        description: None,
+        assignee: None,
+        optional: false,
+    };
+    let t = handlers::create_todo(&b.api, input).await.unwrap();
+    assert_eq!(t.id, 12);
+
+    let logged = audit::for_task(12).unwrap();
+    assert!(logged.iter().any(|e| e.action == "create-todo" && e.ok));
+}
+
+#[tokio::test]
+async fn create_todo_sends_a_checked_deadline_as_it_is() {
+    let b = backend().await;
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .and(body_partial_json(j!({ "title": "File taxes", "deadline": "2030-05-31T21:00:00" })))
+        .respond_with(json(200, "task_todo"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    let due = handlers::check_deadline("5/31/30", false).unwrap();
+    let input = TodoInput { title: "File taxes".into(), estimate: 30, deadline: due, priority: 1, description: None, assignee: None, optional: false };
+    handlers::create_todo(&b.api, input).await.unwrap();
+}
+
+#[tokio::test]
+async fn creation_time_is_stamped_locally_when_the_backend_has_none() {
+    let b = backend().await;
+    let bare = j!({ "id": 31, "title": "Water plants", "type": "todo", "status": "pending", "priority": 1, "estimate": 10 });
+    Mock::given(method("POST")).and(path("/tasks/")).respond_with(ResponseTemplate::new(200).set_body_json(bare)).mount(&b.server).await;
+    let input = TodoInput { title: "Water plants".into(), estimate: 10, deadline: "5/31/30".into(), priority: 1, description: None, assignee: None, optional: false };
+    let t = handlers::create_todo(&b.api, input).await.unwrap();
+    let stamped = todo::meta::get(31).unwrap().created_at.unwrap();
+    assert_eq!(t.created_at.as_deref(), Some(stamped.as_str()));
+
+    let all_meta = todo::meta::load().unwrap();
+    let tasks: Vec<todo::commands::Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "old", "type": "todo", "priority": 9, "created_at": "2020-01-01T09:00:00" },
+        { "id": 2, "title": "unknown", "type": "todo", "deadline": "2030-02-01T21:00:00" },
+        { "id": 3, "title": "newer", "type": "todo", "priority": 2, "deadline": "2030-01-15T21:00:00", "created_at": "2020-03-01T09:00:00" }
+    ]))
+    .unwrap();
+    let mut shown: Vec<&todo::commands::Task> = tasks.iter().chain([&t]).collect();
+    let order = |spec: &str, shown: &mut Vec<&todo::commands::Task>| {
+        spec.parse::<todo::sort::SortSpec>().unwrap().apply(shown, &all_meta);
+        shown.iter().map(|t| t.id).collect::<Vec<_>>()
+    };
+    assert_eq!(order("created", &mut shown), vec![31, 3, 1, 2]);
+    assert_eq!(order("deadline", &mut shown), vec![3, 2, 1, 31]);
+    assert_eq!(order("priority", &mut shown), vec![1, 3, 31, 2]);
+}
+
+#[tokio::test]
+async fn create_todo_rejects_bad_deadline_without_calling_api() {
+    let b = backend().await;
+    Mock::given(method("POST")).respond_with(json(200, "task_todo")).expect(0).mount(&b.server).await;
+
+    let input = TodoInput {
+        title: "x".into(),
+        estimate: 10,
+        deadline: "someday".into(),
+        priority: 0,
+        description: None,
+        assignee: None,
+        optional: false,
+    };
+    let err = handlers::create_todo(&b.api, input).await.err().unwrap();
+    assert!(err.to_string().contains("someday"));
+}
+
+#[tokio::test]
+async fn create_event_posts_times() {
+    let b = backend().await;
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .and(body_partial_json(j!({
+            "type": "event", "start_time": "2030-05-07T14:00:00", "end_time": "2030-05-07T15:00:00",
+            "location": "Dental clinic", "attendees": ["ann@example.com"], "transparency": "tentative",
+            "assignee": "Anna"
+        })))
+        .respond_with(json(200, "task_event"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let input = EventInput {
+        title: "Dentist".into(),
+        start: "2030-05-07T14:00:00".into(),
+        end: "2030-05-07T15:00:00".into(),
+        all_day: false,
+        description: None,
+        location: Some("Dental clinic".into()),
+        attendees: vec!["ann@example.com".into()],
+        organizer: None,
+        meeting_url: None,
+        show_as: Some(ShowAs::Tentative),
+        assignee: Some("Anna".into()),
+    };
+    assert_eq!(handlers::create_event(&b.api, input).await.unwrap().id, 13);
+}
+
+#[test]
+fn a_bare_end_time_before_the_start_rolls_over_midnight() {
+    let span = |e| handlers::event_span("2030-05-06T22:00:00", e).unwrap().1;
+    assert_eq!(span("02:30"), "2030-05-07T02:30:00");
+    assert_eq!(span("23:15"), "2030-05-06T23:15:00");
+    assert_eq!(span("2030-05-08T10:00:00"), "2030-05-08T10:00:00");
+}
+
+#[test]
+fn events_can_be_given_a_length_instead_of_an_end() {
+    let span = |s, e: Option<&str>, l: Option<&str>| handlers::timed_span(s, e, l);
+    assert_eq!(span("2030-05-06T23:00:00", None, Some("1h30m")).unwrap().1, "2030-05-07T00:30:00");
+    let tomorrow = chrono::Local::now().date_naive() + chrono::Duration::days(1);
+    let (start, end) = span("tomorrow 3pm", None, Some("2h")).unwrap();
+    assert_eq!((start, end), (format!("{}T15:00:00", tomorrow), format!("{}T17:00:00", tomorrow)));
+    assert!(span("2030-05-06T23:00:00", Some("23:30"), Some("2h")).is_err());
+    assert!(span("2030-05-06T23:00:00", None, None).is_err());
+    assert!(span("2030-05-06T23:00:00", None, Some("999999999999h")).is_err());
+}
+
+#[test]
+fn all_day_span_covers_whole_days() {
+    let (start, end) = handlers::all_day_span("2030-05-07", Some("2030-05-09")).unwrap();
+    assert_eq!((start.as_str(), end.as_str()), ("2030-05-07T00:00:00", "2030-05-10T00:00:00"));
+    assert_eq!(handlers::all_day_span("2030-05-07", None).unwrap().1, "2030-05-08T00:00:00");
+    assert!(handlers::all_day_span("2030-05-07", Some("2030-05-06")).is_err());
+}
+
+#[tokio::test]
+async fn event_conflicts_flags_overlap_and_suggests_nearest_gap() {
+    let b = backend().await;
+    b.stub("GET", "/tasks/", "tasks_push").await;
+
+    let c = handlers::event_conflicts(&b.api, "2030-05-06T09:00:00", "2030-05-06T10:00:00", 2).await.unwrap().unwrap();
+    assert_eq!(c.clashes.len(), 1);
+    assert!(c.clashes[0].label.contains("Standup"));
+    // 09:00-09:30 is too short, the todo block runs until 11:30
+    assert_eq!(c.suggestions[0].start.to_string(), "2030-05-06 11:30:00");
+    assert_eq!(c.suggestions[0].end.to_string(), "2030-05-06 12:30:00");
+
+    let free = handlers::event_conflicts(&b.api, "2030-05-06T12:00:00", "2030-05-06T13:00:00", 2).await.unwrap();
+    assert!(free.is_none());
+}
+
+#[tokio::test]
+async fn free_slots_avoid_tasks_and_participant_calendars() {
+    let b = backend().await;
+    b.stub("GET", "/tasks/", "tasks_push").await;
+
+    let day = |h: u32, m: u32| NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(h, m, 0).unwrap();
+    let query = FreeQuery {
+        duration: chrono::Duration::hours(1),
+        range: Interval::new(day(0, 0), day(23, 59)),
+        participants: vec![PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/participant.ics")],
+    };
+    let gaps = handlers::free_slots(&b.api, &query).await.unwrap();
+    // Recurring 13:00 sync blocks; the cancelled 15:00 review doesn't
+    assert_eq!(gaps, vec![Interval::new(day(11, 30), day(13, 0)), Interval::new(day(14, 0), day(17, 0))]);
+}
+
+#[tokio::test]
+async fn update_task_requires_a_change() {
+    let b = backend().await;
+    let err = handlers::update_task(&b.api, 10, TaskChanges::default()).await.err().unwrap();
+    assert_eq!(err.to_string(), "No updates provided.");
+}
+
+#[tokio::test]
+async fn update_task_patches_only_given_fields() {
+    let b = backend().await;
+    Mock::given(method("PATCH"))
+        .and(path("/tasks/10"))
+        .and(body_partial_json(j!({ "status": "done", "priority": 9 })))
+        .respond_with(json(200, "task_updated"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let changes = TaskChanges { status: Some("done".into()), priority: Some(9), ..Default::default() };
+    let t = handlers::update_task(&b.api, 10, changes).await.unwrap();
+    assert_eq!(t.status.as_deref(), Some("done"));
+}
+
+#[tokio::test]
+async fn failed_delete_is_audited() {
+    let b = backend().await;
+    Mock::given(method("DELETE"))
+        .and(path("/tasks/404"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&b.server)
+        .await;
+
+    let err = b.api.delete_task(404).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<todo::api::ApiError>().map(|e| e.status), Some(404));
+    assert!(err.to_string().starts_with("404 Not Found from /tasks/404"), "{}", err);
+    let logged = audit::for_task(404).unwrap();
+    assert!(logged.iter().any(|e| e.action == "delete-task" && !e.ok));
+}
+
+#[tokio::test]
+async fn delete_task_accepts_no_content() {
+    let b = backend().await;
+    Mock::given(method("DELETE"))
+        .and(path("/tasks/10"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    b.api.delete_task(10).await.unwrap();
+}
+
+#[tokio::test]
+async fn calendar_endpoints() {
+    let b = full_backend().await;
+    b.stub("POST", "/calendar/push/10", "push_task").await;
+    assert_eq!(b.api.sync_calendar().await.unwrap().imported, Some(4));
+    assert_eq!(b.api.push_task(10, true).await.unwrap().google_event_id.as_deref(), Some("gcal-abc123"));
+    let all = b.api.push_all(false).await.unwrap();
+    assert_eq!((all.pushed, all.updated), (Some(2), Some(1)));
+}
+
+#[tokio::test]
+async fn import_google_tasks_sends_the_list_and_audits() {
+    let b = backend().await;
+    Mock::given(method("POST"))
+        .and(path("/import/google-tasks"))
+        .and(body_json(j!({"task_list": "Groceries", "include_completed": false})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(j!({"imported": 3, "updated": 1, "skipped": 2, "lists": ["Groceries"]})))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    let r = b.api.import_google_tasks(Some("Groceries"), false).await.unwrap();
+    assert_eq!((r.imported, r.updated, r.skipped, r.lists), (3, 1, 2, vec!["Groceries".to_string()]));
+    assert!(audit::read_all().unwrap().iter().any(|e| e.action == "import-google-tasks" && e.ok));
+}
+
+#[tokio::test]
+async fn import_events_skips_what_is_already_there() {
+    let b = backend().await;
+    b.stub("GET", "/tasks/", "tasks").await;
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .and(body_partial_json(j!({"title": "Standup", "type": "event", "start_time": "2030-05-07T09:30:00", "transparency": "busy"})))
+        .respond_with(json(200, "task_updated"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    let raw = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:s\nSUMMARY:Standup\nDTSTART:20300506T093000\nDURATION:PT15M\nRRULE:FREQ=DAILY;COUNT=2\nEND:VEVENT\nEND:VCALENDAR\n";
+    let day = NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let events = todo::ics::parse_events(raw, &Interval::new(day, day + chrono::Duration::days(7)));
+    let r = handlers::import_events(&b.api, &events).await.unwrap();
+    assert_eq!((r.created, r.skipped), (1, 1));
+}
+
+#[tokio::test]
+async fn auto_schedule_uses_config_file() {
+    let b = backend().await;
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), r#"{"availability": {}, "weights": {"priority": 2.0}}"#).unwrap();
+    Mock::given(method("POST"))
+        .and(path("/auto-schedule/"))
+        .and(body_partial_json(j!({ "weights": { "priority": 2.0 } })))
+        .respond_with(json(200, "auto_schedule"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let result = handlers::auto_schedule(&b.api, file.path().to_str(), None).await.unwrap();
+    assert_eq!(result.status.as_deref(), Some("enqueued"));
+}
+
+#[tokio::test]
+async fn task_log_merges_server_history() {
+    let b = backend().await;
+    b.stub("GET", "/tasks/77/history", "history").await;
+    let log = handlers::task_log(&b.api, 77).await.unwrap();
+    assert!(log.server_error.is_none());
+    let activities: Vec<&str> = log.entries.iter().map(|e| e.activity.as_str()).collect();
+    assert_eq!(activities, vec!["created", "rescheduled: 2030-05-06"]);
+}
+
+#[tokio::test]
+async fn task_log_tolerates_missing_history_endpoint() {
+    let b = backend().await;
+    let log = handlers::task_log(&b.api, 78).await.unwrap();
+    assert!(log.entries.is_empty());
+    assert!(log.server_error.is_none());
+}
+
+#[tokio::test]
+async fn task_log_orders_server_times_by_the_moment_they_name() {
+    let b = backend().await;
+    let history = j!([
+        { "timestamp": "2030-05-02T08:30:00.123456", "event": "rescheduled", "detail": null },
+        { "timestamp": "2030-05-02T10:00:00+02:00", "event": "created", "detail": null }
+    ]);
+    Mock::given(method("GET")).and(path("/tasks/80/history")).respond_with(ResponseTemplate::new(200).set_body_json(history)).mount(&b.server).await;
+    let log = handlers::task_log(&b.api, 80).await.unwrap();
+    let activities: Vec<&str> = log.entries.iter().map(|e| e.activity.as_str()).collect();
+    assert_eq!(activities, vec!["created", "rescheduled"]);
+    assert!(log.entries.iter().all(|e| e.at.is_some() && !e.timestamp.contains('+')));
+}
+
+#[tokio::test]
+async fn task_log_reports_a_failing_history_endpoint() {
+    let b = backend().await;
+    Mock::given(method("GET")).and(path("/tasks/79/history")).respond_with(ResponseTemplate::new(403)).mount(&b.server).await;
+    let log = handlers::task_log(&b.api, 79).await.unwrap();
+    assert!(log.server_error.as_deref().is_some_and(|e| e.starts_with("403")), "{:?}", log.server_error);
+}
+
+#[test]
+fn fixtures_match_models() {
+    let tasks: Vec<todo::commands::Task> = serde_json::from_value(fixture("tasks")).unwrap();
+    assert_eq!(tasks.len(), 2);
+}
+
+#[test]
+fn stale_lists_untouched_open_tasks_oldest_first() {
+    let tasks: Vec<todo::commands::Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "fresh", "type": "todo", "status": "pending", "updated_at": "2030-05-20T09:00:00" },
+        { "id": 2, "title": "old", "type": "todo", "status": "pending", "updated_at": "2030-04-10T09:00:00" },
+        { "id": 3, "title": "older, never updated", "type": "todo", "created_at": "2030-03-01T09:00:00" },
+        { "id": 4, "title": "old but done", "type": "todo", "status": "done", "updated_at": "2030-01-01T09:00:00" }
+    ]))
+    .unwrap();
+    let now = NaiveDate::from_ymd_opt(2030, 5, 25).unwrap().and_hms_opt(12, 0, 0).unwrap();
+    let ids: Vec<i32> = handlers::stale(tasks, now, 30).iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![3, 2]);
+}
+
+#[test]
+fn cleanup_takes_old_done_tasks_already_on_the_calendar() {
+    common::isolate_paths();
+    let tasks: Vec<todo::commands::Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "recent", "type": "event", "status": "done", "external_id": "g1", "updated_at": "2030-05-20T09:00:00" },
+        { "id": 2, "title": "old, pushed", "type": "todo", "status": "done", "external_id": "g2", "updated_at": "2030-01-10T09:00:00" },
+        { "id": 3, "title": "old, never pushed", "type": "todo", "status": "done", "updated_at": "2029-12-01T09:00:00" },
+        { "id": 4, "title": "old, open", "type": "todo", "status": "pending", "external_id": "g4", "updated_at": "2030-01-01T09:00:00" }
+    ]))
+    .unwrap();
+    let now = NaiveDate::from_ymd_opt(2030, 5, 25).unwrap().and_hms_opt(12, 0, 0).unwrap();
+    let ids = |pushed_only| handlers::finished(tasks.clone(), now, 90, pushed_only).iter().map(|t| t.id).collect::<Vec<_>>();
+    assert_eq!(ids(true), vec![2]);
+    assert_eq!(ids(false), vec![3, 2]);
+
+    todo::archive::append(&tasks[1], None, now).unwrap();
+    let archived = todo::archive::read_all().unwrap();
+    assert_eq!((archived[0].task.id, archived[0].task.external_id.as_deref()), (2, Some("g2")));
+}
+
+#[test]
+fn slipped_are_todays_unfinished_todo_blocks() {
+    let tasks: Vec<todo::commands::Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "afternoon", "type": "todo", "status": "pending", "start_time": "2030-05-25T15:00:00", "end_time": "2030-05-25T16:00:00" },
+        { "id": 2, "title": "morning", "type": "todo", "status": "pending", "start_time": "2030-05-25T09:00:00", "end_time": "2030-05-25T10:00:00" },
+        { "id": 3, "title": "finished", "type": "todo", "status": "done", "start_time": "2030-05-25T11:00:00", "end_time": "2030-05-25T12:00:00" },
+        { "id": 4, "title": "meeting", "type": "event", "start_time": "2030-05-25T13:00:00", "end_time": "2030-05-25T14:00:00" },
+        { "id": 5, "title": "tomorrow", "type": "todo", "status": "pending", "start_time": "2030-05-26T09:00:00", "end_time": "2030-05-26T10:00:00" }
+    ]))
+    .unwrap();
+    let ids: Vec<i32> = handlers::slipped(tasks, NaiveDate::from_ymd_opt(2030, 5, 25).unwrap()).iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![2, 1]);
+}
+
+#[tokio::test]
+async fn split_task_files_the_rest_as_a_second_part() {
+    let b = backend().await;
+    Mock::given(method("PATCH"))
+        .and(path("/tasks/12"))
+        .and(body_json(j!({ "estimate": 60 })))
+        .respond_with(json(200, "task_todo"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .and(body_partial_json(j!({ "title": "Pay rent (part 2)", "type": "todo", "estimate": 120, "priority": 4, "deadline": "2030-06-03T12:00:00" })))
+        .respond_with(json(200, "task_todo"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    let task: todo::commands::Task = serde_json::from_value(fixture("task_todo")).unwrap();
+    let due = NaiveDate::from_ymd_opt(2030, 6, 3).unwrap().and_hms_opt(12, 0, 0);
+    handlers::split_task(&b.api, &task, 60, 120, due).await.unwrap();
+}
+
+#[tokio::test]
+async fn update_category_finds_it_by_name_and_sets_the_icon() {
+    let b = backend().await;
+    b.stub("GET", "/categories/", "categories").await;
+    Mock::given(method("PATCH"))
+        .and(path("/categories/1"))
+        .and(body_json(j!({ "icon": "📚", "color": "#E15759" })))
+        .respond_with(json(200, "category"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    let changes = handlers::CategoryChanges { color: Some("red".into()), icon: Some(" 📚 ".into()), ..Default::default() };
+    handlers::update_category(&b.api, "thesis", changes).await.unwrap();
+    let missing = handlers::update_category(&b.api, "Garden", handlers::CategoryChanges::default()).await.err().unwrap();
+    assert!(missing.to_string().contains("No category 'Garden'"));
+}
+
+#[test]
+fn today_keeps_only_what_is_actionable_today() {
+    let tasks: Vec<todo::commands::Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "block", "type": "todo", "status": "pending", "start_time": "2030-05-25T15:00:00", "end_time": "2030-05-25T16:00:00" },
+        { "id": 2, "title": "due tonight", "type": "todo", "status": "pending", "deadline": "2030-05-25T21:00:00" },
+        { "id": 3, "title": "overdue", "type": "todo", "status": "pending", "deadline": "2030-05-20T09:00:00" },
+        { "id": 4, "title": "started", "type": "todo", "status": "in-progress", "deadline": "2030-06-10T09:00:00" },
+        { "id": 5, "title": "offsite", "type": "event", "all_day": true, "start_time": "2030-05-24T00:00:00", "end_time": "2030-05-26T00:00:00" },
+        { "id": 6, "title": "next week", "type": "todo", "status": "pending", "deadline": "2030-06-01T09:00:00" },
+        { "id": 7, "title": "finished", "type": "todo", "status": "done", "deadline": "2030-05-25T09:00:00" },
+        { "id": 8, "title": "ended yesterday", "type": "event", "all_day": true, "start_time": "2030-05-24T00:00:00", "end_time": "2030-05-25T00:00:00" },
+        { "id": 9, "title": "someday", "type": "todo", "status": "pending" }
+    ]))
+    .unwrap();
+    let now = NaiveDate::from_ymd_opt(2030, 5, 25).unwrap().and_hms_opt(10, 0, 0).unwrap();
+    assert!(handlers::on_today(&tasks[6], now));
+    let ids: Vec<i32> = handlers::today(tasks, now).iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![4, 3, 5, 1, 2]);
+}
+
+#[test]
+fn schedule_diff_reports_placed_moved_and_evicted_todos() {
+    let before: Vec<todo::commands::Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "same", "type": "todo", "status": "pending", "scheduled_for": "2030-05-06" },
+        { "id": 2, "title": "moved", "type": "todo", "status": "pending", "scheduled_for": "2030-05-06" },
+        { "id": 3, "title": "evicted", "type": "todo", "status": "pending", "scheduled_for": "2030-05-07" },
+        { "id": 4, "title": "new", "type": "todo", "status": "pending" }
+    ]))
+    .unwrap();
+    let after: Vec<todo::commands::Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "same", "type": "todo", "status": "pending", "scheduled_for": "2030-05-06" },
+        { "id": 2, "title": "moved", "type": "todo", "status": "pending", "scheduled_for": "2030-05-08" },
+        { "id": 3, "title": "evicted", "type": "todo", "status": "pending" },
+        { "id": 4, "title": "new", "type": "todo", "status": "pending", "scheduled_for": "2030-05-06" },
+        { "id": 5, "title": "created meanwhile", "type": "todo", "status": "pending", "scheduled_for": "2030-05-09" }
+    ]))
+    .unwrap();
+    let shifts: Vec<(i32, handlers::Shift)> = handlers::schedule_diff(&before, &after, None).iter().map(|c| (c.task_id, c.shift)).collect();
+    use handlers::Shift::*;
+    assert_eq!(shifts, vec![(2, Moved), (3, Evicted), (4, Placed), (5, Placed)]);
+
+    // Each evicted todo says which policy put it out; nothing else does
+    let policies: Vec<(i32, Option<Eviction>)> = handlers::schedule_diff(&before, &after, Some(Eviction::DropLowestPriority)).iter().map(|c| (c.task_id, c.policy)).collect();
+    assert_eq!(policies, vec![(2, None), (3, Some(Eviction::DropLowestPriority)), (4, None), (5, None)]);
+}
+
+#[test]
+fn row_numbers_resolve_against_the_last_listing() {
+    common::isolate_paths();
+    assert_eq!("%2".parse::<TaskRef>(), Ok(TaskRef::Row(2)));
+    assert_eq!("17".parse::<TaskRef>(), Ok(TaskRef::Id(17)));
+    assert!("%0".parse::<TaskRef>().is_err());
+
+    rows::remember(&[40, 41, 42]).unwrap();
+    assert_eq!(rows::resolve(TaskRef::Row(2)).unwrap(), 41);
+    assert_eq!(rows::resolve(TaskRef::Id(7)).unwrap(), 7);
+    let err = rows::resolve(TaskRef::Row(4)).unwrap_err().to_string();
+    assert!(err.contains("had 3 rows"), "{}", err);
+}
+
+#[test]
+fn waiting_tasks_come_due_for_a_follow_up() {
+    let tasks: Vec<todo::commands::Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "Contract", "type": "todo", "status": "waiting" },
+        { "id": 2, "title": "Report", "type": "todo", "status": "pending" },
+        { "id": 3, "title": "Invoice", "type": "todo", "status": "waiting" }
+    ]))
+    .unwrap();
+    let mut meta = todo::meta::MetaMap::new();
+    meta.insert(1, todo::meta::TaskMeta { waiting_on: Some("Alice".into()), waiting_since: Some("2030-05-06".into()), ..Default::default() });
+    meta.insert(3, todo::meta::TaskMeta { waiting_on: Some("Bob".into()), waiting_since: Some("2030-05-02".into()), ..Default::default() });
+    let ids: Vec<i32> = handlers::waiting(tasks, &meta).iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![3, 1]);
+    assert_eq!(handlers::follow_up_on(&meta[&1], 3), NaiveDate::from_ymd_opt(2030, 5, 9));
+}
@@ -0,0 +1,62 @@
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use todo::audit::Entry;
+use todo::config::Week;
+use todo::heatmap;
+use todo::meta::{MetaMap, TaskMeta};
+
+fn done(at: &str, task_id: i32, estimate: Value) -> Entry {
+    let result = json!({ "id": task_id, "estimate": estimate });
+    Entry { timestamp: at.into(), action: "update-task".into(), task_id: Some(task_id), payload: json!({"status": "done"}), ok: true, result }
+}
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn counts_each_task_once_a_day_and_prefers_tracked_time() {
+    let mut failed = done("2030-05-06T12:00:00", 3, json!(30));
+    failed.ok = false;
+    let log = vec![
+        done("2030-05-06T09:00:00", 1, json!(60)),
+        done("2030-05-06T09:05:00", 1, json!(60)),
+        done("2030-05-06T17:30:00", 2, json!(45)),
+        failed,
+        done("2030-05-08T10:00:00", 1, Value::Null),
+    ];
+    let meta: MetaMap = [(2, TaskMeta { actual_minutes: Some(90), ..Default::default() })].into();
+    let days = heatmap::completed_by_day(&log, &meta);
+    assert_eq!(days[&date("2030-05-06")], heatmap::Day { tasks: 2, minutes: 150 });
+    assert_eq!(days[&date("2030-05-08")], heatmap::Day { tasks: 1, minutes: 0 });
+    assert_eq!(days.len(), 2);
+    assert_eq!(
+        heatmap::summary(&days, date("2030-05-01"), date("2030-05-31")),
+        "3 tasks done, 2h 30m tracked, on 2 days; longest run 1 days"
+    );
+}
+
+#[test]
+fn draws_a_grid_and_an_svg() {
+    let log = vec![done("2030-05-06T09:00:00", 1, json!(60)), done("2030-05-06T10:00:00", 2, json!(60)), done("2030-05-07T09:00:00", 3, json!(60))];
+    let days = heatmap::completed_by_day(&log, &MetaMap::new());
+    let (first, last) = heatmap::span(Some(2030), date("2030-10-01"));
+    assert_eq!((first, last), (date("2030-01-01"), date("2030-12-31")));
+    let (f, l) = heatmap::span(None, date("2030-05-10"));
+    assert_eq!((l - f).num_days(), 363);
+
+    let grid = heatmap::render(&days, first, last, &Week::default(), false, false);
+    let lines: Vec<&str> = grid.lines().collect();
+    assert!(lines[0].starts_with("    Jan"));
+    // Monday row has the busiest day, Tuesday half of it
+    assert!(lines[1].contains('█') && lines[2].starts_with("Tue") && lines[2].contains('▒'));
+    assert!(grid.contains("Less · ░ ▒ ▓ █ More (tasks)"));
+
+    let svg = heatmap::svg(&days, first, last, &Week::default(), true);
+    assert_eq!(svg.matches("<rect").count(), 365);
+    assert!(svg.contains("fill=\"#216e39\"><title>2030-05-06: 2h</title>"));
+    assert!(svg.contains("<title>2030-05-07: 1h</title>"));
+
+    let text = heatmap::render_accessible(&days, first, last);
+    assert_eq!(text, "May 2030: 3 tasks on 2 days, busiest Mon May 6 with 2.\n");
+}
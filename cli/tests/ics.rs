@@ -0,0 +1,104 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use serde_json::json;
+use todo::commands::{ShowAs, Task};
+use todo::ics;
+use todo::slots::Interval;
+
+#[test]
+fn export_carries_meeting_details_and_parses_back() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 13, "title": "Review, part 2", "type": "event",
+          "start_time": "2030-05-07T14:00:00", "end_time": "2030-05-07T15:00:00",
+          "location": "Room 4; 2nd floor", "organizer": "ann@example.com",
+          "attendees": ["bob@example.com", "carol@example.com"], "meeting_url": "https://meet.example.com/abc" },
+        { "id": 14, "title": "Unscheduled", "type": "todo" }
+    ]))
+    .unwrap();
+    let out = ics::to_ics(&tasks);
+    assert!(out.contains("SUMMARY:Review\\, part 2\r\n"));
+    assert!(out.contains("LOCATION:Room 4\\; 2nd floor\r\n"));
+    assert!(out.contains("ORGANIZER:mailto:ann@example.com\r\n"));
+    assert!(out.contains("ATTENDEE;ROLE=REQ-PARTICIPANT:mailto:carol@example.com\r\n"));
+    assert!(out.contains("URL:https://meet.example.com/abc\r\n"));
+    assert_eq!(out.matches("BEGIN:VEVENT").count(), 1);
+
+    let day = NaiveDate::from_ymd_opt(2030, 5, 7).unwrap();
+    let range = Interval::new(day.and_hms_opt(0, 0, 0).unwrap(), day.and_hms_opt(23, 0, 0).unwrap());
+    let busy = ics::parse_busy(&out, &range);
+    assert_eq!(busy[0].label, "Review, part 2");
+    assert_eq!(busy[0].interval.start, day.and_hms_opt(14, 0, 0).unwrap());
+}
+
+/// Outlook-style export: a Windows zone name defined inline, a weekly series across the
+/// March DST change with one occurrence skipped and one moved
+const OUTLOOK: &str = "BEGIN:VCALENDAR\r
+BEGIN:VTIMEZONE\r
+TZID:W. Europe Standard Time\r
+BEGIN:STANDARD\r
+DTSTART:16010101T030000\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+RRULE:FREQ=YEARLY;INTERVAL=1;BYDAY=-1SU;BYMONTH=10\r
+END:STANDARD\r
+BEGIN:DAYLIGHT\r
+DTSTART:16010101T020000\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0200\r
+RRULE:FREQ=YEARLY;INTERVAL=1;BYDAY=-1SU;BYMONTH=3\r
+END:DAYLIGHT\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:standup\r
+SUMMARY:Standup\r
+LOCATION:Room 2\r
+DTSTART;TZID=W. Europe Standard Time:20300318T100000\r
+DTEND;TZID=W. Europe Standard Time:20300318T103000\r
+RRULE:FREQ=WEEKLY;COUNT=4;BYDAY=MO\r
+EXDATE;TZID=W. Europe Standard Time:20300325T100000\r
+BEGIN:VALARM\r
+TRIGGER:-PT15M\r
+DESCRIPTION:Reminder\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup\r
+RECURRENCE-ID;TZID=W. Europe Standard Time:20300401T100000\r
+SUMMARY:Standup (moved)\r
+DTSTART;TZID=W. Europe Standard Time:20300402T140000\r
+DTEND;TZID=W. Europe Standard Time:20300402T143000\r
+TRANSP:TRANSPARENT\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:offsite\r
+SUMMARY:Offsite\r
+DTSTART;VALUE=DATE:20300610\r
+DTEND;VALUE=DATE:20300612\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+    let t = NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap();
+    DateTime::<Utc>::from_naive_utc_and_offset(t, Utc).with_timezone(&Local).naive_local()
+}
+
+#[test]
+fn recurring_outlook_series_expands_in_its_own_zone() {
+    let from = NaiveDate::from_ymd_opt(2030, 3, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let events = ics::parse_events(OUTLOOK, &Interval::new(from, from + chrono::Duration::days(60)));
+    let got: Vec<(&str, NaiveDateTime)> = events.iter().map(|e| (e.summary.as_str(), e.interval.start)).collect();
+    // 10:00 Berlin is 09:00 UTC before the change and 08:00 after it
+    assert_eq!(got, vec![("Standup", utc(2030, 3, 18, 9, 0)), ("Standup (moved)", utc(2030, 4, 2, 12, 0)), ("Standup", utc(2030, 4, 8, 8, 0))]);
+    assert_eq!(events[0].interval.end - events[0].interval.start, chrono::Duration::minutes(30));
+    assert_eq!((events[0].location.as_deref(), events[0].description.as_deref()), (Some("Room 2"), None));
+    assert_eq!(events[1].show_as, ShowAs::Free);
+
+    // The --until horizon cuts the series; all-day events stay whole days
+    let march = ics::parse_events(OUTLOOK, &Interval::new(from, from + chrono::Duration::days(20)));
+    assert_eq!(march.len(), 1);
+    let june = NaiveDate::from_ymd_opt(2030, 6, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let offsite = ics::parse_events(OUTLOOK, &Interval::new(june, june + chrono::Duration::days(30)));
+    assert!(offsite[0].all_day);
+    assert_eq!(offsite[0].interval.end - offsite[0].interval.start, chrono::Duration::days(2));
+    assert_eq!(ics::parse_busy(OUTLOOK, &Interval::new(from, from + chrono::Duration::days(60))).len(), 2);
+}
@@ -0,0 +1,27 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::agenda;
+use todo::commands::Task;
+use todo::config::Config;
+use todo::image;
+
+#[test]
+fn week_svg_draws_blocks_on_an_hour_grid() {
+    let cfg = Config::default();
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Standup & <sync>", "type": "event", "start_time": "2030-05-06T07:30:00", "end_time": "2030-05-06T08:00:00" },
+        { "id": 2, "title": "Report", "type": "todo", "start_time": "2030-05-07T14:00:00", "end_time": "2030-05-07T15:30:00" },
+        { "id": 3, "title": "Offsite", "type": "event", "all_day": true, "start_time": "2030-05-08T00:00:00", "end_time": "2030-05-09T00:00:00" }
+    ]))
+    .unwrap();
+    let days = agenda::build(&tasks, &cfg, NaiveDate::from_ymd_opt(2030, 5, 6).unwrap(), 7);
+    let svg = image::week_svg(&days, &cfg);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    // The early standup pulls the grid up to 07:00
+    assert!(svg.contains(">07:00</text>") && !svg.contains(">06:00</text>"), "{}", svg);
+    assert!(svg.contains("<title>07:30–08:00 [1] Standup &amp; &lt;sync&gt;</title>"), "{}", svg);
+    assert!(svg.contains("fill=\"#3fa34d\"><title>14:00–15:30 [2] Report</title>"), "{}", svg);
+    assert!(svg.contains(">Wed May 8</text>") && svg.contains(">Sat May 11 (weekend)</text>"), "{}", svg);
+    assert!(svg.contains("<title>[3] Offsite</title>"), "{}", svg);
+}
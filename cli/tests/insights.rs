@@ -0,0 +1,37 @@
+use serde_json::{json, Value};
+use todo::audit::Entry;
+use todo::insights;
+
+fn entry(at: &str, action: &str, task_id: Option<i32>, payload: Value, ok: bool) -> Entry {
+    Entry { timestamp: at.into(), action: action.into(), task_id, payload, ok, result: Value::Null }
+}
+
+#[test]
+fn ranks_actions_and_times_creation_to_done() {
+    let log = vec![
+        entry("2030-05-01T09:15:00", "create-todo", Some(1), json!({}), true),
+        entry("2030-05-01T09:40:00", "create-todo", Some(2), json!({}), true),
+        entry("2030-05-01T21:05:00", "create-event", Some(3), json!({}), true),
+        entry("2030-05-01T22:00:00", "create-todo", None, json!({}), false),
+        entry("2030-05-02T10:15:00", "update-task", Some(1), json!({"status": "done"}), true),
+        entry("2030-05-02T11:00:00", "update-task", Some(2), json!({"priority": 5}), true),
+        entry("2030-05-04T09:40:00", "update-task", Some(2), json!({"status": "done"}), true),
+        entry("2030-05-05T08:00:00", "update-task", Some(9), json!({"status": "done"}), true),
+    ];
+    let i = insights::compute(&log);
+    assert_eq!(i.actions, vec![("update-task".to_string(), 4), ("create-todo".to_string(), 2), ("create-event".to_string(), 1)]);
+    assert_eq!((i.created_by_hour[9], i.created_by_hour[21], i.created_by_hour[22]), (2, 1, 0));
+    // 25h and 72h; task 9 was created before the log began
+    assert_eq!(i.completed, 2);
+    assert_eq!(i.mean_to_done.map(|d| d.num_minutes()), Some(48 * 60 + 30));
+    assert_eq!(insights::format_span(i.mean_to_done.unwrap()), "2d 0h");
+    assert_eq!(i.since.as_deref(), Some("2030-05-01T09:15:00"));
+    assert_eq!(insights::hour_chart(&i.created_by_hour, 4), vec!["09:00 ████ 2", "21:00 ██   1"]);
+}
+
+#[test]
+fn empty_log_has_nothing_to_say() {
+    let i = insights::compute(&[]);
+    assert!(i.actions.is_empty() && i.since.is_none() && i.mean_to_done.is_none());
+    assert!(insights::hour_chart(&i.created_by_hour, 10).is_empty());
+}
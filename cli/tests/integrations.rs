@@ -0,0 +1,14 @@
+use todo::integrations::shell::{self, Target};
+
+#[test]
+fn quick_add_bindings_call_todo_add_on_the_chosen_key() {
+    for target in [Target::Zsh, Target::Fish, Target::Bash] {
+        let script = shell::binding(target, None).unwrap();
+        assert!(script.contains("todo add -- \"$line\""), "{}", script);
+        assert!(script.contains(target.default_key()) && !script.contains("{{"), "{}", script);
+    }
+    assert!(shell::binding(Target::Zsh, Some("^T")).unwrap().contains("bindkey '^T' _todo_quick_add"));
+    assert!(shell::binding(Target::Fish, Some("\\ct")).unwrap().contains("bind (string unescape -- '\\ct') __todo_quick_add"));
+    assert!(shell::binding(Target::Fish, Some("\\ea'; rm -rf ~; '")).is_err());
+    assert!(shell::binding(Target::Bash, Some("\\C-t'")).is_err());
+}
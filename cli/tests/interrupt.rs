@@ -0,0 +1,53 @@
+mod common;
+
+use common::{backend, json};
+use serde_json::Value;
+use todo::push::{self, Outcome};
+use todo::{batch, interrupt};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, Request};
+
+/// Line numbers of the results
+async fn run_batch(api: &todo::api::Api, input: &str, resume: bool) -> Result<Vec<u64>, String> {
+    let mut out = Vec::new();
+    batch::run(api, input.as_bytes(), &mut out, resume).await.map_err(|e| e.to_string())?;
+    Ok(String::from_utf8(out).unwrap().lines().map(|l| serde_json::from_str::<Value>(l).unwrap()["line"].as_u64().unwrap()).collect())
+}
+
+// One test: the interrupt flag is process-wide
+#[tokio::test]
+async fn interrupted_runs_journal_their_progress_and_resume() {
+    let b = backend().await;
+    b.stub("GET", "/tasks/", "tasks_push").await;
+    b.stub("POST", "/calendar/push/10", "push_task").await;
+    b.stub("POST", "/calendar/push/11", "push_task").await;
+
+    interrupt::request();
+    let reports = push::push_all(&b.api, None, 4).await.unwrap();
+    let outcomes: Vec<Outcome> = reports.iter().map(|r| r.outcome.clone()).collect();
+    assert_eq!(outcomes, vec![Outcome::NotReached, Outcome::NotReached, Outcome::Skipped("not scheduled yet".into())]);
+    assert_eq!(push::load_journal().unwrap().not_reached, vec![10, 11]);
+    interrupt::clear();
+    let resumed = push::push_all(&b.api, Some(&push::load_journal().unwrap().remaining()), 4).await.unwrap();
+    assert_eq!(resumed.iter().map(|r| r.outcome.clone()).collect::<Vec<_>>(), vec![Outcome::Pushed("gcal-abc123".into()); 2]);
+    assert!(push::load_journal().unwrap().is_empty());
+
+    // Ctrl-C arrives while the first line's request is in flight
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .respond_with(|_: &Request| {
+            interrupt::request();
+            json(200, "task_todo")
+        })
+        .mount(&b.server)
+        .await;
+    b.stub("PATCH", "/tasks/10", "task_updated").await;
+    let input = "ct 'Buy milk' -e 10 -d tomorrow\ndone 10\n";
+    assert_eq!(run_batch(&b.api, input, false).await.unwrap(), vec![1]);
+    assert_eq!(batch::load_journal().unwrap().map(|j| j.lines), Some(1));
+    interrupt::clear();
+    let err = run_batch(&b.api, "ct 'Other' -e 5 -d tomorrow\n", true).await.unwrap_err();
+    assert!(err.contains("same input"), "{}", err);
+    assert_eq!(run_batch(&b.api, input, true).await.unwrap(), vec![2]);
+    assert!(batch::load_journal().unwrap().is_none());
+}
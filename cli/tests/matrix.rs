@@ -0,0 +1,31 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::commands::Task;
+use todo::matrix::{self, Quadrant, Thresholds};
+
+#[test]
+fn sorts_open_todos_into_quadrants() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Tax return", "type": "todo", "priority": 8, "deadline": "2030-05-07T17:00:00" },
+        { "id": 2, "title": "Plan offsite", "type": "todo", "priority": 5, "deadline": "2030-06-01T17:00:00" },
+        { "id": 3, "title": "Reply to vendor", "type": "todo", "priority": 1, "deadline": "2030-05-05T09:00:00" },
+        { "id": 4, "title": "Tidy downloads", "type": "todo" },
+        { "id": 5, "title": "Learn Rust", "type": "todo", "priority": 9 },
+        { "id": 6, "title": "Old chore", "type": "todo", "priority": 9, "deadline": "2030-05-06T09:00:00", "status": "done" },
+        { "id": 7, "title": "Standup", "type": "event", "priority": 9, "start_time": "2030-05-06T09:00:00", "end_time": "2030-05-06T09:15:00" }
+    ]))
+    .unwrap();
+    let now = NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(12, 0, 0).unwrap();
+    let q = matrix::build(&tasks, now, &Thresholds::default());
+    let ids: Vec<Vec<i32>> = q.iter().map(|l| l.iter().map(|t| t.id).collect()).collect();
+    assert_eq!(ids, vec![vec![1], vec![2, 5], vec![3], vec![4]]);
+    assert_eq!(Quadrant::of(false, true), Quadrant::Schedule);
+
+    let wider = matrix::build(&tasks, now, &Thresholds { urgent_within: chrono::Duration::days(30), important_from: 9 });
+    let ids: Vec<Vec<i32>> = wider.iter().map(|l| l.iter().map(|t| t.id).collect()).collect();
+    assert_eq!(ids, vec![vec![], vec![5], vec![3, 1, 2], vec![4]]);
+
+    let text = matrix::render(&wider);
+    assert!(text.starts_with("Urgent and important: do first (0)\n  nothing\n\nImportant, not urgent: schedule (1)\n  [5] Learn Rust (priority 9 High)\n"), "{}", text);
+    assert!(text.contains("Neither: drop or defer (1)\n  [4] Tidy downloads (priority 0 Low)\n"), "{}", text);
+}
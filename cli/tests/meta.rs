@@ -0,0 +1,14 @@
+mod common;
+
+use todo::meta;
+
+#[test]
+fn comments_are_stamped_in_order() {
+    common::isolate_paths();
+    let at = |h| chrono::NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(h, 5, 0).unwrap();
+    meta::add_comment(7, "spoke to vendor ", at(9)).unwrap();
+    let thread = meta::add_comment(7, "waiting on quote", at(14)).unwrap();
+    let lines: Vec<String> = thread.iter().map(|c| format!("{} {}", c.at, c.text)).collect();
+    assert_eq!(lines, vec!["2030-05-06 09:05 spoke to vendor", "2030-05-06 14:05 waiting on quote"]);
+    assert!(meta::add_comment(7, "  ", at(15)).is_err());
+}
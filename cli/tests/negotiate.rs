@@ -0,0 +1,32 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::commands::Task;
+use todo::config::Config;
+use todo::engine::Options;
+use todo::negotiate;
+
+fn todo(id: i32, estimate: i32, priority: i32) -> Task {
+    serde_json::from_value(json!({
+        "id": id, "title": format!("todo {}", id), "type": "todo", "status": "pending",
+        "priority": priority, "estimate": estimate, "deadline": "2030-05-06T17:00:00"
+    }))
+    .unwrap()
+}
+
+#[test]
+fn offers_a_later_deadline_a_higher_priority_or_a_split() {
+    let cfg = Config::default();
+    let at = |d: u32, h: u32| NaiveDate::from_ymd_opt(2030, 5, d).unwrap().and_hms_opt(h, 0, 0).unwrap();
+    let opts = Options::new(&cfg, at(6, 8));
+    // Monday has 8 hours: 7 go to the important todo, leaving 1 for a 3-hour one
+    let tasks = vec![todo(1, 420, 9), todo(2, 180, 1)];
+
+    let o = negotiate::offer(&tasks, &opts, 2).unwrap();
+    assert!(o.fits.is_none());
+    assert_eq!(o.earliest, Some(at(7, 12)));
+    assert_eq!(o.raise, Some((10, vec![(1, "todo 1".to_string())])));
+    assert_eq!(o.split, Some((60, 120)));
+
+    assert_eq!(negotiate::offer(&tasks, &opts, 1).unwrap().fits.map(|s| s.start), Some(at(6, 9)));
+    assert!(negotiate::offer(&tasks, &opts, 3).is_err());
+}
@@ -0,0 +1,72 @@
+use clap_complete::Shell;
+use std::path::PathBuf;
+use todo::config;
+use todo::platform::{Completions, Host, Os};
+
+fn windows() -> Host {
+    Host {
+        os: Os::Windows,
+        env: |name| match name {
+            "USERPROFILE" => Some(r"C:\Users\ana".into()),
+            "APPDATA" => Some(r"C:\Users\ana\AppData\Roaming".into()),
+            "ProgramData" => Some(r"D:\ProgramData".into()),
+            _ => None,
+        },
+    }
+}
+
+fn linux() -> Host {
+    Host {
+        os: Os::Linux,
+        env: |name| match name {
+            "HOME" => Some("/home/ana".into()),
+            "SHELL" => Some("/usr/bin/zsh".into()),
+            "XDG_CONFIG_DIRS" => Some(":/etc/corp:/etc/xdg".into()),
+            _ => None,
+        },
+    }
+}
+
+#[test]
+fn windows_uses_its_own_homes_shell_and_opener() {
+    let w = windows();
+    assert_eq!(w.home(), Some(PathBuf::from(r"C:\Users\ana")));
+    assert_eq!(w.system_config_dir(), PathBuf::from(r"D:\ProgramData").join("todo"));
+    assert_eq!(w.shell(), Some(Shell::PowerShell));
+    assert_eq!(w.opener(), ("rundll32", &["url.dll,FileProtocolHandler"][..]));
+    assert_eq!(w.keyring_name(), "Windows Credential Manager");
+
+    let Completions { script, profile } = w.completions(Shell::PowerShell).unwrap();
+    let dir = PathBuf::from(r"C:\Users\ana").join("Documents").join("PowerShell");
+    assert_eq!(script, dir.join("Completions").join("todo.ps1"));
+    assert_eq!(profile, Some((dir.join("Microsoft.PowerShell_profile.ps1"), format!(". \"{}\"", script.display()))));
+    assert_eq!(w.completions(Shell::Fish).unwrap().script, PathBuf::from(r"C:\Users\ana\AppData\Roaming").join("fish").join("completions").join("todo.fish"));
+
+    let bare = Host { os: Os::Windows, env: |name| (name == "HOMEDRIVE").then(|| "C:".into()).or((name == "HOMEPATH").then(|| r"\Users\bo".into())) };
+    assert_eq!(bare.home(), Some(PathBuf::from(r"C:\Users\bo")));
+    assert_eq!(bare.system_config_dir(), PathBuf::from(r"C:\ProgramData").join("todo"));
+}
+
+#[test]
+fn unix_follows_xdg_and_shell() {
+    let l = linux();
+    assert_eq!(l.system_config_dir(), PathBuf::from("/etc/corp/todo"));
+    assert_eq!(l.shell(), Some(Shell::Zsh));
+    assert_eq!(l.completions(Shell::Zsh), Some(Completions { script: "/home/ana/.zfunc/_todo".into(), profile: None }));
+    assert_eq!(l.completions(Shell::Bash).unwrap().script, PathBuf::from("/home/ana/.local/share/bash-completion/completions/todo"));
+    let pwsh = l.completions(Shell::PowerShell).unwrap();
+    assert_eq!(pwsh.script, PathBuf::from("/home/ana/.config/powershell/Completions/todo.ps1"));
+
+    let mac = Host { os: Os::MacOs, env: |_| None };
+    assert_eq!((mac.home(), mac.shell(), mac.opener().0), (None, None, "open"));
+    assert_eq!(mac.completions(Shell::Bash), None);
+}
+
+#[test]
+fn user_config_is_laid_over_the_machine_one() {
+    let system = "api_url = \"http://scheduler.corp\"\n[network]\nrequests_per_second = 2.0\n[stale]\ndays = 30\n";
+    let user = "[stale]\ndays = 10\n";
+    let cfg = config::parse(&config::layer(system, user).unwrap()).unwrap();
+    assert_eq!((cfg.api_url.as_str(), cfg.network.requests_per_second, cfg.stale.days), ("http://scheduler.corp", 2.0, 10));
+    assert!(config::layer(system, "[stale").is_err());
+}
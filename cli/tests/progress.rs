@@ -0,0 +1,53 @@
+mod common;
+
+use common::{backend, fixture, json};
+use todo::commands::Task;
+use todo::{display, handlers, meta};
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn progress_counts_down_locally_when_the_backend_drops_remaining() {
+    let b = backend().await;
+    Mock::given(method("GET")).and(path("/tasks/12")).respond_with(json(200, "task_todo")).mount(&b.server).await;
+    Mock::given(method("PATCH"))
+        .and(path("/tasks/12"))
+        .and(body_json(serde_json::json!({ "remaining": 5 })))
+        .respond_with(json(200, "task_todo"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let (t, left) = handlers::log_progress(&b.api, 12, chrono::Duration::minutes(10)).await.unwrap();
+    assert_eq!((t.id, left), (12, 5));
+    assert_eq!(meta::get(12).unwrap().remaining_minutes, Some(5));
+
+    let all = meta::load().unwrap();
+    assert_eq!(handlers::with_remaining(std::slice::from_ref(&t), &all)[0].estimate, Some(5));
+    let sentence = display::task_sentence(&t, 1, &[], &all, None);
+    assert!(sentence.contains("estimate 10m / 15m done"), "{}", sentence);
+}
+
+#[tokio::test]
+async fn progress_is_stored_on_backends_that_keep_remaining() {
+    let b = backend().await;
+    let mut task = fixture("task_todo");
+    task["id"] = 13.into();
+    task["remaining"] = 3.into();
+    Mock::given(method("GET")).and(path("/tasks/13")).respond_with(ResponseTemplate::new(200).set_body_json(&task)).mount(&b.server).await;
+    let mut updated = task.clone();
+    updated["remaining"] = 0.into();
+    Mock::given(method("PATCH"))
+        .and(path("/tasks/13"))
+        .and(body_json(serde_json::json!({ "remaining": 0 })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&updated))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let (t, left) = handlers::log_progress(&b.api, 13, chrono::Duration::hours(1)).await.unwrap();
+    assert_eq!((t.remaining, left), (Some(0), 0));
+    assert_eq!(meta::get(13).unwrap().remaining_minutes, None);
+    let t: Task = serde_json::from_value(updated).unwrap();
+    assert_eq!(display::task_row(&t)[3], "15m / 15m done");
+}
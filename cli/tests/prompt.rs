@@ -0,0 +1,21 @@
+use todo::prompt::{self, MassChange};
+
+#[test]
+fn broad_changes_need_the_exact_count() {
+    assert_eq!(prompt::mass_change(20, 20, None, false), MassChange::Allowed);
+    assert_eq!(prompt::mass_change(73, 20, Some(73), false), MassChange::Allowed);
+    assert_eq!(prompt::mass_change(73, 20, None, true), MassChange::Confirm);
+    assert!(matches!(prompt::mass_change(73, 20, Some(50), true), MassChange::Refused(why) if why.contains("doesn't match")));
+    assert!(matches!(prompt::mass_change(73, 20, None, false), MassChange::Refused(why) if why.contains("--confirm-count 73")));
+}
+
+#[test]
+fn no_input_takes_defaults_or_fails_fast() {
+    prompt::set_no_input(true);
+    assert_eq!(prompt::ask("Scheduler API URL", "http://localhost:8000").unwrap(), "http://localhost:8000");
+    assert!(prompt::ask("Type 73 to go ahead", "").unwrap_err().to_string().contains("--no-input"));
+    assert!(prompt::confirm("Install completions?", true).unwrap());
+    assert!(!prompt::confirm("Merge into [3]?", false).unwrap());
+    assert!(!prompt::interactive());
+    prompt::set_no_input(false);
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::{backend, json};
+use todo::push::{self, Outcome};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+// One test so the shared journal isn't raced by a parallel test
+#[tokio::test]
+async fn reports_partial_failure_then_resumes_failures_only() {
+    let b = backend().await;
+    b.stub("GET", "/tasks/", "tasks_push").await;
+    b.stub("POST", "/calendar/push/10", "push_task").await;
+    Mock::given(method("POST"))
+        .and(path("/calendar/push/11"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(serde_json::json!({ "detail": "calendar quota" })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&b.server)
+        .await;
+
+    let reports = push::push_all(&b.api, None, 4).await.unwrap();
+    let outcomes: Vec<_> = reports.iter().map(|r| (r.task_id, r.outcome.clone())).collect();
+    assert_eq!(outcomes[0], (10, Outcome::Pushed("gcal-abc123".into())));
+    assert!(matches!(&outcomes[1], (11, Outcome::Failed(why)) if why.contains("calendar quota")));
+    assert_eq!(outcomes[2], (12, Outcome::Skipped("not scheduled yet".into())));
+    assert_eq!(push::load_journal().unwrap().failed, vec![11]);
+
+    // The failing mock is used up, so the retry succeeds
+    Mock::given(method("POST")).and(path("/calendar/push/11")).respond_with(json(200, "push_task")).mount(&b.server).await;
+    let journal = push::load_journal().unwrap();
+    let retried = push::push_all(&b.api, Some(&journal.failed), 4).await.unwrap();
+    assert_eq!(retried.len(), 1);
+    assert_eq!(retried[0].outcome, Outcome::Pushed("gcal-abc123".into()));
+    assert!(push::load_journal().unwrap().failed.is_empty());
+
+    // Deleted tasks leave the journal
+    todo::db::write_doc(push::JOURNAL, &push::Journal { failed: vec![11, 12], not_reached: vec![13] }).unwrap();
+    push::forget(&[11, 13]).unwrap();
+    assert_eq!((push::load_journal().unwrap().failed, push::load_journal().unwrap().not_reached), (vec![12], vec![]));
+    push::forget(&[12]).unwrap();
+    assert!(push::load_journal().unwrap().is_empty());
+}
@@ -0,0 +1,30 @@
+use serde_json::json;
+use todo::commands::Task;
+use todo::qr;
+
+fn task(value: serde_json::Value) -> Task {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn events_become_a_one_event_calendar_as_a_square_code() {
+    let standup = task(json!({ "id": 7, "title": "Standup", "type": "event", "location": "Room 2",
+        "start_time": "2030-05-06T09:30:00", "end_time": "2030-05-06T09:45:00" }));
+    let ics = qr::payload(&standup).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    assert!(ics.contains("DTSTART:20300506T093000") && ics.contains("SUMMARY:Standup") && ics.contains("LOCATION:Room 2"), "{}", ics);
+
+    let code = qr::render(&ics).unwrap();
+    let rows: Vec<&str> = code.lines().collect();
+    let width = rows[0].chars().count();
+    assert!(rows.iter().all(|r| r.chars().count() == width));
+    // Two modules per character row, so the lines are about half as many as the columns
+    assert_eq!(rows.len(), width.div_ceil(2));
+}
+
+#[test]
+fn untimed_tasks_have_nothing_to_share() {
+    let todo = task(json!({ "id": 8, "title": "Report", "type": "todo", "estimate": 60 }));
+    assert!(qr::payload(&todo).is_none());
+    assert!(qr::render(&"x".repeat(5000)).is_err());
+}
@@ -0,0 +1,24 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::agenda;
+use todo::commands::Task;
+use todo::config::Config;
+use todo::receipt;
+
+#[test]
+fn receipt_fits_the_paper_and_ticks_off_todos() {
+    let cfg: Config = toml::from_str("[availability]\nbreaks = [\"12:00-13:00\"]").unwrap();
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Standup", "type": "event", "start_time": "2030-05-07T09:30:00", "end_time": "2030-05-07T09:45:00" },
+        { "id": 2, "title": "Write the quarterly report for the board meeting", "type": "todo",
+          "start_time": "2030-05-07T14:00:00", "end_time": "2030-05-07T15:00:00" }
+    ]))
+    .unwrap();
+    let day = agenda::build(&tasks, &cfg, NaiveDate::from_ymd_opt(2030, 5, 7).unwrap(), 1).pop().unwrap();
+    let card = receipt::render(&day, 32);
+    assert!(card.lines().all(|l| l.chars().count() <= 32), "{}", card);
+    assert!(card.contains("T U E S D A Y") && card.contains("2030-05-07"), "{}", card);
+    assert!(card.contains("09:30  -  [1] Standup\n"), "{}", card);
+    assert!(card.contains("14:00 [ ] [2] Write the\n          quarterly report for\n"), "{}", card);
+    assert!(!card.contains("break"), "{}", card);
+}
@@ -0,0 +1,26 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use todo::recurrence::Rule;
+
+fn at(y: i32, m: u32, d: u32, h: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, 0, 0).unwrap()
+}
+
+#[test]
+fn monthly_and_weekly_rules_expand() {
+    let second_tuesday = Rule::parse("FREQ=MONTHLY;BYDAY=2TU;COUNT=3").unwrap();
+    assert_eq!(second_tuesday.occurrences(at(2030, 5, 14, 9), at(2031, 1, 1, 0)), vec![at(2030, 5, 14, 9), at(2030, 6, 11, 9), at(2030, 7, 9, 9)]);
+
+    let last_workday = Rule::parse("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1").unwrap();
+    assert_eq!(last_workday.occurrences(at(2030, 5, 31, 16), at(2030, 9, 1, 0)), vec![at(2030, 5, 31, 16), at(2030, 6, 28, 16), at(2030, 7, 31, 16), at(2030, 8, 30, 16)]);
+
+    let fortnightly = Rule::parse("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;UNTIL=20300523T235959Z").unwrap();
+    assert!(fortnightly.until_utc);
+    assert_eq!(fortnightly.occurrences(at(2030, 5, 6, 10), at(2031, 1, 1, 0)), vec![at(2030, 5, 6, 10), at(2030, 5, 9, 10), at(2030, 5, 20, 10), at(2030, 5, 23, 10)]);
+
+    // Feb 30th never happens; months without the day are skipped
+    let thirtieth = Rule::parse("FREQ=MONTHLY;BYMONTHDAY=30").unwrap();
+    assert_eq!(thirtieth.occurrences(at(2030, 1, 30, 8), at(2030, 4, 1, 0)), vec![at(2030, 1, 30, 8), at(2030, 3, 30, 8)]);
+    assert!(Rule::parse("FREQ=HOURLY").is_none());
+    // Days that aren't two-letter codes (say, in an imported calendar) are dropped, not a panic
+    assert_eq!(Rule::parse("FREQ=WEEKLY;BYDAY=€,1€,MO").unwrap().by_day.len(), 1);
+}
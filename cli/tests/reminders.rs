@@ -0,0 +1,96 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{backend, fixture, json};
+use serde_json::json as j;
+use todo::{ics, reminders};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const EXPORT: &str = "BEGIN:VCALENDAR\r
+X-WR-CALNAME:Groceries\r
+BEGIN:VTODO\r
+UID:r-1\r
+SUMMARY:Buy oat milk\\, two\r
+DESCRIPTION:the barista one\\nnot sweetened\r
+DUE;VALUE=DATE:20300531\r
+PRIORITY:1\r
+BEGIN:VALARM\r
+DESCRIPTION:Reminder\r
+END:VALARM\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:r-2\r
+SUMMARY:Return bottles\r
+DUE:20300601T170000\r
+STATUS:COMPLETED\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+#[test]
+fn vtodos_keep_their_list_due_date_and_notes() {
+    let found = ics::parse_todos(EXPORT);
+    assert_eq!(found.len(), 2);
+    let milk = &found[0];
+    assert_eq!((milk.id.as_str(), milk.list.as_str(), milk.title.as_str()), ("r-1", "Groceries", "Buy oat milk, two"));
+    assert_eq!(milk.notes.as_deref(), Some("the barista one\nnot sweetened"));
+    assert_eq!(milk.due, NaiveDate::from_ymd_opt(2030, 5, 31).unwrap().and_hms_opt(23, 59, 0));
+    assert_eq!((milk.priority, reminders::priority(milk.priority), milk.completed), (1, 8, false));
+    assert!(found[1].completed);
+    assert_eq!(found[1].due, NaiveDate::from_ymd_opt(2030, 6, 1).unwrap().and_hms_opt(17, 0, 0));
+}
+
+#[test]
+fn bridge_json_parses_and_rejects_bad_dates() {
+    let found = reminders::from_json(r#"[{"id": "x", "list": "Home", "name": "Fix tap", "body": "", "due": "2030-05-31", "completed": false, "priority": 9}]"#).unwrap();
+    assert_eq!(found[0].title, "Fix tap");
+    assert_eq!(found[0].notes, None);
+    assert_eq!(found[0].due, NaiveDate::from_ymd_opt(2030, 5, 31).unwrap().and_hms_opt(23, 59, 0));
+    assert!(reminders::from_json(r#"[{"title": "x", "due": "next week"}]"#).unwrap_err().to_string().contains("next week"));
+}
+
+#[tokio::test]
+async fn import_files_lists_as_categories_and_skips_repeats() {
+    let b = backend().await;
+    b.stub("GET", "/categories/", "categories").await;
+    Mock::given(method("POST"))
+        .and(path("/categories/"))
+        .and(body_partial_json(j!({"name": "Groceries"})))
+        .respond_with(json(200, "category"))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/tasks/"))
+        .and(body_partial_json(j!({"title": "Buy oat milk, two", "type": "todo", "category_id": 3, "priority": 8, "deadline": "2030-05-31T23:59:00"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("task_todo")))
+        .expect(1)
+        .mount(&b.server)
+        .await;
+
+    let found = ics::parse_todos(EXPORT);
+    let r = reminders::import(&b.api, &found, None, false).await.unwrap();
+    assert_eq!((r.tasks.len(), r.categories, r.skipped), (1, 1, 0));
+    let again = reminders::import(&b.api, &found, Some("groceries"), false).await.unwrap();
+    assert_eq!((again.tasks.len(), again.skipped), (0, 1));
+}
+
+#[tokio::test]
+async fn a_list_that_cant_become_a_category_doesnt_lose_the_rest() {
+    let b = backend().await;
+    b.stub("GET", "/categories/", "categories").await;
+    Mock::given(method("POST")).and(path("/categories/")).respond_with(ResponseTemplate::new(500)).mount(&b.server).await;
+    b.stub("POST", "/tasks/", "task_todo").await;
+
+    let found = reminders::from_json(
+        r#"[{"id": "h-1", "list": "Home", "name": "Fix tap", "due": "2030-05-31"},
+            {"id": "w-1", "list": "Work", "name": "File expenses", "due": "2030-05-31"}]"#,
+    )
+    .unwrap();
+    let r = reminders::import(&b.api, &found, None, false).await.unwrap();
+    assert_eq!(r.tasks.len(), 1);
+    assert!(r.failed[0].starts_with("File expenses: couldn't create category 'Work'"), "{:?}", r.failed);
+    let again = reminders::import(&b.api, &found, None, false).await.unwrap();
+    assert_eq!((again.tasks.len(), again.skipped, again.failed.len()), (0, 1, 1));
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::commands::Task;
+use todo::search::{self, Index};
+
+fn tasks() -> Vec<Task> {
+    serde_json::from_value(json!([
+        { "id": 3, "title": "Plan offsite", "type": "todo",
+          "description": "Agenda draft.\nReview the OKR list from last quarter before booking rooms" },
+        { "id": 5, "title": "Quarterly OKR review", "type": "todo" },
+        { "id": 8, "title": "Groceries", "type": "todo", "description": "okra, quarterly budget check" }
+    ]))
+    .unwrap()
+}
+
+fn index() -> Index {
+    Index::in_memory(&tasks()).unwrap()
+}
+
+fn ids(index: &Index, query: &str) -> Vec<i32> {
+    index.search(query).unwrap().iter().map(|h| h.id).collect()
+}
+
+#[test]
+fn every_word_must_match_and_phrases_rank_first() {
+    assert_eq!(ids(&index(), "quarterly OKR"), vec![5]);
+    assert_eq!(ids(&index(), "okr"), vec![3, 5]);
+    assert!(index().search("  ").unwrap().is_empty());
+    // Nothing in a query is read as FTS5 syntax
+    assert!(index().search("okr AND \"NEAR(").unwrap().is_empty());
+}
+
+#[test]
+fn hits_inside_descriptions_carry_a_snippet() {
+    let hit = index().search("rooms").unwrap().remove(0);
+    assert_eq!(hit.id, 3);
+    assert_eq!(hit.snippet.as_deref(), Some("…last quarter before booking rooms"));
+}
+
+#[test]
+fn the_database_index_follows_task_changes() {
+    common::isolate_paths();
+    let index = Index::open().unwrap();
+    let mut groceries = tasks().remove(2);
+    // Nothing is kept in step before the first build
+    search::update(&groceries).unwrap();
+    assert!(ids(&index, "okra").is_empty() && search::built_at().unwrap().is_none());
+
+    let built = NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(9, 0, 0).unwrap();
+    assert_eq!(index.rebuild(&tasks(), built).unwrap(), 3);
+    assert_eq!(search::built_at().unwrap().as_deref(), Some("2030-05-06T09:00:00"));
+    groceries.description = Some("oat milk".into());
+    search::update(&groceries).unwrap();
+    assert_eq!((ids(&index, "okra"), ids(&index, "oat milk")), (vec![], vec![8]));
+    search::forget(8).unwrap();
+    assert!(ids(&index, "oat").is_empty());
+
+    search::clear().unwrap();
+    assert!(ids(&index, "okr").is_empty() && search::built_at().unwrap().is_none());
+}
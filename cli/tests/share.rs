@@ -0,0 +1,40 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::json;
+use todo::commands::Task;
+use todo::config::Config;
+use todo::share;
+use todo::slots::Interval;
+
+fn at(day: u32, h: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2030, 5, day).unwrap().and_hms_opt(h, 0, 0).unwrap()
+}
+
+// 2030-05-06 is a Monday; default availability is 09:00-17:00 on weekdays
+#[test]
+fn shares_merged_times_without_details() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Therapy", "type": "event", "location": "Dr. Who",
+          "start_time": "2030-05-06T09:00:00", "end_time": "2030-05-06T10:30:00" },
+        { "id": 2, "title": "Write report", "type": "todo", "status": "pending",
+          "start_time": "2030-05-06T10:00:00", "end_time": "2030-05-06T11:00:00" },
+        { "id": 3, "title": "Maybe lunch", "type": "event", "transparency": "tentative",
+          "start_time": "2030-05-06T12:00:00", "end_time": "2030-05-06T13:00:00" }
+    ]))
+    .unwrap();
+    let bundle = share::build(&tasks, &Config::default(), Interval::new(at(6, 0), at(7, 0)), at(5, 20));
+    let slots: Vec<(&str, &str, &str)> = bundle.slots.iter().map(|s| (s.start.as_str(), s.end.as_str(), s.state)).collect();
+    assert_eq!(
+        slots,
+        vec![
+            ("2030-05-06T09:00", "2030-05-06T11:00", "busy"),
+            ("2030-05-06T11:00", "2030-05-06T12:00", "free"),
+            ("2030-05-06T12:00", "2030-05-06T13:00", "tentative"),
+            ("2030-05-06T13:00", "2030-05-06T17:00", "free"),
+        ]
+    );
+    let page = share::html(&bundle);
+    let data = serde_json::to_string(&bundle).unwrap();
+    for secret in ["Therapy", "Dr. Who", "Write report", "lunch"] {
+        assert!(!page.contains(secret) && !data.contains(secret), "{} leaked", secret);
+    }
+}
@@ -0,0 +1,41 @@
+use serde_json::json;
+use todo::commands::Task;
+use todo::meta::MetaMap;
+use todo::sort::SortSpec;
+
+fn tasks() -> Vec<Task> {
+    serde_json::from_value(json!([
+        { "id": 5, "title": "pay rent", "type": "todo", "priority": 2, "deadline": "2030-05-07T12:00:00" },
+        { "id": 1, "title": "Ship release", "type": "todo", "priority": 8 },
+        { "id": 4, "title": "Book flights", "type": "todo", "priority": 8, "deadline": "2030-05-07T12:00:00" },
+        { "id": 2, "title": "Answer email", "type": "todo", "priority": 2, "deadline": "2030-05-07T12:00:00" },
+        { "id": 3, "title": "Standup", "type": "event", "start_time": "2030-05-06T09:00:00" }
+    ]))
+    .unwrap()
+}
+
+fn order(spec: &str) -> Vec<i32> {
+    let mut tasks = tasks();
+    spec.parse::<SortSpec>().unwrap().apply(&mut tasks, &MetaMap::new());
+    tasks.iter().map(|t| t.id).collect()
+}
+
+#[test]
+fn later_keys_break_ties_and_missing_values_go_last() {
+    assert_eq!(order("deadline,priority:desc,title"), vec![4, 2, 5, 1, 3]);
+    assert_eq!(order("deadline:desc,title"), vec![2, 4, 5, 1, 3]);
+    // Highest priority first by default, events without one after
+    assert_eq!(order("priority"), vec![1, 4, 2, 5, 3]);
+    assert_eq!(order("priority:asc,title:desc"), vec![5, 2, 1, 4, 3]);
+    // Full ties fall back to id order instead of whatever the backend sent
+    assert_eq!(order("deadline"), vec![2, 4, 5, 1, 3]);
+    assert_eq!(order("due"), vec![3, 2, 4, 5, 1]);
+}
+
+#[test]
+fn rejects_unknown_keys_and_directions() {
+    assert_eq!(" Title : DESC ".parse::<SortSpec>(), "title:desc".parse::<SortSpec>());
+    assert!("deadline,size".parse::<SortSpec>().unwrap_err().contains("Unknown sort key 'size'"));
+    assert!("title:down".parse::<SortSpec>().unwrap_err().contains("asc or desc"));
+    assert!(",".parse::<SortSpec>().is_err());
+}
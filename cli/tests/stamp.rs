@@ -0,0 +1,16 @@
+use chrono::NaiveDate;
+use todo::stamp;
+
+#[test]
+fn unchanged_times_go_back_exactly_as_stored() {
+    let five = NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(17, 0, 0).unwrap();
+    for stored in ["2030-05-06T17:00:00", "2030-05-06T17:00", "2030-05-06T17:00:00.000000", "2030-05-06 17:00:00", "2030-05-06T17:00:00Z"] {
+        assert_eq!(stamp::parse(stored), Some(five), "{}", stored);
+        assert_eq!(stamp::keep(Some(stored), five), stored);
+        assert!(!stamp::changed(Some(stored), "2030-05-06T17:00:00"));
+    }
+    assert_eq!(stamp::keep(Some("2030-05-06T17:00"), five + chrono::Duration::days(1)), "2030-05-07T17:00:00");
+    assert_eq!(stamp::keep(None, five), "2030-05-06T17:00:00");
+    assert!(stamp::changed(Some("2030-05-06T17:00"), "2030-05-06T17:30:00"));
+    assert!(stamp::changed(None, "2030-05-06T17:00:00"));
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use common::{backend, json};
+use serde_json::json as j;
+use todo::commands::Task;
+use todo::status::{self, Format};
+use wiremock::matchers::{method, path};
+use wiremock::Mock;
+
+fn at(h: u32, m: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2030, 5, 6).unwrap().and_hms_opt(h, m, 0).unwrap()
+}
+
+#[test]
+fn summary_shows_the_next_item_and_todays_deadlines() {
+    let tasks: Vec<Task> = serde_json::from_value(j!([
+        { "id": 1, "title": "Standup", "type": "event", "start_time": "2030-05-06T09:30:00", "end_time": "2030-05-06T09:45:00" },
+        { "id": 2, "title": "Review #42 at 100%", "type": "todo", "status": "pending", "deadline": "2030-05-06T17:00:00",
+          "start_time": "2030-05-06T14:00:00", "end_time": "2030-05-06T15:00:00" },
+        { "id": 3, "title": "Taxes", "type": "todo", "status": "done", "deadline": "2030-05-06T12:00:00" }
+    ]))
+    .unwrap();
+    let now = at(10, 0);
+    let s = status::summarize(&tasks, now);
+    assert_eq!(s.due_today, 1);
+    assert_eq!(status::render(&s, Format::Plain, now), "14:00 [2] Review #42 at 100% · 1 due");
+    assert_eq!(status::render(&s, Format::Tmux, now), "14:00 [2] Review ##42 at 100% · 1 due");
+    assert_eq!(status::render(&s, Format::Polybar, now), "14:00 [2] Review #42 at 100%% · 1 due");
+    let bar: serde_json::Value = serde_json::from_str(&status::render(&s, Format::Waybar, now)).unwrap();
+    assert_eq!(bar["class"], "due");
+}
+
+#[tokio::test]
+async fn polling_reuses_the_cached_summary() {
+    let b = backend().await;
+    Mock::given(method("GET")).and(path("/tasks/")).respond_with(json(200, "tasks")).expect(1).mount(&b.server).await;
+    let now = at(10, 0);
+    let first = status::current(&b.api, Duration::seconds(60), now).await.unwrap();
+    let again = status::current(&b.api, Duration::seconds(60), now + Duration::seconds(5)).await.unwrap();
+    assert_eq!(first, again);
+}
@@ -0,0 +1,38 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use todo::agenda;
+use todo::commands::Task;
+use todo::config::Config;
+use todo::theme::{self, Ink};
+
+#[test]
+fn presets_are_found_by_name_and_unknown_ones_listed() {
+    assert_eq!(theme::find(" High-Contrast ").unwrap().name, "high-contrast");
+    assert!(theme::find("sepia").unwrap_err().contains("deuteranopia-safe"));
+    assert_eq!(Ink::Hex("#56B4E9").paint("x"), "\x1b[38;2;86;180;233mx\x1b[0m");
+    assert_eq!(Ink::Plain.paint("x"), "x");
+
+    // Reds and greens both land on colors a deuteranope tells apart
+    let safe = theme::find("deuteranopia-safe").unwrap();
+    assert_eq!((safe.category("#E15759"), safe.category("#59A14F")), ("#D55E00".to_string(), "#009E73".to_string()));
+    assert_eq!(theme::DEFAULT.category("#3fa34d"), "#3fa34d");
+    assert_eq!(safe.priority(Some(9), "High"), "\x1b[1;38;2;213;94;0mHigh\x1b[0m");
+    assert_eq!(safe.priority(Some(1), "Low"), "Low");
+}
+
+#[test]
+fn the_chosen_theme_colors_agenda_blocks() {
+    let tasks: Vec<Task> = serde_json::from_value(json!([
+        { "id": 1, "title": "Standup", "type": "event", "start_time": "2030-05-06T09:30:00", "end_time": "2030-05-06T09:45:00" },
+        { "id": 2, "title": "Report", "type": "todo", "start_time": "2030-05-06T14:00:00", "end_time": "2030-05-06T15:00:00" }
+    ]))
+    .unwrap();
+    let days = agenda::build(&tasks, &Config::default(), NaiveDate::from_ymd_opt(2030, 5, 6).unwrap(), 1);
+    assert!(agenda::render(&days, true).contains("\n  09:30-09:45  [1] Standup\n"));
+
+    theme::set("deuteranopia-safe").unwrap();
+    let shown = agenda::render(&days, true);
+    assert!(shown.contains("\x1b[38;2;86;180;233m  09:30-09:45  [1] Standup\x1b[0m"), "{}", shown);
+    assert!(shown.contains("\x1b[38;2;230;159;0m  14:00-15:00  [2] Report\x1b[0m"), "{}", shown);
+    assert!(theme::set("sepia").is_err());
+}
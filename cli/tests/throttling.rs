@@ -0,0 +1,67 @@
+mod common;
+
+use common::{backend, json};
+use std::time::{Duration, Instant};
+use todo::api::Api;
+use todo::ratelimit::{retry_after, RateLimiter};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn retries_after_429_honoring_retry_after() {
+    let b = backend().await;
+    Mock::given(method("GET"))
+        .and(path("/categories/"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&b.server)
+        .await;
+    b.stub("GET", "/categories/", "categories").await;
+
+    let cats = b.api.categories().await.unwrap();
+    assert_eq!(cats.len(), 2);
+    assert!(b.api.throttle_stats().summary().unwrap().contains("2 retried"));
+}
+
+#[tokio::test]
+async fn gives_up_after_repeated_429s() {
+    let b = backend().await;
+    Mock::given(method("GET"))
+        .and(path("/categories/"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .mount(&b.server)
+        .await;
+    assert!(b.api.categories().await.is_err());
+}
+
+#[tokio::test]
+async fn limiter_spaces_out_requests() {
+    let b = backend().await;
+    Mock::given(method("GET")).and(path("/categories/")).respond_with(json(200, "categories")).mount(&b.server).await;
+    let api = Api::new(&b.server.uri()).with_rate_limit(20.0, 1.0);
+
+    let started = Instant::now();
+    for _ in 0..4 {
+        api.categories().await.unwrap();
+    }
+    // 1 burst token, then 3 more at 50ms each
+    assert!(started.elapsed() >= Duration::from_millis(140));
+    assert!(api.throttle_stats().summary().unwrap().contains("3 delayed"));
+}
+
+#[tokio::test]
+async fn limiter_allows_bursts() {
+    let limiter = RateLimiter::new(1.0, 5.0);
+    for _ in 0..5 {
+        assert!(limiter.acquire().await.is_zero());
+    }
+    assert!(!limiter.acquire().await.is_zero());
+}
+
+#[test]
+fn parses_retry_after_forms() {
+    assert_eq!(retry_after("3"), Some(Duration::from_secs(3)));
+    assert_eq!(retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+    assert_eq!(retry_after("soon"), None);
+}
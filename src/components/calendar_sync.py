@@ -15,7 +15,8 @@ from src.components import models, crud
 
 router = APIRouter(prefix="/calendar", tags=["calendar"])
 
-SCOPES = ['https://www.googleapis.com/auth/calendar']
+# Google Tasks is read for `todo import google-tasks`; tokens granted before it was added need a new sign-in
+SCOPES = ['https://www.googleapis.com/auth/calendar', 'https://www.googleapis.com/auth/tasks.readonly']
 CREDENTIALS_FILE = os.getenv('GOOGLE_CREDENTIALS_PATH', './credentials.json')
 TOKEN_PICKLE = os.getenv('GOOGLE_TOKEN_PATH', './token.pickle')
 REDIRECT_URI = os.getenv('GOOGLE_REDIRECT_URI', 'http://localhost:8000/calendar/oauth2callback')
@@ -35,11 +36,76 @@ def build_description(task: models.Task) -> str:
         "duration": task.duration,
         "deadline": task.deadline.isoformat() if task.deadline else None,
         "scheduled_for": task.scheduled_for.isoformat() if task.scheduled_for else None,
+        "organizer": task.organizer,
+        "meeting_url": task.meeting_url,
     }
     desc = task.description or ""
     return f"{desc}\n\nTASK:{json.dumps(meta)}"
 
 
+def build_event_body(task: models.Task, icons: bool = True) -> dict:
+    """Google Calendar event fields for a scheduled task; with `icons`, the category's icon
+    goes before the title."""
+    summary = task.title
+    private = {}
+    if icons and task.category and task.category.icon:
+        summary = f"{task.category.icon} {task.title}"
+        # Remembered so a sync can take the icon off again instead of saving it into the title
+        private['todo_icon'] = task.category.icon
+    body = {
+        'summary':     summary,
+        'extendedProperties': {'private': private},
+        'description': build_description(task),
+        'start': {
+            'dateTime': task.start_time.isoformat(),
+            'timeZone': DEFAULT_TIMEZONE,
+        },
+        'end': {
+            'dateTime': task.end_time.isoformat(),
+            'timeZone': DEFAULT_TIMEZONE,
+        },
+    }
+    if task.all_day:
+        # Google's end date is exclusive, like ours
+        body['start'] = {'date': task.start_time.date().isoformat()}
+        body['end'] = {'date': task.end_time.date().isoformat()}
+    # Google has no plain meeting-link field, so the link stands in for a missing location
+    if task.location or task.meeting_url:
+        body['location'] = task.location or task.meeting_url
+    if task.attendees:
+        body['attendees'] = [{'email': a} for a in task.attendees]
+    # Google keeps free/busy and tentative apart: transparency vs. event status
+    body['transparency'] = 'transparent' if task.transparency == models.Transparency.FREE else 'opaque'
+    if task.transparency == models.Transparency.TENTATIVE:
+        body['status'] = 'tentative'
+    return body
+
+
+def event_title(item: dict, icon: Optional[str] = None) -> str:
+    """An event's summary without the category icon a push put before it; `icon` covers
+    events pushed before the icon was recorded on them."""
+    title = item.get('summary', '')
+    icon = item.get('extendedProperties', {}).get('private', {}).get('todo_icon') or icon
+    if icon and title.startswith(f"{icon} "):
+        return title[len(icon) + 1:]
+    return title
+
+
+def attendee_emails(item: dict) -> Optional[list]:
+    """Email addresses of a Google event's attendees, or None."""
+    return [a['email'] for a in item.get('attendees', []) if a.get('email')] or None
+
+
+def event_transparency(item: dict) -> models.Transparency:
+    """Free if the event is transparent, tentative if it or our own RSVP is, else busy."""
+    if item.get('transparency') == 'transparent':
+        return models.Transparency.FREE
+    mine = next((a for a in item.get('attendees', []) if a.get('self')), {})
+    if item.get('status') == 'tentative' or mine.get('responseStatus') in ('tentative', 'needsAction'):
+        return models.Transparency.TENTATIVE
+    return models.Transparency.BUSY
+
+
 def parse_description(desc: str) -> Tuple[str, Optional[dict]]:
     """Split description and parse embedded metadata if present."""
     if not desc:
@@ -145,9 +211,13 @@ def sync_calendar(db: Session = Depends(get_db)):
     for item in events:
         start_iso = item['start'].get('dateTime')
         end_iso = item['end'].get('dateTime')
+        all_day = not start_iso and 'date' in item['start']
+        if all_day:
+            start_iso = item['start']['date'] + 'T00:00:00'
+            end_iso = item['end'].get('date', item['start']['date']) + 'T00:00:00'
         if not start_iso or not end_iso:
             continue
-        title = item.get('summary', '')
+        title = event_title(item)
         desc_raw = item.get('description', '')
         user_desc, meta = parse_description(desc_raw)
         start_dt = datetime.fromisoformat(start_iso)
@@ -156,11 +226,21 @@ def sync_calendar(db: Session = Depends(get_db)):
         if meta and meta.get('id'):
             local = crud.get_task(db, int(meta['id']))
             if local:
-                local.title = title
+                local.title = event_title(item, local.category.icon if local.category else None)
                 local.description = user_desc
                 local.start_time = start_dt
                 local.end_time = end_dt
                 local.duration = int((end_dt - start_dt).total_seconds() // 60)
+                local.location = item.get('location')
+                local.all_day = all_day
+                local.attendees = attendee_emails(item)
+                local.transparency = event_transparency(item)
+                if meta.get('organizer'):
+                    local.organizer = meta['organizer']
+                if meta.get('meeting_url'):
+                    local.meeting_url = meta['meeting_url']
+                    if local.location == local.meeting_url:
+                        local.location = None
                 if meta.get('deadline'):
                     local.deadline = datetime.fromisoformat(meta['deadline'])
                 if meta.get('estimate') is not None:
@@ -192,6 +272,10 @@ def sync_calendar(db: Session = Depends(get_db)):
             end_iso,
             external_id=item['id'],
             description=desc_raw,
+            location=item.get('location'),
+            attendees=attendee_emails(item),
+            all_day=all_day,
+            transparency=event_transparency(item),
         )
         imported += 1
 
@@ -205,7 +289,7 @@ def sync_calendar(db: Session = Depends(get_db)):
 
 
 @router.post("/push/{task_id}")
-def push_task(task_id: int, db: Session = Depends(get_db)):
+def push_task(task_id: int, icons: bool = True, db: Session = Depends(get_db)):
     """
     Push a single local event or scheduled todo to Google Calendar.
     """
@@ -218,18 +302,7 @@ def push_task(task_id: int, db: Session = Depends(get_db)):
     creds = get_credentials()
     service = build('calendar', 'v3', credentials=creds)
 
-    event_body = {
-        'summary':     task.title,
-        'description': build_description(task),
-        'start': {
-            'dateTime': task.start_time.isoformat(),
-            'timeZone': DEFAULT_TIMEZONE,
-        },
-        'end': {
-            'dateTime': task.end_time.isoformat(),
-            'timeZone': DEFAULT_TIMEZONE,
-        },
-    }
+    event_body = build_event_body(task, icons)
 
     if task.external_id:
         # patch existing (preserves eventType)
@@ -252,7 +325,7 @@ def push_task(task_id: int, db: Session = Depends(get_db)):
 
 
 @router.post("/push-all")
-def push_all(db: Session = Depends(get_db)):
+def push_all(icons: bool = True, db: Session = Depends(get_db)):
     """
     Push all local events and scheduled todos to Google Calendar.
     """
@@ -268,18 +341,7 @@ def push_all(db: Session = Depends(get_db)):
         if task.type == models.TaskType.TODO and (not task.start_time or not task.end_time):
             continue
 
-        event_body = {
-            'summary':     task.title,
-            'description': build_description(task),
-            'start': {
-                'dateTime': task.start_time.isoformat(),
-                'timeZone': DEFAULT_TIMEZONE,
-            },
-            'end': {
-                'dateTime': task.end_time.isoformat(),
-                'timeZone': DEFAULT_TIMEZONE,
-            },
-        }
+        event_body = build_event_body(task, icons)
 
         if task.external_id:
             # patch, not update
@@ -19,13 +19,22 @@ def create_task(db: Session, task: schemas.TaskCreate, category_id: int = None):
         type=task.type,
         status=task.status,
         priority=task.priority,
+        contexts=task.contexts,
+        assignee=task.assignee or None,
         start_time=task.start_time,
         end_time=task.end_time,
         duration=task.duration,
+        all_day=task.all_day,
+        location=task.location,
+        attendees=task.attendees,
+        organizer=task.organizer,
+        meeting_url=task.meeting_url,
+        transparency=task.transparency,
         deadline=task.deadline,
         estimate=task.estimate,
         scheduled_for=task.scheduled_for,
         recurrence_rule=task.recurrence_rule,
+        external_id=task.external_id,
         category_id=category_id
     )
     if db_task.type == models.TaskType.EVENT and db_task.start_time and db_task.end_time and not db_task.duration:
@@ -42,6 +51,8 @@ def update_task(db: Session, db_task: models.Task, updates: schemas.TaskUpdate):
     for var, value in vars(updates).items():
         if value is not None:
             setattr(db_task, var, value)
+    if db_task.assignee == "":
+        db_task.assignee = None
     if db_task.type == models.TaskType.EVENT and db_task.start_time and db_task.end_time and not db_task.duration:
         delta = db_task.end_time - db_task.start_time
         db_task.duration = int(delta.total_seconds() // 60)
@@ -64,20 +75,35 @@ def get_category(db: Session, category_id: int):
 
 
 def create_category(db: Session, category: schemas.CategoryCreate):
-    db_cat = models.Category(name=category.name, color=category.color)
+    db_cat = models.Category(name=category.name, color=category.color, icon=category.icon or None)
     db.add(db_cat)
     db.commit()
     db.refresh(db_cat)
     return db_cat
 
 
+def update_category(db: Session, db_cat: models.Category, updates: schemas.CategoryUpdate):
+    for var, value in vars(updates).items():
+        if value is not None:
+            setattr(db_cat, var, value)
+    if db_cat.icon == "":
+        db_cat.icon = None
+    db.commit()
+    db.refresh(db_cat)
+    return db_cat
+
+
 def create_or_update_event(
         db: Session,
         title: str,
         start_iso: str,
         end_iso: str,
         external_id: str,
-        description: str = None
+        description: str = None,
+        location: str = None,
+        attendees: list = None,
+        all_day: bool = False,
+        transparency: models.Transparency = models.Transparency.BUSY
 ):
     """
     Upsert a Google Calendar event into the local DB as a Task.
@@ -94,6 +120,10 @@ def create_or_update_event(
         existing.start_time = start_dt
         existing.end_time = end_dt
         existing.duration = duration
+        existing.location = location
+        existing.attendees = attendees
+        existing.all_day = all_day
+        existing.transparency = transparency
         db.commit()
         db.refresh(existing)
         return existing
@@ -106,6 +136,10 @@ def create_or_update_event(
             start_time=start_dt,
             end_time=end_dt,
             duration=duration,
+            location=location,
+            attendees=attendees,
+            all_day=all_day,
+            transparency=transparency,
             external_id=external_id
         )
         db.add(new_event)
@@ -1,5 +1,5 @@
 #src/components/database.py
-from sqlalchemy import create_engine
+from sqlalchemy import create_engine, inspect, text
 from sqlalchemy.orm import sessionmaker
 
 from src.components.models import Base
@@ -8,5 +8,21 @@ SQLALCHEMY_DATABASE_URL = "sqlite:///./tasks.db"
 engine = create_engine(SQLALCHEMY_DATABASE_URL, connect_args={"check_same_thread": False})
 SessionLocal = sessionmaker(autocommit=False, autoflush=False, bind=engine)
 
+
+def add_missing_columns(bind):
+    """create_all never alters existing tables, so add nullable columns introduced since."""
+    inspector = inspect(bind)
+    for table in Base.metadata.sorted_tables:
+        if not inspector.has_table(table.name):
+            continue
+        existing = {c["name"] for c in inspector.get_columns(table.name)}
+        with bind.begin() as conn:
+            for col in table.columns:
+                if col.name not in existing and col.nullable:
+                    col_type = col.type.compile(bind.dialect)
+                    conn.execute(text(f"ALTER TABLE {table.name} ADD COLUMN {col.name} {col_type}"))
+
+
 # j main database
 Base.metadata.create_all(bind=engine)
+add_missing_columns(engine)
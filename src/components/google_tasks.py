@@ -0,0 +1,106 @@
+from datetime import datetime, time
+from typing import Optional
+
+from fastapi import APIRouter, Depends, HTTPException
+from googleapiclient.discovery import build
+from sqlalchemy.orm import Session
+
+from src.components import models
+from src.components.calendar_sync import get_credentials, get_db
+from src.components.schemas import GoogleTasksImport
+
+
+router = APIRouter(prefix="/import", tags=["import"])
+
+# Google Tasks has no estimates, but every todo needs one
+DEFAULT_ESTIMATE_MINUTES = 30
+
+
+def due_deadline(due: Optional[str]) -> Optional[datetime]:
+    """
+    Google Tasks keeps only the date of a due time (sent as midnight UTC),
+    so the task is due by the end of that day.
+    """
+    if not due:
+        return None
+    return datetime.combine(datetime.fromisoformat(due.replace('Z', '+00:00')).date(), time(23, 59))
+
+
+def list_category(db: Session, name: str) -> models.Category:
+    """The category named after a task list, created on first import."""
+    # Category names hold 50 characters; look up the same cut the category was created with
+    name = name[:50]
+    category = db.query(models.Category).filter(models.Category.name == name).first()
+    if not category:
+        category = models.Category(name=name)
+        db.add(category)
+        db.commit()
+        db.refresh(category)
+    return category
+
+
+def fetch_all(request_for, key: str = 'items') -> list:
+    """Follow nextPageToken until a Google list call is exhausted."""
+    items, page_token = [], None
+    while True:
+        result = request_for(page_token).execute()
+        items.extend(result.get(key, []))
+        page_token = result.get('nextPageToken')
+        if not page_token:
+            return items
+
+
+@router.post("/google-tasks")
+def import_google_tasks(req: GoogleTasksImport, db: Session = Depends(get_db)):
+    """
+    Copy Google Tasks into local todos, one category per task list.
+    Due dates become deadlines and notes descriptions; importing again
+    updates the tasks it brought in before rather than duplicating them.
+    Todos need a deadline, so items never given a due date are skipped and
+    counted; one imported before keeps its deadline if the due date goes.
+    """
+    service = build('tasks', 'v1', credentials=get_credentials())
+    lists = fetch_all(lambda token: service.tasklists().list(maxResults=100, pageToken=token))
+    if req.task_list:
+        lists = [l for l in lists if l.get('title', '').lower() == req.task_list.lower()]
+        if not lists:
+            raise HTTPException(status_code=404, detail=f"No Google Tasks list named '{req.task_list}'.")
+
+    imported = 0
+    updated = 0
+    skipped = 0
+    for task_list in lists:
+        category = list_category(db, task_list.get('title') or 'Google Tasks')
+        items = fetch_all(lambda token: service.tasks().list(
+            tasklist=task_list['id'],
+            maxResults=100,
+            showCompleted=req.include_completed,
+            showHidden=req.include_completed,
+            pageToken=token,
+        ))
+        for item in items:
+            if item.get('deleted') or not item.get('title', '').strip():
+                continue
+            deadline = due_deadline(item.get('due'))
+            task = db.query(models.Task).filter(models.Task.google_task_id == item['id']).first()
+            if task:
+                updated += 1
+            elif deadline is None:
+                skipped += 1
+                continue
+            else:
+                task = models.Task(type=models.TaskType.TODO, google_task_id=item['id'], category_id=category.id)
+                imported += 1
+            task.title = item['title'].strip()[:200]
+            task.description = item.get('notes') or task.description
+            task.deadline = deadline or task.deadline
+            if task.estimate is None:
+                task.estimate = DEFAULT_ESTIMATE_MINUTES
+            if item.get('status') == 'completed':
+                task.status = models.Status.DONE
+            elif task.status is None or task.status == models.Status.DONE:
+                task.status = models.Status.NOT_STARTED
+            db.add(task)
+        db.commit()
+
+    return {"imported": imported, "updated": updated, "skipped": skipped, "lists": [l.get('title') for l in lists]}
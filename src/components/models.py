@@ -2,7 +2,7 @@
 import enum
 from datetime import datetime
 
-from sqlalchemy import Column, Integer, String, Text, DateTime, Date, ForeignKey, Enum
+from sqlalchemy import Column, Integer, String, Text, DateTime, Date, ForeignKey, Enum, JSON, Boolean
 from sqlalchemy.ext.declarative import declarative_base
 from sqlalchemy.orm import relationship
 
@@ -18,14 +18,24 @@ class Status(enum.Enum):
     PENDING = "pending"
     LATER = "later"
     NOT_STARTED = "not-started"
+    IN_PROGRESS = "in-progress"
+    WAITING = "waiting"  # delegated; not part of our own scheduled load
     DONE = "done"
 
 
+class Transparency(enum.Enum):
+    """How an event shows on the calendar; only busy events block scheduling."""
+    BUSY = "busy"
+    FREE = "free"
+    TENTATIVE = "tentative"
+
+
 class Category(Base):
     __tablename__ = "categories"
     id = Column(Integer, primary_key=True, autoincrement=True)
     name = Column(String(50), nullable=False, unique=True)
     color = Column(String(7), default="#CCCCCC")
+    icon = Column(String(16), nullable=True)  # emoji shown before task titles, e.g. "📚"
 
     tasks = relationship("Task", back_populates="category")
 
@@ -38,6 +48,8 @@ class Task(Base):
     type = Column(Enum(TaskType), nullable=False)
     status = Column(Enum(Status), nullable=False, default=Status.NOT_STARTED)
     priority = Column(Integer, default=0)
+    contexts = Column(JSON, nullable=True)  # GTD contexts without the "@", e.g. ["errands"]
+    assignee = Column(String(100), nullable=True)  # household member; unassigned tasks are everyone's
 
     created_at = Column(DateTime, default=datetime.utcnow)
     updated_at = Column(DateTime, default=datetime.utcnow, onupdate=datetime.utcnow)
@@ -50,12 +62,21 @@ class Task(Base):
     start_time = Column(DateTime, nullable=True)
     end_time = Column(DateTime, nullable=True)
     duration = Column(Integer, nullable=True)  # in minutes
+    all_day = Column(Boolean, nullable=True, default=False)  # start/end are midnights
+    location = Column(String(200), nullable=True)
+    attendees = Column(JSON, nullable=True)  # list of email addresses
+    organizer = Column(String(200), nullable=True)
+    meeting_url = Column(String(500), nullable=True)
+    transparency = Column(Enum(Transparency), nullable=True, default=Transparency.BUSY)
 
     # kTodo-specific fields
     deadline = Column(DateTime, nullable=True)
     estimate = Column(Integer, nullable=True)  # in minutes
+    remaining = Column(Integer, nullable=True)  # minutes still to do; the estimate until progress is logged
+    optional = Column(Boolean, nullable=True, default=False)  # nice-to-have: scheduled last, only into slack
     scheduled_for = Column(Date, nullable=True)
     recurrence_rule = Column(String, nullable=True)
 
     # External sync field
     external_id = Column(String, unique=True, nullable=True)  # Google Calendar event ID
+    google_task_id = Column(String, unique=True, nullable=True)  # Google Tasks item it was imported from
@@ -1,5 +1,5 @@
 from datetime import datetime, date, time, timedelta
-from typing import List, Tuple, Dict
+from typing import List, Tuple, Dict, Optional
 
 from sqlalchemy.orm import Session
 
@@ -32,26 +32,55 @@ class AvailabilityConfig:
     Represents availability windows for each weekday.
     Attributes:
         availability: Dict[int, List[Tuple[time, time]]]
+        dates: Dict[date, List[Tuple[time, time]]] - per-date windows (day templates)
+            that replace the weekday's
     """
 
-    def __init__(self, availability: Dict[int, List[Tuple[time, time]]]):
+    def __init__(self, availability: Dict[int, List[Tuple[time, time]]],
+                 dates: Optional[Dict[date, List[Tuple[time, time]]]] = None):
         self.availability = availability
+        self.dates = dates or {}
 
     def get_windows_for_date(self, target_date: date) -> List[timeInterval]:
         """
-        Return available datetime intervals for the given date based on weekday availability.
+        Return available datetime intervals for the given date: its own windows if it has
+        any, otherwise the weekday's.
         """
-        weekday = target_date.weekday()
+        if target_date in self.dates:
+            configured = self.dates[target_date]
+        else:
+            configured = self.availability.get(target_date.weekday(), [])
         windows: List[timeInterval] = []
-        for start_t, end_t in self.availability.get(weekday, []):
+        for start_t, end_t in configured:
             windows.append((datetime.combine(target_date, start_t),
                             datetime.combine(target_date, end_t)))
         return windows
 
 
-def find_busy_intervals(db: Session, target_date: date) -> List[timeInterval]:
+def blocks_time(task: models.Task, all_day_busy: bool = False) -> bool:
+    """Whether a scheduled task occupies its slot for scheduling purposes."""
+    if task.all_day and not all_day_busy:
+        return False
+    return task.transparency in (None, models.Transparency.BUSY)
+
+
+def belongs_to_other(task: models.Task, assignee: Optional[str]) -> bool:
+    """Whether the task is assigned to someone other than `assignee` (never, if no assignee is given)."""
+    if not assignee or not task.assignee:
+        return False
+    return task.assignee.lower() != assignee.lower()
+
+
+def find_busy_intervals(
+    db: Session,
+    target_date: date,
+    all_day_busy: bool = False,
+    assignee: Optional[str] = None
+) -> List[timeInterval]:
     """
     Query all scheduled tasks (events + todos) on target_date and return occupied intervals.
+    All-day events (birthdays, holidays) only count when `all_day_busy` is set,
+    free or tentative events never do, and neither do other people's tasks.
     """
     day_start = datetime.combine(target_date, time.min)
     day_end = datetime.combine(target_date, time.max)
@@ -63,6 +92,8 @@ def find_busy_intervals(db: Session, target_date: date) -> List[timeInterval]:
     ).all()
     intervals: List[timeInterval] = []
     for t in tasks:
+        if not blocks_time(t, all_day_busy) or belongs_to_other(t, assignee):
+            continue
         start = max(t.start_time, day_start)
         end = min(t.end_time, day_end)
         intervals.append((start, end))
@@ -117,7 +148,9 @@ def slot_tasks(
     db: Session,
     availability_config: AvailabilityConfig,
     weights: Dict[str, float],
-    now: datetime = None
+    now: datetime = None,
+    all_day_busy: bool = False,
+    assignee: Optional[str] = None
 ) -> None:
     """
     Auto-schedule ALL TODO tasks with minute-level deadline enforcement:
@@ -125,13 +158,17 @@ def slot_tasks(
       Phase 2: queue overflow tasks immediately after today's last busy interval.
 
     Optional `now` can be provided (for testing); defaults to UTC now.
+    With `assignee`, only that person's and unassigned TODOs are (re)scheduled.
     """
     if now is None:
         now = datetime.utcnow()
     today = now.date()
 
     # Fetch and clear all TODO schedules
-    todos = db.query(models.Task).filter(models.Task.type == models.TaskType.TODO).all()
+    todos = [
+        t for t in db.query(models.Task).filter(models.Task.type == models.TaskType.TODO).all()
+        if not belongs_to_other(t, assignee)
+    ]
     for task in todos:
         task.scheduled_for = None
         task.start_time = None
@@ -141,21 +178,22 @@ def slot_tasks(
     if not todos:
         return
 
-    # Sort tasks by descending priority score
+    # Sort tasks by descending priority score, optional ones after everything else; delegated
+    # tasks stay unscheduled
     pending = sorted(
-        todos,
-        key=lambda t: compute_priority_score(t, now, weights),
-        reverse=True
+        [t for t in todos if t.status != models.Status.WAITING],
+        key=lambda t: (bool(t.optional), -compute_priority_score(t, now, weights))
     )
     overflow: List[models.Task] = []
 
     # Phase 1: schedule before deadline
     for task in pending:
-        est = task.estimate or 0
+        est = task.remaining if task.remaining is not None else (task.estimate or 0)
         ddl = task.deadline
         # Expired tasks go straight to overflow
         if ddl and now >= ddl:
-            overflow.append(task)
+            if not task.optional:
+                overflow.append(task)
             continue
 
         scheduled = False
@@ -177,7 +215,7 @@ def slot_tasks(
                 day_offset += 1
                 continue
 
-            busy = find_busy_intervals(db, target_date)
+            busy = find_busy_intervals(db, target_date, all_day_busy, assignee)
             free_slots = find_free_slots(windows, busy)
             for slot_start, slot_end in free_slots:
                 start_time = max(slot_start, now)
@@ -197,7 +235,8 @@ def slot_tasks(
                 break
             day_offset += 1
 
-        if not scheduled:
+        # Optional tasks only go into slack, never into the overflow queue
+        if not scheduled and not task.optional:
             overflow.append(task)
 
     # Phase 2: back-to-back scheduling for overflow, considering only existing EVENTS as busy
@@ -214,6 +253,8 @@ def slot_tasks(
         ).all()
         event_busy = []
         for ev in events:
+            if not blocks_time(ev, all_day_busy) or belongs_to_other(ev, assignee):
+                continue
             start = max(ev.start_time, day_start)
             end = min(ev.end_time, day_end)
             event_busy.append((start, end))
@@ -222,7 +263,7 @@ def slot_tasks(
 
         # Schedule overflow tasks
         for task in overflow:
-            est = task.estimate or 0
+            est = task.remaining if task.remaining is not None else (task.estimate or 0)
             task.start_time = pointer
             task.end_time = pointer + timedelta(minutes=est)
             task.scheduled_for = pointer.date()
@@ -4,19 +4,27 @@ from typing import Optional, Any, Dict, List
 
 from pydantic import BaseModel, Field, model_validator
 
-from src.components.models import TaskType, Status
+from src.components.models import TaskType, Status, Transparency
 
 
 # Category Schemas
 class CategoryBase(BaseModel):
     name: str
     color: Optional[str] = Field(default="#CCCCCC", description="Hex color for UI")
+    icon: Optional[str] = Field(default=None, max_length=16, description="Emoji shown before task titles")
 
 
 class CategoryCreate(CategoryBase):
     pass
 
 
+class CategoryUpdate(BaseModel):
+    name: Optional[str] = None
+    color: Optional[str] = None
+    # "" removes the icon
+    icon: Optional[str] = Field(None, max_length=16)
+
+
 class Category(CategoryBase):
     id: int
 
@@ -32,15 +40,25 @@ class TaskBase(BaseModel):
     status: Status = Status.PENDING
     priority: int = Field(default=0, ge=0)
     category_id: Optional[int] = None
+    contexts: Optional[List[str]] = None
+    assignee: Optional[str] = Field(None, max_length=100)
 
     # Event-specific fields
     start_time: Optional[datetime] = None
     end_time: Optional[datetime] = None
     duration: Optional[int] = Field(None, ge=0)
+    all_day: Optional[bool] = False
+    location: Optional[str] = Field(None, max_length=200)
+    attendees: Optional[List[str]] = None
+    organizer: Optional[str] = Field(None, max_length=200)
+    meeting_url: Optional[str] = Field(None, max_length=500)
+    transparency: Optional[Transparency] = Transparency.BUSY
 
     # kTodo-specific fields
     deadline: Optional[datetime] = None
     estimate: Optional[int] = Field(None, gt=0)
+    remaining: Optional[int] = Field(None, ge=0)
+    optional: Optional[bool] = False
     scheduled_for: Optional[date] = None
     recurrence_rule: Optional[str] = None
 
@@ -58,6 +76,8 @@ class TaskBase(BaseModel):
                 raise ValueError('Event tasks must have both start_time and end_time')
             if end <= start:
                 raise ValueError('end_time must be after start_time')
+            if values.all_day and (start.time() != time.min or end.time() != time.min):
+                raise ValueError('all-day events must start and end at midnight')
             expected_duration = int((end - start).total_seconds() // 60)
             if duration is not None and duration != expected_duration:
                 raise ValueError('duration must match end_time - start_time')
@@ -72,7 +92,8 @@ class TaskBase(BaseModel):
 
 
 class TaskCreate(TaskBase):
-    pass
+    # Set when restoring a backup, so a pushed task stays linked to its calendar event
+    external_id: Optional[str] = None
 
 
 class TaskUpdate(BaseModel):
@@ -82,13 +103,24 @@ class TaskUpdate(BaseModel):
     status: Optional[Status] = None
     priority: Optional[int] = Field(None, ge=0)
     category_id: Optional[int] = None
+    contexts: Optional[List[str]] = None
+    # "" unassigns
+    assignee: Optional[str] = Field(None, max_length=100)
 
     start_time: Optional[datetime] = None
     end_time: Optional[datetime] = None
     duration: Optional[int] = Field(None, ge=0)
+    location: Optional[str] = Field(None, max_length=200)
+    attendees: Optional[List[str]] = None
+    organizer: Optional[str] = Field(None, max_length=200)
+    meeting_url: Optional[str] = Field(None, max_length=500)
+    all_day: Optional[bool] = None
+    transparency: Optional[Transparency] = None
 
     deadline: Optional[datetime] = None
     estimate: Optional[int] = Field(None, gt=0)
+    remaining: Optional[int] = Field(None, ge=0)
+    optional: Optional[bool] = None
     scheduled_for: Optional[date] = None
     recurrence_rule: Optional[str] = None
 
@@ -109,6 +141,8 @@ class Task(TaskBase):
     id: int
     created_at: datetime
     updated_at: datetime
+    # Google Calendar event it was pushed to or imported from
+    external_id: Optional[str] = None
     category: Optional[Category] = None
 
     class Config:
@@ -118,8 +152,21 @@ class AvailabilityWindow(BaseModel):
     start: time
     end:   time
 
+class GoogleTasksImport(BaseModel):
+    # only this task list (by title); all lists when omitted
+    task_list:         Optional[str] = None
+    # also bring in tasks already completed in Google Tasks, as done
+    include_completed: bool = False
+
+
 class AutoScheduleRequest(BaseModel):
     # weekday 0=Mon … 6=Sun → list of availability windows
     availability: Dict[int, List[AvailabilityWindow]]
+    # dates with a day template (office, travel, …) → their windows, instead of the weekday's
+    dates:         Dict[date, List[AvailabilityWindow]] = {}
     # e.g. {"priority": 1.0, "deadline": 100.0, "estimate": 0.5}
-    weights:       Dict[str, float]
\ No newline at end of file
+    weights:       Dict[str, float]
+    # whether all-day events block their days
+    all_day_busy:  bool = False
+    # only schedule this person's (and unassigned) todos; others' tasks don't block them
+    assignee:      Optional[str] = None
\ No newline at end of file
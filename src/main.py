@@ -7,6 +7,7 @@ from sqlalchemy.orm import Session
 
 from src.components import models, schemas, crud
 from src.components.calendar_sync import router as calendar_router  # Calendar sync endpoints
+from src.components.google_tasks import router as google_tasks_router
 from src.components.database import SessionLocal, engine
 
 from fastapi import BackgroundTasks
@@ -23,6 +24,7 @@ models.Base.metadata.create_all(bind=engine)
 app = FastAPI(title="scheduler API",default_response_class=ORJSONResponse)
 
 app.include_router(calendar_router)
+app.include_router(google_tasks_router)
 
 def get_db():
     db = SessionLocal()
@@ -53,6 +55,17 @@ def get_category(category_id: int, db: Session = Depends(get_db)):
     return db_cat
 
 
+@app.patch("/categories/{category_id}", response_model=schemas.Category)
+def update_category(category_id: int, updates: schemas.CategoryUpdate, db: Session = Depends(get_db)):
+    db_cat = crud.get_category(db, category_id)
+    if not db_cat:
+        raise HTTPException(status_code=404, detail="Category not found")
+    if updates.name and updates.name != db_cat.name:
+        if db.query(models.Category).filter(models.Category.name == updates.name).first():
+            raise HTTPException(status_code=400, detail="Category already exists")
+    return crud.update_category(db, db_cat, updates)
+
+
 @app.post("/tasks/", response_model=schemas.Task)
 def create_task(task: schemas.TaskCreate, db: Session = Depends(get_db)):
     # Validate category if provided
@@ -112,7 +125,11 @@ def auto_schedule(
         wd: [(w.start, w.end) for w in windows]
         for wd, windows in req.availability.items()
     }
-    avail_cfg = AvailabilityConfig(availability_map)
+    dates_map = {
+        d: [(w.start, w.end) for w in windows]
+        for d, windows in req.dates.items()
+    }
+    avail_cfg = AvailabilityConfig(availability_map, dates_map)
 
     # Run in background so the HTTP client isn't blocked
     def _run_scheduler():
@@ -120,7 +137,7 @@ def auto_schedule(
             models.Task.type == models.TaskType.TODO,
             models.Task.scheduled_for.is_(None)
         ).count()
-        scheduler.slot_tasks(db, avail_cfg, req.weights)
+        scheduler.slot_tasks(db, avail_cfg, req.weights, all_day_busy=req.all_day_busy, assignee=req.assignee)
         after = db.query(models.Task).filter(
             models.Task.type == models.TaskType.TODO,
             models.Task.scheduled_for.is_(None)
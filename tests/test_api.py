@@ -54,6 +54,32 @@ def test_create_and_list_category():
     assert resp.status_code == 200
     assert any(cat["name"] == "Work" for cat in resp.json())
 
+def test_category_icon_can_be_set_and_removed():
+    resp = client.post("/categories/", json={"name": "Thesis", "icon": "📚"})
+    assert resp.status_code == 200
+    cat = resp.json()
+    assert cat["icon"] == "📚"
+
+    resp = client.patch(f"/categories/{cat['id']}", json={"icon": ""})
+    assert resp.status_code == 200
+    assert resp.json()["icon"] is None
+    assert resp.json()["name"] == "Thesis"
+
+def test_pushed_category_icon_is_not_synced_back_into_the_title():
+    from src.components.calendar_sync import event_title
+    pushed = {"summary": "📚 Read ch. 3", "extendedProperties": {"private": {"todo_icon": "📚"}}}
+    assert event_title(pushed) == "Read ch. 3"
+    assert event_title({"summary": "📚 Read ch. 3"}, icon="📚") == "Read ch. 3"
+    assert event_title({"summary": "📚 Read ch. 3"}) == "📚 Read ch. 3"
+
+def test_restored_task_keeps_its_calendar_event_id():
+    resp = client.post("/tasks/", json={
+        "title": "Dentist", "type": "event", "external_id": "gcal-restored-1",
+        "start_time": "2025-06-02T09:00:00", "end_time": "2025-06-02T10:00:00",
+    })
+    assert resp.status_code == 200
+    assert resp.json()["external_id"] == "gcal-restored-1"
+
 def test_crud_task_event_and_todo():
     # create an event‐type task
     event_payload = {
@@ -98,3 +124,59 @@ def test_crud_task_event_and_todo():
     # ensure it’s gone
     resp = client.get("/tasks/")
     assert all(t["id"] != event_task["id"] for t in resp.json())
+
+def test_event_location_round_trips():
+    resp = client.post("/tasks/", json={
+        "title": "Offsite", "type": "event", "location": "Office, room 4",
+        "start_time": "2025-05-21T14:00:00", "end_time": "2025-05-21T15:00:00",
+    })
+    assert resp.status_code == 200
+    assert resp.json()["location"] == "Office, room 4"
+    assert client.get(f"/tasks/{resp.json()['id']}").json()["location"] == "Office, room 4"
+
+def test_event_attendees_and_meeting_url():
+    resp = client.post("/tasks/", json={
+        "title": "Design review", "type": "event",
+        "start_time": "2025-05-22T14:00:00", "end_time": "2025-05-22T15:00:00",
+        "attendees": ["ann@example.com", "bob@example.com"],
+        "meeting_url": "https://meet.example.com/abc",
+    })
+    assert resp.status_code == 200
+    event = client.get(f"/tasks/{resp.json()['id']}").json()
+    assert event["attendees"] == ["ann@example.com", "bob@example.com"]
+    assert event["meeting_url"] == "https://meet.example.com/abc"
+
+def test_imported_google_tasks_keep_the_task_list_loading(monkeypatch):
+    from unittest.mock import MagicMock
+    from src.components import calendar_sync, google_tasks
+
+    items = [
+        {"id": "g-1", "title": "Buy milk", "due": "2025-06-03T00:00:00.000Z"},
+        {"id": "g-2", "title": "Someday: learn Go"},
+    ]
+    service = MagicMock()
+    service.tasklists().list().execute.return_value = {"items": [{"id": "l-1", "title": "Errands"}]}
+    service.tasks().list().execute.return_value = {"items": items}
+    monkeypatch.setattr(google_tasks, "build", lambda *args, **kwargs: service)
+    monkeypatch.setattr(google_tasks, "get_credentials", lambda: None)
+    app.dependency_overrides[calendar_sync.get_db] = override_get_db
+    try:
+        resp = client.post("/import/google-tasks", json={})
+        assert resp.status_code == 200
+        assert (resp.json()["imported"], resp.json()["skipped"]) == (1, 1)
+
+        resp = client.get("/tasks/")
+        assert resp.status_code == 200
+        milk = next(t for t in resp.json() if t["title"] == "Buy milk")
+        assert milk["estimate"] == google_tasks.DEFAULT_ESTIMATE_MINUTES
+        assert milk["deadline"].startswith("2025-06-03T23:59")
+        assert all(t["title"] != "Someday: learn Go" for t in resp.json())
+
+        # Losing its due date in Google doesn't take the deadline away
+        del items[0]["due"]
+        resp = client.post("/import/google-tasks", json={})
+        assert resp.json()["updated"] == 1
+        assert client.get("/tasks/").status_code == 200
+        assert client.get(f"/tasks/{milk['id']}").json()["deadline"].startswith("2025-06-03T23:59")
+    finally:
+        del app.dependency_overrides[calendar_sync.get_db]
@@ -179,6 +179,15 @@ def test_get_windows_for_date_unconfigured_sunday(standard_availability_config):
     windows = standard_availability_config.get_windows_for_date(target_date)
     assert windows == [] # No slots configured for Sunday
 
+def test_get_windows_for_date_prefers_the_dates_own_windows():
+    cfg = AvailabilityConfig(
+        {0: [(time(9, 0), time(17, 0))]},
+        {date(2025, 5, 19): [(time(9, 45), time(17, 0))], date(2025, 5, 26): []},
+    )
+    assert cfg.get_windows_for_date(date(2025, 5, 19)) == [(datetime(2025, 5, 19, 9, 45), datetime(2025, 5, 19, 17, 0))]
+    assert cfg.get_windows_for_date(date(2025, 5, 26)) == []
+    assert cfg.get_windows_for_date(date(2025, 6, 2)) == [(datetime(2025, 6, 2, 9, 0), datetime(2025, 6, 2, 17, 0))]
+
 def test_get_windows_for_date_with_empty_availability_config(empty_availability):
     target_date = date(2025, 5, 19) # Monday
     windows = empty_availability.get_windows_for_date(target_date)
@@ -232,6 +241,25 @@ def test_find_busy_intervals_task_spans_entire_day(db_session):
     expected_end = datetime.combine(target_date, time.max)
     assert busy == [(expected_start, expected_end)]
 
+def test_find_busy_intervals_all_day_events_only_block_when_asked(db_session):
+    target_date = date(2025, 5, 19)
+    create_task_in_db(
+        db_session, type=TaskType.EVENT, all_day=True,
+        start_time=datetime(2025, 5, 19, 0, 0), end_time=datetime(2025, 5, 20, 0, 0)
+    )
+    assert find_busy_intervals(db_session, target_date) == []
+    busy = find_busy_intervals(db_session, target_date, all_day_busy=True)
+    assert busy == [(datetime(2025, 5, 19, 0, 0), datetime.combine(target_date, time.max))]
+
+def test_find_busy_intervals_skips_free_and_tentative_events(db_session):
+    target_date = date(2025, 5, 19)
+    for hour, shown in ((9, models.Transparency.FREE), (10, models.Transparency.TENTATIVE), (11, models.Transparency.BUSY)):
+        create_task_in_db(
+            db_session, type=TaskType.EVENT, transparency=shown,
+            start_time=datetime(2025, 5, 19, hour, 0), end_time=datetime(2025, 5, 19, hour, 30)
+        )
+    assert find_busy_intervals(db_session, target_date) == [(datetime(2025, 5, 19, 11, 0), datetime(2025, 5, 19, 11, 30))]
+
 
 
 # --- Tests for find_free_slots ---
@@ -369,6 +397,32 @@ def test_slot_tasks_wipes_and_reschedules_existing_todos(db_session, standard_av
     assert task_old.start_time == datetime(2025,5,19,10,0)
     assert task_old.end_time == datetime(2025,5,19,10,30)
 
+def test_slot_tasks_leaves_waiting_tasks_unscheduled(db_session, standard_availability_config, default_weights):
+    now = datetime(2025, 5, 19, 8, 0)
+    delegated = create_task_in_db(db_session, title="Delegated", estimate=60, deadline=datetime(2025,5,19,17,0),
+                                  priority=9, status=Status.WAITING)
+    mine = create_task_in_db(db_session, title="Mine", estimate=60, deadline=datetime(2025,5,19,17,0))
+    slot_tasks(db_session, standard_availability_config, default_weights, now=now)
+    db_session.refresh(delegated)
+    db_session.refresh(mine)
+    assert delegated.start_time is None
+    assert mine.start_time == datetime(2025, 5, 19, 9, 0)
+
+def test_slot_tasks_only_schedules_the_assignees_tasks(db_session, standard_availability_config, default_weights):
+    now = datetime(2025, 5, 19, 8, 0)
+    theirs_placed = datetime(2025, 5, 19, 9, 0)
+    theirs = create_task_in_db(db_session, title="Anna's", estimate=60, deadline=datetime(2025,5,19,17,0),
+                               priority=9, assignee="anna", start_time=theirs_placed,
+                               end_time=theirs_placed + timedelta(minutes=60))
+    mine = create_task_in_db(db_session, title="Mine", estimate=60, deadline=datetime(2025,5,19,17,0), assignee="Ben")
+    shared = create_task_in_db(db_session, title="Shared", estimate=60, deadline=datetime(2025,5,19,17,0))
+    slot_tasks(db_session, standard_availability_config, default_weights, now=now, assignee="ben")
+    for t in (theirs, mine, shared):
+        db_session.refresh(t)
+    # Anna's placement is left alone and doesn't block Ben's morning
+    assert theirs.start_time == theirs_placed
+    assert {mine.start_time, shared.start_time} == {datetime(2025, 5, 19, 9, 0), datetime(2025, 5, 19, 10, 0)}
+
 def test_slot_tasks_priority_and_deadline_ordering(db_session, standard_availability_config, default_weights):
     now = datetime(2025, 5, 19, 8, 0)
     # Task A: High priority, later deadline
@@ -421,6 +475,21 @@ def test_slot_tasks_task_cannot_fit_before_deadline_goes_to_phase2_overflow(db_s
     assert task_overflow.start_time == datetime(2025,5,19,10,0)
     assert task_overflow.end_time == datetime(2025,5,19,11,0)
 
+def test_slot_tasks_places_optional_tasks_last_and_never_overflows_them(db_session, default_weights):
+    now = datetime(2025, 5, 19, 8, 0)
+    custom_avail = AvailabilityConfig({0: [(time(9,0), time(12,0))]}) # Mon 9AM-12PM only
+    nice = create_task_in_db(db_session, title="Nice", priority=9, estimate=60, deadline=datetime(2025,5,19,12,0), optional=True)
+    must = create_task_in_db(db_session, title="Must", priority=1, estimate=120, deadline=datetime(2025,5,19,12,0))
+    extra = create_task_in_db(db_session, title="Extra", priority=5, estimate=60, deadline=datetime(2025,5,19,12,0), optional=True)
+
+    slot_tasks(db_session, custom_avail, default_weights, now=now)
+    for t in (nice, must, extra):
+        db_session.refresh(t)
+    # The required task goes first despite its low priority; the optional ones share the slack
+    assert must.start_time == datetime(2025,5,19,9,0)
+    assert nice.start_time == datetime(2025,5,19,11,0)
+    assert extra.start_time is None
+
 def test_slot_tasks_deadline_already_passed_goes_to_phase2_overflow(db_session, standard_availability_config, default_weights):
     now = datetime(2025, 5, 19, 10, 0)
     event = create_task_in_db(db_session, type=TaskType.EVENT, title="Blocker", start_time=datetime(2025,5,19,13,0), end_time=datetime(2025,5,19,14,0))